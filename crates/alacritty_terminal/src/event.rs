@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::fmt::{self, Debug, Formatter};
+use std::ops::Range;
 use std::sync::Arc;
 
 use crate::term::ClipboardType;
@@ -61,6 +62,18 @@ pub enum Event {
     /// This allows embedders to implement custom private modes like Mode 2031
     /// (dark/light color-scheme notifications).
     PrivateModeUpdate(u16, bool),
+
+    /// An OSC sequence that the terminal doesn't handle itself (e.g. OSC 133 shell
+    /// integration marks), forwarded so embedders can implement it.
+    /// `payload` is the text between `ESC ]` and the terminator. `line` is the cursor line when
+    /// the sequence arrived, as `Grid::scrolled_lines()` plus the screen line; `column` is the
+    /// cursor column.
+    UnhandledOsc { payload: String, line: u64, column: usize },
+
+    /// Images placed with [`EventListener::graphics`] on `lines` (same coordinates as
+    /// [`GraphicsCursor::line`]) of the primary or alternate screen were erased, e.g. by clearing
+    /// scrollback, entering the alternate screen or a full reset.
+    GraphicsCleared { alt_screen: bool, lines: Range<u64> },
 }
 
 impl Debug for Event {
@@ -82,6 +95,12 @@ impl Debug for Event {
             Event::PrivateModeUpdate(mode, enabled) => {
                 write!(f, "PrivateModeUpdate({mode}, {enabled})")
             },
+            Event::UnhandledOsc { payload, line, column } => {
+                write!(f, "UnhandledOsc({payload}, {line}, {column})")
+            },
+            Event::GraphicsCleared { alt_screen, lines } => {
+                write!(f, "GraphicsCleared({alt_screen}, {lines:?})")
+            },
         }
     }
 }
@@ -110,6 +129,46 @@ pub trait OnResize {
 /// Event Loop for notifying the renderer about terminal events.
 pub trait EventListener {
     fn send_event(&self, _event: Event) {}
+
+    /// Place the image carried by a kitty graphics or sixel sequence at `at`.
+    ///
+    /// Returns how far to move the cursor past the image, or `None` to leave it in place. The
+    /// terminal doesn't decode images itself; embedders that don't implement this ignore them.
+    fn graphics(&self, _graphics: Graphics, _at: GraphicsCursor) -> Option<CursorAdvance> {
+        None
+    }
+
+    /// Called with each image sequence as it's read, before the terminal is locked to parse it.
+    ///
+    /// [`EventListener::graphics`] follows for the same sequence, in the same order; slow work
+    /// such as reading files and decoding can be done here instead.
+    fn prepare_graphics(&self, _graphics: &Graphics) {}
+}
+
+/// An image sequence forwarded to [`EventListener::graphics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Graphics {
+    /// Kitty graphics protocol: the text between `ESC _` and the terminator, starting with `G`.
+    Kitty(Vec<u8>),
+    /// Sixel: the text between `ESC P` and the terminator, i.e. parameters, `q` and the data.
+    Sixel(Vec<u8>),
+}
+
+/// Cursor position an image sequence arrived at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphicsCursor {
+    /// `Grid::scrolled_lines()` plus the screen line.
+    pub line: u64,
+    pub column: usize,
+    pub alt_screen: bool,
+}
+
+/// Cursor movement after an image was placed: `lines` line feeds (scrolling as needed), then
+/// `columns` to the right.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CursorAdvance {
+    pub lines: usize,
+    pub columns: usize,
 }
 
 /// Null sink for events.
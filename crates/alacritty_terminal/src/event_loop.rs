@@ -5,6 +5,7 @@ use std::collections::VecDeque;
 use std::fmt::{self, Display, Formatter};
 use std::fs::File;
 use std::io::{self, ErrorKind, Read, Write};
+use std::mem;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
@@ -14,7 +15,7 @@ use std::time::Instant;
 use log::error;
 use polling::{Event as PollingEvent, Events, PollMode};
 
-use crate::event::{self, Event, EventListener, WindowSize};
+use crate::event::{self, Event, EventListener, Graphics, WindowSize};
 use crate::sync::FairMutex;
 use crate::term::Term;
 use crate::{thread, tty};
@@ -39,6 +40,9 @@ pub enum Msg {
     Resize(WindowSize),
 }
 
+/// Receives a copy of every chunk of PTY output before it is parsed.
+pub type OutputTap = Box<dyn FnMut(&[u8]) + Send>;
+
 /// The main event loop.
 ///
 /// Handles all the PTY I/O and runs the PTY parser which updates terminal
@@ -52,6 +56,7 @@ pub struct EventLoop<T: tty::EventedPty, U: EventListener> {
     event_proxy: U,
     drain_on_exit: bool,
     ref_test: bool,
+    output_tap: Option<OutputTap>,
 }
 
 impl<T, U> EventLoop<T, U>
@@ -78,9 +83,15 @@ where
             event_proxy,
             drain_on_exit,
             ref_test,
+            output_tap: None,
         })
     }
 
+    /// Install a callback that sees all PTY output (e.g. for session recording).
+    pub fn set_output_tap(&mut self, tap: OutputTap) {
+        self.output_tap = Some(tap);
+    }
+
     pub fn channel(&self) -> EventLoopSender {
         EventLoopSender { sender: self.tx.clone(), poller: self.poll.clone() }
     }
@@ -114,15 +125,30 @@ where
         let mut processed = 0;
 
         // Reserve the next terminal lock for PTY reading.
-        let _terminal_lease = Some(self.terminal.lease());
+        let mut terminal_lease = Some(self.terminal.lease());
         let mut terminal = None;
+        let mut graphics = Vec::new();
 
         loop {
             // Read from the PTY.
             match self.pty.reader().read(&mut buf[unprocessed..]) {
                 // This is received on Windows/macOS when no more data is readable from the PTY.
                 Ok(0) if unprocessed == 0 => break,
-                Ok(got) => unprocessed += got,
+                Ok(got) => {
+                    // Image sequences go to the listener before they're parsed, with the terminal
+                    // unlocked, so it can read and decode them without stalling other threads.
+                    let read = &buf[unprocessed..unprocessed + got];
+                    state.graphics_lookahead.scan(read, &mut graphics);
+                    if !graphics.is_empty() {
+                        terminal = None;
+                        drop(terminal_lease.take());
+                        for graphics in graphics.drain(..) {
+                            self.event_proxy.prepare_graphics(&graphics);
+                        }
+                        terminal_lease = Some(self.terminal.lease());
+                    }
+                    unprocessed += got;
+                },
                 Err(err) => match err.kind() {
                     ErrorKind::Interrupted | ErrorKind::WouldBlock => {
                         // Go back to mio if we're caught up on parsing and the PTY would block.
@@ -150,8 +176,12 @@ where
                 writer.write_all(&buf[..unprocessed]).unwrap();
             }
 
+            if let Some(tap) = &mut self.output_tap {
+                tap(&buf[..unprocessed]);
+            }
+
             // Parse the incoming bytes.
-            state.parser.advance(&mut **terminal, &buf[..unprocessed]);
+            state.osc_passthrough.advance(&mut state.parser, terminal, &buf[..unprocessed]);
 
             processed += unprocessed;
             unprocessed = 0;
@@ -392,6 +422,216 @@ impl EventLoopSender {
     }
 }
 
+/// Longest OSC payload forwarded by [`OscPassthrough`]; longer sequences are dropped.
+const MAX_PASSTHROUGH_OSC: usize = 4096;
+
+/// Longest kitty graphics or sixel sequence forwarded by [`OscPassthrough`]; longer sequences
+/// are dropped.
+const MAX_PASSTHROUGH_GRAPHICS: usize = 32 * 1024 * 1024;
+
+/// OSC numbers vte handles itself. Everything else is forwarded as
+/// [`Event::UnhandledOsc`].
+const HANDLED_OSC: &[&[u8]] =
+    &[b"0", b"2", b"4", b"8", b"10", b"11", b"12", b"22", b"50", b"52", b"104", b"110", b"111", b"112"];
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum OscScanState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+    /// Inside `ESC _` (APC) or `ESC P` (DCS).
+    Graphics,
+    GraphicsEscape,
+}
+
+/// What the bytes of an APC or DCS sequence turned out to be.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum GraphicsKind {
+    /// APC that hasn't started yet.
+    #[default]
+    Apc,
+    /// DCS still in its parameters.
+    Dcs,
+    Kitty,
+    Sixel,
+    /// Some other APC or DCS, or one that grew too long.
+    Ignored,
+}
+
+/// A sequence [`OscPassthrough::feed`] just finished.
+enum Passthrough {
+    Osc,
+    Graphics(Graphics),
+}
+
+/// Spots OSC sequences that vte ignores (e.g. OSC 133 shell integration marks), and kitty
+/// graphics APC and sixel DCS sequences, which vte swallows.
+///
+/// The parser is advanced up to the end of each such sequence before the event is sent, so the
+/// reported cursor position is the one the sequence applies to.
+#[derive(Default)]
+pub struct OscPassthrough {
+    state: OscScanState,
+    payload: Vec<u8>,
+    overflow: bool,
+    graphics: Vec<u8>,
+    graphics_kind: GraphicsKind,
+}
+
+impl OscPassthrough {
+    /// Feed PTY output through `parser` into `term`, forwarding unhandled OSC sequences.
+    pub fn advance<L: EventListener>(
+        &mut self,
+        parser: &mut ansi::Processor,
+        term: &mut Term<L>,
+        bytes: &[u8],
+    ) {
+        let mut start = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            // Fast path: nothing interesting happens until the next ESC.
+            if self.state == OscScanState::Ground {
+                match bytes[i..].iter().position(|&b| b == 0x1b) {
+                    Some(offset) => i += offset,
+                    None => break,
+                }
+            }
+
+            let byte = bytes[i];
+            i += 1;
+            let Some(finished) = self.feed(byte) else { continue };
+            parser.advance(term, &bytes[start..i]);
+            start = i;
+            match finished {
+                Passthrough::Osc => {
+                    let payload = String::from_utf8_lossy(&self.payload).into_owned();
+                    term.unhandled_osc(payload);
+                },
+                Passthrough::Graphics(graphics) => term.graphics(graphics),
+            }
+        }
+        parser.advance(term, &bytes[start..]);
+    }
+
+    /// Scan `bytes` without parsing them, collecting the image sequences that end in them.
+    pub fn scan(&mut self, bytes: &[u8], found: &mut Vec<Graphics>) {
+        let mut i = 0;
+        while i < bytes.len() {
+            if self.state == OscScanState::Ground {
+                match bytes[i..].iter().position(|&b| b == 0x1b) {
+                    Some(offset) => i += offset,
+                    None => break,
+                }
+            }
+            let byte = bytes[i];
+            i += 1;
+            if let Some(Passthrough::Graphics(graphics)) = self.feed(byte) {
+                found.push(graphics);
+            }
+        }
+    }
+
+    /// Advance the scanner by one byte, returning the sequence that just ended, if any.
+    fn feed(&mut self, byte: u8) -> Option<Passthrough> {
+        match (self.state, byte) {
+            (OscScanState::Ground, 0x1b) => self.state = OscScanState::Escape,
+            (OscScanState::Ground, _) => (),
+            (OscScanState::Escape, b']') => {
+                self.payload.clear();
+                self.overflow = false;
+                self.state = OscScanState::Osc;
+            },
+            (OscScanState::Escape, b'_' | b'P') => {
+                self.graphics.clear();
+                self.graphics_kind =
+                    if byte == b'_' { GraphicsKind::Apc } else { GraphicsKind::Dcs };
+                self.state = OscScanState::Graphics;
+            },
+            (OscScanState::Escape, 0x1b) => (),
+            (OscScanState::Escape, _) => self.state = OscScanState::Ground,
+            (OscScanState::Osc, 0x07) => {
+                let finished = self.finish();
+                self.state = OscScanState::Ground;
+                return finished;
+            },
+            (OscScanState::Osc, 0x1b) => self.state = OscScanState::OscEscape,
+            (OscScanState::Graphics, 0x1b) => self.state = OscScanState::GraphicsEscape,
+            // CAN and SUB abort the sequence.
+            (OscScanState::Osc | OscScanState::Graphics, 0x18 | 0x1a) => {
+                self.state = OscScanState::Ground
+            },
+            (OscScanState::Osc, _) => {
+                if self.payload.len() < MAX_PASSTHROUGH_OSC {
+                    self.payload.push(byte);
+                } else {
+                    self.overflow = true;
+                }
+            },
+            (OscScanState::Graphics, _) => self.push_graphics(byte),
+            // ST terminator.
+            (OscScanState::OscEscape | OscScanState::GraphicsEscape, b'\\') => {
+                let finished = self.finish();
+                self.state = OscScanState::Ground;
+                return finished;
+            },
+            // Any other ESC ends the sequence and starts a new escape sequence.
+            (OscScanState::OscEscape | OscScanState::GraphicsEscape, _) => {
+                let finished = self.finish();
+                self.state = OscScanState::Escape;
+                // An escape sequence's first byte never finishes anything.
+                let _ = self.feed(byte);
+                return finished;
+            },
+        }
+        None
+    }
+
+    fn push_graphics(&mut self, byte: u8) {
+        self.graphics_kind = match self.graphics_kind {
+            GraphicsKind::Ignored => return,
+            GraphicsKind::Apc if byte == b'G' => GraphicsKind::Kitty,
+            GraphicsKind::Apc => GraphicsKind::Ignored,
+            // Sixel is `DCS P1;P2;P3 q`: the final byte decides.
+            GraphicsKind::Dcs if byte == b'q' => GraphicsKind::Sixel,
+            GraphicsKind::Dcs if byte.is_ascii_digit() || byte == b';' => GraphicsKind::Dcs,
+            GraphicsKind::Dcs => GraphicsKind::Ignored,
+            _ if self.graphics.len() >= MAX_PASSTHROUGH_GRAPHICS => GraphicsKind::Ignored,
+            kind => kind,
+        };
+        if self.graphics_kind == GraphicsKind::Ignored {
+            self.graphics = Vec::new();
+        } else {
+            self.graphics.push(byte);
+        }
+    }
+
+    fn finish(&mut self) -> Option<Passthrough> {
+        match self.state {
+            OscScanState::Osc | OscScanState::OscEscape => {
+                if self.overflow {
+                    return None;
+                }
+                let number = self.payload.split(|&b| b == b';').next().unwrap_or_default();
+                let unhandled = !number.is_empty()
+                    && number.iter().all(u8::is_ascii_digit)
+                    && !HANDLED_OSC.contains(&number);
+                unhandled.then_some(Passthrough::Osc)
+            },
+            OscScanState::Graphics | OscScanState::GraphicsEscape => {
+                let data = mem::take(&mut self.graphics);
+                match self.graphics_kind {
+                    GraphicsKind::Kitty => Some(Passthrough::Graphics(Graphics::Kitty(data))),
+                    GraphicsKind::Sixel => Some(Passthrough::Graphics(Graphics::Sixel(data))),
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
 /// All of the mutable state needed to run the event loop.
 ///
 /// Contains list of items to write, current write state, etc. Anything that
@@ -401,6 +641,9 @@ pub struct State {
     write_list: VecDeque<Cow<'static, [u8]>>,
     writing: Option<Writing>,
     parser: ansi::Processor,
+    osc_passthrough: OscPassthrough,
+    /// Sees PTY output as it's read, ahead of `osc_passthrough`.
+    graphics_lookahead: OscPassthrough,
 }
 
 impl State {
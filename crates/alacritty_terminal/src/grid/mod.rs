@@ -135,6 +135,13 @@ pub struct Grid<T> {
 
     /// Maximum number of lines in history.
     max_scroll_limit: usize,
+
+    /// Total number of lines ever scrolled into history.
+    ///
+    /// Unlike `history_size`, this keeps growing once history is full, so
+    /// `scrolled_lines + screen line` is a stable position for a line of output.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    scrolled_lines: u64,
 }
 
 impl<T: GridCell + Default + PartialEq> Grid<T> {
@@ -147,6 +154,7 @@ impl<T: GridCell + Default + PartialEq> Grid<T> {
             cursor: Cursor::default(),
             lines,
             columns,
+            scrolled_lines: 0,
         }
     }
 
@@ -270,6 +278,8 @@ impl<T: GridCell + Default + PartialEq> Grid<T> {
 
         // Only rotate the entire history if the active region starts at the top.
         if region.start == 0 {
+            self.scrolled_lines += positions as u64;
+
             // Create scrollback for the new lines.
             self.increase_scroll_limit(positions);
 
@@ -379,6 +389,12 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Total number of lines ever scrolled into history.
+    #[inline]
+    pub fn scrolled_lines(&self) -> u64 {
+        self.scrolled_lines
+    }
+
     #[inline]
     pub fn clear_history(&mut self) {
         // Explicitly purge all lines from history.
@@ -17,4 +17,5 @@ pub mod vi_mode;
 
 pub use crate::grid::Grid;
 pub use crate::term::Term;
+pub use polling;
 pub use vte;
@@ -13,7 +13,7 @@ use bitflags::bitflags;
 use log::{debug, trace};
 use unicode_width::UnicodeWidthChar;
 
-use crate::event::{Event, EventListener};
+use crate::event::{Event, EventListener, Graphics, GraphicsCursor};
 use crate::grid::{Dimensions, Grid, GridIterator, Scroll};
 use crate::index::{self, Boundary, Column, Direction, Line, Point, Side};
 use crate::selection::{Selection, SelectionRange, SelectionType};
@@ -351,6 +351,9 @@ pub struct Config {
 
     /// OSC52 support mode.
     pub osc52: Osc52,
+
+    /// Whether to advertise sixel graphics in the primary device attributes.
+    pub sixel: bool,
 }
 
 impl Default for Config {
@@ -362,6 +365,7 @@ impl Default for Config {
             vi_mode_cursor_style: Default::default(),
             kitty_keyboard: Default::default(),
             osc52: Default::default(),
+            sixel: Default::default(),
         }
     }
 }
@@ -385,6 +389,47 @@ pub enum Osc52 {
 }
 
 impl<T> Term<T> {
+    /// Forward an OSC sequence that vte ignores, with the current cursor position.
+    pub(crate) fn unhandled_osc(&mut self, payload: String)
+    where
+        T: EventListener,
+    {
+        let point = self.grid.cursor.point;
+        let line = self.grid.scrolled_lines() + point.line.0.max(0) as u64;
+        let column = point.column.0;
+        self.event_proxy.send_event(Event::UnhandledOsc { payload, line, column });
+    }
+
+    /// Hand a kitty graphics or sixel sequence to the listener and move the cursor past the
+    /// image it placed.
+    pub(crate) fn graphics(&mut self, graphics: Graphics)
+    where
+        T: EventListener,
+    {
+        let point = self.grid.cursor.point;
+        let at = GraphicsCursor {
+            line: self.grid.scrolled_lines() + point.line.0.max(0) as u64,
+            column: point.column.0,
+            alt_screen: self.mode.contains(TermMode::ALT_SCREEN),
+        };
+        let Some(advance) = self.event_proxy.graphics(graphics, at) else { return };
+        for _ in 0..advance.lines {
+            self.linefeed();
+        }
+        if advance.columns > 0 {
+            self.move_forward(advance.columns);
+        }
+    }
+
+    /// Tell the listener that images on `lines` of the active screen are gone.
+    fn graphics_cleared(&self, lines: Range<u64>)
+    where
+        T: EventListener,
+    {
+        let alt_screen = self.mode.contains(TermMode::ALT_SCREEN);
+        self.event_proxy.send_event(Event::GraphicsCleared { alt_screen, lines });
+    }
+
     #[inline]
     pub fn scroll_display(&mut self, scroll: Scroll)
     where
@@ -525,6 +570,26 @@ impl<T> Term<T> {
         self.mark_fully_damaged();
     }
 
+    /// Change the scrollback limit, dropping the oldest history beyond it.
+    pub fn set_scrolling_history(&mut self, history_size: usize) {
+        self.config.scrolling_history = history_size;
+        self.trim_history(history_size);
+    }
+
+    /// Drop the oldest history beyond `keep` lines without lowering the scrollback limit.
+    pub fn trim_history(&mut self, keep: usize) {
+        let limit = self.config.scrolling_history;
+        let grid = if self.mode.contains(TermMode::ALT_SCREEN) {
+            &mut self.inactive_grid
+        } else {
+            &mut self.grid
+        };
+        grid.update_history(keep.min(limit));
+        grid.update_history(limit);
+
+        self.mark_fully_damaged();
+    }
+
     /// Convert the active selection to a String.
     pub fn selection_to_string(&self) -> Option<String> {
         let selection_range = self.selection.as_ref().and_then(|s| s.to_range(self))?;
@@ -711,7 +776,10 @@ impl<T> Term<T> {
     }
 
     /// Swap primary and alternate screen buffer.
-    pub fn swap_alt(&mut self) {
+    pub fn swap_alt(&mut self)
+    where
+        T: EventListener,
+    {
         if !self.mode.contains(TermMode::ALT_SCREEN) {
             // Set alt screen cursor to the current primary screen cursor.
             self.inactive_grid.cursor = self.grid.cursor.clone();
@@ -721,6 +789,7 @@ impl<T> Term<T> {
 
             // Reset alternate screen contents.
             self.inactive_grid.reset_region(..);
+            self.event_proxy.send_event(Event::GraphicsCleared { alt_screen: true, lines: 0..u64::MAX });
         }
 
         mem::swap(&mut self.keyboard_mode_stack, &mut self.inactive_keyboard_mode_stack);
@@ -930,8 +999,7 @@ impl<T> Term<T> {
         &self.config.semantic_escape_chars
     }
 
-    #[cfg(test)]
-    pub(crate) fn set_semantic_escape_chars(&mut self, semantic_escape_chars: &str) {
+    pub fn set_semantic_escape_chars(&mut self, semantic_escape_chars: &str) {
         self.config.semantic_escape_chars = semantic_escape_chars.into();
     }
 
@@ -1258,7 +1326,11 @@ impl<T: EventListener> Handler for Term<T> {
         match intermediate {
             None => {
                 trace!("Reporting primary device attributes");
-                let text = String::from("\x1b[?6c");
+                let text = if self.config.sixel {
+                    String::from("\x1b[?62;4c")
+                } else {
+                    String::from("\x1b[?6c")
+                };
                 self.event_proxy.send_event(Event::PtyWrite(text));
             },
             Some('>') => {
@@ -1293,7 +1365,7 @@ impl<T: EventListener> Handler for Term<T> {
         trace!("Pushing `{mode:?}` keyboard mode into the stack");
 
         if self.keyboard_mode_stack.len() >= KEYBOARD_MODE_STACK_MAX_DEPTH {
-            let removed = self.title_stack.remove(0);
+            let removed = self.keyboard_mode_stack.remove(0);
             trace!(
                 "Removing '{removed:?}' from bottom of keyboard mode stack that exceeds its \
                  maximum depth"
@@ -1325,7 +1397,20 @@ impl<T: EventListener> Handler for Term<T> {
             return;
         }
 
-        self.set_keyboard_mode(mode.into(), apply);
+        // `CSI = flags ; mode u` edits the top of the stack, so that a later
+        // query (`CSI ? u`) reports the flags actually in effect.
+        let current = self.keyboard_mode_stack.last().copied().unwrap_or(KeyboardModes::NO_MODE);
+        let mode = match apply {
+            KeyboardModesApplyBehavior::Replace => mode,
+            KeyboardModesApplyBehavior::Union => current.union(mode),
+            KeyboardModesApplyBehavior::Difference => current.difference(mode),
+        };
+        match self.keyboard_mode_stack.last_mut() {
+            Some(top) => *top = mode,
+            None => self.keyboard_mode_stack.push(mode),
+        }
+
+        self.set_keyboard_mode(mode.into(), KeyboardModesApplyBehavior::Replace);
     }
 
     #[inline]
@@ -1788,6 +1873,7 @@ impl<T: EventListener> Handler for Term<T> {
             ansi::ClearMode::All => {
                 if self.mode.contains(TermMode::ALT_SCREEN) {
                     self.grid.reset_region(..);
+                    self.graphics_cleared(0..u64::MAX);
                 } else {
                     let old_offset = self.grid.display_offset();
 
@@ -1803,7 +1889,9 @@ impl<T: EventListener> Handler for Term<T> {
                 self.selection = None;
             },
             ansi::ClearMode::Saved if self.history_size() > 0 => {
+                let history_end = self.grid.scrolled_lines();
                 self.grid.clear_history();
+                self.graphics_cleared(0..history_end);
 
                 self.vi_mode_cursor.point.line =
                     self.vi_mode_cursor.point.line.grid_clamp(self, Boundary::Cursor);
@@ -1836,6 +1924,9 @@ impl<T: EventListener> Handler for Term<T> {
         if self.mode.contains(TermMode::ALT_SCREEN) {
             mem::swap(&mut self.grid, &mut self.inactive_grid);
         }
+        for alt_screen in [false, true] {
+            self.event_proxy.send_event(Event::GraphicsCleared { alt_screen, lines: 0..u64::MAX });
+        }
         self.active_charset = Default::default();
         self.cursor_style = None;
         self.grid.reset();
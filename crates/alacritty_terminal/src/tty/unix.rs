@@ -28,10 +28,10 @@ use crate::event::{OnResize, WindowSize};
 use crate::tty::{ChildEvent, EventedPty, EventedReadWrite, Options};
 
 // Interest in PTY read/writes.
-pub(crate) const PTY_READ_WRITE_TOKEN: usize = 0;
+pub const PTY_READ_WRITE_TOKEN: usize = 0;
 
 // Interest in new child events.
-pub(crate) const PTY_CHILD_EVENT_TOKEN: usize = 1;
+pub const PTY_CHILD_EVENT_TOKEN: usize = 1;
 
 macro_rules! die {
     ($($arg:tt)*) => {{
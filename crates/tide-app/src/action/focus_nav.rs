@@ -158,4 +158,33 @@ impl App {
         self.cache.invalidate_pane(pane_id);
     }
 
+    /// Scroll the focused terminal to the previous/next shell prompt
+    /// (OSC 133 marks from shell integration).
+    pub(super) fn jump_to_prompt(&mut self, forward: bool) {
+        let Some(pane_id) = self.focused else { return };
+        if let Some(PaneKind::Terminal(tp)) = self.panes.get_mut(&pane_id) {
+            if tp.backend.scroll_to_prompt(forward) {
+                self.cache.invalidate_pane(pane_id);
+            }
+        }
+    }
+
+    /// Select the focused terminal's last command output (scrolling it into
+    /// view), or copy all of it to the clipboard when `copy` is set.
+    pub(super) fn last_command_output(&mut self, copy: bool) {
+        let Some(pane_id) = self.focused else { return };
+        let Some(PaneKind::Terminal(tp)) = self.panes.get_mut(&pane_id) else { return };
+        if copy {
+            // Copy straight from the backend, leaving the selection alone.
+            if let Some(text) = tp.backend.last_command_output().filter(|t| !t.is_empty()) {
+                if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                    let _ = clipboard.set_text(&text);
+                }
+            }
+            return;
+        }
+        if tp.backend.reveal_last_output() {
+            self.cache.invalidate_pane(pane_id);
+        }
+    }
 }
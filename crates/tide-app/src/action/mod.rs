@@ -1,6 +1,6 @@
 mod pane_lifecycle;
 mod focus_nav;
-mod text_extract;
+pub(crate) mod text_extract;
 mod file_ops;
 
 pub(crate) use pane_lifecycle::LauncherChoice;
@@ -10,6 +10,7 @@ use std::time::Instant;
 use tide_core::{InputEvent, LayoutEngine, Size, SplitDirection, TerminalBackend, Vec2};
 use tide_editor::input::EditorAction;
 use tide_input::{Action, AreaSlot, GlobalAction};
+use tide_terminal::MouseReport;
 use crate::search::SearchState;
 
 use crate::pane::PaneKind;
@@ -18,7 +19,7 @@ use crate::ui_state::FocusArea;
 use crate::App;
 
 impl App {
-    fn cleanup_closed_pane_state(&mut self, pane_id: tide_core::PaneId) {
+    pub(crate) fn cleanup_closed_pane_state(&mut self, pane_id: tide_core::PaneId) {
         self.cache.invalidate_pane(pane_id);
         self.interaction.scroll_accumulator.remove(&pane_id);
         self.ime.pending_removes.push(pane_id);
@@ -173,8 +174,12 @@ impl App {
                     let mods = self.modifiers;
                     if mods.ctrl || mods.meta {
                         // Try URL first — open in embedded browser panel
+                        // (file:// hyperlinks open in an editor instead)
                         if let Some(url) = self.extract_url_at(id, position) {
-                            self.open_browser_pane(Some(url));
+                            match text_extract::file_uri_to_path(&url) {
+                                Some(path) if !path.is_dir() => self.open_editor_pane_at_line(path, None),
+                                _ => self.open_browser_pane(Some(url)),
+                            }
                             return;
                         }
                         if let Some((path, line)) = self.extract_file_path_at(id, position) {
@@ -220,8 +225,9 @@ impl App {
                                 // Dead terminal: any key respawns a new shell
                                 self.respawn_terminal(id);
                             } else {
-                                pane.selection = None; // Clear selection on key input
+                                pane.backend.clear_selection(); // Clear selection on key input
                                 pane.handle_key(&key, &modifiers);
+                                self.broadcast_key(id, &key, &modifiers);
                                 self.input_just_sent = true;
                                 self.input_sent_at = Some(Instant::now());
                             }
@@ -318,9 +324,17 @@ impl App {
                             }
                         }
                         Some(PaneKind::Diff(_)) => {} // Diff pane has no keyboard input
+                        Some(PaneKind::Replay(rp)) => {
+                            let handled = rp.handle_key(&key);
+                            if handled {
+                                self.cache.invalidate_pane(id);
+                                self.cache.invalidate_chrome();
+                            }
+                        }
                         Some(PaneKind::Browser(_)) => {} // Browser keyboard handled by webview / URL bar
                         Some(PaneKind::Launcher(_)) => {
-                            // Launcher key handling: T/E/O/B to select pane type, Escape to close
+                            // Launcher key handling: T/E/O/B to select pane type, 1-9 for
+                            // a settings profile, Escape to close
                             let choice = match key {
                                 tide_core::Key::Char('t') | tide_core::Key::Char('T') => {
                                     Some(crate::action::pane_lifecycle::LauncherChoice::Terminal)
@@ -334,6 +348,9 @@ impl App {
                                 tide_core::Key::Char('b') | tide_core::Key::Char('B') => {
                                     Some(crate::action::pane_lifecycle::LauncherChoice::Browser)
                                 }
+                                tide_core::Key::Char(ch @ '1'..='9') => {
+                                    crate::action::LauncherChoice::for_digit(ch, self.settings.profiles.len())
+                                }
                                 tide_core::Key::Escape => {
                                     self.close_specific_pane(id);
                                     None
@@ -349,7 +366,9 @@ impl App {
                 }
 
                 // Forward mouse scroll to pane
-                if let Some(InputEvent::MouseScroll { delta, .. }) = event {
+                if let Some(InputEvent::MouseScroll { delta, position }) = event {
+                    let term_cell = self.pixel_to_cell(position, id);
+                    let mods = self.modifiers;
                     // Compute actual visible rows/cols for the pane
                     let content_top = TAB_BAR_HEIGHT;
                     let (visible_rows, visible_cols) = {
@@ -405,9 +424,19 @@ impl App {
                             let lines = acc.trunc() as i32;
                             if lines != 0 {
                                 *acc -= lines as f32;
-                                pane.scroll_display(lines);
-                                pane.backend.process();
-                                self.cache.invalidate_pane(id);
+                                // Mouse-reporting apps get wheel events instead of
+                                // scrollback movement; Shift keeps local scrolling.
+                                let report = if lines > 0 { MouseReport::WheelUp } else { MouseReport::WheelDown };
+                                let reported = match term_cell {
+                                    Some(cell) if !mods.shift => (0..lines.unsigned_abs())
+                                        .all(|_| pane.report_mouse(report, cell, &mods)),
+                                    _ => false,
+                                };
+                                if !reported {
+                                    pane.scroll_display(lines);
+                                    pane.backend.process();
+                                    self.cache.invalidate_pane(id);
+                                }
                             }
                         }
                         Some(PaneKind::Diff(dp)) => {
@@ -418,7 +447,7 @@ impl App {
                             self.cache.invalidate_pane(id);
                         }
                         Some(PaneKind::Browser(_)) => {} // Scroll handled by native WKWebView
-                        Some(PaneKind::Launcher(_)) => {}
+                        Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => {}
                         None => {}
                     }
                 }
@@ -554,31 +583,8 @@ impl App {
                             if let Ok(mut clipboard) = arboard::Clipboard::new() {
                                 if let Ok(text) = clipboard.get_text() {
                                     if !text.is_empty() {
-                                        // Scroll to bottom so pasted text is visible
-                                        if pane.backend.display_offset() > 0 {
-                                            pane.backend.request_scroll_to_bottom();
-                                        }
-                                        let bracketed = pane.backend.is_bracketed_paste_mode();
-                                        let mut data = Vec::new();
-                                        if bracketed {
-                                            data.extend_from_slice(b"\x1b[200~");
-                                            // Sanitize: strip the bracket-close sequence from
-                                            // clipboard text to prevent pastejacking attacks
-                                            // that escape bracketed paste mode.
-                                            let safe = text.replace("\x1b[201~", "");
-                                            data.extend_from_slice(safe.as_bytes());
-                                        } else {
-                                            data.extend_from_slice(text.as_bytes());
-                                        }
-                                        if bracketed {
-                                            data.extend_from_slice(b"\x1b[201~");
-                                            // Nudge shell to redraw and clear paste standout
-                                            // (left + right arrow = net-zero cursor move that
-                                            // triggers zsh/bash/fish to re-render without
-                                            // the INVERSE highlight on pasted text).
-                                            data.extend_from_slice(b"\x1b[D\x1b[C");
-                                        }
-                                        pane.backend.write(&data);
+                                        pane.paste(&text);
+                                        self.broadcast_paste(target_id, &text);
                                         self.input_just_sent = true;
                                         self.input_sent_at = Some(Instant::now());
                                     }
@@ -617,12 +623,9 @@ impl App {
                 if let Some(target_id) = self.action_target_id() {
                     match self.panes.get(&target_id) {
                         Some(PaneKind::Terminal(pane)) => {
-                            if let Some(ref sel) = pane.selection {
-                                let text = pane.selected_text(sel);
-                                if !text.is_empty() {
-                                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                                        let _ = clipboard.set_text(&text);
-                                    }
+                            if let Some(text) = pane.backend.selection_text() {
+                                if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                                    let _ = clipboard.set_text(&text);
                                 }
                             }
                         }
@@ -715,6 +718,9 @@ impl App {
                         crate::pane::PaneKind::Editor(ep) => {
                             ep.editor.set_dark_mode(dark);
                         }
+                        crate::pane::PaneKind::Replay(rp) => {
+                            rp.player.set_dark_mode(dark);
+                        }
                         crate::pane::PaneKind::Diff(_) => {}
                         crate::pane::PaneKind::Browser(_) => {}
                         crate::pane::PaneKind::Launcher(_) => {}
@@ -730,6 +736,20 @@ impl App {
             GlobalAction::ScrollHalfPageDown => {
                 self.scroll_half_page(tide_input::Direction::Down);
             }
+            GlobalAction::PrevPrompt => self.jump_to_prompt(false),
+            GlobalAction::NextPrompt => self.jump_to_prompt(true),
+            GlobalAction::SelectLastOutput => self.last_command_output(false),
+            GlobalAction::CopyLastOutput => self.last_command_output(true),
+            GlobalAction::ShowProblems => self.toggle_problems_panel(),
+            GlobalAction::NextError => self.step_problem(true),
+            GlobalAction::PrevError => self.step_problem(false),
+            GlobalAction::CopyMode => self.toggle_copy_mode(),
+            GlobalAction::BroadcastTabGroup => self.toggle_broadcast_tab_group(),
+            GlobalAction::BroadcastWorkspace => self.toggle_broadcast_workspace(),
+            GlobalAction::BroadcastPane => self.toggle_broadcast_pane(),
+            GlobalAction::PurgeSavedScrollback => crate::session::purge_saved_scrollback(),
+            GlobalAction::ToggleRecording => self.toggle_recording(),
+            GlobalAction::OpenProfile(n) => self.open_profile_tab(n),
         }
     }
 
@@ -6,10 +6,22 @@ use crate::browser_pane::BrowserPane;
 use crate::drag_drop::PaneDragState;
 use crate::editor_pane::EditorPane;
 use crate::pane::{PaneKind, TerminalPane};
+use crate::replay_pane::ReplayPane;
 use crate::App;
 
 impl App {
     pub(crate) fn create_terminal_pane(&mut self, id: tide_core::PaneId, cwd: Option<std::path::PathBuf>) {
+        self.create_terminal_pane_with_profile(id, cwd, None);
+    }
+
+    /// Like `create_terminal_pane`, starting the named settings profile.
+    /// An unknown profile name falls back to the login shell.
+    pub(crate) fn create_terminal_pane_with_profile(
+        &mut self,
+        id: tide_core::PaneId,
+        cwd: Option<std::path::PathBuf>,
+        profile: Option<&str>,
+    ) {
         let cell_size = self.cell_size();
         if cell_size.width <= 0.0 || cell_size.height <= 0.0 {
             log::error!("Cannot create terminal pane: cell_size is zero ({:?})", cell_size);
@@ -19,7 +31,7 @@ impl App {
         let cols = ((logical.width / 2.0 / cell_size.width).max(1.0).min(1000.0)) as u16;
         let rows = ((logical.height / cell_size.height).max(1.0).min(500.0)) as u16;
 
-        match TerminalPane::with_cwd(id, cols, rows, cwd, self.dark_mode) {
+        match self.spawn_terminal_pane(id, cols, rows, cwd, profile, None) {
             Ok(pane) => {
                 self.install_pty_waker(&pane);
                 self.panes.insert(id, PaneKind::Terminal(pane));
@@ -31,17 +43,60 @@ impl App {
         }
     }
 
+    /// Spawn a terminal pane running `profile` (a settings profile name) or
+    /// the login shell. `restored_output` is replayed above the first prompt.
+    pub(crate) fn spawn_terminal_pane(
+        &self,
+        id: tide_core::PaneId,
+        cols: u16,
+        rows: u16,
+        cwd: Option<PathBuf>,
+        profile: Option<&str>,
+        restored_output: Option<&[u8]>,
+    ) -> Result<TerminalPane, Box<dyn std::error::Error>> {
+        let profile = profile.and_then(|name| {
+            let found = self.settings.profile(name);
+            if found.is_none() {
+                log::warn!("Unknown terminal profile {:?}, starting the login shell", name);
+            }
+            found
+        });
+        let (cwd, command) = match profile {
+            Some(p) => (p.initial_cwd().or(cwd), p.shell_command()),
+            None => (cwd, tide_terminal::ShellCommand::default()),
+        };
+        let backend =
+            tide_terminal::Terminal::with_command(cols, rows, cwd, self.dark_mode, &command, restored_output)?;
+        let mut pane = TerminalPane::with_terminal(id, backend);
+        self.apply_terminal_settings(&mut pane, profile);
+        Ok(pane)
+    }
+
+    /// Apply color schemes, word separators, triggers and the profile's
+    /// scrollback to a new terminal pane.
+    pub(crate) fn apply_terminal_settings(&self, pane: &mut TerminalPane, profile: Option<&crate::settings::TerminalProfile>) {
+        pane.backend.set_color_schemes(self.settings.color_schemes_for(profile));
+        pane.backend.set_word_separators(&self.settings.word_separators);
+        if !self.settings.triggers.is_empty() {
+            pane.backend.set_triggers(self.settings.compiled_triggers());
+        }
+        if let Some(profile) = profile {
+            pane.scrollback_lines = profile.scrollback_lines;
+            pane.profile = Some(profile.name.clone());
+        }
+    }
+
     /// Respawn a new shell in a dead terminal pane, preserving its position in the layout.
     pub(crate) fn respawn_terminal(&mut self, id: tide_core::PaneId) {
-        // Get the CWD of the dead terminal before removing it
-        let cwd = if let Some(PaneKind::Terminal(pane)) = self.panes.get(&id) {
-            pane.context.cwd.clone().or_else(|| pane.backend.detect_cwd_fallback())
+        // Get the CWD and profile of the dead terminal before removing it
+        let (cwd, profile) = if let Some(PaneKind::Terminal(pane)) = self.panes.get(&id) {
+            (pane.context.cwd.clone().or_else(|| pane.backend.detect_cwd_fallback()), pane.profile.clone())
         } else {
-            None
+            (None, None)
         };
         // Remove old terminal and create a new one in-place
         self.panes.remove(&id);
-        self.create_terminal_pane(id, cwd);
+        self.create_terminal_pane_with_profile(id, cwd, profile.as_deref());
         // Clear IME composition if the recreated pane was the target.
         if self.ime.last_target == Some(id) {
             self.ime.clear_composition();
@@ -140,11 +195,25 @@ impl App {
         self.compute_layout();
     }
 
+    /// Open a terminal tab with the Nth (1-based) settings profile.
+    pub(crate) fn open_profile_tab(&mut self, n: u8) {
+        let Some(index) = (n as usize).checked_sub(1).filter(|&i| i < self.settings.profiles.len()) else {
+            log::warn!("OpenProfile{}: settings define only {} profiles", n, self.settings.profiles.len());
+            return;
+        };
+        self.new_terminal_tab();
+        if let Some(id) = self.focused {
+            if matches!(self.panes.get(&id), Some(PaneKind::Launcher(_))) {
+                self.resolve_launcher(id, LauncherChoice::Profile(index));
+            }
+        }
+    }
+
     /// Replace a Launcher pane with the chosen pane type.
     pub(crate) fn resolve_launcher(&mut self, launcher_id: tide_core::PaneId, choice: LauncherChoice) {
         let context_terminal = self.resolve_context_terminal_id();
         match choice {
-            LauncherChoice::Terminal => {
+            LauncherChoice::Terminal | LauncherChoice::Profile(_) => {
                 // Remove the old launcher's IME proxy before creating the replacement.
                 // The new pane reuses the same PaneId, so without this the platform's
                 // ime_proxies map still holds the stale launcher proxy and
@@ -155,7 +224,11 @@ impl App {
                 // Terminal resolves don't get association (they ARE terminals)
                 self.associated_terminal.remove(&launcher_id);
                 self.panes.remove(&launcher_id);
-                self.create_terminal_pane(launcher_id, cwd);
+                let profile = match choice {
+                    LauncherChoice::Profile(index) => self.settings.profiles.get(index).map(|p| p.name.clone()),
+                    _ => None,
+                };
+                self.create_terminal_pane_with_profile(launcher_id, cwd, profile.as_deref());
             }
             LauncherChoice::NewFile => {
                 self.ime.pending_removes.push(launcher_id);
@@ -198,6 +271,10 @@ impl App {
             Some(id) => id,
             None => return,
         };
+        // tmux panes are split by tmux; the new pane arrives with its layout
+        if self.split_tmux_pane(focused, direction) {
+            return;
+        }
         let context_terminal = self.resolve_context_terminal_id();
         if self.zoomed_pane.is_some() {
             self.zoomed_pane = None;
@@ -245,6 +322,21 @@ impl App {
     /// Replace an existing pane (e.g. a Launcher) with an editor for the given file.
     /// The editor reuses the same layout slot (PaneId stays in the same TabGroup position).
     pub(crate) fn replace_pane_with_editor(&mut self, pane_id: tide_core::PaneId, path: PathBuf) {
+        if ReplayPane::is_cast_file(&path) {
+            match ReplayPane::open(path.clone(), self.dark_mode) {
+                Ok(pane) => {
+                    self.panes.insert(pane_id, PaneKind::Replay(pane));
+                    self.focused = Some(pane_id);
+                    self.router.set_focused(pane_id);
+                    self.focus_area = crate::ui_state::FocusArea::PaneArea;
+                    self.cache.invalidate_chrome();
+                    self.cache.pane_generations.clear();
+                    self.compute_layout();
+                }
+                Err(e) => log::error!("Failed to open recording {:?}: {}", path, e),
+            }
+            return;
+        }
         // Check if already open anywhere -> activate & focus (and close the launcher)
         for (&id, pane) in &self.panes {
             if let PaneKind::Editor(editor) = pane {
@@ -300,6 +392,10 @@ impl App {
     /// If focused is non-terminal → add as tab in the same group.
     /// If already open, activate its tab.
     pub(crate) fn open_editor_pane(&mut self, path: PathBuf) {
+        if ReplayPane::is_cast_file(&path) {
+            self.open_replay_pane(path);
+            return;
+        }
         let focused = match self.focused {
             Some(id) => id,
             None => return,
@@ -348,16 +444,70 @@ impl App {
         }
     }
 
+    /// Play an asciicast recording in a replay pane, placed like an editor tab.
+    /// Each open starts a fresh playback, even if the file is already open.
+    pub(crate) fn open_replay_pane(&mut self, path: PathBuf) {
+        let focused = match self.focused {
+            Some(id) => id,
+            None => return,
+        };
+        let new_id = self.layout.alloc_id();
+        match ReplayPane::open(path.clone(), self.dark_mode) {
+            Ok(pane) => {
+                self.panes.insert(new_id, PaneKind::Replay(pane));
+                self.add_to_non_terminal_group(focused, new_id);
+                self.layout.set_active_tab(new_id);
+                self.focused = Some(new_id);
+                self.router.set_focused(new_id);
+                self.focus_area = crate::ui_state::FocusArea::PaneArea;
+                self.cache.invalidate_chrome();
+                self.compute_layout();
+            }
+            Err(e) => {
+                log::error!("Failed to open recording {:?}: {}", path, e);
+            }
+        }
+    }
+
+    /// Start recording the focused terminal to `<config>/tide/recordings/`,
+    /// or stop the recording in progress and play it back in a replay pane.
+    pub(crate) fn toggle_recording(&mut self) {
+        let Some(pane_id) = self.focused else { return };
+        let Some(PaneKind::Terminal(tp)) = self.panes.get_mut(&pane_id) else { return };
+        self.cache.invalidate_chrome();
+        if tp.backend.is_recording() {
+            if let Some(path) = tp.backend.stop_recording() {
+                log::info!("Saved recording to {:?}", path);
+                self.open_replay_pane(path);
+            }
+            return;
+        }
+        let Some(config_dir) = dirs::config_dir() else { return };
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = config_dir.join("tide").join("recordings").join(format!("tide-{}.cast", stamp));
+        if let Err(e) = tp.backend.start_recording(&path) {
+            log::error!("Failed to start recording to {:?}: {}", path, e);
+        }
+    }
+
     /// Open a file in the editor and jump to a specific line.
     pub(crate) fn open_editor_pane_at_line(&mut self, path: PathBuf, line: Option<usize>) {
+        self.open_editor_pane_at(path, line, None);
+    }
+
+    /// Open a file in the editor and jump to a line and (1-based) column.
+    pub(crate) fn open_editor_pane_at(&mut self, path: PathBuf, line: Option<usize>, column: Option<usize>) {
         self.open_editor_pane(path);
         if let Some(line) = line {
             if let Some(active_id) = self.focused {
                 let visible_rows = self.visible_editor_size(active_id).0;
                 if let Some(PaneKind::Editor(pane)) = self.panes.get_mut(&active_id) {
                     let target_line = line.saturating_sub(1); // 1-based to 0-based
+                    let target_col = column.unwrap_or(1).saturating_sub(1);
                     pane.handle_action(
-                        tide_editor::input::EditorAction::SetCursor { line: target_line, col: 0 },
+                        tide_editor::input::EditorAction::SetCursor { line: target_line, col: target_col },
                         visible_rows,
                     );
                     pane.editor.ensure_cursor_visible(visible_rows.max(30));
@@ -409,13 +559,13 @@ impl App {
             self.modal.save_confirm = None;
         }
         // Unwatch the file before removing the pane
-        let watch_path = if let Some(PaneKind::Editor(editor)) = self.panes.get(&tab_id) {
-            editor.editor.file_path().map(|p| p.to_path_buf())
+        let (watch_path, unsaved) = if let Some(PaneKind::Editor(editor)) = self.panes.get(&tab_id) {
+            (editor.editor.file_path().map(|p| p.to_path_buf()), editor.editor.is_modified())
         } else {
-            None
+            (None, false)
         };
-        if let Some(path) = watch_path {
-            self.unwatch_file(&path);
+        if let Some(path) = &watch_path {
+            self.unwatch_file(path);
         }
 
         // Determine next focus target BEFORE removal so we can find the
@@ -470,6 +620,11 @@ impl App {
             self.focus_area = crate::ui_state::FocusArea::PaneArea;
         }
 
+        // Release `tide open --wait` clients (e.g. `git commit`) waiting on this file
+        if let Some(path) = &watch_path {
+            self.finish_ipc_waits(path, !unsaved);
+        }
+
         // Check if layout is now empty
         if self.layout.pane_ids().is_empty() {
             // If other workspaces exist, close this one instead of exiting
@@ -477,8 +632,8 @@ impl App {
                 self.close_workspace();
                 return;
             }
-            let session = crate::session::Session::from_app(self);
-            crate::session::save_session(&session);
+            self.save_session_on_exit();
+            self.stop_ipc_server();
             crate::session::delete_running_marker();
             std::process::exit(0);
         }
@@ -532,12 +687,17 @@ impl App {
         }
 
         // Browser panes and clean editors close immediately
-        if matches!(self.panes.get(&pane_id), Some(PaneKind::Editor(_) | PaneKind::Browser(_) | PaneKind::Diff(_))) {
+        if matches!(self.panes.get(&pane_id), Some(PaneKind::Editor(_) | PaneKind::Browser(_) | PaneKind::Diff(_) | PaneKind::Replay(_))) {
             self.force_close_editor_panel_tab(pane_id);
             self.update_file_tree_cwd();
             return;
         }
 
+        // tmux pane: tmux closes it, and it leaves the layout with the next window list
+        if self.kill_tmux_pane(pane_id) {
+            return;
+        }
+
         // Terminal pane: proceed to force close (with branch cleanup check)
         self.force_close_specific_pane(pane_id);
     }
@@ -623,8 +783,8 @@ impl App {
                 self.close_workspace();
                 return;
             }
-            let session = crate::session::Session::from_app(self);
-            crate::session::save_session(&session);
+            self.save_session_on_exit();
+            self.stop_ipc_server();
             std::process::exit(0);
         }
 
@@ -819,4 +979,14 @@ pub(crate) enum LauncherChoice {
     NewFile,
     OpenFile,
     Browser,
+    /// Terminal from `TideSettings::profiles[index]` (keys 1-9).
+    Profile(usize),
+}
+
+impl LauncherChoice {
+    /// Profile choice for a digit key 1-9, if that many profiles exist.
+    pub(crate) fn for_digit(ch: char, profile_count: usize) -> Option<Self> {
+        let index = (ch.to_digit(10)? as usize).checked_sub(1)?;
+        (index < profile_count).then_some(LauncherChoice::Profile(index))
+    }
 }
@@ -8,7 +8,8 @@ use crate::App;
 
 impl App {
     /// Try to extract a URL from the terminal grid at the given click position.
-    /// Checks if the click is within a detected URL range and extracts the URL string.
+    /// An OSC 8 hyperlink on the clicked cell wins; otherwise checks if the click
+    /// is within a detected URL range and extracts the URL string.
     pub(crate) fn extract_url_at(&self, pane_id: tide_core::PaneId, position: Vec2) -> Option<String> {
         let pane = match self.panes.get(&pane_id) {
             Some(PaneKind::Terminal(p)) => p,
@@ -33,6 +34,10 @@ impl App {
         let col = ((position.x - inner_x - extra_x) / cell_size.width) as usize;
         let row = ((position.y - inner_y) / cell_size.height) as usize;
 
+        if let Some(uri) = pane.backend.grid().link_at(row, col) {
+            return Some(uri.to_string());
+        }
+
         let url_ranges = pane.backend.url_ranges();
         if row >= url_ranges.len() {
             return None;
@@ -223,3 +228,46 @@ impl App {
         None
     }
 }
+
+/// Convert a `file://` URI (as emitted by `ls --hyperlink`, gcc, delta) to a local path.
+/// The host part is ignored; percent-escapes are decoded.
+pub(crate) fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(b) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                decoded.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_uri_with_host_and_escapes() {
+        assert_eq!(
+            file_uri_to_path("file://my-mac.local/Users/me/My%20Project/src/main.rs"),
+            Some(PathBuf::from("/Users/me/My Project/src/main.rs"))
+        );
+        assert_eq!(file_uri_to_path("file:///tmp/a.rs"), Some(PathBuf::from("/tmp/a.rs")));
+    }
+
+    #[test]
+    fn non_file_uri_is_not_a_path() {
+        assert_eq!(file_uri_to_path("https://example.com/a.rs"), None);
+        assert_eq!(file_uri_to_path("file://host-only"), None);
+    }
+}
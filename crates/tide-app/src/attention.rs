@@ -0,0 +1,85 @@
+// Bell and attention: terminals that ring the bell or send a desktop
+// notification (OSC 9/777) while not focused get an attention badge on their
+// tab, their tab group and their workspace sidebar entry, until focused. The
+// bell itself follows `TideSettings::bell`: a brief flash over the pane, the
+// system alert sound, or nothing.
+
+use std::time::{Duration, Instant};
+
+use tide_core::PaneId;
+
+use crate::pane::PaneKind;
+use crate::settings::BellMode;
+use crate::App;
+
+/// How long the visual bell flash stays on screen.
+const VISUAL_BELL_DURATION: Duration = Duration::from_millis(150);
+
+impl App {
+    /// Pick up bells and notifications from every terminal (all workspaces),
+    /// raise or clear attention, and ring the bell.
+    pub(crate) fn poll_attention(&mut self) {
+        let focused = self.focused.filter(|_| self.window_focused);
+        let mut rang: Vec<PaneId> = Vec::new();
+        let mut changed = false;
+        let active = self.panes.iter_mut().map(|(id, pane)| (true, id, pane));
+        let background = self.ws.workspaces.iter_mut()
+            .flat_map(|ws| ws.panes.iter_mut())
+            .map(|(id, pane)| (false, id, pane));
+        for (in_active_ws, &id, pane) in active.chain(background) {
+            let PaneKind::Terminal(t) = pane else { continue };
+            let ctx = &mut t.context;
+            let had_attention = ctx.attention;
+            let is_focused = in_active_ws && focused == Some(id);
+            if ctx.sync_attention(t.backend.bell_count(), t.backend.notification_count(), is_focused) {
+                rang.push(id);
+            }
+            changed |= ctx.attention != had_attention;
+        }
+        if changed {
+            self.cache.invalidate_chrome();
+        }
+
+        if !rang.is_empty() {
+            match self.settings.bell {
+                BellMode::Visual => {
+                    let until = Instant::now() + VISUAL_BELL_DURATION;
+                    for id in rang {
+                        if self.visual_pane_rects.iter().any(|&(vid, _)| vid == id) {
+                            self.visual_bells.insert(id, until);
+                            self.cache.needs_redraw = true;
+                        }
+                    }
+                }
+                BellMode::Sound => self.bell_pending = true,
+                BellMode::None => {}
+            }
+        }
+
+        // Redraw once a flash ends
+        let now = Instant::now();
+        let before = self.visual_bells.len();
+        self.visual_bells.retain(|_, until| *until > now);
+        if self.visual_bells.len() != before {
+            self.cache.needs_redraw = true;
+        }
+    }
+
+    /// When the next visual bell flash ends, if any is showing.
+    pub(crate) fn next_visual_bell_end(&self) -> Option<Instant> {
+        self.visual_bells.values().min().copied()
+    }
+
+    /// Whether any terminal in workspace `idx` needs attention.
+    pub(crate) fn workspace_needs_attention(&self, idx: usize) -> bool {
+        let panes = if idx == self.ws.active {
+            &self.panes
+        } else {
+            match self.ws.workspaces.get(idx) {
+                Some(ws) => &ws.panes,
+                None => return false,
+            }
+        };
+        panes.values().any(|p| matches!(p, PaneKind::Terminal(t) if t.context.attention))
+    }
+}
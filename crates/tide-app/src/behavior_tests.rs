@@ -3,6 +3,112 @@
 //! Each test name reads as a natural language sentence describing a system behavior.
 //! Organized by feature domain so tests serve as a browsable specification.
 
+/// Fixtures for tests that drive a real shell script through the app.
+#[cfg(test)]
+mod script_fixtures {
+    use std::time::{Duration, Instant};
+
+    use tide_core::{Key, Modifiers, PaneId};
+
+    use crate::pane::{PaneKind, TerminalPane};
+    use crate::settings::TerminalProfile;
+    use crate::ui_state::FocusArea;
+    use crate::App;
+
+    pub(super) fn test_app() -> App {
+        let mut app = App::new();
+        app.cached_cell_size = tide_core::Size::new(8.0, 16.0);
+        app.window_size = (960, 640);
+        app
+    }
+
+    /// An app whose one pane runs `script` under /bin/sh, picked from the
+    /// launcher as a "script" profile. `setup` runs just before the launch,
+    /// to adjust settings or the profile.
+    pub(super) fn app_running(script: &str, setup: impl FnOnce(&mut App)) -> App {
+        let mut app = test_app();
+        let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        app.panes.insert(id, PaneKind::Launcher(id));
+        app.focused = Some(id);
+        app.focus_area = FocusArea::PaneArea;
+        app.settings.profiles = vec![TerminalProfile {
+            name: "script".to_string(),
+            command: Some("/bin/sh".to_string()),
+            args: vec!["-c".to_string(), script.to_string()],
+            ..Default::default()
+        }];
+        setup(&mut app);
+        app.handle_ime_commit("1");
+        app.compute_layout();
+        app
+    }
+
+    /// `app_running`, once `output` is on screen.
+    pub(super) fn app_running_until(script: &str, output: &str) -> App {
+        let mut app = app_running(script, |_| {});
+        wait_for_output(&mut app, output);
+        app
+    }
+
+    /// Call `done` every 10ms until it holds; fail after 10 seconds.
+    pub(super) fn poll_until(app: &mut App, what: &str, mut done: impl FnMut(&mut App) -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !done(app) {
+            assert!(Instant::now() < deadline, "timed out waiting for {}", what);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Wait until the focused terminal shows `output`.
+    pub(super) fn wait_for_output(app: &mut App, output: &str) {
+        poll_until(app, "output from the script", |app| terminal(app).backend.recent_text(50).contains(output));
+    }
+
+    /// Launch a `cat` profile in pane `id` (already in the layout) and focus
+    /// it. It prints "ready", then echoes what it is sent.
+    pub(super) fn cat_terminal(app: &mut App, id: PaneId) {
+        app.settings.profiles = vec![TerminalProfile {
+            name: "cat".to_string(),
+            command: Some("/bin/sh".to_string()),
+            args: vec!["-c".to_string(), "echo ready; exec cat".to_string()],
+            ..Default::default()
+        }];
+        app.panes.insert(id, PaneKind::Launcher(id));
+        app.focused = Some(id);
+        app.focus_area = FocusArea::PaneArea;
+        app.handle_ime_commit("1");
+    }
+
+    /// The last lines of terminal `id`.
+    pub(super) fn pane_text(app: &mut App, id: PaneId) -> String {
+        match app.panes.get_mut(&id) {
+            Some(PaneKind::Terminal(pane)) => pane.backend.recent_text(50),
+            _ => panic!("pane {} is not a terminal", id),
+        }
+    }
+
+    /// Wait until terminal `id` shows `text`.
+    pub(super) fn wait_for_pane_text(app: &mut App, id: PaneId, text: &str) {
+        poll_until(app, &format!("{:?} in pane {}", text, id), |app| pane_text(app, id).contains(text));
+    }
+
+    pub(super) fn terminal(app: &mut App) -> &mut TerminalPane {
+        let id = app.focused.unwrap();
+        match app.panes.get_mut(&id) {
+            Some(PaneKind::Terminal(pane)) => pane,
+            _ => panic!("focused pane is not a terminal"),
+        }
+    }
+
+    pub(super) fn type_keys(app: &mut App, keys: &str) {
+        for ch in keys.chars() {
+            let key = if ch == '\r' { Key::Enter } else { Key::Char(ch) };
+            app.handle_key_down(key, Modifiers::default(), Some(ch.to_string()));
+        }
+    }
+}
+
 #[cfg(test)]
 mod focus_management {
     // Spec: docs/specs/input-routing.md — UC-3: ManageFocus
@@ -884,6 +990,39 @@ mod keyboard_routing {
         app.handle_key_down(Key::Escape, Modifiers::default(), None);
         assert!(app.modal.branch_cleanup.is_none());
     }
+
+    #[test]
+    fn clipboard_prompt_lets_typing_through_and_enter_does_not_allow() {
+        // OSC 52: only a click allows; keys go to the pane underneath
+        let (mut app, id) = app_with_editor();
+        app.modal.clipboard_prompt = Some(crate::ClipboardPromptState {
+            pane_id: id,
+            request: tide_terminal::ClipboardRequest::Store("secret".to_string()),
+            message: "vim wants to set the clipboard".to_string(),
+        });
+        app.handle_key_down(Key::Char('x'), Modifiers::default(), Some("x".to_string()));
+        app.handle_key_down(Key::Enter, Modifiers::default(), None);
+        assert!(app.modal.clipboard_prompt.is_some());
+        assert!(app.toast.is_none());
+        let Some(PaneKind::Editor(pane)) = app.panes.get(&id) else { panic!("the editor pane is gone") };
+        assert!(pane.editor.is_modified(), "typed keys reach the editor");
+    }
+
+    #[test]
+    fn clipboard_prompt_escape_denies_request() {
+        // OSC 52: ESC on the clipboard bar drops the request without touching the clipboard
+        let (mut app, id) = app_with_editor();
+        app.modal.clipboard_prompt = Some(crate::ClipboardPromptState {
+            pane_id: id,
+            request: tide_terminal::ClipboardRequest::Store("secret".to_string()),
+            message: "vim wants to set the clipboard".to_string(),
+        });
+        app.handle_key_down(Key::Char('x'), Modifiers::default(), Some("x".to_string()));
+        assert!(app.modal.clipboard_prompt.is_some());
+        app.handle_key_down(Key::Escape, Modifiers::default(), None);
+        assert!(app.modal.clipboard_prompt.is_none());
+        assert!(app.toast.is_none());
+    }
 }
 
 #[cfg(test)]
@@ -962,6 +1101,54 @@ mod launcher_behavior {
         assert!(app.ime.pending_removes.contains(&id), "old launcher proxy not queued for removal");
         assert!(app.ime.pending_creates.contains(&id), "new editor proxy not queued for creation");
     }
+
+    fn sh_profile() -> crate::settings::TerminalProfile {
+        crate::settings::TerminalProfile {
+            name: "Plain sh".to_string(),
+            command: Some("/bin/sh".to_string()),
+            scrollback_lines: Some(500),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pressing_digit_in_launcher_pane_starts_that_profile() {
+        // UC-1 BR-7: '1'-'9' resolve to a terminal running the Nth profile
+        let (mut app, id) = app_with_launcher();
+        app.settings.profiles = vec![sh_profile()];
+        app.handle_ime_commit("1");
+        match app.panes.get(&id) {
+            Some(PaneKind::Terminal(t)) => {
+                assert_eq!(t.profile.as_deref(), Some("Plain sh"));
+                assert_eq!(t.scrollback_lines, Some(500));
+            }
+            _ => panic!("expected a terminal pane"),
+        }
+    }
+
+    #[test]
+    fn digit_without_matching_profile_is_ignored() {
+        // UC-1 BR-8: A digit past the last profile leaves the Launcher
+        let (mut app, id) = app_with_launcher();
+        app.settings.profiles = vec![sh_profile()];
+        app.handle_ime_commit("2");
+        assert!(matches!(app.panes.get(&id), Some(PaneKind::Launcher(_))));
+    }
+
+    #[test]
+    fn open_profile_action_adds_profile_terminal_tab() {
+        // UC-2 BR-9: OpenProfileN opens the Nth profile as a new tab
+        let (mut app, id) = app_with_launcher();
+        app.settings.profiles = vec![sh_profile()];
+        app.handle_global_action(tide_input::GlobalAction::OpenProfile(1));
+        let new_id = app.focused.unwrap();
+        assert_ne!(new_id, id);
+        assert!(matches!(app.panes.get(&new_id), Some(PaneKind::Terminal(t)) if t.profile.as_deref() == Some("Plain sh")));
+
+        // Unknown slots do nothing
+        app.handle_global_action(tide_input::GlobalAction::OpenProfile(5));
+        assert_eq!(app.focused, Some(new_id));
+    }
 }
 
 #[cfg(test)]
@@ -1048,18 +1235,8 @@ mod workspace_behavior {
         let id2: u64 = 200;
 
         // Push two workspace slots
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
-        app.ws.workspaces.push(Workspace {
-            name: "WS2".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
+        app.ws.workspaces.push(Workspace::new("WS2", SplitLayout::new()));
 
         // Set up WS1 as active
         app.ws.active = 0;
@@ -1142,12 +1319,7 @@ mod workspace_behavior {
     fn closing_only_workspace_in_workspace_manager_is_a_no_op() {
         // UC-2 BR-7: Closing the only Workspace is a no-op
         let mut app = test_app();
-        app.ws.workspaces.push(Workspace {
-            name: "Only".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("Only", SplitLayout::new()));
         app.close_workspace();
         assert_eq!(app.ws.workspaces.len(), 1);
     }
@@ -1320,18 +1492,8 @@ mod ime_behavior {
         let mut app = test_app();
         let id1: u64 = 100;
         let id2: u64 = 200;
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
-        app.ws.workspaces.push(Workspace {
-            name: "WS2".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
+        app.ws.workspaces.push(Workspace::new("WS2", SplitLayout::new()));
         app.ws.active = 0;
         app.panes = HashMap::new();
         app.panes.insert(id1, PaneKind::Editor(EditorPane::new_empty(id1)));
@@ -1718,7 +1880,7 @@ mod session_behavior {
     fn session_preserves_dark_mode_preference() {
         // UC-1 BR-1: Session preserves dark_mode preference
         let session = Session {
-            layout: SessionLayout::Leaf { pane_id: 1, cwd: None },
+            layout: SessionLayout::Leaf { pane_id: 1, cwd: None, profile: None },
             focused_pane_id: Some(1),
             show_file_tree: false,
             file_tree_width: 200.0,
@@ -1738,7 +1900,7 @@ mod session_behavior {
     fn session_preserves_file_tree_visibility() {
         // UC-1 BR-2: Session preserves file tree visibility and width
         let session = Session {
-            layout: SessionLayout::Leaf { pane_id: 1, cwd: None },
+            layout: SessionLayout::Leaf { pane_id: 1, cwd: None, profile: None },
             focused_pane_id: Some(1),
             show_file_tree: true,
             file_tree_width: 300.0,
@@ -2134,3 +2296,716 @@ mod terminal_context {
         assert!(app.retained_contexts.contains_key(&terminal_id));
     }
 }
+
+#[cfg(test)]
+mod trigger_behavior {
+    // Spec: docs/specs/triggers.md — UC-1: RunTrigger
+    use super::script_fixtures::{app_running, poll_until};
+    use crate::pane::PaneKind;
+    use crate::settings::{TriggerKind, TriggerSettings};
+    use crate::App;
+
+    fn trigger(regex: &str, action: TriggerKind, text: &str) -> TriggerSettings {
+        TriggerSettings { regex: regex.to_string(), action, color: None, text: text.to_string() }
+    }
+
+    /// An app running `script` under /bin/sh with `triggers`, in an
+    /// unfocused window (so badges stick).
+    fn app_with_triggers(script: &str, triggers: Vec<TriggerSettings>) -> (App, u64) {
+        let app = app_running(script, |app| {
+            app.window_focused = false;
+            app.settings.triggers = triggers;
+        });
+        let id = app.focused.unwrap();
+        (app, id)
+    }
+
+    /// Poll triggers until the terminal has an attention badge.
+    fn wait_for_attention(app: &mut App, id: u64) {
+        poll_until(app, "an attention badge", |app| {
+            app.poll_triggers();
+            matches!(app.panes.get(&id), Some(PaneKind::Terminal(t)) if t.context.attention)
+        });
+    }
+
+    #[test]
+    fn badge_trigger_raises_attention() {
+        // UC-1 BR-1: A badge trigger raises attention on an unfocused terminal
+        let (mut app, id) = app_with_triggers(
+            "printf 'BUILD %s\\n' FAILED; sleep 5",
+            vec![trigger("BUILD FAILED", TriggerKind::Badge, "")],
+        );
+        wait_for_attention(&mut app, id);
+    }
+
+    #[test]
+    fn reply_trigger_answers_a_prompt() {
+        // UC-1 BR-2: A reply is written to the program, even for a prompt
+        // that hasn't finished its line
+        let (mut app, id) = app_with_triggers(
+            "printf 'name? '; read n; echo \"hello-$n\"; sleep 5",
+            vec![
+                trigger(r"name\?", TriggerKind::Reply, "Ada\r"),
+                trigger("hello-Ada", TriggerKind::Badge, ""),
+            ],
+        );
+        wait_for_attention(&mut app, id);
+    }
+}
+
+#[cfg(test)]
+mod problems_behavior {
+    // Spec: docs/specs/problems.md — UC-1: StepThroughProblems
+    use std::path::PathBuf;
+
+    use tide_input::GlobalAction;
+
+    use super::script_fixtures::{self, poll_until};
+    use crate::pane::PaneKind;
+    use crate::App;
+
+    /// An app running `script` under /bin/sh in `dir`.
+    fn app_running(script: &str, dir: &std::path::Path) -> App {
+        script_fixtures::app_running(script, |app| {
+            app.settings.profiles[0].cwd = Some(dir.display().to_string());
+        })
+    }
+
+    /// Refresh until `count` problems are parsed.
+    fn wait_for_problems(app: &mut App, count: usize) {
+        poll_until(app, &format!("{} problems", count), |app| {
+            app.refresh_problems();
+            app.problems.items.len() == count
+        });
+    }
+
+    /// The focused editor's file and 0-based cursor (line, column).
+    fn focused_editor(app: &App) -> Option<(PathBuf, usize, usize)> {
+        let Some(PaneKind::Editor(pane)) = app.panes.get(&app.focused?) else { return None };
+        let pos = pane.editor.cursor_position();
+        Some((pane.editor.file_path()?.to_path_buf(), pos.line, pos.col))
+    }
+
+    #[test]
+    fn next_and_previous_error_open_each_problem() {
+        // UC-1 BR-1: Next error opens problems in order at their line and
+        // column, wrapping around; previous error goes back
+        let dir = std::env::temp_dir().join(format!("tide-problems-behavior-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.c"), "int a;\nint b;\nint c;\n").unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let main_c = dir.join("main.c");
+
+        let mut app = app_running(
+            "printf 'main.c:3:5: error: boom\\nmain.c:1: warning: meh\\nother.c:1:1: error: gone\\n'; sleep 5",
+            &dir,
+        );
+        // The problem in a missing file is dropped
+        wait_for_problems(&mut app, 2);
+
+        app.handle_global_action(GlobalAction::NextError);
+        assert_eq!(focused_editor(&app), Some((main_c.clone(), 2, 4)));
+        app.handle_global_action(GlobalAction::NextError);
+        assert_eq!(focused_editor(&app), Some((main_c.clone(), 0, 0)));
+        app.handle_global_action(GlobalAction::NextError);
+        assert_eq!(focused_editor(&app), Some((main_c.clone(), 2, 4)), "wraps around");
+        app.handle_global_action(GlobalAction::PrevError);
+        assert_eq!(focused_editor(&app), Some((main_c, 0, 0)));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn problems_panel_opens_the_selected_problem() {
+        // UC-2 BR-1: The panel lists the problems; Enter opens the selected one
+        let dir = std::env::temp_dir().join(format!("tide-problems-panel-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.py"), "a\nb\n").unwrap();
+        let dir = dir.canonicalize().unwrap();
+
+        let mut app = app_running("printf 'lib.py:1: E1\\nlib.py:2: E2\\n'; sleep 5", &dir);
+        wait_for_problems(&mut app, 2);
+
+        app.handle_global_action(GlobalAction::ShowProblems);
+        assert!(app.modal.problems.is_some());
+        app.handle_key_down(tide_core::Key::Down, tide_core::Modifiers::default(), None);
+        app.handle_key_down(tide_core::Key::Enter, tide_core::Modifiers::default(), None);
+        assert!(app.modal.problems.is_none());
+        assert_eq!(focused_editor(&app), Some((dir.join("lib.py"), 1, 0)));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
+
+#[cfg(test)]
+mod copy_mode_behavior {
+    // Spec: docs/specs/copy-mode.md — UC-1: SelectWithViKeys
+    use tide_core::{Key, Modifiers, TerminalBackend};
+    use tide_input::GlobalAction;
+    use tide_terminal::CopyModeView;
+
+    use super::script_fixtures::{app_running_until, poll_until, terminal, type_keys};
+    use crate::App;
+
+    /// The copy mode view once the sync thread publishes one matching `done`.
+    fn wait_for_view(app: &mut App, done: impl Fn(&CopyModeView) -> bool) -> CopyModeView {
+        poll_until(app, "the copy mode view", |app| {
+            let pane = terminal(app);
+            pane.backend.process();
+            pane.backend.copy_mode().is_some_and(&done)
+        });
+        terminal(app).backend.copy_mode().unwrap().clone()
+    }
+
+    #[test]
+    fn search_select_and_escape_out_of_copy_mode() {
+        // UC-1 BR-1, BR-3, BR-5: `?` searches back, `w` `v` `e` select a word,
+        // Esc clears the selection and then leaves copy mode
+        let mut app = app_running_until("printf 'alpha beta\\ngamma delta\\n'; sleep 5", "gamma delta");
+        let id = app.focused.unwrap();
+        app.handle_global_action(GlobalAction::CopyMode);
+        assert!(app.in_copy_mode(id));
+
+        type_keys(&mut app, "?alpha\r");
+        wait_for_view(&mut app, |v| v.cursor == (0, 0));
+        type_keys(&mut app, "wve");
+        wait_for_view(&mut app, |v| v.selection.is_some());
+        assert_eq!(terminal(&mut app).backend.selection_spans(), [(0, 6, 10)]);
+        assert_eq!(terminal(&mut app).backend.copy_mode_yank().as_deref(), Some("beta"));
+
+        app.handle_key_down(Key::Escape, Modifiers::default(), None);
+        wait_for_view(&mut app, |v| v.selection.is_none());
+        assert!(app.in_copy_mode(id), "first Esc only clears the selection");
+        app.handle_key_down(Key::Escape, Modifiers::default(), None);
+        assert!(!app.in_copy_mode(id));
+        assert!(terminal(&mut app).backend.copy_mode().is_none());
+    }
+
+    #[test]
+    fn cmd_shift_c_enters_and_q_leaves() {
+        // UC-1 BR-1, BR-5: the hotkey enters copy mode; typed keys are vi
+        // keys, not shell input, until `q`
+        let mut app = app_running_until("printf 'one\\ntwo\\nthree\\n'; sleep 5", "three");
+        let id = app.focused.unwrap();
+        let meta_shift = Modifiers { meta: true, shift: true, ..Default::default() };
+        app.handle_key_down(Key::Char('c'), meta_shift, None);
+        assert!(app.in_copy_mode(id));
+
+        type_keys(&mut app, "gg2jV");
+        let view = wait_for_view(&mut app, |v| v.selection.is_some());
+        assert_eq!(view.cursor.0, 2);
+        assert_eq!(terminal(&mut app).backend.copy_mode_yank().as_deref(), Some("three\n"));
+
+        type_keys(&mut app, "q");
+        assert!(!app.in_copy_mode(id));
+    }
+}
+
+#[cfg(test)]
+mod selection_behavior {
+    // Spec: docs/specs/selection.md — UC-1: SelectWithMouse
+    use tide_core::{Modifiers, MouseButton, TerminalBackend, Vec2};
+    use tide_platform::WindowProxy;
+
+    use super::script_fixtures::{app_running_until, poll_until, terminal};
+    use crate::theme::{PANE_PADDING, TAB_BAR_HEIGHT};
+    use crate::App;
+
+    fn window() -> WindowProxy {
+        WindowProxy::new(std::sync::mpsc::channel().0, std::sync::Arc::new(|| {}))
+    }
+
+    /// Logical position in the left (or right) half of terminal cell `(row, col)`.
+    fn cell_pos(app: &App, (row, col): (usize, usize), right_half: bool) -> Vec2 {
+        let id = app.focused.unwrap();
+        let (_, rect) = app.visual_pane_rects.iter().find(|(pid, _)| *pid == id).unwrap();
+        let x = rect.x + PANE_PADDING + col as f32 * 8.0 + if right_half { 6.0 } else { 2.0 };
+        Vec2::new(x, rect.y + TAB_BAR_HEIGHT + row as f32 * 16.0 + 8.0)
+    }
+
+    fn click(app: &mut App, pos: Vec2, window: &WindowProxy) {
+        app.handle_cursor_moved_logical(pos, window);
+        app.handle_mouse_down(MouseButton::Left, window);
+        app.handle_mouse_up(MouseButton::Left);
+    }
+
+    /// The selected text and spans once the sync thread publishes `spans`.
+    fn wait_for_spans(app: &mut App, spans: &[(usize, usize, usize)]) -> Option<String> {
+        poll_until(app, &format!("selection spans {:?}", spans), |app| {
+            let pane = terminal(app);
+            pane.backend.process();
+            pane.backend.selection_spans() == spans
+        });
+        terminal(app).backend.selection_text()
+    }
+
+    #[test]
+    fn repeated_clicks_select_word_line_then_smart_match() {
+        // UC-1 BR-2, BR-3, BR-4: double, triple and quadruple clicks
+        let mut app = app_running_until("printf 'open src/main.rs:12:5 now\\nnext\\n'; sleep 5", "next");
+        let window = window();
+        let pos = cell_pos(&app, (0, 9), false);
+
+        click(&mut app, pos, &window);
+        click(&mut app, pos, &window);
+        assert_eq!(wait_for_spans(&mut app, &[(0, 5, 16)]).as_deref(), Some("src/main.rs"));
+        click(&mut app, pos, &window);
+        let cols = terminal(&mut app).backend.grid().cols as usize;
+        let line = wait_for_spans(&mut app, &[(0, 0, cols)]);
+        assert_eq!(line.as_deref(), Some("open src/main.rs:12:5 now\n"));
+        click(&mut app, pos, &window);
+        assert_eq!(wait_for_spans(&mut app, &[(0, 5, 21)]).as_deref(), Some("src/main.rs:12:5"));
+
+        // Typing clears it
+        app.handle_ime_commit("x");
+        assert_eq!(wait_for_spans(&mut app, &[]), None);
+    }
+
+    #[test]
+    fn alt_drag_selects_a_block() {
+        // UC-1 BR-1: Alt-drag selects a rectangle of columns
+        let mut app = app_running_until("printf 'alpha one\\nbravo two\\n'; sleep 5", "bravo two");
+        let window = window();
+        app.modifiers = Modifiers { alt: true, ..Default::default() };
+        app.handle_cursor_moved_logical(cell_pos(&app, (0, 1), false), &window);
+        app.handle_mouse_down(MouseButton::Left, &window);
+        app.handle_cursor_moved_logical(cell_pos(&app, (1, 3), true), &window);
+        app.handle_mouse_up(MouseButton::Left);
+        assert_eq!(wait_for_spans(&mut app, &[(0, 1, 4), (1, 1, 4)]).as_deref(), Some("lph\nrav"));
+    }
+}
+
+#[cfg(test)]
+mod broadcast_behavior {
+    // Spec: docs/specs/broadcast.md — UC-1: BroadcastInput
+    use std::time::Duration;
+
+    use tide_core::{LayoutEngine, PaneId, SplitDirection};
+    use tide_input::GlobalAction;
+
+    use super::script_fixtures::{cat_terminal, pane_text, test_app, wait_for_pane_text};
+    use crate::App;
+
+    /// Three `cat` terminals: `a` and `c` tabs of one group, `b` split beside them.
+    fn three_terminals() -> (App, [PaneId; 3]) {
+        let mut app = test_app();
+        let (mut layout, a) = tide_layout::SplitLayout::with_initial_pane();
+        let b = layout.split(a, SplitDirection::Horizontal);
+        let c = layout.alloc_id();
+        layout.add_tab(a, c);
+        app.layout = layout;
+        for id in [a, b, c] {
+            cat_terminal(&mut app, id);
+        }
+        for id in [a, b, c] {
+            wait_for_pane_text(&mut app, id, "ready");
+        }
+        (app, [a, b, c])
+    }
+
+    fn type_into(app: &mut App, id: PaneId, text: &str) {
+        app.focused = Some(id);
+        app.send_text_to_target(text);
+    }
+
+    #[test]
+    fn tab_group_broadcast_reaches_every_tab_and_nothing_else() {
+        let (mut app, [a, b, c]) = three_terminals();
+        app.focused = Some(a);
+        app.handle_action(tide_input::Action::GlobalAction(GlobalAction::BroadcastTabGroup), None);
+        assert_eq!(app.broadcast_members(), vec![a, c]);
+        assert!(app.toast.as_ref().unwrap().message.contains("2 terminals"));
+
+        type_into(&mut app, a, "to-the-group\r");
+        wait_for_pane_text(&mut app, a, "to-the-group");
+        wait_for_pane_text(&mut app, c, "to-the-group");
+        // Typing into a terminal outside the scope isn't mirrored
+        type_into(&mut app, b, "only-b\r");
+        wait_for_pane_text(&mut app, b, "only-b");
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!pane_text(&mut app, b).contains("to-the-group"));
+        assert!(!pane_text(&mut app, a).contains("only-b"));
+
+        // Same hotkey again stops broadcasting
+        app.focused = Some(c);
+        app.handle_action(tide_input::Action::GlobalAction(GlobalAction::BroadcastTabGroup), None);
+        assert_eq!(app.broadcast, None);
+        assert!(!app.is_broadcasting(a));
+    }
+
+    #[test]
+    fn hand_picked_and_workspace_broadcasts() {
+        let (mut app, [a, b, c]) = three_terminals();
+        for id in [a, b] {
+            app.focused = Some(id);
+            app.toggle_broadcast_pane();
+        }
+        assert_eq!(app.broadcast_members(), vec![a, b]);
+        type_into(&mut app, b, "picked\r");
+        wait_for_pane_text(&mut app, a, "picked");
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!pane_text(&mut app, c).contains("picked"));
+
+        // A workspace broadcast replaces the set; it's kept per workspace
+        app.toggle_broadcast_workspace();
+        assert_eq!(app.broadcast_members().len(), 3);
+        type_into(&mut app, c, "everyone\r");
+        for id in [a, b] {
+            wait_for_pane_text(&mut app, id, "everyone");
+        }
+        app.ws.workspaces.push(crate::workspace::Workspace::new("WS1", tide_layout::SplitLayout::new()));
+        app.save_active_workspace();
+        assert_eq!(app.broadcast, None);
+        app.load_active_workspace();
+        assert_eq!(app.broadcast, Some(crate::broadcast::BroadcastScope::Workspace));
+    }
+}
+
+#[cfg(test)]
+mod tmux_behavior {
+    // Spec: docs/specs/tmux.md — UC-1: AttachTmuxSession, UC-2: SplitAndCloseTmuxPanes
+    use tide_core::PaneId;
+    use tide_input::GlobalAction;
+    use tide_layout::SplitLayout;
+
+    use super::script_fixtures;
+    use crate::pane::PaneKind;
+    use crate::settings::TerminalProfile;
+    use crate::ui_state::FocusArea;
+    use crate::workspace::Workspace;
+    use crate::App;
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.cached_cell_size = tide_core::Size::new(8.0, 16.0);
+        app.window_size = (960, 640);
+        app
+    }
+
+    /// Poll tmux until `done` holds.
+    fn poll_until(app: &mut App, what: &str, done: impl Fn(&App) -> bool) {
+        script_fixtures::poll_until(app, what, |app| {
+            app.poll_tmux();
+            done(app)
+        });
+    }
+
+    fn tmux_panes(app: &App) -> Vec<(PaneId, u32)> {
+        let mut panes: Vec<(PaneId, u32)> = app.panes.iter()
+            .filter_map(|(&id, pane)| match pane {
+                PaneKind::Terminal(t) => t.backend.tmux_pane().map(|(_, n)| (id, n)),
+                _ => None,
+            })
+            .collect();
+        panes.sort();
+        panes
+    }
+
+    #[test]
+    fn tmux_session_becomes_a_workspace_that_splits_and_closes_in_tmux() {
+        if std::process::Command::new("tmux").arg("-V").output().is_err() {
+            eprintln!("tmux not installed, skipping");
+            return;
+        }
+        let socket = format!("tide-behavior-{}", std::process::id());
+        let mut app = test_app();
+        let (layout, gateway) = SplitLayout::with_initial_pane();
+        app.layout = layout;
+        app.ws.workspaces.push(Workspace::new("Workspace 1", SplitLayout::new()));
+        app.focus_area = FocusArea::PaneArea;
+        app.settings.profiles = vec![TerminalProfile {
+            name: "tmux".to_string(),
+            command: Some("tmux".to_string()),
+            args: ["-L", &socket, "-f", "/dev/null", "-CC", "new-session", "-s", "work", "/bin/sh"]
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+            ..Default::default()
+        }];
+        app.panes.insert(gateway, PaneKind::Launcher(gateway));
+        app.focused = Some(gateway);
+        app.handle_ime_commit("1");
+
+        // UC-1: the session opens in a workspace of its own
+        poll_until(&mut app, "the tmux workspace", |app| app.ws.active == 1);
+        assert_eq!(app.ws.workspaces[1].name, "tmux: work");
+        let first = tmux_panes(&app);
+        assert_eq!(first.len(), 1);
+        assert_eq!(app.focused, Some(first[0].0));
+
+        // UC-2: splitting sends split-window; the new pane comes back as a split
+        app.handle_action(tide_input::Action::GlobalAction(GlobalAction::SplitHorizontal), None);
+        poll_until(&mut app, "the split", |app| tmux_panes(app).len() == 2);
+        assert_eq!(app.layout.pane_ids().len(), 2);
+        assert!(matches!(
+            app.layout.snapshot(),
+            Some(tide_layout::LayoutSnapshot::Split { direction: tide_core::SplitDirection::Horizontal, .. })
+        ));
+        assert!(tmux_panes(&app).contains(&first[0]), "the first pane keeps its terminal");
+
+        // Closing a pane kills it in tmux
+        let second = tmux_panes(&app).into_iter().find(|p| *p != first[0]).unwrap();
+        app.close_specific_pane(second.0);
+        poll_until(&mut app, "the close", |app| tmux_panes(app) == first);
+
+        // Closing the workspace detaches; the gateway's workspace is left
+        app.handle_action(tide_input::Action::GlobalAction(GlobalAction::CloseWorkspace), None);
+        assert_eq!(app.ws.workspaces.len(), 1);
+        let detached = |app: &App| match app.panes.get(&gateway) {
+            Some(PaneKind::Terminal(t)) => !t.backend.tmux_gateway().is_active(),
+            _ => false,
+        };
+        poll_until(&mut app, "the detach", detached);
+        let _ = std::process::Command::new("tmux").args(["-L", &socket, "kill-server"]).status();
+    }
+}
+
+#[cfg(test)]
+mod ipc_behavior {
+    // Spec: docs/specs/ipc.md — UC-1: ScriptRunningApp
+    use tide_core::{LayoutEngine, PaneId, SplitDirection};
+    use tide_layout::SplitLayout;
+
+    use super::script_fixtures::{cat_terminal, test_app, wait_for_pane_text};
+    use crate::ipc::Request;
+    use crate::pane::PaneKind;
+    use crate::workspace::Workspace;
+    use crate::App;
+
+    /// A `cat` terminal in the first of two workspaces ("Workspace 1", "Logs").
+    fn app_with_terminal() -> (App, PaneId) {
+        let mut app = test_app();
+        let (layout, id) = SplitLayout::with_initial_pane();
+        app.layout = layout;
+        cat_terminal(&mut app, id);
+        for name in ["Workspace 1", "Logs"] {
+            let (layout, _) = SplitLayout::with_initial_pane();
+            app.ws.workspaces.push(Workspace::new(name, layout));
+        }
+        wait_for_pane_text(&mut app, id, "ready");
+        (app, id)
+    }
+
+    #[test]
+    fn list_describes_workspaces_and_panes() {
+        // UC-1 BR-4: list reports every workspace, numbered from 1, with its panes
+        let (mut app, id) = app_with_terminal();
+        let response = app.handle_ipc_request(Request::List);
+        assert!(response.ok);
+        let data = response.data.unwrap();
+        let workspaces = data["workspaces"].as_array().unwrap();
+        assert_eq!(workspaces.len(), 2);
+        assert_eq!(workspaces[0]["name"], "Workspace 1");
+        assert_eq!(workspaces[0]["active"], true);
+        assert_eq!(workspaces[0]["panes"][0]["id"], id);
+        assert_eq!(workspaces[0]["panes"][0]["kind"], "terminal");
+        assert_eq!(workspaces[0]["panes"][0]["focused"], true);
+        assert_eq!(workspaces[1]["index"], 2);
+        assert_eq!(workspaces[1]["active"], false);
+    }
+
+    #[test]
+    fn workspace_switches_by_number_or_name() {
+        // UC-1 BR-5: workspace takes a 1-based number or a name
+        let (mut app, _) = app_with_terminal();
+        assert!(app.handle_ipc_request(Request::Workspace { target: "Logs".into() }).ok);
+        assert_eq!(app.ws.active, 1);
+        assert!(app.handle_ipc_request(Request::Workspace { target: "1".into() }).ok);
+        assert_eq!(app.ws.active, 0);
+        let response = app.handle_ipc_request(Request::Workspace { target: "3".into() });
+        assert!(!response.ok);
+        assert_eq!(app.ws.active, 0);
+    }
+
+    #[test]
+    fn action_runs_a_global_action_by_key() {
+        // UC-1 BR-6: action invokes any GlobalAction by its action key
+        let (mut app, id) = app_with_terminal();
+        app.layout.split(id, SplitDirection::Horizontal);
+        assert!(app.handle_ipc_request(Request::Action { key: "ToggleZoom".into() }).ok);
+        assert_eq!(app.zoomed_pane, Some(id));
+        let response = app.handle_ipc_request(Request::Action { key: "MakeCoffee".into() });
+        assert_eq!(response.error.as_deref(), Some("unknown action \"MakeCoffee\""));
+    }
+
+    #[test]
+    fn send_types_into_a_terminal() {
+        // UC-1 BR-3: send writes text to the focused terminal or a given pane
+        let (mut app, id) = app_with_terminal();
+        assert!(app.handle_ipc_request(Request::Send { pane: None, text: "from-script\r".into() }).ok);
+        wait_for_pane_text(&mut app, id, "from-script");
+        let response = app.handle_ipc_request(Request::Send { pane: Some(id + 100), text: "x".into() });
+        assert!(!response.ok);
+    }
+
+    #[test]
+    fn open_shows_the_file_at_a_line() {
+        // UC-1 BR-1: open puts the file in an editor pane at the given line
+        let (mut app, _) = app_with_terminal();
+        let path = std::env::temp_dir().join(format!("tide-ipc-open-{}.txt", std::process::id()));
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        let response = app.handle_ipc_request(Request::Open { path: path.clone(), line: Some(3), column: None, terminal: None, wait: false });
+        assert!(response.ok, "{:?}", response.error);
+        let Some(PaneKind::Editor(pane)) = app.panes.get(&app.focused.unwrap()) else { panic!("no editor focused") };
+        assert_eq!(pane.editor.file_path(), Some(path.as_path()));
+        assert_eq!(pane.editor.cursor_position().line, 2);
+        let _ = std::fs::remove_file(&path);
+
+        let response = app.handle_ipc_request(Request::Open { path, line: None, column: None, terminal: None, wait: false });
+        assert!(!response.ok, "a missing file is an error");
+    }
+
+    #[test]
+    fn split_runs_a_command_in_a_new_terminal() {
+        // UC-1 BR-2: split opens a terminal beside the focused pane and types the command
+        let (mut app, id) = app_with_terminal();
+        let response = app.handle_ipc_request(Request::Split {
+            horizontal: false,
+            command: Some("echo tide-$((40+2))".into()),
+        });
+        assert!(response.ok, "{:?}", response.error);
+        let new_id = response.data.unwrap()["pane"].as_u64().unwrap();
+        assert_ne!(new_id, id);
+        assert_eq!(app.focused, Some(new_id));
+        wait_for_pane_text(&mut app, new_id, "tide-42");
+    }
+}
+
+#[cfg(test)]
+mod editor_wait_behavior {
+    // Spec: docs/specs/ipc.md — UC-2: EditInTideTab
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+
+    use tide_core::{LayoutEngine, PaneId, SplitDirection};
+    use tide_layout::SplitLayout;
+
+    use crate::ipc::{IpcCall, Request, Response};
+    use crate::pane::PaneKind;
+    use crate::ui_state::FocusArea;
+    use crate::workspace::Workspace;
+    use crate::App;
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.cached_cell_size = tide_core::Size::new(8.0, 16.0);
+        app.window_size = (960, 640);
+        app
+    }
+
+    /// Two terminals side by side, the second focused, and a file to edit.
+    fn app_with_terminals(name: &str) -> (App, [PaneId; 2], PathBuf) {
+        let mut app = test_app();
+        let (mut layout, a) = SplitLayout::with_initial_pane();
+        let b = layout.split(a, SplitDirection::Horizontal);
+        app.layout = layout;
+        app.focus_area = FocusArea::PaneArea;
+        for id in [a, b] {
+            app.create_terminal_pane(id, None);
+        }
+        app.focused = Some(b);
+        app.ws.workspaces.push(Workspace::new("Workspace 1", SplitLayout::new()));
+        let path = std::env::temp_dir().join(format!("tide-wait-{}-{}.txt", name, std::process::id()));
+        std::fs::write(&path, "message\n").unwrap();
+        (app, [a, b], path)
+    }
+
+    /// Another workspace, so the active one can be closed.
+    fn push_workspace(app: &mut App) {
+        app.ws.workspaces.push(Workspace::new("Workspace 2", SplitLayout::new()));
+    }
+
+    fn terminal_id(app: &App, id: PaneId) -> Option<u64> {
+        match app.panes.get(&id) {
+            Some(PaneKind::Terminal(tp)) => tp.backend.terminal_id(),
+            _ => None,
+        }
+    }
+
+    /// Send `tide-editor`'s request from the terminal `from` and poll it.
+    fn open_and_wait(app: &mut App, from: PaneId, path: &std::path::Path) -> mpsc::Receiver<Response> {
+        let (tx, rx) = mpsc::channel();
+        app.ipc_rx = Some(rx);
+        let (reply, reply_rx) = mpsc::channel();
+        let request = Request::Open {
+            path: path.to_path_buf(),
+            line: None,
+            column: None,
+            terminal: terminal_id(app, from),
+            wait: true,
+        };
+        tx.send(IpcCall { request, reply }).unwrap();
+        app.poll_ipc();
+        reply_rx
+    }
+
+    fn editor_for(app: &App, path: &std::path::Path) -> Option<PaneId> {
+        app.panes.iter().find_map(|(&id, pane)| match pane {
+            PaneKind::Editor(ep) if ep.editor.file_path() == Some(path) => Some(id),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn wait_returns_when_the_tab_is_closed() {
+        // UC-2 BR-1, BR-2, BR-4: the file opens next to the invoking terminal,
+        // the client waits until the tab closes, then that terminal is focused
+        let (mut app, [a, _], path) = app_with_terminals("close");
+        let replies = open_and_wait(&mut app, a, &path);
+        let editor = editor_for(&app, &path).expect("file opened");
+        assert_eq!(app.focused, Some(editor));
+        let group = app.layout.tab_group_containing(editor).unwrap().tabs.clone();
+        assert!(!group.contains(&a), "editor goes in a non-terminal group");
+        assert!(replies.try_recv().is_err(), "still waiting while the tab is open");
+
+        app.close_editor_panel_tab(editor);
+        let response = replies.try_recv().expect("released on close");
+        assert!(response.ok);
+        assert_eq!(app.focused, Some(a));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn saved_and_closed_succeeds_and_discarded_fails() {
+        // UC-2 BR-3: save-and-close exits 0; discarding changes exits non-zero
+        let (mut app, [a, _], path) = app_with_terminals("save");
+        let replies = open_and_wait(&mut app, a, &path);
+        let editor = editor_for(&app, &path).unwrap();
+        app.handle_ime_commit("x");
+        app.close_editor_panel_tab(editor);
+        assert!(app.modal.save_confirm.is_some(), "dirty tab asks first");
+        app.confirm_save_and_close();
+        assert!(replies.try_recv().unwrap().ok);
+        assert!(std::fs::read_to_string(&path).unwrap().contains('x'));
+
+        let replies = open_and_wait(&mut app, a, &path);
+        let editor = editor_for(&app, &path).unwrap();
+        app.handle_ime_commit("y");
+        app.close_editor_panel_tab(editor);
+        app.confirm_discard_and_close();
+        let response = replies.try_recv().unwrap();
+        assert!(!response.ok, "discarded changes fail the editor");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn closing_the_workspace_answers_with_the_saved_state() {
+        // UC-2 BR-3: a tab that goes away with its workspace answers like a
+        // closed tab: saved or unchanged succeeds, unsaved changes fail
+        let (mut app, [a, _], path) = app_with_terminals("workspace");
+        let replies = open_and_wait(&mut app, a, &path);
+        push_workspace(&mut app);
+        app.close_workspace();
+        assert!(replies.try_recv().expect("released with the workspace").ok);
+        let _ = std::fs::remove_file(&path);
+
+        let (mut app, [a, _], path) = app_with_terminals("workspace-dirty");
+        let replies = open_and_wait(&mut app, a, &path);
+        push_workspace(&mut app);
+        app.handle_ime_commit("x");
+        app.close_workspace();
+        assert!(!replies.try_recv().expect("released with the workspace").ok);
+        assert!(app.ipc_waits.is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,147 @@
+// Broadcast input: keystrokes, pastes and IME commits typed into a terminal
+// are mirrored to the other terminals in the broadcast scope — the tab group
+// it was started in, the whole workspace, or a hand-picked set of panes. Each
+// workspace has its own scope. Members are resolved against the live layout
+// whenever input arrives, so terminals opened in a broadcasting tab group or
+// workspace join it and closed ones drop out. Typing into a terminal outside
+// the scope only reaches that terminal.
+
+use std::collections::BTreeSet;
+use std::time::Instant;
+
+use tide_core::{Key, Modifiers, PaneId};
+
+use crate::clipboard::TOAST_DURATION;
+use crate::pane::{PaneKind, TerminalPane};
+use crate::ui_state::Toast;
+use crate::App;
+
+/// Which terminals of the active workspace receive broadcast input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum BroadcastScope {
+    /// The tab group holding this pane.
+    TabGroup(PaneId),
+    /// Every terminal in the workspace.
+    Workspace,
+    /// Hand-picked terminals.
+    Panes(BTreeSet<PaneId>),
+}
+
+impl App {
+    /// Terminals in the broadcast scope, in layout order.
+    pub(crate) fn broadcast_members(&self) -> Vec<PaneId> {
+        let Some(scope) = &self.broadcast else { return Vec::new() };
+        let candidates = match scope {
+            BroadcastScope::TabGroup(anchor) => self.layout.tab_group_containing(*anchor)
+                .map(|tg| tg.tabs.clone())
+                .unwrap_or_default(),
+            BroadcastScope::Workspace => self.layout.pane_ids(),
+            BroadcastScope::Panes(ids) => self.layout.pane_ids().into_iter()
+                .filter(|id| ids.contains(id))
+                .collect(),
+        };
+        candidates.into_iter()
+            .filter(|id| matches!(self.panes.get(id), Some(PaneKind::Terminal(_))))
+            .collect()
+    }
+
+    pub(crate) fn is_broadcasting(&self, id: PaneId) -> bool {
+        self.broadcast_members().contains(&id)
+    }
+
+    /// Terminals that mirror input typed into `source`: the other live
+    /// members, if `source` is one. Panes in copy mode are left alone.
+    fn broadcast_peers(&self, source: PaneId) -> Vec<PaneId> {
+        let members = self.broadcast_members();
+        if !members.contains(&source) {
+            return Vec::new();
+        }
+        members.into_iter()
+            .filter(|&id| id != source)
+            .filter(|id| matches!(
+                self.panes.get(id),
+                Some(PaneKind::Terminal(tp)) if !tp.context.child_dead && tp.copy_mode.is_none()
+            ))
+            .collect()
+    }
+
+    /// Run `send` on every broadcast peer of `source`.
+    fn for_each_peer(&mut self, source: PaneId, mut send: impl FnMut(&mut TerminalPane)) {
+        for id in self.broadcast_peers(source) {
+            if let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&id) {
+                send(pane);
+            }
+        }
+    }
+
+    /// Mirror typed text (keyboard or IME commit) from `source`.
+    pub(crate) fn broadcast_text(&mut self, source: PaneId, text: &str) {
+        self.for_each_peer(source, |pane| {
+            pane.backend.clear_selection();
+            pane.handle_text(text);
+        });
+    }
+
+    /// Mirror a key press from `source`.
+    pub(crate) fn broadcast_key(&mut self, source: PaneId, key: &Key, modifiers: &Modifiers) {
+        self.for_each_peer(source, |pane| {
+            pane.backend.clear_selection();
+            pane.handle_key(key, modifiers);
+        });
+    }
+
+    /// Mirror a key release from `source` (kitty keyboard protocol).
+    pub(crate) fn broadcast_key_release(&mut self, source: PaneId, key: &Key, modifiers: &Modifiers) {
+        self.for_each_peer(source, |pane| pane.handle_key_release(key, modifiers));
+    }
+
+    /// Mirror a paste from `source`.
+    pub(crate) fn broadcast_paste(&mut self, source: PaneId, text: &str) {
+        self.for_each_peer(source, |pane| pane.paste(text));
+    }
+
+    /// Broadcast to the focused tab group, or stop if it already is.
+    pub(crate) fn toggle_broadcast_tab_group(&mut self) {
+        let Some(focused) = self.focused_terminal() else { return };
+        let already = matches!(&self.broadcast, Some(BroadcastScope::TabGroup(_))) && self.is_broadcasting(focused);
+        self.set_broadcast(if already { None } else { Some(BroadcastScope::TabGroup(focused)) }, "tab group");
+    }
+
+    /// Broadcast to the whole workspace, or stop if it already is.
+    pub(crate) fn toggle_broadcast_workspace(&mut self) {
+        let already = self.broadcast == Some(BroadcastScope::Workspace);
+        self.set_broadcast(if already { None } else { Some(BroadcastScope::Workspace) }, "workspace");
+    }
+
+    /// Add the focused terminal to the hand-picked set, or take it out. A
+    /// tab group or workspace broadcast is replaced by a new set.
+    pub(crate) fn toggle_broadcast_pane(&mut self) {
+        let Some(focused) = self.focused_terminal() else { return };
+        let mut ids = match self.broadcast.take() {
+            Some(BroadcastScope::Panes(ids)) => ids,
+            _ => BTreeSet::new(),
+        };
+        if !ids.remove(&focused) {
+            ids.insert(focused);
+        }
+        // Forget picked panes that have since closed
+        ids.retain(|id| self.panes.contains_key(id));
+        self.set_broadcast((!ids.is_empty()).then_some(BroadcastScope::Panes(ids)), "selected panes");
+    }
+
+    fn focused_terminal(&self) -> Option<PaneId> {
+        self.focused.filter(|id| matches!(self.panes.get(id), Some(PaneKind::Terminal(_))))
+    }
+
+    fn set_broadcast(&mut self, scope: Option<BroadcastScope>, what: &str) {
+        self.broadcast = scope;
+        let message = match self.broadcast_members().len() {
+            _ if self.broadcast.is_none() => "Broadcast off".to_string(),
+            1 => format!("Broadcasting to 1 terminal ({})", what),
+            n => format!("Broadcasting to {} terminals ({})", n, what),
+        };
+        self.toast = Some(Toast { message, until: Instant::now() + TOAST_DURATION });
+        self.cache.invalidate_chrome();
+        self.cache.needs_redraw = true;
+    }
+}
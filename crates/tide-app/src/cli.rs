@@ -0,0 +1,332 @@
+// `tide` command line client. The app binary doubles as the client: started
+// with one of the subcommands below (or as `tide`, the link Tide puts on the
+// PATH of its shells), it sends the request to the running app over the
+// scripting socket (ipc.rs) instead of opening a window. Started as
+// `tide-editor` it is `tide open --wait`, for `EDITOR` and `GIT_EDITOR`.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::ipc::{self, Request};
+
+const USAGE: &str = "\
+usage: tide <command> [args]
+
+  open [--wait] [+LINE] FILE[:LINE[:COL]]
+                                    open a file in an editor pane; --wait returns when
+                                    its tab closes, failing if changes were discarded
+  split [--horizontal] [COMMAND [ARG...]]
+                                    split the focused pane into a terminal running COMMAND
+  send [--pane ID] [--enter] [TEXT] type TEXT (or stdin) into a terminal
+  list                              print workspaces and panes as JSON
+  workspace NUMBER|NAME             switch workspace
+  action KEY                        run an action by key (see `tide action --list`)";
+
+const COMMANDS: &[&str] = &["open", "split", "send", "list", "workspace", "action"];
+
+/// Name of the link that runs `tide open --wait`.
+pub(crate) const EDITOR_SHIM: &str = "tide-editor";
+
+/// Run the client if the command line asks for it, returning the exit
+/// status. None means start the app.
+pub(crate) fn run(args: &[String]) -> Option<i32> {
+    let invoked_as = args.first()
+        .and_then(|arg0| Path::new(arg0).file_name())
+        .and_then(|name| name.to_str());
+    if invoked_as == Some(EDITOR_SHIM) {
+        let args: Vec<String> = ["tide", "open", "--wait"].iter().map(|s| s.to_string())
+            .chain(args[1..].iter().cloned())
+            .collect();
+        return run(&args);
+    }
+    let invoked_as_tide = invoked_as == Some("tide");
+    let command = args.get(1).map(String::as_str);
+    match command {
+        Some(cmd) if COMMANDS.contains(&cmd) => {}
+        Some("help" | "--help" | "-h") => {
+            println!("{}", USAGE);
+            return Some(0);
+        }
+        _ if invoked_as_tide => {
+            eprintln!("{}", USAGE);
+            return Some(2);
+        }
+        _ => return None,
+    }
+    let command = command.unwrap_or_default();
+    let rest = &args[2..];
+
+    if command == "action" && rest.first().map(String::as_str) == Some("--list") {
+        for action in tide_input::GlobalAction::all_actions() {
+            println!("{}", action.action_key());
+        }
+        return Some(0);
+    }
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let mut request = match parse(command, rest, &cwd) {
+        Ok(Some(request)) => request,
+        Ok(None) => match read_stdin_text(rest) {
+            Ok(request) => request,
+            Err(e) => {
+                eprintln!("tide: {}", e);
+                return Some(1);
+            }
+        },
+        Err(e) => {
+            eprintln!("tide: {}\n\n{}", e, USAGE);
+            return Some(2);
+        }
+    };
+
+    if let Request::Open { terminal, .. } = &mut request {
+        *terminal = std::env::var(tide_terminal::TERMINAL_ID_ENV).ok().and_then(|id| id.parse().ok());
+    }
+
+    let path = ipc::socket_path();
+    let response = match ipc::request(&path, &request) {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("tide: can't reach Tide at {}: {}", path.display(), e);
+            return Some(1);
+        }
+    };
+    if !response.ok {
+        eprintln!("tide: {}", response.error.unwrap_or_default());
+        return Some(1);
+    }
+    match response.data {
+        Some(data) if matches!(request, Request::List) => {
+            println!("{}", serde_json::to_string_pretty(&data).unwrap_or_default());
+        }
+        Some(data) => {
+            if let Some(pane) = data.get("pane") {
+                println!("{}", pane);
+            }
+        }
+        None => {}
+    }
+    Some(0)
+}
+
+/// Parse a subcommand's arguments. Relative paths resolve against `cwd`.
+/// `Ok(None)` is a `send` without text, which reads it from stdin.
+fn parse(command: &str, args: &[String], cwd: &Path) -> Result<Option<Request>, String> {
+    let request = match command {
+        "open" => {
+            let mut wait = false;
+            let mut plus_line = None;
+            let mut files = Vec::new();
+            for arg in args {
+                match arg.strip_prefix('+').map(str::parse::<usize>) {
+                    // `+LINE FILE`, as vi-style editors take it
+                    Some(Ok(line)) => plus_line = Some(line),
+                    _ if arg == "--wait" => wait = true,
+                    _ => files.push(arg),
+                }
+            }
+            let [target] = files[..] else { return Err("open takes one file".into()) };
+            let (path, line, column) = split_location(target, cwd);
+            Request::Open { path, line: plus_line.or(line), column, terminal: None, wait }
+        }
+        "split" => {
+            let (horizontal, rest) = match args.first().map(String::as_str) {
+                Some("--horizontal") => (true, &args[1..]),
+                Some("--vertical") => (false, &args[1..]),
+                _ => (false, args),
+            };
+            let words: Vec<String> = rest.iter().map(|arg| shell_quote(arg)).collect();
+            let command = (!words.is_empty()).then(|| words.join(" "));
+            Request::Split { horizontal, command }
+        }
+        "send" => {
+            let mut pane = None;
+            let mut enter = false;
+            let mut text = Vec::new();
+            let mut args = args.iter();
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--pane" => {
+                        let id = args.next().ok_or("--pane needs a pane id")?;
+                        pane = Some(id.parse().map_err(|_| format!("bad pane id {:?}", id))?);
+                    }
+                    "--enter" => enter = true,
+                    _ => text.push(arg.as_str()),
+                }
+            }
+            if text.is_empty() {
+                return Ok(None);
+            }
+            let mut text = text.join(" ");
+            if enter {
+                text.push('\r');
+            }
+            Request::Send { pane, text }
+        }
+        "list" => Request::List,
+        "workspace" => {
+            let [target] = args else { return Err("workspace takes a number or name".into()) };
+            Request::Workspace { target: target.clone() }
+        }
+        "action" => {
+            let [key] = args else { return Err("action takes one action key".into()) };
+            Request::Action { key: key.clone() }
+        }
+        _ => return Err(format!("unknown command {:?}", command)),
+    };
+    Ok(Some(request))
+}
+
+/// Quote `arg` for the shell the split command is typed into, so it stays
+/// one argument.
+fn shell_quote(arg: &str) -> String {
+    let plain = |b: u8| b.is_ascii_alphanumeric() || b"-_./=:,+@%".contains(&b);
+    if !arg.is_empty() && arg.bytes().all(plain) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// `send` with the text on stdin.
+fn read_stdin_text(args: &[String]) -> Result<Request, String> {
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text).map_err(|e| e.to_string())?;
+    match parse("send", &[args, &[text]].concat(), Path::new("")) {
+        Ok(Some(request)) => Ok(request),
+        Ok(None) => Err("nothing to send".into()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Split `FILE:LINE:COL` into its parts. A suffix only counts when the whole
+/// string isn't itself an existing file.
+fn split_location(target: &str, cwd: &Path) -> (PathBuf, Option<usize>, Option<usize>) {
+    let resolve = |p: &str| cwd.join(p);
+    if resolve(target).exists() {
+        return (resolve(target), None, None);
+    }
+    let mut parts = target.rsplitn(3, ':');
+    let last = parts.next().and_then(|s| s.parse::<usize>().ok());
+    let middle = parts.next();
+    match (last, middle.map(|s| s.parse::<usize>().ok()), parts.next()) {
+        (Some(col), Some(Some(line)), Some(file)) => (resolve(file), Some(line), Some(col)),
+        (Some(line), Some(_), _) => {
+            let file = target.rsplit_once(':').map_or(target, |(file, _)| file);
+            (resolve(file), Some(line), None)
+        }
+        _ => (resolve(target), None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn open_resolves_relative_paths_and_locations() {
+        let cwd = Path::new("/nonexistent-tide-cli");
+        assert_eq!(
+            parse("open", &args(&["src/main.rs:12"]), cwd),
+            Ok(Some(Request::Open { path: cwd.join("src/main.rs"), line: Some(12), column: None, terminal: None, wait: false })),
+        );
+        assert_eq!(
+            parse("open", &args(&["/tmp/a.rs:3:7"]), cwd),
+            Ok(Some(Request::Open { path: PathBuf::from("/tmp/a.rs"), line: Some(3), column: Some(7), terminal: None, wait: false })),
+        );
+        assert_eq!(
+            parse("open", &args(&["notes.txt"]), cwd),
+            Ok(Some(Request::Open { path: cwd.join("notes.txt"), line: None, column: None, terminal: None, wait: false })),
+        );
+        // As `$EDITOR`: `tide-editor +5 file` waits for the tab
+        assert_eq!(
+            parse("open", &args(&["--wait", "+5", "COMMIT_EDITMSG"]), cwd),
+            Ok(Some(Request::Open { path: cwd.join("COMMIT_EDITMSG"), line: Some(5), column: None, terminal: None, wait: true })),
+        );
+        assert!(parse("open", &[], cwd).is_err());
+    }
+
+    #[test]
+    fn split_joins_the_command() {
+        let cwd = Path::new("/");
+        assert_eq!(
+            parse("split", &args(&["--horizontal", "cargo", "test"]), cwd),
+            Ok(Some(Request::Split { horizontal: true, command: Some("cargo test".into()) })),
+        );
+        // Each argument stays one word in the shell
+        assert_eq!(
+            parse("split", &args(&["grep", "-r", "it's here", "$HOME", ""]), cwd),
+            Ok(Some(Request::Split {
+                horizontal: false,
+                command: Some(r"grep -r 'it'\''s here' '$HOME' ''".into()),
+            })),
+        );
+        assert_eq!(parse("split", &[], cwd), Ok(Some(Request::Split { horizontal: false, command: None })));
+    }
+
+    #[test]
+    fn send_takes_pane_and_enter() {
+        let cwd = Path::new("/");
+        assert_eq!(
+            parse("send", &args(&["--pane", "4", "--enter", "make", "all"]), cwd),
+            Ok(Some(Request::Send { pane: Some(4), text: "make all\r".into() })),
+        );
+        // No text: read it from stdin
+        assert_eq!(parse("send", &args(&["--enter"]), cwd), Ok(None));
+        assert!(parse("send", &args(&["--pane", "x", "hi"]), cwd).is_err());
+    }
+
+    #[test]
+    fn requests_round_trip_as_json_lines() {
+        let request = Request::Action { key: "ToggleZoom".into() };
+        let line = serde_json::to_string(&request).unwrap();
+        assert_eq!(line, r#"{"cmd":"action","key":"ToggleZoom"}"#);
+        assert_eq!(serde_json::from_str::<Request>(&line).unwrap(), request);
+        let list: Request = serde_json::from_str(r#"{"cmd":"list"}"#).unwrap();
+        assert_eq!(list, Request::List);
+    }
+
+    #[test]
+    fn app_binary_starts_the_app_without_a_command() {
+        assert_eq!(run(&args(&["/Applications/Tide.app/Contents/MacOS/Tide"])), None);
+        assert_eq!(run(&args(&["Tide", "-psn_0_1234"])), None);
+    }
+
+    #[test]
+    fn client_talks_to_the_socket() {
+        let dir = std::env::temp_dir().join(format!("tide-ipc-test-{}", std::process::id()));
+        let path = dir.join("tide.sock");
+        let rx = ipc::serve(ipc::bind(&path).unwrap(), None);
+        let client = {
+            let path = path.clone();
+            std::thread::spawn(move || ipc::request(&path, &Request::List).unwrap())
+        };
+        let call = rx.recv().unwrap();
+        assert_eq!(call.request, Request::List);
+        call.reply.send(ipc::Response::ok(Some(serde_json::json!({ "workspaces": [] })))).unwrap();
+        let response = client.join().unwrap();
+        assert!(response.ok);
+        assert_eq!(response.data, Some(serde_json::json!({ "workspaces": [] })));
+
+        // A second app can't take over a live socket
+        assert!(ipc::bind(&path).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn socket_directory_must_be_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("tide-ipc-shared-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(ipc::bind(&dir.join("tide.sock")).is_err());
+
+        // Created fresh, it's 0700
+        let _ = std::fs::remove_dir_all(&dir);
+        let _listener = ipc::bind(&dir.join("tide.sock")).unwrap();
+        assert_eq!(std::fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,124 @@
+// OSC 52 clipboard: programs in a terminal (vim over ssh, tmux, neovim) set or
+// read the system clipboard. Each request is checked against
+// `TideSettings::clipboard_write` / `clipboard_read`: it runs, is dropped, or
+// waits on a bar over the terminal where "Always" allows that kind of request
+// from the terminal for the rest of its life. A write shows a short toast.
+
+use std::time::{Duration, Instant};
+
+use tide_core::PaneId;
+use tide_terminal::ClipboardRequest;
+
+use crate::pane::PaneKind;
+use crate::settings::ClipboardPolicy;
+use crate::ui_state::{ClipboardPromptState, Toast};
+use crate::App;
+
+/// How long the "copied to clipboard" toast stays on screen.
+pub(crate) const TOAST_DURATION: Duration = Duration::from_millis(1500);
+
+impl App {
+    /// Handle OSC 52 requests from the active workspace's terminals and
+    /// expire the toast. Background workspaces keep theirs queued.
+    pub(crate) fn poll_clipboard_requests(&mut self) {
+        let mut requests: Vec<(PaneId, ClipboardRequest)> = Vec::new();
+        for (&id, pane) in &self.panes {
+            if let PaneKind::Terminal(t) = pane {
+                requests.extend(t.backend.take_clipboard_requests().into_iter().map(|r| (id, r)));
+            }
+        }
+        for (pane_id, request) in requests {
+            self.handle_clipboard_request(pane_id, request);
+        }
+
+        if self.toast.as_ref().is_some_and(|t| t.until <= Instant::now()) {
+            self.toast = None;
+            self.cache.needs_redraw = true;
+        }
+    }
+
+    fn handle_clipboard_request(&mut self, pane_id: PaneId, request: ClipboardRequest) {
+        let Some(PaneKind::Terminal(t)) = self.panes.get(&pane_id) else { return };
+        let (policy, granted) = match request {
+            ClipboardRequest::Store(_) => (self.settings.clipboard_write, t.context.clipboard_write_granted),
+            ClipboardRequest::Load(_) => (self.settings.clipboard_read, t.context.clipboard_read_granted),
+        };
+        match policy {
+            ClipboardPolicy::Allow => self.run_clipboard_request(pane_id, request),
+            ClipboardPolicy::Ask if granted => self.run_clipboard_request(pane_id, request),
+            ClipboardPolicy::Ask => {
+                // One question at a time, and only about a terminal on screen;
+                // anything else is denied (a program reading gets no reply).
+                let visible = self.visual_pane_rects.iter().any(|&(id, _)| id == pane_id);
+                if visible && self.modal.clipboard_prompt.is_none() {
+                    let message = self.clipboard_prompt_message(pane_id, &request);
+                    self.modal.clipboard_prompt = Some(ClipboardPromptState { pane_id, request, message });
+                    self.cache.needs_redraw = true;
+                }
+            }
+            ClipboardPolicy::Deny => {}
+        }
+    }
+
+    fn run_clipboard_request(&mut self, pane_id: PaneId, request: ClipboardRequest) {
+        let Some(PaneKind::Terminal(t)) = self.panes.get_mut(&pane_id) else { return };
+        let Ok(mut clipboard) = arboard::Clipboard::new() else { return };
+        match request {
+            ClipboardRequest::Store(text) => {
+                if clipboard.set_text(&text).is_ok() {
+                    let message = match t.backend.foreground_process_name() {
+                        Some(name) => format!("Copied to clipboard by {name}"),
+                        None => "Copied to clipboard".to_string(),
+                    };
+                    self.toast = Some(Toast { message, until: Instant::now() + TOAST_DURATION });
+                    self.cache.needs_redraw = true;
+                }
+            }
+            ClipboardRequest::Load(reply) => {
+                let text = clipboard.get_text().unwrap_or_default();
+                t.backend.reply_clipboard(&reply, &text);
+            }
+        }
+    }
+
+    /// Run the request the clipboard bar asks about. With `always`, requests
+    /// of the same kind from that terminal no longer ask.
+    pub(crate) fn allow_clipboard_prompt(&mut self, always: bool) {
+        let Some(prompt) = self.modal.clipboard_prompt.take() else { return };
+        if always {
+            if let Some(PaneKind::Terminal(t)) = self.panes.get_mut(&prompt.pane_id) {
+                match prompt.request {
+                    ClipboardRequest::Store(_) => t.context.clipboard_write_granted = true,
+                    ClipboardRequest::Load(_) => t.context.clipboard_read_granted = true,
+                }
+            }
+        }
+        self.run_clipboard_request(prompt.pane_id, prompt.request);
+        self.cache.needs_redraw = true;
+    }
+
+    /// Drop the request the clipboard bar asks about.
+    pub(crate) fn deny_clipboard_prompt(&mut self) {
+        if self.modal.clipboard_prompt.take().is_some() {
+            self.cache.needs_redraw = true;
+        }
+    }
+
+    /// Text for the clipboard bar.
+    fn clipboard_prompt_message(&self, pane_id: PaneId, request: &ClipboardRequest) -> String {
+        let name = match self.panes.get(&pane_id) {
+            Some(PaneKind::Terminal(t)) => t.backend.foreground_process_name(),
+            _ => None,
+        };
+        let name = name.as_deref().unwrap_or("A program");
+        match request {
+            ClipboardRequest::Store(_) => format!("{name} wants to set the clipboard"),
+            ClipboardRequest::Load(_) => format!("{name} wants to read the clipboard"),
+        }
+    }
+
+    /// When the toast goes away, if one is showing.
+    pub(crate) fn toast_end(&self) -> Option<Instant> {
+        self.toast.as_ref().map(|t| t.until)
+    }
+}
@@ -0,0 +1,272 @@
+// Copy mode: vi keys over a terminal's scrollback (Cmd+Shift+C). The cursor,
+// motions and selection live in tide-terminal on top of alacritty's vi mode;
+// this is the key map, the count / `gg` prefix and the `/` `?` search prompt.
+// While a terminal is in copy mode its keys come here instead of the PTY, and
+// `y` copies the selection to the clipboard and leaves.
+
+use std::time::Instant;
+
+use tide_core::{Key, Modifiers, PaneId};
+use tide_terminal::{CopyMotion, CopySelection, SearchOptions};
+
+use crate::pane::PaneKind;
+use crate::ui_state::{InputLine, Toast};
+use crate::App;
+
+/// Largest count prefix accepted (`9999j`).
+const MAX_COUNT: usize = 9999;
+
+/// Per-terminal copy mode input state.
+pub struct CopyModeState {
+    /// Count typed before a motion (`5j`); 0 = none.
+    pub count: usize,
+    /// `g` was typed; a second `g` goes to the top.
+    pub pending_g: bool,
+    /// `/` or `?` prompt being typed, and whether it searches forward.
+    pub prompt: Option<(InputLine, bool)>,
+    /// Last search, repeated by `n` / `N`.
+    pub last_search: Option<(String, bool)>,
+}
+
+impl CopyModeState {
+    pub fn new() -> Self {
+        Self { count: 0, pending_g: false, prompt: None, last_search: None }
+    }
+
+    /// Status shown over the terminal: the prompt, or the selection kind.
+    pub fn status(&self, selection: Option<CopySelection>) -> String {
+        if let Some((ref input, forward)) = self.prompt {
+            return format!("{}{}", if forward { '/' } else { '?' }, input.text);
+        }
+        let mode = match selection {
+            None => "COPY",
+            Some(CopySelection::Chars) => "VISUAL",
+            Some(CopySelection::Lines) => "VISUAL LINE",
+            Some(CopySelection::Block) => "VISUAL BLOCK",
+        };
+        if self.count > 0 {
+            format!("{mode} {}", self.count)
+        } else {
+            mode.to_string()
+        }
+    }
+}
+
+/// The motion a key moves by, if it's one.
+pub(crate) fn motion_for_key(key: Key, modifiers: &Modifiers) -> Option<CopyMotion> {
+    if modifiers.ctrl {
+        return match key {
+            Key::Char('u') | Key::Char('U') => Some(CopyMotion::HalfPageUp),
+            Key::Char('d') | Key::Char('D') => Some(CopyMotion::HalfPageDown),
+            _ => None,
+        };
+    }
+    Some(match key {
+        Key::Char('h') | Key::Left => CopyMotion::Left,
+        Key::Char('j') | Key::Down => CopyMotion::Down,
+        Key::Char('k') | Key::Up => CopyMotion::Up,
+        Key::Char('l') | Key::Right => CopyMotion::Right,
+        Key::Char('0') | Key::Home => CopyMotion::LineStart,
+        Key::Char('$') | Key::End => CopyMotion::LineEnd,
+        Key::Char('^') => CopyMotion::FirstNonBlank,
+        Key::Char('w') => CopyMotion::WordRight,
+        Key::Char('b') => CopyMotion::WordLeft,
+        Key::Char('e') => CopyMotion::WordEnd,
+        Key::Char('W') => CopyMotion::BigWordRight,
+        Key::Char('B') => CopyMotion::BigWordLeft,
+        Key::Char('E') => CopyMotion::BigWordEnd,
+        Key::Char('{') => CopyMotion::ParagraphUp,
+        Key::Char('}') => CopyMotion::ParagraphDown,
+        Key::Char('H') => CopyMotion::ScreenTop,
+        Key::Char('M') => CopyMotion::ScreenMiddle,
+        Key::Char('L') => CopyMotion::ScreenBottom,
+        Key::Char('%') => CopyMotion::Bracket,
+        Key::Char('G') => CopyMotion::Bottom,
+        Key::PageUp => CopyMotion::HalfPageUp,
+        Key::PageDown => CopyMotion::HalfPageDown,
+        _ => return None,
+    })
+}
+
+/// Key for a character typed in copy mode (text input carries Enter, Esc
+/// and Backspace as control characters).
+pub(crate) fn key_for_char(ch: char) -> Key {
+    match ch {
+        '\r' | '\n' => Key::Enter,
+        '\u{1b}' => Key::Escape,
+        '\u{7f}' | '\u{8}' => Key::Backspace,
+        ch => Key::Char(ch),
+    }
+}
+
+impl App {
+    /// Whether terminal `id` is in copy mode.
+    pub(crate) fn in_copy_mode(&self, id: PaneId) -> bool {
+        matches!(self.panes.get(&id), Some(PaneKind::Terminal(pane)) if pane.copy_mode.is_some())
+    }
+
+    /// Enter copy mode in the focused terminal, or leave it.
+    pub(crate) fn toggle_copy_mode(&mut self) {
+        let Some(id) = self.focused else { return };
+        if self.in_copy_mode(id) {
+            self.exit_copy_mode(id);
+            return;
+        }
+        let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&id) else { return };
+        pane.copy_mode = Some(CopyModeState::new());
+        pane.backend.enter_copy_mode();
+        if self.search_focus == Some(id) {
+            self.search_focus = None;
+        }
+        self.cache.invalidate_pane(id);
+        self.cache.invalidate_chrome();
+    }
+
+    pub(crate) fn exit_copy_mode(&mut self, id: PaneId) {
+        let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&id) else { return };
+        pane.copy_mode = None;
+        pane.backend.exit_copy_mode();
+        self.cache.invalidate_pane(id);
+        self.cache.invalidate_chrome();
+    }
+
+    /// A key typed into terminal `id` while it's in copy mode.
+    pub(crate) fn handle_copy_mode_key(&mut self, id: PaneId, key: Key, modifiers: &Modifiers) {
+        let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&id) else { return };
+        let Some(state) = pane.copy_mode.as_mut() else { return };
+        self.cache.invalidate_pane(id);
+        self.cache.needs_redraw = true;
+
+        // Search prompt
+        if let Some((ref mut input, forward)) = state.prompt {
+            match key {
+                Key::Escape => state.prompt = None,
+                Key::Backspace if input.is_empty() => state.prompt = None,
+                Key::Backspace => input.backspace(),
+                Key::Left => input.move_cursor_left(),
+                Key::Right => input.move_cursor_right(),
+                Key::Enter => {
+                    let query = std::mem::take(&mut input.text);
+                    state.prompt = None;
+                    if !query.is_empty() {
+                        state.last_search = Some((query.clone(), forward));
+                        self.copy_mode_search(id, &query, forward);
+                    }
+                }
+                Key::Char(ch) if !modifiers.ctrl && !modifiers.meta => input.insert_char(ch),
+                _ => {}
+            }
+            return;
+        }
+
+        // Count prefix (a leading 0 is the line start motion)
+        let digit = match key {
+            Key::Char(ch @ '0'..='9') if !modifiers.ctrl && (ch != '0' || state.count > 0) => ch.to_digit(10),
+            _ => None,
+        };
+        if let Some(digit) = digit {
+            state.count = (state.count * 10 + digit as usize).min(MAX_COUNT);
+            return;
+        }
+        let count = std::mem::take(&mut state.count).max(1);
+        let pending_g = std::mem::take(&mut state.pending_g);
+
+        if let Some(motion) = motion_for_key(key, modifiers) {
+            for _ in 0..count {
+                pane.backend.copy_mode_motion(motion);
+            }
+            return;
+        }
+
+        match key {
+            Key::Char('g') if pending_g => pane.backend.copy_mode_motion(CopyMotion::Top),
+            Key::Char('g') => state.pending_g = true,
+            Key::Char('v') | Key::Char('V') if modifiers.ctrl => pane.backend.toggle_copy_selection(CopySelection::Block),
+            Key::Char('v') => pane.backend.toggle_copy_selection(CopySelection::Chars),
+            Key::Char('V') => pane.backend.toggle_copy_selection(CopySelection::Lines),
+            Key::Char('/') => state.prompt = Some((InputLine::new(), true)),
+            Key::Char('?') => state.prompt = Some((InputLine::new(), false)),
+            Key::Char('n') | Key::Char('N') => {
+                if let Some((query, forward)) = state.last_search.clone() {
+                    // N searches the other way
+                    let forward = forward == (key == Key::Char('n'));
+                    for _ in 0..count {
+                        if !self.copy_mode_search(id, &query, forward) {
+                            break;
+                        }
+                    }
+                }
+            }
+            Key::Char('y') => self.copy_mode_yank(id),
+            // Enter copies like `y`, or just leaves without a selection
+            Key::Enter => {
+                self.copy_mode_yank(id);
+                self.exit_copy_mode(id);
+            }
+            // Esc clears the selection first
+            Key::Escape if pane.backend.clear_copy_selection() => {}
+            Key::Escape | Key::Char('q') => self.exit_copy_mode(id),
+            Key::Char('c') if modifiers.ctrl => self.exit_copy_mode(id),
+            _ => {}
+        }
+    }
+
+    /// Copy the selection to the clipboard and leave copy mode. Nothing
+    /// happens without a selection.
+    fn copy_mode_yank(&mut self, id: PaneId) {
+        let Some(PaneKind::Terminal(pane)) = self.panes.get(&id) else { return };
+        let Some(text) = pane.backend.copy_mode_yank() else { return };
+        if let Ok(mut clipboard) = arboard::Clipboard::new() {
+            if clipboard.set_text(&text).is_ok() {
+                self.toast = Some(Toast {
+                    message: "Copied to clipboard".to_string(),
+                    until: Instant::now() + crate::clipboard::TOAST_DURATION,
+                });
+            }
+        }
+        self.exit_copy_mode(id);
+    }
+
+    /// Move the copy cursor to the next match of `query` (plain text, any
+    /// case). Shows a toast when there is none; returns whether it moved.
+    fn copy_mode_search(&mut self, id: PaneId, query: &str, forward: bool) -> bool {
+        let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&id) else { return false };
+        let pattern = SearchOptions::default().pattern(query);
+        let found = pane.backend.copy_mode_search(&pattern, forward).unwrap_or(false);
+        if !found {
+            self.toast = Some(Toast {
+                message: format!("Pattern not found: {query}"),
+                until: Instant::now() + crate::clipboard::TOAST_DURATION,
+            });
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vi_keys_map_to_motions() {
+        let none = Modifiers::default();
+        let ctrl = Modifiers { ctrl: true, ..Default::default() };
+        assert_eq!(motion_for_key(Key::Char('j'), &none), Some(CopyMotion::Down));
+        assert_eq!(motion_for_key(Key::Char('W'), &none), Some(CopyMotion::BigWordRight));
+        assert_eq!(motion_for_key(Key::Char('}'), &none), Some(CopyMotion::ParagraphDown));
+        assert_eq!(motion_for_key(Key::Char('d'), &ctrl), Some(CopyMotion::HalfPageDown));
+        // `d` alone and `g` (a prefix) aren't motions
+        assert_eq!(motion_for_key(Key::Char('d'), &none), None);
+        assert_eq!(motion_for_key(Key::Char('g'), &none), None);
+    }
+
+    #[test]
+    fn status_shows_prompt_or_selection_kind() {
+        let mut state = CopyModeState::new();
+        assert_eq!(state.status(None), "COPY");
+        state.count = 3;
+        assert_eq!(state.status(Some(CopySelection::Lines)), "VISUAL LINE 3");
+        state.prompt = Some((InputLine::with_text("err".to_string()), false));
+        assert_eq!(state.status(None), "?err");
+    }
+}
@@ -3,7 +3,7 @@
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
-use tide_core::{Color, PaneId, Rect, Renderer, TextStyle, Vec2};
+use tide_core::{Color, PaneId, Rect, Renderer, TextStyle, UnderlineStyle, Vec2};
 use tide_renderer::WgpuRenderer;
 use tide_terminal::git;
 
@@ -265,7 +265,7 @@ impl DiffPane {
                 let arrow = if is_expanded { '▾' } else { '▸' };
                 let dim_style = TextStyle {
                     foreground: dimmed_color, background: None,
-                    bold: false, dim: false, italic: false, underline: false,
+                    bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                 };
                 renderer.draw_grid_cell(arrow, vi, col, dim_style, cell_size, Vec2::new(rect.x, rect.y));
                 col += 2; // arrow + space
@@ -287,7 +287,7 @@ impl DiffPane {
                 };
                 let status_style = TextStyle {
                     foreground: status_color, background: None,
-                    bold: true, dim: false, italic: false, underline: false,
+                    bold: true, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                 };
                 renderer.draw_grid_cell(status_ch, vi, col, status_style, cell_size, Vec2::new(rect.x, rect.y));
                 col += 2; // status + space
@@ -300,11 +300,11 @@ impl DiffPane {
                 };
                 let dir_style = TextStyle {
                     foreground: dimmed_color, background: None,
-                    bold: false, dim: true, italic: false, underline: false,
+                    bold: false, dim: true, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                 };
                 let file_style = TextStyle {
                     foreground: text_color, background: None,
-                    bold: true, dim: false, italic: false, underline: false,
+                    bold: true, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                 };
                 // Build stats string early so we know how much space to reserve
                 let stats_str = if file.additions > 0 || file.deletions > 0 {
@@ -333,7 +333,7 @@ impl DiffPane {
                         let color = if ci < dash_pos { added_gutter } else { removed_gutter };
                         let stat_style = TextStyle {
                             foreground: color, background: None,
-                            bold: false, dim: false, italic: false, underline: false,
+                            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                         };
                         renderer.draw_grid_cell(ch, vi, start_col + ci, stat_style, cell_size, Vec2::new(rect.x, rect.y));
                     }
@@ -377,7 +377,7 @@ impl DiffPane {
                                     }
                                     let style = TextStyle {
                                         foreground: fg, background: None,
-                                        bold: false, dim: is_dim, italic: false, underline: false,
+                                        bold: false, dim: is_dim, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                                     };
                                     renderer.draw_grid_cell(gutter_ch, vi, 1, style, cell_size, left_origin);
                                     for (ci, ch) in text.chars().skip(self.h_scroll).enumerate().take(half_cols.saturating_sub(3)) {
@@ -403,7 +403,7 @@ impl DiffPane {
                                     }
                                     let style = TextStyle {
                                         foreground: fg, background: None,
-                                        bold: false, dim: is_dim, italic: false, underline: false,
+                                        bold: false, dim: is_dim, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                                     };
                                     renderer.draw_grid_cell(gutter_ch, vi, 1, style, cell_size, right_origin);
                                     for (ci, ch) in text.chars().skip(self.h_scroll).enumerate().take(half_cols.saturating_sub(3)) {
@@ -444,14 +444,14 @@ impl DiffPane {
                                 };
                                 let gutter_style = TextStyle {
                                     foreground: fg, background: None,
-                                    bold: false, dim: false, italic: false, underline: false,
+                                    bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                                 };
                                 renderer.draw_grid_cell(gutter_ch, vi, 2, gutter_style, cell_size, Vec2::new(rect.x, rect.y));
 
                                 let content_style = TextStyle {
                                     foreground: fg, background: None,
                                     bold: false, dim: matches!(line, DiffLine::Context(_)),
-                                    italic: false, underline: false,
+                                    italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                                 };
                                 let max_cols = (rect.width / cell_size.width).floor() as usize;
                                 for (ci, ch) in text.chars().skip(self.h_scroll).enumerate().take(max_cols.saturating_sub(4)) {
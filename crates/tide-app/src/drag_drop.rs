@@ -40,6 +40,7 @@ pub(crate) enum HoverTarget {
     PaneTabClose(PaneId),
     PaneMaximize(PaneId),
     FileFinderItem(usize),
+    ProblemsItem(usize),
     SidebarHandle,
     TitlebarSwap,
     TitlebarSettings,
@@ -2,7 +2,7 @@
 
 use unicode_width::UnicodeWidthChar;
 
-use tide_core::{Color, Rect, Renderer, TextStyle, Vec2};
+use tide_core::{Color, Rect, Renderer, TextStyle, UnderlineStyle, Vec2};
 use tide_renderer::WgpuRenderer;
 
 use crate::search::SearchState;
@@ -110,7 +110,9 @@ impl EditorPane {
                 bold: false,
                 dim: false,
                 italic: false,
-                underline: false,
+                underline: UnderlineStyle::None,
+                underline_color: None,
+                strikethrough: false,
             };
             for (ci, ch) in line_num.chars().enumerate() {
                 if ch != ' ' {
@@ -268,7 +270,9 @@ impl EditorPane {
                         bold: false,
                         dim: false,
                         italic: false,
-                        underline: false,
+                        underline: UnderlineStyle::None,
+                        underline_color: None,
+                        strikethrough: false,
                     };
                     for (ci, ch) in gutter_str.chars().enumerate().take(GUTTER_WIDTH_CELLS) {
                         if ch != ' ' {
@@ -284,7 +288,9 @@ impl EditorPane {
                             bold: false,
                             dim: false,
                             italic: false,
-                            underline: false,
+                            underline: UnderlineStyle::None,
+                            underline_color: None,
+                            strikethrough: false,
                         };
                         let mut char_idx = 0usize;
                         let mut display_col = 0usize;
@@ -315,7 +321,9 @@ impl EditorPane {
                         bold: false,
                         dim: false,
                         italic: false,
-                        underline: false,
+                        underline: UnderlineStyle::None,
+                        underline_color: None,
+                        strikethrough: false,
                     };
                     for (ci, ch) in gutter_str.chars().enumerate().take(GUTTER_WIDTH_CELLS) {
                         if ch != ' ' {
@@ -331,7 +339,9 @@ impl EditorPane {
                             bold: false,
                             dim: true,
                             italic: false,
-                            underline: false,
+                            underline: UnderlineStyle::None,
+                            underline_color: None,
+                            strikethrough: false,
                         };
                         let mut char_idx = 0usize;
                         let mut display_col = 0usize;
@@ -25,6 +25,16 @@ impl App {
         }
     }
 
+    /// Whether `pos` is in the right half of its terminal cell, i.e. the side
+    /// of the cell a selection starts or ends on.
+    pub(crate) fn in_right_half_of_cell(&self, pos: Vec2, pane_id: tide_core::PaneId) -> bool {
+        let Some((_, visual_rect)) = self.visual_pane_rects.iter().find(|(id, _)| *id == pane_id) else {
+            return false;
+        };
+        let x = (pos.x - visual_rect.x - PANE_PADDING) / self.cell_size().width;
+        x.fract() >= 0.5
+    }
+
     /// Compute the hover target for a given cursor position.
     /// Priority: TopHandles → SplitBorder → PaneTabBar → FileTreeBorder → FileTreeEntry → None
     pub(crate) fn compute_hover_target(&self, pos: Vec2) -> Option<HoverTarget> {
@@ -133,6 +143,11 @@ impl App {
             return Some(HoverTarget::FileFinderItem(idx));
         }
 
+        // Problems panel row hover
+        if let Some(idx) = self.problems_item_at(pos) {
+            return Some(HoverTarget::ProblemsItem(idx));
+        }
+
 
         // Split pane border (resize handle between tiled panes)
         if let Some(dir) = self.split_border_at(pos) {
@@ -1085,6 +1100,48 @@ impl App {
         true
     }
 
+    /// Handle clipboard bar button clicks.
+    /// Returns true if the click was consumed.
+    pub(crate) fn handle_clipboard_prompt_click(&mut self, pos: tide_core::Vec2) -> bool {
+        let prompt_pane_id = match self.modal.clipboard_prompt {
+            Some(ref prompt) => prompt.pane_id,
+            None => return false,
+        };
+        let bar_rect = match self.notification_bar_rect(prompt_pane_id) {
+            Some(r) => r,
+            None => return false,
+        };
+        if pos.y < bar_rect.y || pos.y > bar_rect.y + bar_rect.height
+            || pos.x < bar_rect.x || pos.x > bar_rect.x + bar_rect.width
+        {
+            return false;
+        }
+        let cell_size = self.cell_size();
+        let btn_pad = 8.0;
+
+        // Deny (rightmost)
+        let deny_w = 4.0 * cell_size.width + btn_pad * 2.0;
+        let deny_x = bar_rect.x + bar_rect.width - deny_w - 4.0;
+
+        // Always
+        let always_w = 6.0 * cell_size.width + btn_pad * 2.0;
+        let always_x = deny_x - always_w - 4.0;
+
+        // Allow
+        let allow_w = 5.0 * cell_size.width + btn_pad * 2.0;
+        let allow_x = always_x - allow_w - 4.0;
+
+        if pos.x >= deny_x {
+            self.deny_clipboard_prompt();
+        } else if pos.x >= always_x {
+            self.allow_clipboard_prompt(true);
+        } else if pos.x >= allow_x {
+            self.allow_clipboard_prompt(false);
+        }
+        self.cache.needs_redraw = true;
+        true
+    }
+
     /// Handle a completed drop operation.
     /// Tab-aware: Center zone adds source as a tab in target's TabGroup.
     /// Directional zones remove source from its group and create a new split leaf.
@@ -79,7 +79,7 @@ impl App {
             }
         }
         // Launcher pane: intercept single-char text to resolve launcher choice.
-        // On macOS, plain keys (t/e/o/b) arrive via ImeCommit, not KeyDown.
+        // On macOS, plain keys (t/e/o/b, 1-9) arrive via ImeCommit, not KeyDown.
         if let Some(id) = self.focused {
             if matches!(self.panes.get(&id), Some(PaneKind::Launcher(_))) {
                 for ch in text.chars() {
@@ -88,7 +88,7 @@ impl App {
                         'e' | 'E' | 'ㄷ' => Some(crate::action::LauncherChoice::NewFile),
                         'o' | 'O' | 'ㅐ' => Some(crate::action::LauncherChoice::OpenFile),
                         'b' | 'B' | 'ㅠ' => Some(crate::action::LauncherChoice::Browser),
-                        _ => None,
+                        _ => crate::action::LauncherChoice::for_digit(ch, self.settings.profiles.len()),
                     };
                     if let Some(c) = choice {
                         self.resolve_launcher(id, c);
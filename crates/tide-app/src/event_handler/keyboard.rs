@@ -4,8 +4,10 @@
 //! KeyDown only fires for keys NOT consumed by the IME (hotkeys, control keys).
 
 use tide_core::{FileTreeSource, InputEvent, Key, Modifiers};
+use tide_terminal::KeyboardFlags;
 
 use crate::drag_drop::PaneDragState;
+use crate::event_handler::text_routing::TextInputTarget;
 use crate::pane::PaneKind;
 use crate::ui_state::FocusArea;
 use crate::App;
@@ -30,9 +32,12 @@ impl App {
         // route via the text input system.
         // Exception: skip text routing when the active editor is in preview mode
         // AND no search bar is active, so keys like j/k/d/u fall through to
-        // the preview scroll handler.
+        // the preview scroll handler. Also skipped for non-character keys
+        // (Enter, Tab, ...) when a kitty keyboard protocol app has focus, so
+        // they get encoded as keys.
         if let Some(ref text) = chars {
-            if !modifiers.meta && !modifiers.ctrl && !modifiers.alt {
+            let kitty_key = !matches!(key, Key::Char(_)) && !self.focused_terminal_keyboard_flags().is_legacy();
+            if !modifiers.meta && !modifiers.ctrl && !modifiers.alt && !kitty_key {
                 let in_preview = self.search_focus.is_none()
                     && self.focused
                         .and_then(|id| self.panes.get(&id))
@@ -53,8 +58,8 @@ impl App {
             && !modifiers.shift
             && !modifiers.alt
         {
-            let session = crate::session::Session::from_app(self);
-            crate::session::save_session(&session);
+            self.save_session_on_exit();
+            self.stop_ipc_server();
             crate::session::delete_running_marker();
             std::process::exit(0);
         }
@@ -83,6 +88,12 @@ impl App {
             return;
         }
 
+        // Problems panel interception
+        if self.modal.problems.is_some() {
+            self.handle_problems_key(key);
+            return;
+        }
+
         // File finder interception
         if self.modal.file_finder.is_some() {
             self.handle_file_finder_key(key, &modifiers);
@@ -116,6 +127,17 @@ impl App {
             }
         }
 
+        // Clipboard bar: Esc denies. Allowing takes a click, so typing meant
+        // for the shell can't grant a request; other keys reach the pane.
+        if let Some(ref prompt) = self.modal.clipboard_prompt {
+            if !self.panes.contains_key(&prompt.pane_id) {
+                self.modal.clipboard_prompt = None;
+            } else if matches!(key, Key::Escape) {
+                self.deny_clipboard_prompt();
+                return;
+            }
+        }
+
         // Save confirm bar interception
         if self.modal.save_confirm.is_some() {
             if matches!(key, Key::Escape) {
@@ -181,6 +203,18 @@ impl App {
                     return;
                 }
 
+                // Copy mode interception: keys that aren't hotkeys drive the
+                // copy cursor instead of reaching the PTY
+                if let TextInputTarget::CopyMode(id) = self.text_input_target() {
+                    let input = InputEvent::KeyPress { key, modifiers };
+                    match self.router.process(input, &self.pane_rects) {
+                        tide_input::Action::RouteToPane(_) => self.handle_copy_mode_key(id, key, &modifiers),
+                        action => self.handle_action(action, Some(input)),
+                    }
+                    self.cache.needs_redraw = true;
+                    return;
+                }
+
                 // Fall through to normal routing
             }
         }
@@ -191,6 +225,28 @@ impl App {
         self.cache.needs_redraw = true;
     }
 
+    /// Key releases only matter to terminal apps using the kitty keyboard
+    /// protocol's event reporting.
+    pub(crate) fn handle_key_up(&mut self, key: Key, modifiers: Modifiers) {
+        let Some(id) = self.focused else { return };
+        if let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&id) {
+            pane.handle_key_release(&key, &modifiers);
+            self.broadcast_key_release(id, &key, &modifiers);
+        }
+    }
+
+    /// Kitty keyboard flags of the terminal that typed text would go to
+    /// (legacy when text goes anywhere else).
+    fn focused_terminal_keyboard_flags(&self) -> KeyboardFlags {
+        match self.text_input_target() {
+            TextInputTarget::Pane(id) => match self.panes.get(&id) {
+                Some(PaneKind::Terminal(pane)) => pane.backend.keyboard_flags(),
+                _ => KeyboardFlags::default(),
+            },
+            _ => KeyboardFlags::default(),
+        }
+    }
+
     fn handle_git_switcher_key(&mut self, key: Key, modifiers: &Modifiers) {
         // Cmd+Backspace → delete selected item
         if matches!(key, Key::Backspace) && modifiers.meta && !modifiers.ctrl && !modifiers.alt {
@@ -427,6 +483,28 @@ impl App {
         self.cache.needs_redraw = true;
     }
 
+    fn handle_problems_key(&mut self, key: Key) {
+        let count = self.problems.items.len();
+        match key {
+            Key::Escape => self.close_problems_panel(),
+            Key::Enter => self.open_selected_problem(),
+            Key::Up => {
+                if let Some(ref mut panel) = self.modal.problems {
+                    panel.select_up();
+                    self.cache.invalidate_chrome();
+                }
+            }
+            Key::Down => {
+                if let Some(ref mut panel) = self.modal.problems {
+                    panel.select_down(count);
+                    self.cache.invalidate_chrome();
+                }
+            }
+            _ => {}
+        }
+        self.cache.needs_redraw = true;
+    }
+
     fn handle_context_menu_key(&mut self, key: Key) {
         match key {
             Key::Escape => {
@@ -878,13 +956,24 @@ impl App {
                 Some(PaneKind::Editor(pane)) => {
                     pane.search = None;
                 }
-                Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => {}
+                Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => {}
                 None => {}
             }
             self.search_focus = None;
             return;
         }
 
+        // Alt+C / Alt+W / Alt+R → case-sensitive / whole-word / regex toggles
+        if let Key::Char(ch) = key {
+            if modifiers.alt && !modifiers.ctrl && !modifiers.meta {
+                if let Some(toggle) = crate::search::SearchToggle::from_alt_key(ch) {
+                    self.search_bar_toggle(search_pane_id, toggle);
+                    self.cache.needs_redraw = true;
+                    return;
+                }
+            }
+        }
+
         match key {
             Key::Escape => {
                 match self.panes.get_mut(&search_pane_id) {
@@ -894,7 +983,7 @@ impl App {
                     Some(PaneKind::Editor(pane)) => {
                         pane.search = None;
                     }
-                    Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => {}
+                    Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => {}
                     None => {}
                 }
                 self.search_focus = None;
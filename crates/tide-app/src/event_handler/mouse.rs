@@ -1,7 +1,8 @@
 //! Mouse event handling — platform-agnostic.
 
-use tide_core::{FileTreeSource, InputEvent, LayoutEngine, MouseButton, Rect, Vec2};
+use tide_core::{FileTreeSource, InputEvent, LayoutEngine, MouseButton, PaneId, Rect, Vec2};
 use tide_platform::WindowProxy;
+use tide_terminal::{MouseReport, SelectionKind};
 
 use crate::drag_drop::PaneDragState;
 use crate::pane::{PaneKind, Selection};
@@ -11,6 +12,13 @@ use crate::App;
 
 impl App {
     pub(crate) fn handle_mouse_down(&mut self, button: MouseButton, window: &WindowProxy) {
+        // Apps with mouse reporting (vim, htop, tmux) get the click instead of
+        // local selection. Holding Shift forces local selection.
+        if self.report_mouse_down(button) {
+            self.cache.needs_redraw = true;
+            return;
+        }
+
         if button == MouseButton::Left {
             self.interaction.mouse_left_pressed = true;
 
@@ -34,14 +42,18 @@ impl App {
                     content.contains(self.last_cursor_pos)
                 }) {
                     let pid = *pane_id;
+                    let clicks = self.interaction.register_click(self.last_cursor_pos, std::time::Instant::now());
                     for (_, pane) in self.panes.iter_mut() {
                         match pane {
-                            PaneKind::Terminal(p) => p.selection = None,
+                            PaneKind::Terminal(p) => {
+                                p.backend.clear_selection();
+                            }
                             PaneKind::Editor(p) => p.selection = None,
-                            PaneKind::Diff(_) | PaneKind::Browser(_) | PaneKind::Launcher(_) => {}
+                            PaneKind::Diff(_) | PaneKind::Browser(_) | PaneKind::Replay(_) | PaneKind::Launcher(_) => {}
                         }
                     }
                     let term_cell = self.pixel_to_cell(self.last_cursor_pos, pid);
+                    let right_half = self.in_right_half_of_cell(self.last_cursor_pos, pid);
                     let editor_cell = {
                         let cs = Some(self.cell_size());
                         if let (Some(cs), Some((_, rect))) =
@@ -65,10 +77,18 @@ impl App {
                     match self.panes.get_mut(&pid) {
                         Some(PaneKind::Terminal(pane)) => {
                             if let Some(cell) = term_cell {
-                                pane.selection = Some(Selection {
-                                    anchor: cell,
-                                    end: cell,
-                                });
+                                // Double-click selects a word, triple-click a line and
+                                // quadruple-click a URL, path, IP or quoted string
+                                let kind = match clicks {
+                                    1 if mods.alt => SelectionKind::Block,
+                                    1 => SelectionKind::Simple,
+                                    2 => SelectionKind::Word,
+                                    _ => SelectionKind::Lines,
+                                };
+                                if clicks < 4 || !pane.backend.select_smart(cell) {
+                                    pane.backend.start_selection(kind, cell, right_half);
+                                }
+                                self.interaction.selecting = Some(pid);
                             }
                         }
                         Some(PaneKind::Browser(_)) => {}
@@ -99,6 +119,20 @@ impl App {
                                 });
                             }
                         }
+                        Some(PaneKind::Replay(rp)) => {
+                            // Click along the bottom edge (progress bar) to seek
+                            if let Some(&(_, rect)) = self.visual_pane_rects.iter().find(|(id, _)| *id == pid) {
+                                let bottom = rect.y + rect.height - PANE_PADDING;
+                                let pos = self.last_cursor_pos;
+                                if pos.y >= bottom - cell_size_cached.height {
+                                    let x0 = rect.x + PANE_PADDING;
+                                    let fraction = ((pos.x - x0) / (rect.width - 2.0 * PANE_PADDING)).clamp(0.0, 1.0);
+                                    rp.player.seek(rp.player.duration() * fraction as f64);
+                                    self.cache.invalidate_pane(pid);
+                                    self.cache.invalidate_chrome();
+                                }
+                            }
+                        }
                         Some(PaneKind::Diff(_)) => {}
                         Some(PaneKind::Launcher(_)) => {}
                         None => {}
@@ -152,6 +186,17 @@ impl App {
                 return;
             }
 
+            if self.modal.problems.is_some() {
+                if let Some(idx) = self.problems_item_at(self.last_cursor_pos) {
+                    self.close_problems_panel();
+                    self.open_problem(idx);
+                } else if !self.problems_contains(self.last_cursor_pos) {
+                    self.close_problems_panel();
+                }
+                self.cache.needs_redraw = true;
+                return;
+            }
+
             if self.modal.git_switcher.is_some() {
                 // Tab click: switch between Branches / Worktrees
                 if let Some(mode) = self.git_switcher_tab_at(self.last_cursor_pos) {
@@ -191,6 +236,14 @@ impl App {
             }
         }
 
+        // Clipboard bar clicks
+        if button == MouseButton::Left
+            && self.modal.clipboard_prompt.is_some()
+            && self.handle_clipboard_prompt_click(self.last_cursor_pos)
+        {
+            return;
+        }
+
         // Notification bar clicks
         if button == MouseButton::Left {
             if self.handle_notification_bar_click(self.last_cursor_pos) {
@@ -430,6 +483,21 @@ impl App {
     pub(crate) fn handle_mouse_up(&mut self, button: MouseButton) {
         if button == MouseButton::Left {
             self.interaction.mouse_left_pressed = false;
+            self.interaction.selecting = None;
+        }
+
+        // Release of a button whose press was forwarded to a terminal app
+        if let Some((pid, pressed)) = self.interaction.mouse_report {
+            if pressed == button {
+                self.interaction.mouse_report = None;
+                let cell = self.pixel_to_cell(self.last_cursor_pos, pid)
+                    .or(self.interaction.mouse_report_cell);
+                let mods = self.modifiers;
+                if let (Some(PaneKind::Terminal(pane)), Some(cell)) = (self.panes.get_mut(&pid), cell) {
+                    pane.report_mouse(MouseReport::Release(button), cell, &mods);
+                }
+                return;
+            }
         }
 
         // End workspace sidebar drag
@@ -662,6 +730,8 @@ impl App {
             self.compute_layout();
             self.cache.needs_redraw = true;
         } else {
+            self.report_mouse_motion(pos);
+
             // Text selection drag
             if self.interaction.mouse_left_pressed {
                 let cell_size = Some(self.cell_size());
@@ -683,6 +753,7 @@ impl App {
                         continue;
                     }
                     let cell = self.pixel_to_cell(pos, pid);
+                    let right_half = self.in_right_half_of_cell(pos, pid);
                     let editor_cell = if let Some(cs) = cell_size {
                         let gutter_width = crate::editor_pane::GUTTER_WIDTH_CELLS as f32 * cs.width;
                         let content_x = rect.x + PANE_PADDING + gutter_width;
@@ -700,8 +771,8 @@ impl App {
 
                     match self.panes.get_mut(&pid) {
                         Some(PaneKind::Terminal(pane)) => {
-                            if let (Some(ref mut sel), Some(c)) = (&mut pane.selection, cell) {
-                                sel.end = c;
+                            if let Some(c) = cell.filter(|_| self.interaction.selecting == Some(pid)) {
+                                pane.backend.update_selection(c, right_half);
                             }
                         }
                         Some(PaneKind::Browser(_)) => {}
@@ -728,7 +799,7 @@ impl App {
                                 );
                             }
                         }
-                        Some(PaneKind::Diff(_)) => {}
+                        Some(PaneKind::Diff(_)) | Some(PaneKind::Replay(_)) => {}
                         Some(PaneKind::Launcher(_)) => {}
                         None => {}
                     }
@@ -824,4 +895,76 @@ impl App {
         }
     }
 
+    /// Terminal pane under the cursor whose app has enabled mouse reporting,
+    /// with the viewport cell under the cursor. None when Shift is held
+    /// (local selection) or a modal/search bar is in the way.
+    fn mouse_reporting_target(&self) -> Option<(PaneId, (usize, usize))> {
+        if self.modifiers.shift || self.modal.is_any_open() {
+            return None;
+        }
+        let pos = self.last_cursor_pos;
+        let content_top_offset = TAB_BAR_HEIGHT;
+        let (pid, _) = self.visual_pane_rects.iter().find(|(_, r)| {
+            let content = Rect::new(
+                r.x + PANE_PADDING,
+                r.y + content_top_offset,
+                r.width - 2.0 * PANE_PADDING,
+                r.height - content_top_offset - PANE_PADDING,
+            );
+            content.contains(pos)
+        })?;
+        match self.panes.get(pid) {
+            Some(PaneKind::Terminal(pane))
+                if !pane.context.child_dead
+                    && !pane.search.as_ref().is_some_and(|s| s.visible)
+                    && pane.backend.is_mouse_reporting() => {}
+            _ => return None,
+        }
+        let cell = self.pixel_to_cell(pos, *pid)?;
+        Some((*pid, cell))
+    }
+
+    /// Forward a button press to a mouse-reporting terminal app.
+    /// Returns true if the press was consumed.
+    fn report_mouse_down(&mut self, button: MouseButton) -> bool {
+        let (pid, cell) = match self.mouse_reporting_target() {
+            Some(target) => target,
+            None => return false,
+        };
+        self.focus_terminal(pid);
+        let mods = self.modifiers;
+        if let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&pid) {
+            pane.backend.clear_selection();
+            if pane.report_mouse(MouseReport::Press(button), cell, &mods) {
+                self.interaction.mouse_report = Some((pid, button));
+                self.interaction.mouse_report_cell = Some(cell);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Forward drag (1002/1003) or plain motion (1003) to a terminal app.
+    /// Only sent when the cursor enters a new cell.
+    fn report_mouse_motion(&mut self, pos: Vec2) {
+        let (pid, report) = match self.interaction.mouse_report {
+            Some((pid, button)) => (pid, MouseReport::Drag(button)),
+            None => match self.mouse_reporting_target() {
+                Some((pid, _)) => (pid, MouseReport::Move),
+                None => return,
+            },
+        };
+        let cell = match self.pixel_to_cell(pos, pid) {
+            Some(cell) => cell,
+            None => return,
+        };
+        if self.interaction.mouse_report_cell == Some(cell) {
+            return;
+        }
+        self.interaction.mouse_report_cell = Some(cell);
+        let mods = self.modifiers;
+        if let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&pid) {
+            pane.report_mouse(report, cell, &mods);
+        }
+    }
 }
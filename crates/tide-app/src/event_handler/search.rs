@@ -36,7 +36,7 @@ impl App {
         let has_search = match self.panes.get(&id) {
             Some(PaneKind::Terminal(p)) => p.search.as_ref().is_some_and(|s| s.visible),
             Some(PaneKind::Editor(p)) => p.search.as_ref().is_some_and(|s| s.visible),
-            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => false,
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => false,
             None => false,
         };
         if !has_search {
@@ -44,7 +44,7 @@ impl App {
         }
 
         let bar_w = SEARCH_BAR_WIDTH.min(rect.width - 16.0);
-        if bar_w < 80.0 { return false; }
+        if bar_w < SEARCH_BAR_MIN_WIDTH { return false; }
         let bar_h = SEARCH_BAR_HEIGHT;
         let bar_x = rect.x + rect.width - bar_w - 8.0;
         let bar_y = rect.y + TAB_BAR_HEIGHT + 4.0;
@@ -54,6 +54,13 @@ impl App {
             return false;
         }
 
+        // Option toggles re-run the search in place
+        if let Some(&(toggle, _)) = search::search_toggle_rects(bar_rect).iter().find(|(_, r)| r.contains(pos)) {
+            self.search_bar_toggle(id, toggle);
+            self.search_focus = Some(id);
+            return true;
+        }
+
         // Check close button (rightmost SEARCH_BAR_CLOSE_SIZE px)
         let close_x = bar_x + bar_w - SEARCH_BAR_CLOSE_SIZE;
         if pos.x >= close_x {
@@ -61,7 +68,7 @@ impl App {
             match self.panes.get_mut(&id) {
                 Some(PaneKind::Terminal(pane)) => { pane.search = None; }
                 Some(PaneKind::Editor(pane)) => { pane.search = None; }
-                Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => {}
+                Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => {}
                 None => {}
             }
             if self.search_focus == Some(id) {
@@ -108,7 +115,7 @@ impl App {
                     s.input.insert_char(ch);
                 }
             }
-            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => return,
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => return,
             None => return,
         }
         self.execute_search(pane_id);
@@ -127,7 +134,7 @@ impl App {
                     s.input.backspace();
                 }
             }
-            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => return,
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => return,
             None => return,
         }
         self.execute_search(pane_id);
@@ -146,7 +153,22 @@ impl App {
                     s.input.delete_char();
                 }
             }
-            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => return,
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => return,
+            None => return,
+        }
+        self.execute_search(pane_id);
+        self.search_scroll_to_current(pane_id);
+    }
+
+    pub(crate) fn search_bar_toggle(&mut self, pane_id: tide_core::PaneId, toggle: search::SearchToggle) {
+        match self.panes.get_mut(&pane_id) {
+            Some(PaneKind::Terminal(pane)) => {
+                if let Some(ref mut s) = pane.search { s.toggle(toggle); }
+            }
+            Some(PaneKind::Editor(pane)) => {
+                if let Some(ref mut s) = pane.search { s.toggle(toggle); }
+            }
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => return,
             None => return,
         }
         self.execute_search(pane_id);
@@ -161,7 +183,7 @@ impl App {
             Some(PaneKind::Editor(pane)) => {
                 if let Some(ref mut s) = pane.search { s.input.move_cursor_left(); }
             }
-            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => {}
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => {}
             None => {}
         }
     }
@@ -174,7 +196,7 @@ impl App {
             Some(PaneKind::Editor(pane)) => {
                 if let Some(ref mut s) = pane.search { s.input.move_cursor_right(); }
             }
-            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => {}
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => {}
             None => {}
         }
     }
@@ -196,7 +218,7 @@ impl App {
                     }
                 }
             }
-            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => {}
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => {}
             None => {}
         }
     }
@@ -246,7 +268,7 @@ impl App {
                     }
                 }
             }
-            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => {}
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => {}
             None => {}
         }
     }
@@ -301,7 +323,7 @@ impl App {
                     }
                 }
             }
-            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => {}
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => {}
             None => {}
         }
     }
@@ -355,7 +377,7 @@ impl App {
                     }
                 }
             }
-            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) => {}
+            Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Replay(_)) | Some(PaneKind::Launcher(_)) => {}
             None => {}
         }
     }
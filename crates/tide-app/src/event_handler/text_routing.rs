@@ -6,8 +6,6 @@
 
 use std::time::Instant;
 
-use tide_core::TerminalBackend;
-
 use crate::pane::PaneKind;
 use crate::ui_state::FocusArea;
 use crate::App;
@@ -22,6 +20,8 @@ pub(crate) enum TextInputTarget {
     FileFinder,
     SaveAsInput,
     SearchBar(tide_core::PaneId),
+    /// Terminal in copy mode: characters are vi keys.
+    CopyMode(tide_core::PaneId),
     BrowserUrlBar(tide_core::PaneId),
     Pane(tide_core::PaneId),
     /// Input should be silently consumed (modal popup, file tree focus, etc.)
@@ -44,7 +44,7 @@ impl App {
                 TextInputTarget::Consumed
             };
         }
-        if self.modal.context_menu.is_some() || self.modal.save_confirm.is_some() {
+        if self.modal.context_menu.is_some() || self.modal.save_confirm.is_some() || self.modal.problems.is_some() {
             return TextInputTarget::Consumed;
         }
         // Text-input popups
@@ -77,6 +77,9 @@ impl App {
                         // When URL bar not focused, consume text (webview handles its own input)
                         return TextInputTarget::Consumed;
                     }
+                    if self.in_copy_mode(id) {
+                        return TextInputTarget::CopyMode(id);
+                    }
                 }
                 self.focused
                     .map(TextInputTarget::Pane)
@@ -162,6 +165,12 @@ impl App {
                     self.search_bar_insert(pane_id, ch);
                 }
             }
+            TextInputTarget::CopyMode(pane_id) => {
+                for ch in text.chars() {
+                    let key = crate::copy_mode::key_for_char(ch);
+                    self.handle_copy_mode_key(pane_id, key, &tide_core::Modifiers::default());
+                }
+            }
             TextInputTarget::BrowserUrlBar(pane_id) => {
                 if let Some(PaneKind::Browser(bp)) = self.panes.get_mut(&pane_id) {
                     for ch in text.chars() {
@@ -187,10 +196,9 @@ impl App {
                         if pane.context.child_dead {
                             self.respawn_terminal(id);
                         } else {
-                            if pane.backend.display_offset() > 0 {
-                                pane.backend.request_scroll_to_bottom();
-                            }
-                            pane.backend.write(text.as_bytes());
+                            pane.backend.clear_selection(); // Clear selection on typed input
+                            pane.handle_text(text);
+                            self.broadcast_text(id, text);
                             self.input_just_sent = true;
                             self.input_sent_at = Some(Instant::now());
                         }
@@ -223,6 +231,17 @@ impl App {
                         // Editor has no PTY output loop — must invalidate cache explicitly
                         self.cache.invalidate_pane(id);
                     }
+                    Some(PaneKind::Replay(rp)) => {
+                        // Space (play/pause) arrives as text
+                        let mut handled = false;
+                        for ch in text.chars() {
+                            handled |= rp.handle_key(&tide_core::Key::Char(ch));
+                        }
+                        if handled {
+                            self.cache.invalidate_pane(id);
+                            self.cache.invalidate_chrome();
+                        }
+                    }
                     Some(PaneKind::Diff(_)) | Some(PaneKind::Browser(_)) | Some(PaneKind::Launcher(_)) | None => {}
                 }
             }
@@ -272,6 +291,16 @@ mod tests {
         assert_eq!(app.text_input_target(), TextInputTarget::FileFinder);
     }
 
+    #[test]
+    fn problems_panel_consumes_text() {
+        let mut app = test_app();
+        let id: tide_core::PaneId = 1;
+        app.panes.insert(id, PaneKind::Editor(crate::editor_pane::EditorPane::new_empty(id)));
+        app.focused = Some(id);
+        app.modal.problems = Some(crate::ui_state::ProblemsPanelState::new(0));
+        assert_eq!(app.text_input_target(), TextInputTarget::Consumed);
+    }
+
     #[test]
     fn git_switcher_overrides_pane() {
         let mut app = test_app();
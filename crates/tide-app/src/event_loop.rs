@@ -37,7 +37,13 @@ impl App {
         // become stale if the shell process is killed mid-rehash (e.g. on app quit).
         cleanup_stale_shell_locks();
 
-        if is_crash {
+        // Serve the scripting socket first: every shell inherits TIDE_SOCKET.
+        self.start_ipc_server();
+
+        // Saved scrollback belongs to the saved panes, so restore the full layout.
+        let restore_layout = is_crash || (self.settings.persist_scrollback && saved_session.is_some());
+
+        if restore_layout {
             // In crash recovery, skip pre-spawning a shell: restore_from_session
             // will create its own terminals, and the pre-spawned shell would just
             // be killed mid-init (potentially leaving pyenv-rehash locks).
@@ -165,6 +171,24 @@ impl App {
             }
         }
 
+        // Visual bell flash ending
+        if let Some(at) = self.next_visual_bell_end() {
+            if at > now {
+                timeout = timeout.min(at - now);
+            } else {
+                return Duration::ZERO;
+            }
+        }
+
+        // Toast expiring
+        if let Some(at) = self.toast_end() {
+            if at > now {
+                timeout = timeout.min(at - now);
+            } else {
+                return Duration::ZERO;
+            }
+        }
+
         // Badge check
         if let Some(at) = self.badge_check_at {
             if at > now {
@@ -221,8 +245,8 @@ impl App {
                 return;
             }
             PlatformEvent::CloseRequested => {
-                let session = session::Session::from_app(self);
-                session::save_session(&session);
+                self.save_session_on_exit();
+                self.stop_ipc_server();
                 session::delete_running_marker();
                 std::process::exit(0);
             }
@@ -284,6 +308,7 @@ impl App {
                 }
             }
             PlatformEvent::Focused(focused) => {
+                self.window_focused = focused;
                 if focused {
                     self.modifiers = tide_core::Modifiers::default();
                     // windowDidBecomeKey may have changed the actual first
@@ -370,7 +395,9 @@ impl App {
                 self.cursor_blink_at = Instant::now();
                 self.cursor_visible = true;
             }
-            PlatformEvent::KeyUp { .. } => {}
+            PlatformEvent::KeyUp { key, modifiers } => {
+                self.handle_key_up(key, modifiers);
+            }
             PlatformEvent::MouseDown { button, position } => {
                 let pos = self.physical_to_logical(position);
                 self.last_cursor_pos = pos;
@@ -584,6 +611,24 @@ impl App {
             }
         }
 
+        // Bell and attention badges
+        self.poll_attention();
+        if std::mem::take(&mut self.bell_pending) {
+            window.beep();
+        }
+
+        // OSC 52 clipboard requests
+        self.poll_clipboard_requests();
+
+        // Output triggers (highlights are painted by the sync thread)
+        self.poll_triggers();
+
+        // tmux control mode sessions
+        self.poll_tmux();
+
+        // Requests from `tide` clients
+        self.poll_ipc();
+
         // Update IME cursor area
         self.update_ime_cursor_area(window);
     }
@@ -143,19 +143,33 @@ impl App {
 
         for id in &pane_ids {
             if let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(id) {
-                // CWD (reads /proc or sysctl — no subprocess)
-                let new_cwd = pane.backend.detect_cwd_fallback();
+                // Program-set title (OSC 0/1/2)
+                let format = &self.settings.terminal_title;
+                pane.context.sync_title(&pane.backend, format);
+
+                // CWD (reads /proc or sysctl — no subprocess); a path in the
+                // title stands in when that fails
+                let new_cwd = pane.backend.detect_cwd_fallback().or_else(|| pane.context.title_dir());
                 if new_cwd != pane.context.cwd {
                     pane.context.cwd = new_cwd;
                     changed = true;
                 }
 
+                if pane.context.update_display_title(format) {
+                    changed = true;
+                }
+
                 // Shell idle
                 let new_idle = pane.backend.is_shell_idle();
                 if new_idle != pane.context.shell_idle {
                     pane.context.shell_idle = new_idle;
                     changed = true;
                 }
+
+                // Finished commands (exit status + duration badges)
+                if pane.context.sync_commands(&pane.backend) {
+                    changed = true;
+                }
             }
         }
 
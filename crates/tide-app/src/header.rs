@@ -2,12 +2,14 @@
 // When a TabGroup has multiple tabs, renders a tab bar instead of single-pane header.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
-use tide_core::{PaneId, Rect, Renderer, TextStyle, Vec2};
+use tide_core::{PaneId, Rect, Renderer, TextStyle, UnderlineStyle, Vec2};
 use tide_layout::TabGroup;
 use tide_renderer::WgpuRenderer;
+use tide_terminal::CommandStatus;
 
-use crate::pane::PaneKind;
+use crate::pane::{PaneKind, TerminalContext};
 use crate::theme::*;
 
 /// Clickable zone within a pane header.
@@ -89,6 +91,55 @@ pub(crate) fn editor_header_badges(ep: &crate::editor_pane::EditorPane) -> Vec<E
     badges
 }
 
+/// Compact command duration: "850ms", "4.2s", "3m 05s", "1h 02m".
+pub(crate) fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs < 1 {
+        format!("{}ms", d.as_millis())
+    } else if secs < 60 {
+        format!("{:.1}s", d.as_secs_f32())
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+/// Text for the last-command badge: check + runtime, or cross + exit code + runtime.
+pub(crate) fn command_badge_text(status: &CommandStatus) -> String {
+    let duration = format_duration(status.duration);
+    match status.exit_code {
+        Some(code) if code != 0 => format!("\u{f00d} {} {}", code, duration),
+        _ => format!("\u{f00c} {}", duration),
+    }
+}
+
+/// Render the last command's exit status badge right-aligned at `badge_right`.
+/// Returns the new `badge_right`.
+fn render_command_badge(
+    ctx: &TerminalContext,
+    badge_right: f32,
+    content_left: f32,
+    text_y: f32,
+    p: &ThemePalette,
+    renderer: &mut WgpuRenderer,
+) -> f32 {
+    let Some(status) = ctx.last_command_result() else {
+        return badge_right;
+    };
+    let cell_size = renderer.cell_size();
+    let text = command_badge_text(status);
+    let color = if status.failed() { p.command_failed } else { p.command_ok };
+    let bg = tide_core::Color::new(color.r, color.g, color.b, 0.094);
+    let badge_w = text.chars().count() as f32 * cell_size.width + BADGE_PADDING_H * 2.0;
+    let badge_x = badge_right - badge_w;
+    if badge_x > content_left + 60.0 {
+        render_badge_colored(renderer, badge_x, text_y, badge_w, cell_size.height, &text, color, bg, BADGE_RADIUS);
+        return badge_x - BADGE_GAP;
+    }
+    badge_right
+}
+
 /// Render the header for a pane (or tab bar for multi-tab groups).
 /// When `tab_group` has more than 1 tab, renders a tab bar.
 /// Otherwise renders the single-pane header as before.
@@ -100,12 +151,13 @@ pub fn render_pane_header(
     focused: Option<PaneId>,
     tab_group: Option<&TabGroup>,
     is_zoomed: bool,
+    broadcasting: &[PaneId],
     p: &ThemePalette,
     renderer: &mut WgpuRenderer,
 ) -> Vec<HeaderHitZone> {
     // Always render tab bar style (consistent look whether 1 tab or many)
     if let Some(tg) = tab_group {
-        return render_tab_bar(tg, rect, panes, focused, is_zoomed, p, renderer);
+        return render_tab_bar(tg, rect, panes, focused, is_zoomed, broadcasting, p, renderer);
     }
 
     let mut zones = Vec::new();
@@ -139,7 +191,7 @@ pub fn render_pane_header(
         let close_style = TextStyle {
             foreground: close_color,
             background: None,
-            bold: false, dim: false, italic: false, underline: false,
+            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
         };
         renderer.draw_chrome_text(
             close_icon_str,
@@ -161,7 +213,7 @@ pub fn render_pane_header(
         let max_style = TextStyle {
             foreground: p.close_icon,
             background: None,
-            bold: false, dim: false, italic: false, underline: false,
+            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
         };
         renderer.draw_chrome_text(
             "\u{f065}", // expand icon
@@ -184,6 +236,10 @@ pub fn render_pane_header(
     // Determine title and badges based on pane kind
     match panes.get(&id) {
         Some(PaneKind::Terminal(pane)) => {
+            if broadcasting.contains(&id) {
+                badge_right = render_broadcast_badge(badge_right, content_left, text_y, p, renderer);
+            }
+
             // Dead process badge
             if pane.context.child_dead {
                 let dead_text = "exited";
@@ -195,6 +251,20 @@ pub fn render_pane_header(
                 }
             }
 
+            // Recording badge (asciicast capture in progress)
+            if pane.backend.is_recording() {
+                let rec_text = "\u{f111} REC";
+                let rec_w = rec_text.chars().count() as f32 * cell_size.width + BADGE_PADDING_H * 2.0;
+                let rec_x = badge_right - rec_w;
+                if rec_x > content_left + 40.0 {
+                    render_badge_colored(renderer, rec_x, text_y, rec_w, cell_height, rec_text, p.badge_deleted, badge_bg, BADGE_RADIUS);
+                    badge_right = rec_x - BADGE_GAP;
+                }
+            }
+
+            // Last command exit status + runtime (shell integration)
+            badge_right = render_command_badge(&pane.context, badge_right, content_left, text_y, p, renderer);
+
             // Git status badge — green tinted, focused pane only (per Tide.pen)
             if is_focused {
                 if let Some(ref git) = pane.context.git_info {
@@ -242,7 +312,9 @@ pub fn render_pane_header(
             }
 
             // Title: plain text label (not a badge)
-            let title = if let Some(ref cwd) = pane.context.cwd {
+            let title = if let Some(ref title) = pane.context.display_title {
+                title.clone()
+            } else if let Some(ref cwd) = pane.context.cwd {
                 let dir_name = cwd.file_name()
                     .map(|n| n.to_string_lossy().to_string())
                     .unwrap_or_else(|| cwd.display().to_string());
@@ -263,7 +335,9 @@ pub fn render_pane_header(
                 bold: false,
                 dim: false,
                 italic: false,
-                underline: false,
+                underline: UnderlineStyle::None,
+                underline_color: None,
+                strikethrough: false,
             };
             let title_w = ((title.chars().count() as f32 + 1.0) * cell_size.width)
                 .min(badge_right - content_left);
@@ -379,6 +453,25 @@ pub fn render_pane_header(
                 render_badge_colored(renderer, content_left, text_y, title_w, cell_height, title, diff_text_color, badge_bg, BADGE_RADIUS);
             }
         }
+        Some(PaneKind::Replay(rp)) => {
+            let replay_text_color = if is_focused { p.badge_text } else { p.tab_text };
+            // Playback status: play state, position / duration, speed
+            let status = rp.status_text();
+            let status_w = status.chars().count() as f32 * cell_size.width + BADGE_PADDING_H * 2.0;
+            let status_x = badge_right - status_w;
+            if status_x > content_left + 60.0 {
+                render_badge_colored(renderer, status_x, text_y, status_w, cell_height, &status, replay_text_color, badge_bg, BADGE_RADIUS);
+                badge_right = status_x - BADGE_GAP;
+            }
+
+            // Title badge: recording file name
+            let title = format!("\u{f03d} {}", rp.title());
+            let title_w = (title.chars().count() as f32 * cell_size.width + BADGE_PADDING_H * 2.0)
+                .min(badge_right - content_left);
+            if title_w > 20.0 {
+                render_badge_colored(renderer, content_left, text_y, title_w, cell_height, &title, replay_text_color, badge_bg, BADGE_RADIUS);
+            }
+        }
         Some(PaneKind::Launcher(_)) => {
             let title = "New Tab";
             let title_color = if is_focused { p.tab_text_focused } else { p.tab_text };
@@ -396,12 +489,14 @@ pub fn render_pane_header(
 
 /// Render a tab bar for a TabGroup with multiple tabs.
 /// Each tab shows: icon + name + close(x). Active tab has accent underline.
+#[allow(clippy::too_many_arguments)]
 fn render_tab_bar(
     tg: &TabGroup,
     rect: Rect,
     panes: &HashMap<PaneId, PaneKind>,
     focused: Option<PaneId>,
     is_zoomed: bool,
+    broadcasting: &[PaneId],
     p: &ThemePalette,
     renderer: &mut WgpuRenderer,
 ) -> Vec<HeaderHitZone> {
@@ -424,7 +519,7 @@ fn render_tab_bar(
         let max_style = TextStyle {
             foreground: if is_zoomed { p.badge_git_branch } else { p.close_icon },
             background: None,
-            bold: false, dim: false, italic: false, underline: false,
+            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
         };
         renderer.draw_chrome_text(
             max_icon,
@@ -443,6 +538,25 @@ fn render_tab_bar(
     let badge_bg = if is_group_focused { p.badge_bg } else { p.badge_bg_unfocused };
     let mut badge_right = max_x - BADGE_GAP;
 
+    // Attention badge: terminals in this group that rang the bell or sent a
+    // notification since they were last focused
+    let attention = tg.tabs.iter()
+        .filter(|id| matches!(panes.get(id), Some(PaneKind::Terminal(t)) if t.context.attention))
+        .count();
+    if attention > 0 {
+        let text = format!("\u{f0f3} {}", attention);
+        let badge_w = text.chars().count() as f32 * cell_size.width + BADGE_PADDING_H * 2.0;
+        let badge_x = badge_right - badge_w;
+        if badge_x > content_left + 40.0 {
+            render_badge_colored(renderer, badge_x, text_y, badge_w, cell_height, &text, p.attention, badge_bg, BADGE_RADIUS);
+            badge_right = badge_x - BADGE_GAP;
+        }
+    }
+
+    if broadcasting.contains(&active_pane) {
+        badge_right = render_broadcast_badge(badge_right, content_left, text_y, p, renderer);
+    }
+
     if let Some(PaneKind::Terminal(pane)) = panes.get(&active_pane) {
         // Dead process badge
         if pane.context.child_dead {
@@ -455,6 +569,9 @@ fn render_tab_bar(
             }
         }
 
+        // Last command exit status + runtime (shell integration)
+        badge_right = render_command_badge(&pane.context, badge_right, content_left, text_y, p, renderer);
+
         // Git status badge (e.g. "3 +10 -2") — only when focused
         if is_group_focused {
             if let Some(ref git) = pane.context.git_info {
@@ -610,7 +727,7 @@ fn render_tab_bar(
         let style = TextStyle {
             foreground: text_color,
             background: None,
-            bold: false, dim: false, italic: false, underline: false,
+            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
         };
 
         // Clip rect: intersect tab rect with the visible tab area
@@ -619,12 +736,24 @@ fn render_tab_bar(
         let clip_w = (clip_right - clip_left).max(0.0);
         let clip = Rect::new(clip_left, rect.y, clip_w, TAB_BAR_HEIGHT);
 
-        // Draw icon
+        // Draw icon — terminal tabs tint it with the last command's outcome
+        // so failures in background tabs stand out (a bell when they need
+        // attention, the broadcast color while mirroring input).
+        let icon_style = match panes.get(&tab_id) {
+            Some(PaneKind::Terminal(pane)) if pane.context.attention => TextStyle { foreground: p.attention, ..style },
+            Some(PaneKind::Terminal(_)) if broadcasting.contains(&tab_id) => TextStyle { foreground: p.broadcast, ..style },
+            Some(PaneKind::Terminal(pane)) => match pane.context.last_command_result() {
+                Some(status) if status.failed() => TextStyle { foreground: p.command_failed, ..style },
+                Some(_) => TextStyle { foreground: p.command_ok, ..style },
+                None => style,
+            },
+            _ => style,
+        };
         let icon_x = tab_x + BADGE_PADDING_H;
         renderer.draw_chrome_text(
             &icon,
             Vec2::new(icon_x, text_y),
-            style,
+            icon_style,
             clip,
         );
 
@@ -651,7 +780,7 @@ fn render_tab_bar(
         let close_style = TextStyle {
             foreground: close_icon_color,
             background: None,
-            bold: false, dim: false, italic: false, underline: false,
+            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
         };
         renderer.draw_chrome_text(
             close_icon_str,
@@ -693,6 +822,7 @@ fn render_tab_bar(
 /// Get the icon character for a pane (used in tab bar labels).
 fn tab_icon(panes: &HashMap<PaneId, PaneKind>, id: PaneId) -> String {
     match panes.get(&id) {
+        Some(PaneKind::Terminal(tp)) if tp.context.attention => "\u{f0f3}".to_string(), // bell icon
         Some(PaneKind::Terminal(_)) => "\u{f120}".to_string(), // terminal icon
         Some(PaneKind::Editor(ep)) => {
             let name = ep.title();
@@ -701,11 +831,33 @@ fn tab_icon(panes: &HashMap<PaneId, PaneKind>, id: PaneId) -> String {
         }
         Some(PaneKind::Diff(_)) => "\u{f126}".to_string(), // code-fork icon
         Some(PaneKind::Browser(_)) => "\u{f0ac}".to_string(), // globe icon
+        Some(PaneKind::Replay(_)) => "\u{f03d}".to_string(), // video camera icon
         Some(PaneKind::Launcher(_)) => "+".to_string(), // plus icon for launcher
         None => "\u{f15b}".to_string(), // generic file icon
     }
 }
 
+/// Render the "broadcast" badge right-aligned at `badge_right`.
+/// Returns the new `badge_right`.
+fn render_broadcast_badge(
+    badge_right: f32,
+    content_left: f32,
+    text_y: f32,
+    p: &ThemePalette,
+    renderer: &mut WgpuRenderer,
+) -> f32 {
+    let cell_size = renderer.cell_size();
+    let text = "\u{f1eb} broadcast";
+    let bg = tide_core::Color::new(p.broadcast.r, p.broadcast.g, p.broadcast.b, 0.094);
+    let badge_w = text.chars().count() as f32 * cell_size.width + BADGE_PADDING_H * 2.0;
+    let badge_x = badge_right - badge_w;
+    if badge_x > content_left + 40.0 {
+        render_badge_colored(renderer, badge_x, text_y, badge_w, cell_size.height, text, p.broadcast, bg, BADGE_RADIUS);
+        return badge_x - BADGE_GAP;
+    }
+    badge_right
+}
+
 /// Render a badge pill with custom background color.
 pub(crate) fn render_badge_colored(
     renderer: &mut WgpuRenderer,
@@ -731,7 +883,9 @@ pub(crate) fn render_badge_colored(
         bold: false,
         dim: false,
         italic: false,
-        underline: false,
+        underline: UnderlineStyle::None,
+        underline_color: None,
+        strikethrough: false,
     };
     renderer.draw_chrome_text(
         text,
@@ -758,6 +912,41 @@ mod tests {
         ep
     }
 
+    fn status(exit_code: Option<i32>, millis: u64) -> CommandStatus {
+        CommandStatus {
+            exit_code,
+            started_at: std::time::SystemTime::UNIX_EPOCH,
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn format_duration_scales_units() {
+        assert_eq!(format_duration(Duration::from_millis(850)), "850ms");
+        assert_eq!(format_duration(Duration::from_millis(4_240)), "4.2s");
+        assert_eq!(format_duration(Duration::from_secs(185)), "3m 05s");
+        assert_eq!(format_duration(Duration::from_secs(3_720)), "1h 02m");
+    }
+
+    #[test]
+    fn command_badge_shows_exit_code_only_on_failure() {
+        assert_eq!(command_badge_text(&status(Some(0), 1_500)), "\u{f00c} 1.5s");
+        assert_eq!(command_badge_text(&status(None, 1_500)), "\u{f00c} 1.5s");
+        assert_eq!(command_badge_text(&status(Some(2), 65_000)), "\u{f00d} 2 1m 05s");
+        assert!(status(Some(130), 0).failed());
+        assert!(!status(None, 0).failed());
+    }
+
+    #[test]
+    fn command_result_hidden_while_running() {
+        let mut ctx = TerminalContext::default();
+        assert!(ctx.last_command_result().is_none());
+        ctx.commands.push(status(Some(1), 10));
+        assert!(ctx.last_command_result().is_some_and(|s| s.failed()));
+        ctx.shell_idle = false;
+        assert!(ctx.last_command_result().is_none());
+    }
+
     #[test]
     fn plain_file_no_badges() {
         let ep = make_editor(1);
@@ -0,0 +1,458 @@
+// Scripting socket: the running app serves a Unix domain socket that the
+// `tide` command line client (cli.rs) talks to. Each connection carries one
+// request and one response, both a line of JSON. Connections are served on
+// background threads; requests are handed to the app thread over a channel
+// and answered from `poll_ipc`, like the other background sources.
+//
+// Shells started by Tide find the socket through `TIDE_SOCKET`, and `tide`
+// on their PATH runs this binary as the client. `tide-editor`, exported as
+// `EDITOR` and `GIT_EDITOR`, is `tide open --wait`: the response to its open
+// is held until the editor tab closes.
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tide_core::{PaneId, SplitDirection, TerminalBackend};
+
+use crate::action::LauncherChoice;
+use crate::cli::EDITOR_SHIM;
+use crate::pane::PaneKind;
+use crate::App;
+
+/// Environment variable naming the socket of the app a shell runs in.
+pub(crate) const SOCKET_ENV: &str = "TIDE_SOCKET";
+
+/// One request from the `tide` client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub(crate) enum Request {
+    /// Open a file in an editor pane, optionally at a 1-based line and column,
+    /// next to the terminal whose `TIDE_TERMINAL_ID` is `terminal`. With
+    /// `wait`, the response comes when the tab closes.
+    Open {
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+        terminal: Option<u64>,
+        #[serde(default)]
+        wait: bool,
+    },
+    /// Split the focused pane into a new terminal, typing `command` into it.
+    Split {
+        #[serde(default)]
+        horizontal: bool,
+        command: Option<String>,
+    },
+    /// Write text to a terminal of the active workspace (the focused one by default).
+    Send { pane: Option<PaneId>, text: String },
+    /// Workspaces and their panes.
+    List,
+    /// Switch to a workspace by 1-based number or name.
+    Workspace { target: String },
+    /// Run a `GlobalAction` by its action key (e.g. "ToggleZoom").
+    Action { key: String },
+}
+
+/// Reply to a request: `data` on success, `error` on failure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl Response {
+    pub(crate) fn ok(data: Option<serde_json::Value>) -> Self {
+        Self { ok: true, error: None, data }
+    }
+
+    pub(crate) fn error(message: impl Into<String>) -> Self {
+        Self { ok: false, error: Some(message.into()), data: None }
+    }
+}
+
+/// A request waiting for the app thread, with the channel its reply goes back on.
+pub(crate) struct IpcCall {
+    pub request: Request,
+    pub reply: mpsc::Sender<Response>,
+}
+
+/// A `tide open --wait` client waiting for its editor tab to close.
+pub(crate) struct IpcWait {
+    pub path: PathBuf,
+    /// The terminal it ran in, focused again when the tab closes.
+    pub terminal: Option<u64>,
+    pub reply: mpsc::Sender<Response>,
+}
+
+/// Socket the app serves: `$TIDE_SOCKET`, or `tide.sock` in `private_dir()`.
+pub(crate) fn socket_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(SOCKET_ENV) {
+        return PathBuf::from(path);
+    }
+    private_dir().join("tide.sock")
+}
+
+/// Directory for the socket and the command links: `tide` in the per-user
+/// runtime directory, or `tide-<uid>` in the temp directory where there is
+/// none (shared with other users on Linux).
+fn private_dir() -> PathBuf {
+    match dirs::runtime_dir() {
+        Some(dir) => dir.join("tide"),
+        None => std::env::temp_dir().join(format!("tide-{}", unsafe { libc::getuid() })),
+    }
+}
+
+/// Create `dir` (0700) if needed, and check that nobody else can change it:
+/// a directory another user made first, or a link to one, is refused.
+fn ensure_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+    std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+    let meta = std::fs::symlink_metadata(dir)?;
+    if !meta.is_dir() || meta.uid() != unsafe { libc::getuid() } || meta.mode() & 0o022 != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{} is not a private directory of this user", dir.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// Bind the socket at `path`, replacing a stale one left by an app that
+/// didn't exit cleanly. Fails if another app is serving it, or if its
+/// directory isn't private to the user.
+pub(crate) fn bind(path: &Path) -> std::io::Result<UnixListener> {
+    if let Some(parent) = path.parent() {
+        ensure_private_dir(parent)?;
+    }
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                format!("another Tide is serving {}", path.display()),
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// Accept connections on `listener` until the app exits. Requests arrive on
+/// the returned channel; `waker` wakes the app thread for each.
+pub(crate) fn serve(
+    listener: UnixListener,
+    waker: Option<tide_platform::WakeCallback>,
+) -> mpsc::Receiver<IpcCall> {
+    let (tx, rx) = mpsc::channel();
+    let _ = std::thread::Builder::new().name("ipc".into()).spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let tx = tx.clone();
+            let waker = waker.clone();
+            let _ = std::thread::Builder::new().name("ipc-client".into()).spawn(move || {
+                if let Err(e) = serve_client(stream, &tx, waker.as_ref()) {
+                    log::debug!("IPC client: {}", e);
+                }
+            });
+        }
+    });
+    rx
+}
+
+fn serve_client(
+    stream: UnixStream,
+    tx: &mpsc::Sender<IpcCall>,
+    waker: Option<&tide_platform::WakeCallback>,
+) -> std::io::Result<()> {
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    let response = match serde_json::from_str::<Request>(&line) {
+        Ok(request) => {
+            let (reply, reply_rx) = mpsc::channel();
+            if tx.send(IpcCall { request, reply }).is_err() {
+                return Ok(());
+            }
+            if let Some(waker) = waker {
+                waker();
+            }
+            // Blocks until the app thread answers (or drops the call)
+            reply_rx.recv().unwrap_or_else(|_| Response::error("Tide is shutting down"))
+        }
+        Err(e) => Response::error(format!("bad request: {}", e)),
+    };
+    let mut out = serde_json::to_string(&response).map_err(std::io::Error::other)?;
+    out.push('\n');
+    (&stream).write_all(out.as_bytes())
+}
+
+/// Send `request` to the app serving `path` and wait for its response.
+pub(crate) fn request(path: &Path, request: &Request) -> std::io::Result<Response> {
+    let mut stream = UnixStream::connect(path)?;
+    let mut line = serde_json::to_string(request).map_err(std::io::Error::other)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply)?;
+    serde_json::from_str(&reply).map_err(std::io::Error::other)
+}
+
+/// Put `tide` and `tide-editor` links to this executable in a directory of
+/// their own, for the PATH of spawned shells. A bare `tide` next to `Tide`
+/// would clash on case-insensitive file systems. The directory goes at the
+/// front of PATH, so it must be private to the user.
+fn install_cli_links(dir: &Path) -> std::io::Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    ensure_private_dir(dir)?;
+    for name in ["tide", EDITOR_SHIM] {
+        let link = dir.join(name);
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&exe, &link)?;
+    }
+    Ok(dir.to_path_buf())
+}
+
+impl App {
+    /// Serve the scripting socket and point shells started from now on at it.
+    /// Called before any terminal is spawned so they inherit the environment.
+    pub(crate) fn start_ipc_server(&mut self) {
+        let path = socket_path();
+        let listener = match bind(&path) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::warn!("Scripting socket unavailable: {}", e);
+                return;
+            }
+        };
+        self.ipc_rx = Some(serve(listener, self.event_loop_waker.clone()));
+        std::env::set_var(SOCKET_ENV, &path);
+        let bin_dir = path.with_file_name("tide-bin");
+        match install_cli_links(&bin_dir) {
+            Ok(dir) => {
+                if self.settings.use_as_editor {
+                    std::env::set_var("EDITOR", dir.join(EDITOR_SHIM));
+                    std::env::set_var("GIT_EDITOR", dir.join(EDITOR_SHIM));
+                }
+                let mut paths = vec![dir];
+                paths.extend(std::env::var_os("PATH").iter().flat_map(std::env::split_paths));
+                if let Ok(joined) = std::env::join_paths(paths) {
+                    std::env::set_var("PATH", joined);
+                }
+            }
+            Err(e) => log::warn!("Failed to link the tide command in {:?}: {}", bin_dir, e),
+        }
+    }
+
+    /// Remove the socket on exit so clients fail fast instead of hanging.
+    pub(crate) fn stop_ipc_server(&mut self) {
+        if self.ipc_rx.take().is_some() {
+            let _ = std::fs::remove_file(socket_path());
+        }
+    }
+
+    /// Answer requests from `tide` clients.
+    pub(crate) fn poll_ipc(&mut self) {
+        let Some(rx) = &self.ipc_rx else { return };
+        let calls: Vec<IpcCall> = rx.try_iter().collect();
+        for call in calls {
+            let wait = match &call.request {
+                Request::Open { path, terminal, wait: true, .. } => Some((path.clone(), *terminal)),
+                _ => None,
+            };
+            let response = self.handle_ipc_request(call.request);
+            match wait {
+                Some((path, terminal)) if response.ok => {
+                    self.ipc_waits.push(IpcWait { path, terminal, reply: call.reply });
+                }
+                _ => {
+                    let _ = call.reply.send(response);
+                }
+            }
+            self.cache.needs_redraw = true;
+        }
+    }
+
+    /// Answer the `tide open --wait` clients waiting on `path`, whose tab
+    /// just closed: success if it was saved, an error if changes were
+    /// discarded. The terminal each was started from gets focus back.
+    pub(crate) fn finish_ipc_waits(&mut self, path: &Path, saved: bool) {
+        let (done, waiting): (Vec<IpcWait>, Vec<IpcWait>) =
+            std::mem::take(&mut self.ipc_waits).into_iter().partition(|wait| wait.path == path);
+        self.ipc_waits = waiting;
+        for wait in done {
+            let response = if saved {
+                Response::ok(None)
+            } else {
+                Response::error(format!("{} was closed without saving", path.display()))
+            };
+            let _ = wait.reply.send(response);
+            if let Some((ws, id)) = wait.terminal.and_then(|t| self.find_terminal(t)) {
+                if ws == self.ws.active {
+                    self.layout.set_active_tab(id);
+                    self.focus_terminal(id);
+                }
+            }
+        }
+    }
+
+    /// Workspace index and pane id of the terminal with `terminal_id`.
+    fn find_terminal(&self, terminal_id: u64) -> Option<(usize, PaneId)> {
+        let is_it = |pane: &PaneKind| {
+            matches!(pane, PaneKind::Terminal(tp) if tp.backend.terminal_id() == Some(terminal_id))
+        };
+        if let Some((&id, _)) = self.panes.iter().find(|(_, pane)| is_it(pane)) {
+            return Some((self.ws.active, id));
+        }
+        self.ws.workspaces.iter().enumerate()
+            .filter(|&(i, _)| i != self.ws.active)
+            .find_map(|(i, ws)| ws.panes.iter().find(|(_, pane)| is_it(pane)).map(|(&id, _)| (i, id)))
+    }
+
+    pub(crate) fn handle_ipc_request(&mut self, request: Request) -> Response {
+        match request {
+            Request::Open { path, line, column, terminal, .. } => {
+                // Open next to the terminal the client runs in
+                if let Some((ws, id)) = terminal.and_then(|t| self.find_terminal(t)) {
+                    self.switch_workspace(ws);
+                    self.layout.set_active_tab(id);
+                    self.focus_terminal(id);
+                }
+                if self.focused.is_none() {
+                    return Response::error("no focused pane to open the file next to");
+                }
+                if !path.is_file() {
+                    return Response::error(format!("no such file: {}", path.display()));
+                }
+                self.open_editor_pane_at(path.clone(), line, column);
+                match self.focused {
+                    Some(id) if matches!(
+                        self.panes.get(&id),
+                        Some(PaneKind::Editor(e)) if e.editor.file_path() == Some(path.as_path())
+                    ) => Response::ok(Some(json!({ "pane": id }))),
+                    _ => Response::error(format!("failed to open {}", path.display())),
+                }
+            }
+            Request::Split { horizontal, command } => {
+                let Some(focused) = self.focused else {
+                    return Response::error("no focused pane to split");
+                };
+                let direction = if horizontal { SplitDirection::Horizontal } else { SplitDirection::Vertical };
+                self.split_with_launcher(direction);
+                let new_id = match self.focused {
+                    Some(id) if id != focused && matches!(self.panes.get(&id), Some(PaneKind::Launcher(_))) => id,
+                    // tmux splits its own panes; the new one arrives with the next layout
+                    _ if command.is_none() => return Response::ok(None),
+                    _ => return Response::error("can't run a command in this split"),
+                };
+                self.resolve_launcher(new_id, LauncherChoice::Terminal);
+                let Some(PaneKind::Terminal(tp)) = self.panes.get_mut(&new_id) else {
+                    return Response::error("failed to start a terminal");
+                };
+                if let Some(command) = command {
+                    // The shell reads it once its prompt is up
+                    tp.backend.write(format!("{}\r", command).as_bytes());
+                }
+                Response::ok(Some(json!({ "pane": new_id })))
+            }
+            Request::Send { pane, text } => {
+                let Some(id) = pane.or(self.focused) else {
+                    return Response::error("no focused pane");
+                };
+                match self.panes.get_mut(&id) {
+                    Some(PaneKind::Terminal(tp)) => {
+                        tp.backend.write(text.as_bytes());
+                        Response::ok(None)
+                    }
+                    Some(_) => Response::error(format!("pane {} is not a terminal", id)),
+                    None => Response::error(format!("no pane {} in the active workspace", id)),
+                }
+            }
+            Request::List => Response::ok(Some(self.ipc_list())),
+            Request::Workspace { target } => {
+                let index = match target.parse::<usize>() {
+                    Ok(n) => n.checked_sub(1),
+                    Err(_) => self.ws.workspaces.iter().position(|ws| ws.name == target),
+                };
+                match index.filter(|&i| i < self.ws.workspaces.len()) {
+                    Some(i) => {
+                        self.switch_workspace(i);
+                        Response::ok(None)
+                    }
+                    None => Response::error(format!("no workspace {:?}", target)),
+                }
+            }
+            Request::Action { key } => match tide_input::GlobalAction::from_action_key(&key) {
+                Some(action) => {
+                    self.handle_global_action(action);
+                    Response::ok(None)
+                }
+                None => Response::error(format!("unknown action {:?}", key)),
+            },
+        }
+    }
+
+    /// Workspaces (numbered from 1) with their panes in layout order.
+    fn ipc_list(&self) -> serde_json::Value {
+        let describe = |layout: &tide_layout::SplitLayout, panes: &std::collections::HashMap<PaneId, PaneKind>, focused: Option<PaneId>| {
+            layout.pane_ids().into_iter().filter_map(|id| {
+                let pane = panes.get(&id)?;
+                let mut entry = json!({
+                    "id": id,
+                    "kind": pane_kind_name(pane),
+                    "title": crate::ui::pane_title(panes, id),
+                    "focused": focused == Some(id),
+                });
+                match pane {
+                    PaneKind::Terminal(tp) => {
+                        entry["cwd"] = json!(tp.backend.detect_cwd_fallback());
+                    }
+                    PaneKind::Editor(ep) => {
+                        entry["path"] = json!(ep.editor.file_path());
+                        entry["modified"] = json!(ep.editor.is_modified());
+                    }
+                    _ => {}
+                }
+                Some(entry)
+            }).collect::<Vec<_>>()
+        };
+        let workspaces: Vec<_> = if self.ws.workspaces.is_empty() {
+            vec![json!({
+                "index": 1,
+                "name": "Workspace 1",
+                "active": true,
+                "panes": describe(&self.layout, &self.panes, self.focused),
+            })]
+        } else {
+            self.ws.workspaces.iter().enumerate().map(|(i, ws)| {
+                let active = i == self.ws.active;
+                // The active workspace's state lives on App
+                let panes = if active {
+                    describe(&self.layout, &self.panes, self.focused)
+                } else {
+                    describe(&ws.layout, &ws.panes, ws.focused)
+                };
+                json!({ "index": i + 1, "name": ws.name, "active": active, "panes": panes })
+            }).collect()
+        };
+        json!({ "workspaces": workspaces })
+    }
+}
+
+fn pane_kind_name(pane: &PaneKind) -> &'static str {
+    match pane {
+        PaneKind::Terminal(_) => "terminal",
+        PaneKind::Editor(_) => "editor",
+        PaneKind::Diff(_) => "diff",
+        PaneKind::Browser(_) => "browser",
+        PaneKind::Replay(_) => "replay",
+        PaneKind::Launcher(_) => "launcher",
+    }
+}
@@ -16,6 +16,7 @@ impl App {
             | Some(HoverTarget::PaneTabBar(_))
             | Some(HoverTarget::PaneTabClose(_))
             | Some(HoverTarget::FileFinderItem(_))
+            | Some(HoverTarget::ProblemsItem(_))
             | Some(HoverTarget::TitlebarSwap)
             | Some(HoverTarget::TitlebarSettings)
             | Some(HoverTarget::TitlebarTheme)
@@ -62,6 +63,26 @@ impl App {
         }
     }
 
+    /// Check if a position is on a problems panel row. Returns the index into the problem list.
+    pub(crate) fn problems_item_at(&self, pos: tide_core::Vec2) -> Option<usize> {
+        let panel = self.modal.problems.as_ref()?;
+        let cell_size = self.cell_size();
+        let logical = self.logical_size();
+        let count = self.problems.items.len();
+        let geo = panel.geometry(count, cell_size.height, logical.width, logical.height);
+
+        if pos.y < geo.list_top || pos.x < geo.popup_x || pos.x > geo.popup_x + geo.popup_w {
+            return None;
+        }
+
+        let vi = ((pos.y - geo.list_top) / geo.line_height) as usize;
+        if vi >= geo.max_visible {
+            return None;
+        }
+        let idx = vi + panel.scroll_offset;
+        (idx < count).then_some(idx)
+    }
+
     /// Hit-test the git switcher popup. Returns the filtered index of the item under pos.
     pub(crate) fn git_switcher_item_at(&self, pos: tide_core::Vec2) -> Option<usize> {
         let gs = self.modal.git_switcher.as_ref()?;
@@ -284,6 +305,18 @@ impl App {
         false
     }
 
+    /// Check if a position is inside the problems panel.
+    pub(crate) fn problems_contains(&self, pos: tide_core::Vec2) -> bool {
+        if let Some(ref panel) = self.modal.problems {
+            let cell_size = self.cell_size();
+            let logical = self.logical_size();
+            let geo = panel.geometry(self.problems.items.len(), cell_size.height, logical.width, logical.height);
+            let popup_rect = Rect::new(geo.popup_x, geo.popup_y, geo.popup_w, geo.popup_h);
+            return popup_rect.contains(pos);
+        }
+        false
+    }
+
     /// Check if a position is inside the save-as popup area.
     /// Uses the anchor_rect from the save-as input to position the popup.
     pub(crate) fn save_as_contains(&self, pos: tide_core::Vec2) -> bool {
@@ -494,6 +527,10 @@ impl App {
         if !skip_pty_resize {
             let content_top = TAB_BAR_HEIGHT;
             let cell_size = self.cell_size();
+            let cell_pixels = (
+                (cell_size.width * self.scale_factor).round() as u16,
+                (cell_size.height * self.scale_factor).round() as u16,
+            );
             if cell_size.width > 0.0 {
                 for &(id, vr) in &self.visual_pane_rects {
                     if let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&id) {
@@ -503,6 +540,7 @@ impl App {
                             (vr.width - 2.0 * PANE_PADDING).max(cell_size.width),
                             (vr.height - content_top - PANE_PADDING).max(cell_size.height),
                         );
+                        pane.backend.set_cell_pixel_size(cell_pixels.0, cell_pixels.1);
                         pane.resize_to_rect(content_rect, cell_size);
                     }
                 }
@@ -569,7 +607,8 @@ impl App {
             let popup_open = self.modal.file_finder.is_some()
                 || self.modal.save_as_input.is_some()
                 || self.modal.git_switcher.is_some()
-                || self.modal.config_page.is_some();
+                || self.modal.config_page.is_some()
+                || self.modal.problems.is_some();
 
             if let Some(vr) = visual_rect {
                 if popup_open {
@@ -3,7 +3,12 @@
 // layout engine, input router, file tree, and CWD following.
 
 mod action;
+mod attention;
+mod broadcast;
 mod browser_pane;
+mod cli;
+mod clipboard;
+mod copy_mode;
 mod diff;
 mod diff_pane;
 mod drag_drop;
@@ -13,14 +18,20 @@ mod event_loop;
 mod file_tree;
 mod gpu;
 mod header;
+mod ipc;
 mod layout_compute;
 mod pane;
+mod problems;
+mod replay_pane;
 mod render_thread;
 mod rendering;
+mod scrollback;
 mod search;
 mod session;
 mod settings;
 mod theme;
+mod tmux;
+mod triggers;
 mod ui;
 mod ui_state;
 mod update;
@@ -108,6 +119,8 @@ struct App {
     pub(crate) last_frame: Instant,
     /// Last time we checked child process liveness (throttled to ~2s).
     pub(crate) last_child_check: Instant,
+    /// Last time the scrollback memory budget was enforced.
+    pub(crate) last_scrollback_check: Instant,
 
     /// Deferred PTY resize after window resize settles (debounce).
     /// While Some, compute_layout skips PTY resize to avoid SIGWINCH spam.
@@ -160,6 +173,25 @@ struct App {
     pub(crate) is_fullscreen: bool,
     pub(crate) pending_fullscreen_toggle: bool,
     pub(crate) is_occluded: bool,
+    /// Whether the window has keyboard focus (attention isn't cleared without it).
+    pub(crate) window_focused: bool,
+
+    // Bell (see attention.rs)
+    /// Panes showing the visual bell flash, with when it ends.
+    pub(crate) visual_bells: HashMap<PaneId, Instant>,
+    /// Play the system alert sound on the next background poll.
+    pub(crate) bell_pending: bool,
+    /// Short message at the bottom of the window (e.g. a program set the clipboard).
+    pub(crate) toast: Option<ui_state::Toast>,
+    /// Problems parsed from terminal output (see problems.rs).
+    pub(crate) problems: problems::ProblemList,
+    /// Terminals that mirror input typed into one of them (see broadcast.rs).
+    /// Per workspace: swapped with the rest of the workspace state.
+    pub(crate) broadcast: Option<broadcast::BroadcastScope>,
+    /// Requests from `tide` clients on the scripting socket (see ipc.rs).
+    pub(crate) ipc_rx: Option<mpsc::Receiver<ipc::IpcCall>>,
+    /// `tide open --wait` clients waiting for their editor tab to close.
+    pub(crate) ipc_waits: Vec<ipc::IpcWait>,
 
     // Header hit zones (for badge click handling)
     pub(crate) header_hit_zones: Vec<header::HeaderHitZone>,
@@ -253,6 +285,7 @@ impl App {
             badge_check_at: None,
             last_frame: Instant::now(),
             last_child_check: Instant::now(),
+            last_scrollback_check: Instant::now(),
             resize_deferred_at: None,
             ime: ui_state::ImeState::new(),
             pane_rects: Vec::new(),
@@ -274,10 +307,18 @@ impl App {
             is_fullscreen: false,
             pending_fullscreen_toggle: false,
             is_occluded: false,
+            window_focused: true,
+            visual_bells: HashMap::new(),
+            bell_pending: false,
+            toast: None,
+            problems: problems::ProblemList::default(),
+            broadcast: None,
+            ipc_rx: None,
+            ipc_waits: Vec::new(),
             header_hit_zones: Vec::new(),
             focus_area: FocusArea::PaneArea,
             ws: ui_state::WorkspaceManager::new(),
-            settings: settings::load_settings(),
+            settings: settings::load_settings().with_theme_files(),
             file_watcher: None,
             file_watch_rx: None,
             file_watch_dirty: Arc::new(AtomicBool::new(false)),
@@ -342,7 +383,8 @@ impl App {
         };
 
         match result {
-            Ok(pane) => {
+            Ok(mut pane) => {
+                pane.backend.set_word_separators(&self.settings.word_separators);
                 self.install_pty_waker(&pane);
                 self.panes.insert(pane_id, PaneKind::Terminal(pane));
                 self.ime.pending_creates.push(pane_id);
@@ -361,12 +403,7 @@ impl App {
         self.last_cwd = Some(cwd);
 
         // Create the first workspace (placeholder — active data lives on App fields)
-        self.ws.workspaces.push(Workspace {
-            name: "Workspace 1".to_string(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        self.ws.workspaces.push(Workspace::new("Workspace 1", SplitLayout::new()));
         self.ws.active = 0;
     }
 
@@ -430,6 +467,12 @@ impl App {
 // ──────────────────────────────────────────────
 
 fn main() {
+    // Started as the `tide` client: talk to the running app and exit
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     // Enable backtraces for panic diagnostics
     std::env::set_var("RUST_BACKTRACE", "1");
 
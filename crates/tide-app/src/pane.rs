@@ -4,29 +4,39 @@ use std::path::PathBuf;
 
 use unicode_width::UnicodeWidthChar;
 
-use tide_core::{Color, CursorShape, Key, Modifiers, Rect, Renderer, Size, TerminalBackend, Vec2};
-use tide_renderer::WgpuRenderer;
-use tide_terminal::Terminal;
+use tide_core::{Color, CursorShape, Key, Modifiers, Rect, Renderer, Size, TerminalBackend, TerminalGrid, Vec2};
+use tide_renderer::{GridImage, WgpuRenderer};
+use tide_terminal::{CommandStatus, CopyModeView, KeyEventKind, MouseReport, Terminal};
 use tide_terminal::git::GitInfo;
 
 use crate::browser_pane::BrowserPane;
+use crate::copy_mode::CopyModeState;
 use crate::diff_pane::DiffPane;
 use crate::editor_pane::EditorPane;
+use crate::replay_pane::ReplayPane;
 use crate::search::SearchState;
 
 pub type PaneId = tide_core::PaneId;
 
-/// Polymorphic pane: terminal, editor, diff viewer, embedded browser, or launcher.
+/// Bound on `TerminalPane::pressed_keys`.
+const MAX_PRESSED_KEYS: usize = 16;
+
+/// Outline width of the copy mode cursor.
+const COPY_CURSOR_BORDER: f32 = 2.0;
+
+/// Polymorphic pane: terminal, editor, diff viewer, embedded browser, recording replay, or launcher.
 pub enum PaneKind {
     Terminal(TerminalPane),
     Editor(EditorPane),
     Diff(DiffPane),
     Browser(BrowserPane),
+    Replay(ReplayPane),
     /// Launcher: type-selection screen. Press T/E/O/B to create a pane.
     Launcher(PaneId),
 }
 
-/// Text selection state (anchor = drag start, end = current position).
+/// Editor text selection state (anchor = drag start, end = current position).
+/// Terminals keep theirs in the backend (see `Terminal::start_selection`).
 #[derive(Debug, Clone)]
 pub struct Selection {
     pub anchor: (usize, usize), // (row, col)
@@ -47,6 +57,133 @@ pub struct TerminalContext {
     pub worktree_count: usize,
     /// Whether the child shell process has died.
     pub child_dead: bool,
+    /// Finished commands, oldest first (capped at `CommandStatus::HISTORY_LIMIT`).
+    pub commands: Vec<CommandStatus>,
+    /// Backend's finished-command count already copied into `commands`.
+    pub commands_seen: u64,
+    /// Title set by the running program (OSC 0/2).
+    pub title: Option<String>,
+    /// Icon name set by the running program (OSC 1).
+    pub icon_name: Option<String>,
+    /// Foreground process name.
+    pub process: Option<String>,
+    /// Label for tabs, headers and the workspace sidebar, built from
+    /// `TideSettings::terminal_title`. None falls back to the cwd.
+    pub display_title: Option<String>,
+    /// Bell or desktop notification (OSC 9/777) while the pane wasn't
+    /// focused. Cleared once it is.
+    pub attention: bool,
+    /// Backend bell / notification counts already seen.
+    pub bells_seen: u64,
+    pub notifications_seen: u64,
+    /// "Always" was chosen when asked about an OSC 52 clipboard write / read.
+    pub clipboard_write_granted: bool,
+    pub clipboard_read_granted: bool,
+}
+
+impl TerminalContext {
+    /// The most recent finished command, if shell integration reported any.
+    pub fn last_command(&self) -> Option<&CommandStatus> {
+        self.commands.last()
+    }
+
+    /// Last command's outcome, once the shell is back at the prompt.
+    pub fn last_command_result(&self) -> Option<&CommandStatus> {
+        if self.shell_idle && !self.child_dead {
+            self.last_command()
+        } else {
+            None
+        }
+    }
+
+    /// Copy commands finished since the last sync from the backend.
+    /// Returns true if any were added.
+    pub fn sync_commands(&mut self, backend: &Terminal) -> bool {
+        let finished = backend.finished_command_count();
+        if finished == self.commands_seen {
+            return false;
+        }
+        let (finished, done) = backend.finished_commands(self.commands_seen);
+        self.commands_seen = finished;
+        self.commands.extend(done);
+        let excess = self.commands.len().saturating_sub(CommandStatus::HISTORY_LIMIT);
+        self.commands.drain(..excess);
+        true
+    }
+
+    /// Refresh title, icon name and process (only if `format` shows it)
+    /// from the backend.
+    pub fn sync_title(&mut self, backend: &Terminal, format: &str) {
+        self.title = backend.title();
+        self.icon_name = backend.icon_name();
+        self.process = if format.contains("{process}") { backend.foreground_process_name() } else { None };
+    }
+
+    /// Rebuild `display_title` from `format`. Returns true if it changed.
+    pub fn update_display_title(&mut self, format: &str) -> bool {
+        let title = self.format_title(format);
+        if title == self.display_title {
+            return false;
+        }
+        self.display_title = title;
+        true
+    }
+
+    /// Take in the backend's bell and notification counts. New ones raise
+    /// `attention` unless the pane is `focused`, which clears it instead.
+    /// Returns true if the bell rang since the last call.
+    pub fn sync_attention(&mut self, bells: u64, notifications: u64, focused: bool) -> bool {
+        let rang = bells != self.bells_seen;
+        let notified = notifications != self.notifications_seen;
+        self.bells_seen = bells;
+        self.notifications_seen = notifications;
+        self.attention = !focused && (self.attention || rang || notified);
+        rang
+    }
+
+    /// Expand `{title}`, `{icon}`, `{process}`, `{cwd}` (~-abbreviated) and
+    /// `{dir}` (last cwd component). None if every placeholder was empty.
+    pub fn format_title(&self, format: &str) -> Option<String> {
+        let cwd = self.cwd.as_deref().map(crate::ui_state::abbreviate_path);
+        let dir = self.cwd.as_deref().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().into_owned());
+        let values = [
+            ("{title}", self.title.clone()),
+            ("{icon}", self.icon_name.clone()),
+            ("{process}", self.process.clone()),
+            ("{cwd}", cwd),
+            ("{dir}", dir),
+        ];
+        let mut out = format.to_string();
+        let mut any = false;
+        for (placeholder, value) in values {
+            if out.contains(placeholder) {
+                any |= value.is_some();
+                out = out.replace(placeholder, value.as_deref().unwrap_or(""));
+            }
+        }
+        let out = out.trim();
+        (any && !out.is_empty()).then(|| out.to_string())
+    }
+
+    /// A directory named in the program-set title, e.g. `user@host: ~/src`.
+    /// Used as the cwd when it can't be read from the process.
+    pub fn title_dir(&self) -> Option<PathBuf> {
+        let title = self.title.as_deref()?;
+        title
+            .split_whitespace()
+            .rev()
+            .map(|word| word.trim_matches(|c: char| "()[]:,;'\"".contains(c)))
+            .find_map(|word| {
+                let path = match word.strip_prefix('~') {
+                    Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+                        dirs::home_dir()?.join(rest.trim_start_matches('/'))
+                    }
+                    _ if word.starts_with('/') => PathBuf::from(word),
+                    _ => return None,
+                };
+                path.is_dir().then_some(path)
+            })
+    }
 }
 
 impl Default for TerminalContext {
@@ -57,6 +194,17 @@ impl Default for TerminalContext {
             shell_idle: true,
             worktree_count: 0,
             child_dead: false,
+            commands: Vec::new(),
+            commands_seen: 0,
+            title: None,
+            icon_name: None,
+            process: None,
+            display_title: None,
+            attention: false,
+            bells_seen: 0,
+            notifications_seen: 0,
+            clipboard_write_granted: false,
+            clipboard_read_granted: false,
         }
     }
 }
@@ -65,21 +213,29 @@ pub struct TerminalPane {
     #[allow(dead_code)]
     pub id: PaneId,
     pub backend: Terminal,
-    pub selection: Option<Selection>,
     pub search: Option<SearchState>,
     /// Suppress cursor rendering for N frames after creation to avoid flicker
     /// while the shell re-renders its prompt after SIGWINCH resize.
     pub cursor_suppress: u8,
     /// Cached terminal context (cwd, git info, shell state).
     pub context: TerminalContext,
+    /// Per-profile override of `TideSettings::scrollback_lines`.
+    pub scrollback_lines: Option<usize>,
+    /// Name of the settings profile this terminal was started with.
+    pub profile: Option<String>,
+    /// Keys whose press was reported to a kitty keyboard protocol app and
+    /// whose release is still owed to it.
+    pub pressed_keys: Vec<Key>,
+    /// Copy mode key state; Some while the terminal is in copy mode.
+    pub copy_mode: Option<CopyModeState>,
 }
 
 impl TerminalPane {
     pub fn with_cwd(id: PaneId, cols: u16, rows: u16, cwd: Option<std::path::PathBuf>, dark_mode: bool) -> Result<Self, Box<dyn std::error::Error>> {
         let backend = Terminal::with_cwd(cols, rows, cwd, dark_mode)?;
         Ok(Self {
-            id, backend, selection: None, search: None, cursor_suppress: 3,
-            context: TerminalContext::default(),
+            id, backend, search: None, cursor_suppress: 3,
+            context: TerminalContext::default(), scrollback_lines: None, profile: None, pressed_keys: Vec::new(), copy_mode: None,
         })
     }
 
@@ -88,79 +244,73 @@ impl TerminalPane {
     /// so the shell starts loading in parallel with GPU initialization.
     pub fn with_terminal(id: PaneId, backend: Terminal) -> Self {
         Self {
-            id, backend, selection: None, search: None, cursor_suppress: 3,
-            context: TerminalContext::default(),
+            id, backend, search: None, cursor_suppress: 3,
+            context: TerminalContext::default(), scrollback_lines: None, profile: None, pressed_keys: Vec::new(), copy_mode: None,
         }
     }
 
-    /// Extract selected text from the terminal grid.
-    pub fn selected_text(&self, sel: &Selection) -> String {
-        let grid = self.backend.grid();
-        let (start, end) = if sel.anchor < sel.end {
-            (sel.anchor, sel.end)
-        } else {
-            (sel.end, sel.anchor)
-        };
-
-        let mut result = String::new();
-        for row in start.0..=end.0 {
-            if row >= grid.cells.len() {
-                break;
-            }
-            let line = &grid.cells[row];
-            let col_start = if row == start.0 { start.1 } else { 0 };
-            let col_end = if row == end.0 {
-                end.1.min(line.len())
-            } else {
-                line.len()
-            };
-            for col in col_start..col_end {
-                if col < line.len() {
-                    let ch = line[col].character;
-                    if ch != '\0' {
-                        result.push(ch);
-                    }
-                }
-            }
-            if row != end.0 {
-                // Trim trailing spaces from line before adding newline
-                let trimmed = result.trim_end_matches(' ');
-                result.truncate(trimmed.len());
-                result.push('\n');
-            }
-        }
-        result
-    }
-
     /// Render the grid cells into the cached grid layer.
     pub fn render_grid(&self, rect: Rect, renderer: &mut WgpuRenderer) {
+        render_terminal_grid(self.backend.grid(), rect, renderer);
+    }
+
+    /// Render inline images (kitty graphics, sixel) into the cached grid layer,
+    /// clipped to the grid area.
+    pub fn render_images(&self, rect: Rect, renderer: &mut WgpuRenderer) {
+        let images = self.backend.visible_images();
+        if images.is_empty() {
+            return;
+        }
         let cell_size = renderer.cell_size();
-        let grid = self.backend.grid();
 
-        // Center the grid horizontally within the rect to equalize left/right padding
+        // Center offset matching render_grid
         let max_cols = (rect.width / cell_size.width).floor() as usize;
         let actual_width = max_cols as f32 * cell_size.width;
         let extra_x = (rect.width - actual_width) / 2.0;
-        let offset = Vec2::new(rect.x + extra_x, rect.y);
+        let clip = Rect::new(rect.x + extra_x, rect.y, actual_width, rect.height);
+
+        for visible in images {
+            let image = &visible.image;
+            renderer.draw_grid_image(&GridImage {
+                id: image.id,
+                width: image.width,
+                height: image.height,
+                rgba: &image.rgba,
+                rect: Rect::new(
+                    clip.x + visible.col as f32 * cell_size.width,
+                    rect.y + visible.row as f32 * cell_size.height,
+                    visible.cols * cell_size.width,
+                    visible.rows * cell_size.height,
+                ),
+                uv: visible.uv,
+                clip,
+                below_text: visible.below_text,
+            });
+        }
+    }
+
+    /// Render success/failure markers in the left gutter next to prompts
+    /// whose command has finished (shell integration).
+    pub fn render_prompt_marks(&self, rect: Rect, renderer: &mut WgpuRenderer, ok_color: Color, failed_color: Color) {
+        let marks = self.backend.prompt_marks();
+        if marks.is_empty() {
+            return;
+        }
+        let cell_size = renderer.cell_size();
 
-        // Clamp to the number of rows/cols that fit within the pane rect
+        // Left edge of the grid, matching render_grid's centering
+        let max_cols = (rect.width / cell_size.width).floor() as usize;
+        let extra_x = (rect.width - max_cols as f32 * cell_size.width) / 2.0;
+        let x = rect.x + extra_x - 7.0;
         let max_rows = (rect.height / cell_size.height).ceil() as usize;
-        let rows = (grid.rows as usize).min(max_rows).min(grid.cells.len());
-        let cols = (grid.cols as usize).min(max_cols);
 
-        for row in 0..rows {
-            for col in 0..cols {
-                if col >= grid.cells[row].len() {
-                    break;
-                }
-                let cell = &grid.cells[row][col];
-                if (cell.character == '\0' || cell.character == ' ')
-                    && cell.style.background.is_none()
-                {
-                    continue;
-                }
-                renderer.draw_grid_cell(cell.character, row, col, cell.style, cell_size, offset);
+        for mark in marks {
+            if mark.row >= max_rows {
+                break;
             }
+            let color = if mark.exit_code == 0 { ok_color } else { failed_color };
+            let y = rect.y + mark.row as f32 * cell_size.height + 2.0;
+            renderer.draw_rect(Rect::new(x, y, 3.0, (cell_size.height - 4.0).max(1.0)), color);
         }
     }
 
@@ -286,17 +436,140 @@ impl TerminalPane {
         }
     }
 
+    /// Draw the selected cells (mouse or copy mode selection).
+    pub fn render_selection(&self, rect: Rect, renderer: &mut WgpuRenderer, selection_color: Color) {
+        let cell_size = renderer.cell_size();
+        // Center offset matching render_grid
+        let max_cols = (rect.width / cell_size.width).floor() as usize;
+        let max_rows = (rect.height / cell_size.height).ceil() as usize;
+        let extra_x = (rect.width - max_cols as f32 * cell_size.width) / 2.0;
+        for &(row, first, end) in self.backend.selection_spans() {
+            let end = end.min(max_cols);
+            if row >= max_rows || first >= end {
+                continue;
+            }
+            let x = rect.x + extra_x + first as f32 * cell_size.width;
+            let y = rect.y + row as f32 * cell_size.height;
+            let width = (end - first) as f32 * cell_size.width;
+            renderer.draw_rect(Rect::new(x, y, width, cell_size.height), selection_color);
+        }
+    }
+
+    /// Draw the copy mode cursor: an outlined cell so it can't be mistaken
+    /// for the terminal's own.
+    pub fn render_copy_mode(&self, view: &CopyModeView, rect: Rect, renderer: &mut WgpuRenderer, cursor_color: Color) {
+        let cell_size = renderer.cell_size();
+        // Center offset matching render_grid
+        let max_cols = (rect.width / cell_size.width).floor() as usize;
+        let max_rows = (rect.height / cell_size.height).ceil() as usize;
+        let extra_x = (rect.width - max_cols as f32 * cell_size.width) / 2.0;
+        let (row, col) = view.cursor;
+        if row >= max_rows || col >= max_cols {
+            return;
+        }
+        let x = rect.x + extra_x + col as f32 * cell_size.width;
+        let y = rect.y + row as f32 * cell_size.height;
+        let (w, h) = (cell_size.width, cell_size.height);
+        let t = COPY_CURSOR_BORDER;
+        renderer.draw_top_rect(Rect::new(x, y, w, t), cursor_color);
+        renderer.draw_top_rect(Rect::new(x, y + h - t, w, t), cursor_color);
+        renderer.draw_top_rect(Rect::new(x, y, t, h), cursor_color);
+        renderer.draw_top_rect(Rect::new(x + w - t, y, t, h), cursor_color);
+    }
+
     pub fn handle_key(&mut self, key: &Key, modifiers: &Modifiers) {
-        let bytes = Terminal::key_to_bytes(key, modifiers);
+        self.press_key(key, modifiers, None);
+    }
+
+    /// Send typed text. Apps using the kitty keyboard protocol may want each
+    /// character as a key event instead (and its release later).
+    pub fn handle_text(&mut self, text: &str) {
+        if !self.backend.keyboard_flags().wants_text_keys() {
+            self.write_input(text.as_bytes());
+            return;
+        }
+        for ch in text.chars() {
+            let modifiers = Modifiers { shift: ch.is_uppercase(), ..Default::default() };
+            let mut buf = [0; 4];
+            self.press_key(&Key::Char(ch), &modifiers, Some(ch.encode_utf8(&mut buf)));
+        }
+    }
+
+    /// Paste text, wrapped in bracketed-paste markers if the app asked for them.
+    pub fn paste(&mut self, text: &str) {
+        let bracketed = self.backend.is_bracketed_paste_mode();
+        let mut data = Vec::new();
+        if bracketed {
+            data.extend_from_slice(b"\x1b[200~");
+            // Sanitize: strip the bracket-close sequence from
+            // clipboard text to prevent pastejacking attacks
+            // that escape bracketed paste mode.
+            let safe = text.replace("\x1b[201~", "");
+            data.extend_from_slice(safe.as_bytes());
+            data.extend_from_slice(b"\x1b[201~");
+            // Nudge shell to redraw and clear paste standout
+            // (left + right arrow = net-zero cursor move that
+            // triggers zsh/bash/fish to re-render without
+            // the INVERSE highlight on pasted text).
+            data.extend_from_slice(b"\x1b[D\x1b[C");
+        } else {
+            data.extend_from_slice(text.as_bytes());
+        }
+        // write_input scrolls to the bottom so the pasted text is visible
+        self.write_input(&data);
+    }
+
+    /// Report a key release, if the app asked for releases and saw the press.
+    pub fn handle_key_release(&mut self, key: &Key, modifiers: &Modifiers) {
+        let flags = self.backend.keyboard_flags();
+        if !flags.report_events {
+            self.pressed_keys.clear();
+            return;
+        }
+        // KeyUp carries the unshifted character even if the press was typed text
+        let same_key = |pressed: &Key| match (pressed, key) {
+            (Key::Char(a), Key::Char(b)) => a.to_lowercase().eq(b.to_lowercase()),
+            _ => pressed == key,
+        };
+        let Some(i) = self.pressed_keys.iter().position(same_key) else { return };
+        let pressed = self.pressed_keys.remove(i);
+        let bytes = Terminal::encode_key(&pressed, modifiers, KeyEventKind::Release, None, flags);
+        self.write_input(&bytes);
+    }
+
+    fn press_key(&mut self, key: &Key, modifiers: &Modifiers, text: Option<&str>) {
+        let flags = self.backend.keyboard_flags();
+        let bytes = Terminal::encode_key(key, modifiers, KeyEventKind::Press, text, flags);
+        if flags.report_events && !bytes.is_empty() && !self.pressed_keys.contains(key) {
+            // Releases can get lost (focus moved mid-press); don't grow forever
+            if self.pressed_keys.len() >= MAX_PRESSED_KEYS {
+                self.pressed_keys.remove(0);
+            }
+            self.pressed_keys.push(*key);
+        }
+        self.write_input(&bytes);
+    }
+
+    fn write_input(&mut self, bytes: &[u8]) {
         if !bytes.is_empty() {
             // Scroll back to bottom on user input (applied atomically during next grid sync)
             if self.backend.display_offset() > 0 {
                 self.backend.request_scroll_to_bottom();
             }
-            self.backend.write(&bytes);
+            self.backend.write(bytes);
         }
     }
 
+    /// Forward a mouse event at a viewport cell to the app running in the
+    /// terminal. The cell is clamped to the grid so drags that leave the pane
+    /// still report the nearest edge. Returns false if the app didn't ask for it.
+    pub fn report_mouse(&mut self, report: MouseReport, cell: (usize, usize), modifiers: &Modifiers) -> bool {
+        let grid = self.backend.grid();
+        let row = cell.0.min((grid.rows as usize).saturating_sub(1));
+        let col = cell.1.min((grid.cols as usize).saturating_sub(1));
+        self.backend.report_mouse(report, col, row, modifiers)
+    }
+
     pub fn scroll_display(&mut self, delta: i32) {
         self.backend.scroll_display(delta);
     }
@@ -307,3 +580,82 @@ impl TerminalPane {
         self.backend.resize(cols, rows);
     }
 }
+
+/// Draw terminal grid cells into the cached grid layer (terminal and replay panes).
+pub fn render_terminal_grid(grid: &TerminalGrid, rect: Rect, renderer: &mut WgpuRenderer) {
+    let cell_size = renderer.cell_size();
+
+    // Center the grid horizontally within the rect to equalize left/right padding
+    let max_cols = (rect.width / cell_size.width).floor() as usize;
+    let actual_width = max_cols as f32 * cell_size.width;
+    let extra_x = (rect.width - actual_width) / 2.0;
+    let offset = Vec2::new(rect.x + extra_x, rect.y);
+
+    // Clamp to the number of rows/cols that fit within the pane rect
+    let max_rows = (rect.height / cell_size.height).ceil() as usize;
+    let rows = (grid.rows as usize).min(max_rows).min(grid.cells.len());
+    let cols = (grid.cols as usize).min(max_cols);
+
+    for row in 0..rows {
+        for col in 0..cols {
+            if col >= grid.cells[row].len() {
+                break;
+            }
+            let cell = &grid.cells[row][col];
+            if (cell.character == '\0' || cell.character == ' ')
+                && cell.style.background.is_none()
+                && !cell.style.has_decoration()
+            {
+                continue;
+            }
+            renderer.draw_grid_cell(cell.character, row, col, cell.style, cell_size, offset);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_title_expands_placeholders() {
+        let ctx = TerminalContext {
+            title: Some("vim main.rs".into()),
+            process: Some("nvim".into()),
+            cwd: Some(PathBuf::from("/srv/app")),
+            ..Default::default()
+        };
+        assert_eq!(ctx.format_title("{title}").as_deref(), Some("vim main.rs"));
+        assert_eq!(ctx.format_title("{process} — {dir}").as_deref(), Some("nvim — app"));
+        assert_eq!(ctx.format_title("{cwd}").as_deref(), Some("/srv/app"));
+        // Nothing to show: callers fall back to the cwd
+        assert_eq!(ctx.format_title("{icon}"), None);
+        assert_eq!(TerminalContext::default().format_title("{title}"), None);
+    }
+
+    #[test]
+    fn attention_raised_in_background_and_cleared_on_focus() {
+        let mut ctx = TerminalContext::default();
+        assert!(ctx.sync_attention(1, 0, false));
+        assert!(ctx.attention);
+        // Stays raised with nothing new, until the pane is focused
+        assert!(!ctx.sync_attention(1, 0, false));
+        assert!(ctx.attention);
+        assert!(!ctx.sync_attention(1, 0, true));
+        assert!(!ctx.attention);
+        // Notifications raise it too; the focused pane never gets it
+        ctx.sync_attention(1, 1, false);
+        assert!(ctx.attention);
+        assert!(ctx.sync_attention(2, 2, true));
+        assert!(!ctx.attention);
+    }
+
+    #[test]
+    fn title_dir_finds_existing_directory() {
+        let dir = std::env::temp_dir();
+        let ctx = TerminalContext { title: Some(format!("user@host: {}", dir.display())), ..Default::default() };
+        assert_eq!(ctx.title_dir(), Some(dir));
+        let ctx = TerminalContext { title: Some("vim (/no/such/dir)".into()), ..Default::default() };
+        assert_eq!(ctx.title_dir(), None);
+    }
+}
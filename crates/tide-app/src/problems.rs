@@ -0,0 +1,409 @@
+// Problems list: file/line/column/severity/message entries parsed from a
+// terminal's output by problem matchers (`problem_matchers` in settings.json:
+// the built-in rustc/cargo, tsc, eslint, gcc/clang, pytest and go matchers, or
+// custom regexes). The list is re-parsed from the context terminal each time
+// it's used: the last command's output when shell integration marks it, else
+// the recent scrollback. Next/previous error step through it and open each
+// problem in an editor; the problems panel lists it.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use regex::{Captures, Regex};
+
+use tide_core::PaneId;
+
+use crate::pane::PaneKind;
+use crate::ui_state::{ProblemsPanelState, Toast};
+use crate::App;
+
+/// Rows of scrollback parsed when the terminal has no shell integration.
+const SCROLLBACK_ROWS: usize = 5000;
+
+/// Names of the built-in matchers, in the order they're tried.
+pub(crate) const BUILTIN_MATCHERS: [&str; 6] = ["rustc", "tsc", "eslint", "gcc", "pytest", "go"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    /// Parse a severity word (`error`, `fatal error`, `Warning`, `note`, ...).
+    pub(crate) fn parse(word: &str) -> Option<Self> {
+        let word = word.to_ascii_lowercase();
+        if word.contains("error") {
+            Some(Severity::Error)
+        } else if word.starts_with("warn") {
+            Some(Severity::Warning)
+        } else if matches!(word.as_str(), "note" | "info" | "hint" | "help") {
+            Some(Severity::Info)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Problem {
+    pub path: PathBuf,
+    /// 1-based.
+    pub line: usize,
+    /// 1-based.
+    pub column: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// A problem matcher: `regex` matches one problem per line with the named
+/// groups `file`, `line`, `col`, `severity` and `message`. Groups it doesn't
+/// have come from the last line matching `header` (rustc's `error: ...` line
+/// above the location, eslint's file heading above its problems).
+pub(crate) struct ProblemMatcher {
+    header: Option<Regex>,
+    /// Whether a header belongs to one problem only, instead of every
+    /// problem until the next header.
+    header_once: bool,
+    regex: Regex,
+    /// Severity when the line doesn't name one.
+    severity: Severity,
+}
+
+impl ProblemMatcher {
+    pub(crate) fn new(regex: &str, header: Option<&str>, severity: Severity) -> Result<Self, regex::Error> {
+        Ok(Self {
+            header: header.map(Regex::new).transpose()?,
+            header_once: false,
+            regex: Regex::new(regex)?,
+            severity,
+        })
+    }
+
+    /// The matchers behind a built-in name. None for an unknown name.
+    pub(crate) fn builtin(name: &str) -> Option<Vec<ProblemMatcher>> {
+        let line = |regex: &str| Self::new(regex, None, Severity::Error).expect("built-in matcher");
+        let matchers = match name {
+            "rustc" => vec![
+                ProblemMatcher {
+                    header_once: true,
+                    ..Self::new(
+                        r"^\s*--> (?P<file>.+?):(?P<line>\d+):(?P<col>\d+)$",
+                        Some(r"^(?P<severity>error|warning)(?:\[\w+\])?: (?P<message>.+)$"),
+                        Severity::Error,
+                    )
+                    .expect("built-in matcher")
+                },
+                line(r"^thread '.*' panicked at (?P<file>.+?):(?P<line>\d+):(?P<col>\d+):?$"),
+            ],
+            "tsc" => vec![
+                line(r"^(?P<file>[^\s(]+)\((?P<line>\d+),(?P<col>\d+)\): (?P<severity>error|warning) TS\d+: (?P<message>.+)$"),
+                line(r"^(?P<file>\S+):(?P<line>\d+):(?P<col>\d+) - (?P<severity>error|warning) TS\d+: (?P<message>.+)$"),
+            ],
+            "eslint" => vec![
+                Self::new(
+                    r"^\s+(?P<line>\d+):(?P<col>\d+)\s+(?P<severity>error|warning)\s+(?P<message>.+?)(?:\s{2,}[\w@/-]+)?$",
+                    Some(r"^(?P<file>(?:/|[A-Za-z]:\\)\S.*)$"),
+                    Severity::Error,
+                )
+                .expect("built-in matcher"),
+                line(r"^(?P<file>[^:\s]+):(?P<line>\d+):(?P<col>\d+): (?P<message>.+) \[(?P<severity>Error|Warning)(?:/[\w@/-]+)?\]$"),
+            ],
+            "gcc" => vec![line(
+                r"^(?P<file>[^:\s][^:]*):(?P<line>\d+)(?::(?P<col>\d+))?: (?P<severity>fatal error|error|warning|note): (?P<message>.+)$",
+            )],
+            "pytest" => vec![line(r"^(?P<file>[^\s:]+\.py):(?P<line>\d+): (?P<message>.+)$")],
+            "go" => vec![
+                line(r"^(?P<file>[\w./-]+\.go):(?P<line>\d+):(?P<col>\d+): (?P<message>.+)$"),
+                line(r"^\s+(?P<file>[\w./-]+_test\.go):(?P<line>\d+): (?P<message>.+)$"),
+            ],
+            _ => return None,
+        };
+        Some(matchers)
+    }
+}
+
+/// Groups carried from a header line to the problems below it.
+#[derive(Default, Clone)]
+struct Header {
+    file: Option<String>,
+    severity: Option<String>,
+    message: Option<String>,
+}
+
+/// Parse problems out of `text`. Relative paths resolve against `cwd` or,
+/// failing that, its ancestors (cargo reports paths from the workspace
+/// root); problems in files that don't exist are dropped, as are repeats of
+/// a location.
+pub(crate) fn parse_problems(text: &str, matchers: &[ProblemMatcher], cwd: Option<&Path>) -> Vec<Problem> {
+    let mut headers: Vec<Option<Header>> = vec![None; matchers.len()];
+    let mut seen = HashSet::new();
+    let mut problems = Vec::new();
+    for line in text.lines() {
+        for (matcher, header) in matchers.iter().zip(headers.iter_mut()) {
+            if let Some(caps) = matcher.header.as_ref().and_then(|h| h.captures(line)) {
+                let group = |name: &str| caps.name(name).map(|m| m.as_str().to_string());
+                *header = Some(Header { file: group("file"), severity: group("severity"), message: group("message") });
+                continue;
+            }
+            let Some(caps) = matcher.regex.captures(line) else { continue };
+            if matcher.header.is_some() && header.is_none() {
+                continue;
+            }
+            let carried = if matcher.header_once { header.take() } else { header.clone() }.unwrap_or_default();
+            let Some(problem) = problem_from(&caps, carried, line, matcher.severity, cwd) else { continue };
+            if seen.insert((problem.path.clone(), problem.line, problem.column)) {
+                problems.push(problem);
+            }
+        }
+    }
+    problems
+}
+
+fn problem_from(caps: &Captures, header: Header, line: &str, severity: Severity, cwd: Option<&Path>) -> Option<Problem> {
+    let group = |name: &str| caps.name(name).map(|m| m.as_str().to_string());
+    let file = group("file").or(header.file)?;
+    let path = resolve_path(&file, cwd)?;
+    let severity = group("severity").or(header.severity).and_then(|s| Severity::parse(&s)).unwrap_or(severity);
+    let message = group("message").or(header.message).unwrap_or_else(|| line.trim().to_string());
+    Some(Problem {
+        path,
+        line: group("line")?.parse().ok()?,
+        column: group("col").and_then(|c| c.parse().ok()),
+        severity,
+        message,
+    })
+}
+
+fn resolve_path(file: &str, cwd: Option<&Path>) -> Option<PathBuf> {
+    let path = Path::new(file.trim().trim_start_matches("./"));
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    cwd?.ancestors().map(|dir| dir.join(path)).find(|p| p.is_file())
+}
+
+/// The problems last parsed, and which one next/previous error is on.
+#[derive(Default)]
+pub(crate) struct ProblemList {
+    pub items: Vec<Problem>,
+    pub current: Option<usize>,
+    /// Terminal they were parsed from; used when the focused pane has no
+    /// terminal (an editor opened from the panel).
+    pub source: Option<PaneId>,
+    /// That terminal's cwd, for showing paths relative to it.
+    pub base_dir: Option<PathBuf>,
+}
+
+impl App {
+    /// Re-parse the problems from the context terminal (or the last source).
+    /// The position is kept if the list didn't change.
+    pub(crate) fn refresh_problems(&mut self) {
+        let source = self
+            .resolve_context_terminal_id()
+            .filter(|id| matches!(self.panes.get(id), Some(PaneKind::Terminal(_))))
+            .or(self.problems.source);
+        let Some(PaneKind::Terminal(t)) = source.and_then(|id| self.panes.get(&id)) else {
+            self.problems = ProblemList::default();
+            return;
+        };
+        let text = t
+            .backend
+            .last_command_output()
+            .filter(|text| !text.trim().is_empty())
+            .unwrap_or_else(|| t.backend.recent_text(SCROLLBACK_ROWS));
+        let cwd = t.backend.detect_cwd_fallback();
+        let items = parse_problems(&text, &self.settings.compiled_problem_matchers(), cwd.as_deref());
+        if items != self.problems.items {
+            self.problems.current = None;
+            self.problems.items = items;
+        }
+        self.problems.source = source;
+        self.problems.base_dir = cwd;
+    }
+
+    /// Open the next (or previous) problem, wrapping around.
+    pub(crate) fn step_problem(&mut self, forward: bool) {
+        self.refresh_problems();
+        let len = self.problems.items.len();
+        if len == 0 {
+            self.toast = Some(Toast {
+                message: "No problems found".to_string(),
+                until: Instant::now() + crate::clipboard::TOAST_DURATION,
+            });
+            self.cache.needs_redraw = true;
+            return;
+        }
+        let index = match (self.problems.current, forward) {
+            (None, true) => 0,
+            (None, false) => len - 1,
+            (Some(i), true) => (i + 1) % len,
+            (Some(i), false) => (i + len - 1) % len,
+        };
+        self.open_problem(index);
+    }
+
+    /// Open problem `index` in an editor at its line and column.
+    pub(crate) fn open_problem(&mut self, index: usize) {
+        let Some(problem) = self.problems.items.get(index).cloned() else { return };
+        self.problems.current = Some(index);
+        self.open_editor_pane_at(problem.path, Some(problem.line), problem.column);
+    }
+
+    pub(crate) fn toggle_problems_panel(&mut self) {
+        if self.modal.problems.is_some() {
+            self.close_problems_panel();
+            return;
+        }
+        self.interaction.pane_drag = crate::drag_drop::PaneDragState::Idle;
+        self.refresh_problems();
+        self.modal.problems = Some(ProblemsPanelState::new(self.problems.current.unwrap_or(0)));
+        self.cache.invalidate_chrome();
+        // Hide browser webviews so they don't cover the popup
+        self.sync_browser_webview_frames();
+    }
+
+    pub(crate) fn close_problems_panel(&mut self) {
+        if self.modal.problems.take().is_some() {
+            self.cache.invalidate_chrome();
+            self.sync_browser_webview_frames();
+        }
+    }
+
+    /// Open the problem selected in the panel and close it.
+    pub(crate) fn open_selected_problem(&mut self) {
+        let Some(selected) = self.modal.problems.as_ref().map(|p| p.selected) else { return };
+        self.close_problems_panel();
+        self.open_problem(selected);
+    }
+
+    /// A problem's location as shown in the panel: relative to the source
+    /// terminal's cwd when inside it.
+    pub(crate) fn problem_location(&self, problem: &Problem) -> String {
+        let path = match self.problems.base_dir.as_deref().and_then(|base| problem.path.strip_prefix(base).ok()) {
+            Some(rel) => rel.display().to_string(),
+            None => crate::ui_state::abbreviate_path(&problem.path),
+        };
+        match problem.column {
+            Some(col) => format!("{}:{}:{}", path, problem.line, col),
+            None => format!("{}:{}", path, problem.line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builtins() -> Vec<ProblemMatcher> {
+        BUILTIN_MATCHERS.iter().flat_map(|name| ProblemMatcher::builtin(name).unwrap()).collect()
+    }
+
+    /// A temp dir holding `files` (relative paths), removed on drop.
+    struct Tree(PathBuf);
+
+    impl Tree {
+        fn new(name: &str, files: &[&str]) -> Self {
+            let root = std::env::temp_dir().join(format!("tide-problems-{}-{}", name, std::process::id()));
+            for file in files {
+                let path = root.join(file);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, "").unwrap();
+            }
+            Tree(root)
+        }
+    }
+
+    impl Drop for Tree {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    fn summary(problems: &[Problem], root: &Path) -> Vec<(String, usize, Option<usize>, Severity, String)> {
+        problems
+            .iter()
+            .map(|p| {
+                let rel = p.path.strip_prefix(root).unwrap().display().to_string();
+                (rel, p.line, p.column, p.severity, p.message.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rustc_errors_take_their_message_from_the_header() {
+        let tree = Tree::new("rustc", &["src/main.rs", "crates/app/src/lib.rs"]);
+        let output = "\
+   Compiling app v0.1.0
+error[E0308]: mismatched types
+ --> src/main.rs:4:18
+  |
+4 |     let x: u8 = \"a\";
+  |            --   ^^^ expected `u8`, found `&str`
+warning: unused variable: `y`
+  --> crates/app/src/lib.rs:2:9
+   |
+help: if this is intentional, prefix it with an underscore
+thread 'main' panicked at src/main.rs:9:5:
+warning: `app` (bin \"app\") generated 1 warning
+";
+        let problems = parse_problems(output, &builtins(), Some(&tree.0.join("crates/app")));
+        assert_eq!(
+            summary(&problems, &tree.0),
+            [
+                ("src/main.rs".into(), 4, Some(18), Severity::Error, "mismatched types".into()),
+                ("crates/app/src/lib.rs".into(), 2, Some(9), Severity::Warning, "unused variable: `y`".into()),
+                ("src/main.rs".into(), 9, Some(5), Severity::Error, "thread 'main' panicked at src/main.rs:9:5:".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tsc_eslint_gcc_pytest_and_go_lines_parse() {
+        let tree = Tree::new("others", &["src/a.ts", "src/b.ts", "web/c.js", "main.c", "tests/test_x.py", "pkg/x.go", "pkg/x_test.go"]);
+        let root = tree.0.display().to_string();
+        let output = format!(
+            "\
+src/a.ts(3,5): error TS2322: Type 'string' is not assignable to type 'number'.
+src/b.ts:7:1 - warning TS6133: 'z' is declared but its value is never read.
+{root}/web/c.js
+  10:3  error    'foo' is not defined  no-undef
+  12:1  warning  Unexpected console statement  no-console
+web/c.js:20:2: Missing semicolon. [Warning/semi]
+main.c:8:12: fatal error: stdio.h: No such file or directory
+main.c:9: note: declared here
+tests/test_x.py:14: AssertionError
+./pkg/x.go:3:5: undefined: Foo
+    pkg/x_test.go:21: got 1, want 2
+not/a/file.c:1:1: error: ignored
+"
+        );
+        let problems = parse_problems(&output, &builtins(), Some(&tree.0));
+        assert_eq!(
+            summary(&problems, &tree.0),
+            [
+                ("src/a.ts".into(), 3, Some(5), Severity::Error, "Type 'string' is not assignable to type 'number'.".into()),
+                ("src/b.ts".into(), 7, Some(1), Severity::Warning, "'z' is declared but its value is never read.".into()),
+                ("web/c.js".into(), 10, Some(3), Severity::Error, "'foo' is not defined".into()),
+                ("web/c.js".into(), 12, Some(1), Severity::Warning, "Unexpected console statement".into()),
+                ("web/c.js".into(), 20, Some(2), Severity::Warning, "Missing semicolon.".into()),
+                ("main.c".into(), 8, Some(12), Severity::Error, "stdio.h: No such file or directory".into()),
+                ("main.c".into(), 9, None, Severity::Info, "declared here".into()),
+                ("tests/test_x.py".into(), 14, None, Severity::Error, "AssertionError".into()),
+                ("pkg/x.go".into(), 3, Some(5), Severity::Error, "undefined: Foo".into()),
+                ("pkg/x_test.go".into(), 21, None, Severity::Error, "got 1, want 2".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_matcher_uses_defaults_and_skips_unknown_builtins() {
+        let tree = Tree::new("custom", &["notes.txt"]);
+        let matcher = ProblemMatcher::new(r"^TODO (?P<file>\S+) line (?P<line>\d+)$", None, Severity::Info).unwrap();
+        let problems = parse_problems("TODO notes.txt line 3\nTODO notes.txt line 3", &[matcher], Some(&tree.0));
+        assert_eq!(summary(&problems, &tree.0), [("notes.txt".into(), 3, None, Severity::Info, "TODO notes.txt line 3".into())]);
+        assert!(ProblemMatcher::builtin("cobol").is_none());
+    }
+}
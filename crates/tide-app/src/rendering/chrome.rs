@@ -1,7 +1,8 @@
-use tide_core::{FileTreeSource, Rect, Renderer, TextStyle, Vec2};
+use tide_core::{FileTreeSource, Rect, Renderer, TextStyle, UnderlineStyle, Vec2};
 
 use crate::drag_drop::HoverTarget;
 use crate::header;
+use crate::pane::PaneKind;
 use crate::theme::*;
 use crate::ui::file_icon;
 use crate::ui_state::FocusArea;
@@ -50,7 +51,7 @@ pub(crate) fn render_chrome(
                 TextStyle {
                     foreground: p.tab_text,
                     background: None,
-                    bold: false, dim: false, italic: false, underline: false,
+                    bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                 },
                 tb,
             );
@@ -84,7 +85,7 @@ pub(crate) fn render_chrome(
                     TextStyle {
                         foreground: gear_color,
                         background: None,
-                        bold: false, dim: false, italic: false, underline: false,
+                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     },
                     tb,
                 );
@@ -115,7 +116,7 @@ pub(crate) fn render_chrome(
                 TextStyle {
                     foreground: p.tab_text,
                     background: None,
-                    bold: false, dim: false, italic: false, underline: false,
+                    bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                 },
                 tb,
             );
@@ -163,7 +164,7 @@ pub(crate) fn render_chrome(
                     TextStyle {
                         foreground: icon_color,
                         background: None,
-                        bold: false, dim: false, italic: false, underline: false,
+                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     },
                     tb_clip,
                 );
@@ -177,7 +178,7 @@ pub(crate) fn render_chrome(
                     TextStyle {
                         foreground: hint_color,
                         background: None,
-                        bold: false, dim: false, italic: false, underline: false,
+                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     },
                     tb_clip,
                 );
@@ -274,16 +275,35 @@ pub(crate) fn render_chrome(
                     foreground: name_color,
                     background: None,
                     bold: is_active,
-                    dim: false, italic: false, underline: false,
+                    dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                 },
                 inset,
             );
 
-            // CWD text (second line)
+            // Attention bell (right-aligned on the name line)
+            if app.workspace_needs_attention(i) {
+                let bell_x = item_rect.x + item_rect.width - WS_SIDEBAR_ITEM_PAD_H - cs.width;
+                renderer.draw_chrome_text(
+                    "\u{f0f3}",
+                    Vec2::new(bell_x, item_rect.y + WS_SIDEBAR_ITEM_PAD_V),
+                    TextStyle {
+                        foreground: p.attention,
+                        background: None,
+                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
+                    },
+                    inset,
+                );
+            }
+
+            // Title or CWD text (second line)
             let cwd_text = if is_active {
-                // Use live cwd from the focused terminal
-                app.focused_terminal_cwd()
-                    .map(|p| crate::ui_state::abbreviate_path(&p))
+                // Use the focused terminal's title, else its live cwd
+                let title = app.focused.and_then(|id| match app.panes.get(&id) {
+                    Some(PaneKind::Terminal(tp)) => tp.context.display_title.clone(),
+                    _ => None,
+                });
+                title
+                    .or_else(|| app.focused_terminal_cwd().map(|p| crate::ui_state::abbreviate_path(&p)))
                     .unwrap_or_default()
             } else {
                 String::new()
@@ -295,7 +315,7 @@ pub(crate) fn render_chrome(
                     TextStyle {
                         foreground: p.tab_text,
                         background: None,
-                        bold: false, dim: false, italic: false, underline: false,
+                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     },
                     inset,
                 );
@@ -343,7 +363,7 @@ pub(crate) fn render_chrome(
             TextStyle {
                 foreground: p.tab_text,
                 background: None,
-                bold: false, dim: false, italic: false, underline: false,
+                bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
             },
             inset,
         );
@@ -423,7 +443,7 @@ pub(crate) fn render_chrome(
                     TextStyle {
                         foreground: p.tree_dir_icon,
                         background: None,
-                        bold: false, dim: false, italic: false, underline: false,
+                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     },
                     tree_text_clip,
                 );
@@ -439,7 +459,7 @@ pub(crate) fn render_chrome(
                     TextStyle {
                         foreground: p.tab_text_focused,
                         background: None,
-                        bold: true, dim: false, italic: false, underline: false,
+                        bold: true, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     },
                     tree_text_clip,
                 );
@@ -474,7 +494,7 @@ pub(crate) fn render_chrome(
                     let icon_style = TextStyle {
                         foreground: p.tree_icon,
                         background: None,
-                        bold: false, dim: false, italic: false, underline: false,
+                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     };
                     let icon_str: String = std::iter::once(icon).collect();
                     renderer.draw_chrome_text(&icon_str, Vec2::new(x, text_y), icon_style, tree_text_clip);
@@ -494,7 +514,7 @@ pub(crate) fn render_chrome(
                     let ts = TextStyle {
                         foreground: p.tab_text_focused,
                         background: None,
-                        bold: false, dim: false, italic: false, underline: false,
+                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     };
                     renderer.draw_chrome_text(&rename.input.text, Vec2::new(name_x, text_y), ts, tree_text_clip);
                     // Cursor beam
@@ -561,7 +581,9 @@ pub(crate) fn render_chrome(
                     bold: false,
                     dim: false,
                     italic: false,
-                    underline: false,
+                    underline: UnderlineStyle::None,
+                    underline_color: None,
+                    strikethrough: false,
                 };
                 let icon_str: String = std::iter::once(icon).collect();
                 renderer.draw_chrome_text(
@@ -589,7 +611,9 @@ pub(crate) fn render_chrome(
                     bold: is_expanded_dir,
                     dim: false,
                     italic: false,
-                    underline: false,
+                    underline: UnderlineStyle::None,
+                    underline_color: None,
+                    strikethrough: false,
                 };
                 renderer.draw_chrome_text(
                     &entry.entry.name,
@@ -604,7 +628,7 @@ pub(crate) fn render_chrome(
                     let badge_style = TextStyle {
                         foreground: status_color.unwrap_or(p.tree_text),
                         background: None,
-                        bold: true, dim: false, italic: false, underline: false,
+                        bold: true, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     };
                     renderer.draw_chrome_text(badge, Vec2::new(badge_x, text_y), badge_style, tree_text_clip);
                 }
@@ -635,12 +659,21 @@ pub(crate) fn render_chrome(
     }
 
     // Draw pane backgrounds + borders with rounded corners
+    let broadcasting = app.broadcast_members();
     for &(id, rect) in visual_pane_rects {
         // Only show pane focus highlight when focus is in the pane area
         let is_focused = focused == Some(id) && app.focus_area == FocusArea::PaneArea;
-        let border_color = if is_focused { p.border_focused } else { p.border_subtle };
-        let top_border = if is_focused { 2.0 } else { 1.0 };
-        let side_border = if is_focused { 2.0_f32 } else { 1.0_f32 };
+        // Broadcasting terminals get a thick border in the broadcast color
+        let is_broadcasting = broadcasting.contains(&id);
+        let border_color = if is_broadcasting {
+            p.broadcast
+        } else if is_focused {
+            p.border_focused
+        } else {
+            p.border_subtle
+        };
+        let top_border = if is_focused || is_broadcasting { 2.0 } else { 1.0 };
+        let side_border = if is_focused || is_broadcasting { 2.0_f32 } else { 1.0_f32 };
 
         // Focused pane: draw outer glow shadow
         if is_focused {
@@ -657,7 +690,22 @@ pub(crate) fn render_chrome(
             rect.width - 2.0 * side_border,
             rect.height - top_border - side_border,
         );
-        renderer.draw_chrome_rounded_rect(inset, p.pane_bg, (PANE_CORNER_RADIUS - side_border).max(0.0));
+        let inner_radius = (PANE_CORNER_RADIUS - side_border).max(0.0);
+        renderer.draw_chrome_rounded_rect(inset, p.pane_bg, inner_radius);
+
+        // Terminal with a color scheme: its background below the tab bar
+        // (square top corners, rounded bottom ones)
+        if let Some(PaneKind::Terminal(t)) = app.panes.get(&id) {
+            if let Some(scheme) = t.backend.color_scheme() {
+                let body_top = rect.y + TAB_BAR_HEIGHT;
+                let body = Rect::new(inset.x, body_top, inset.width, (inset.y + inset.height - body_top).max(0.0));
+                renderer.draw_chrome_rounded_rect(body, scheme.background, inner_radius);
+                renderer.draw_chrome_rect(
+                    Rect::new(body.x, body.y, body.width, inner_radius.min(body.height)),
+                    scheme.background,
+                );
+            }
+        }
     }
 
     // Render per-pane headers (title + badges + close, or tab bar for multi-tab groups)
@@ -679,7 +727,7 @@ pub(crate) fn render_chrome(
         }
 
         let zones = header::render_pane_header(
-            id, rect, &app.panes, focused, tab_group, is_zoomed, p, renderer,
+            id, rect, &app.panes, focused, tab_group, is_zoomed, &broadcasting, p, renderer,
         );
         all_hit_zones.extend(zones);
     }
@@ -726,7 +774,7 @@ fn render_browser_nav_bar(
     renderer.draw_chrome_text(
         "\u{2190}",
         Vec2::new(cx, text_y),
-        TextStyle { foreground: back_color, background: None, bold: false, dim: false, italic: false, underline: false },
+        TextStyle { foreground: back_color, background: None, bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false },
         Rect::new(cx, nav_y, cell_w * 2.0, nav_h),
     );
     cx += cell_w * 2.0;
@@ -736,7 +784,7 @@ fn render_browser_nav_bar(
     renderer.draw_chrome_text(
         "\u{2192}",
         Vec2::new(cx, text_y),
-        TextStyle { foreground: fwd_color, background: None, bold: false, dim: false, italic: false, underline: false },
+        TextStyle { foreground: fwd_color, background: None, bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false },
         Rect::new(cx, nav_y, cell_w * 2.0, nav_h),
     );
     cx += cell_w * 2.0;
@@ -746,7 +794,7 @@ fn render_browser_nav_bar(
     renderer.draw_chrome_text(
         refresh_icon,
         Vec2::new(cx, text_y),
-        TextStyle { foreground: p.tab_text_focused, background: None, bold: false, dim: false, italic: false, underline: false },
+        TextStyle { foreground: p.tab_text_focused, background: None, bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false },
         Rect::new(cx, nav_y, cell_w * 2.0, nav_h),
     );
     cx += cell_w * 2.0 + 4.0;
@@ -781,7 +829,7 @@ fn render_browser_nav_bar(
             renderer.draw_chrome_text(
                 &truncated,
                 Vec2::new(cx + 4.0, text_y),
-                TextStyle { foreground: p.tab_text_focused, background: None, bold: false, dim: false, italic: false, underline: false },
+                TextStyle { foreground: p.tab_text_focused, background: None, bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false },
                 url_rect,
             );
 
@@ -814,7 +862,7 @@ fn render_browser_nav_bar(
             renderer.draw_chrome_text(
                 &truncated,
                 Vec2::new(cx + 4.0, text_y),
-                TextStyle { foreground: p.tab_text_focused, background: None, bold: false, dim: false, italic: false, underline: false },
+                TextStyle { foreground: p.tab_text_focused, background: None, bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false },
                 url_rect,
             );
         }
@@ -42,48 +42,19 @@ pub(crate) fn render_cursor_and_highlights(
             Some(PaneKind::Terminal(pane)) => {
                 // Only render cursor on the focused pane (and hide when search bar is active
                 // or IME preedit is composing — preedit overlay replaces the cursor).
-                if focused == Some(id) && search_focus != Some(id) && app.ime.preedit.is_empty() {
-                    pane.render_cursor(inner, renderer, p.cursor_accent);
+                // Copy mode draws its own cursor instead.
+                let cursor_color = pane.backend.color_scheme().and_then(|s| s.cursor).unwrap_or(p.cursor_accent);
+                if let Some(view) = pane.backend.copy_mode() {
+                    pane.render_copy_mode(view, inner, renderer, cursor_color);
+                } else if focused == Some(id) && search_focus != Some(id) && app.ime.preedit.is_empty() {
+                    pane.render_cursor(inner, renderer, cursor_color);
                 }
                 // Render URL underlines when Cmd/Meta is held
                 if app.modifiers.meta {
                     pane.render_url_underlines(inner, renderer, p.link_color);
                 }
                 // Render selection highlight
-                if let Some(ref sel) = pane.selection {
-                    let cell_size = renderer.cell_size();
-                    let (start, end) = if sel.anchor <= sel.end {
-                        (sel.anchor, sel.end)
-                    } else {
-                        (sel.end, sel.anchor)
-                    };
-                    // Skip rendering if anchor == end (no actual selection)
-                    if start != end {
-                        let sel_color = p.selection;
-                        let grid = pane.backend.grid();
-                        let max_rows = (inner.height / cell_size.height).ceil() as usize;
-                        let max_cols = (inner.width / cell_size.width).floor() as usize;
-                        let visible_rows = (grid.rows as usize).min(max_rows);
-                        let visible_cols = (grid.cols as usize).min(max_cols);
-                        // Center offset matching terminal grid
-                        let actual_w = max_cols as f32 * cell_size.width;
-                        let center_x = (inner.width - actual_w) / 2.0;
-                        for row in start.0..=end.0.min(visible_rows.saturating_sub(1)) {
-                            let col_start = if row == start.0 { start.1 } else { 0 };
-                            let col_end = if row == end.0 { end.1 } else { visible_cols };
-                            if col_start >= col_end {
-                                continue;
-                            }
-                            let rx = inner.x + center_x + col_start as f32 * cell_size.width;
-                            let ry = inner.y + row as f32 * cell_size.height;
-                            let rw = (col_end - col_start) as f32 * cell_size.width;
-                            renderer.draw_rect(
-                                Rect::new(rx, ry, rw, cell_size.height),
-                                sel_color,
-                            );
-                        }
-                    }
-                }
+                pane.render_selection(inner, renderer, p.selection);
                 // Render terminal search highlights
                 if let Some(ref search) = pane.search {
                     if search.visible && !search.input.is_empty() {
@@ -99,20 +70,32 @@ pub(crate) fn render_cursor_and_highlights(
                         // Visible absolute line range
                         let visible_start = history_size.saturating_sub(display_offset);
                         let visible_end = visible_start + screen_rows;
+                        let grid_cols = (grid.cols as usize).max(1);
                         for (mi, m) in search.matches.iter().enumerate() {
-                            if m.line < visible_start || m.line >= visible_end {
+                            // Matches that follow a soft wrap run past the row end
+                            let last_line = m.line + (m.col + m.len - 1) / grid_cols;
+                            if last_line < visible_start || m.line >= visible_end {
                                 continue;
                             }
-                            let visual_row = m.line - visible_start;
-                            let rx = inner.x + center_x + m.col as f32 * cell_size.width;
-                            let ry = inner.y + visual_row as f32 * cell_size.height;
-                            let rw = m.len as f32 * cell_size.width;
                             let color = if search.current == Some(mi) {
                                 p.search_current_bg
                             } else {
                                 p.search_match_bg
                             };
-                            renderer.draw_rect(Rect::new(rx, ry, rw, cell_size.height), color);
+                            let (mut line, mut col, mut remaining) = (m.line, m.col, m.len);
+                            while remaining > 0 && line < visible_end {
+                                let seg = remaining.min(grid_cols.saturating_sub(col)).max(1);
+                                if line >= visible_start {
+                                    let visual_row = line - visible_start;
+                                    let rx = inner.x + center_x + col as f32 * cell_size.width;
+                                    let ry = inner.y + visual_row as f32 * cell_size.height;
+                                    let rw = seg as f32 * cell_size.width;
+                                    renderer.draw_rect(Rect::new(rx, ry, rw, cell_size.height), color);
+                                }
+                                remaining -= seg;
+                                line += 1;
+                                col = 0;
+                            }
                         }
                     }
                 }
@@ -151,6 +134,7 @@ pub(crate) fn render_cursor_and_highlights(
             }
             Some(PaneKind::Diff(_)) => {}
             Some(PaneKind::Browser(_)) => {}
+            Some(PaneKind::Replay(_)) => {}
             Some(PaneKind::Launcher(_)) => {}
             None => {}
         }
@@ -1,4 +1,4 @@
-use tide_core::{Rect, Renderer};
+use tide_core::{Rect, Renderer, UnderlineStyle};
 
 use crate::pane::PaneKind;
 use crate::theme::*;
@@ -45,6 +45,7 @@ pub(crate) fn render_grid(
             Some(PaneKind::Terminal(pane)) => pane.backend.grid_generation(),
             Some(PaneKind::Editor(pane)) => pane.generation(),
             Some(PaneKind::Diff(dp)) => dp.generation(),
+            Some(PaneKind::Replay(rp)) => rp.generation(),
             Some(PaneKind::Browser(_)) => continue, // webview renders natively
             Some(PaneKind::Launcher(_)) => 0, // static content, always render on first check
             None => continue,
@@ -63,6 +64,8 @@ pub(crate) fn render_grid(
             match app.panes.get(&id) {
                 Some(PaneKind::Terminal(pane)) => {
                     pane.render_grid(inner, renderer);
+                    pane.render_images(inner, renderer);
+                    pane.render_prompt_marks(inner, renderer, p.command_ok, p.command_failed);
                     // Overlay message for dead terminals
                     if pane.context.child_dead {
                         let cs = renderer.cell_size();
@@ -79,7 +82,7 @@ pub(crate) fn render_grid(
                             tide_core::TextStyle {
                                 foreground: p.tab_text_focused,
                                 background: None,
-                                bold: false, dim: false, italic: false, underline: false,
+                                bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                             },
                             strip,
                         );
@@ -101,16 +104,24 @@ pub(crate) fn render_grid(
                         p.border_subtle);
                     app.cache.pane_generations.insert(id, dp.generation());
                 }
+                Some(PaneKind::Replay(rp)) => {
+                    rp.render_grid(inner, renderer, p.border_subtle, p.cursor_accent);
+                    app.cache.pane_generations.insert(id, rp.generation());
+                }
                 Some(PaneKind::Browser(_)) => {} // webview renders natively
                 Some(PaneKind::Launcher(_launcher_id)) => {
                     // Render launcher type-selection UI
                     let cs = renderer.cell_size();
-                    let lines: [(&str, tide_core::Color); 4] = [
-                        ("\u{f120}  [T]  Terminal", p.tab_text_focused),
-                        ("\u{f15c}  [E]  New File", p.tab_text),
-                        ("\u{f07c}  [O]  Open File", p.tab_text),
-                        ("\u{f268}  [B]  Browser", p.tab_text),
+                    let mut lines: Vec<(String, tide_core::Color)> = vec![
+                        ("\u{f120}  [T]  Terminal".to_string(), p.tab_text_focused),
+                        ("\u{f15c}  [E]  New File".to_string(), p.tab_text),
+                        ("\u{f07c}  [O]  Open File".to_string(), p.tab_text),
+                        ("\u{f268}  [B]  Browser".to_string(), p.tab_text),
                     ];
+                    // Settings profiles on the digit keys
+                    for (i, profile) in app.settings.profiles.iter().take(9).enumerate() {
+                        lines.push((format!("\u{f120}  [{}]  {}", i + 1, profile.name), p.tab_text));
+                    }
                     let line_h = cs.height * 1.8;
                     let block_h = lines.len() as f32 * line_h;
                     let start_y = inner.y + (inner.height - block_h) / 2.0;
@@ -127,7 +138,9 @@ pub(crate) fn render_grid(
                                 bold: i == 0,
                                 dim: false,
                                 italic: false,
-                                underline: false,
+                                underline: UnderlineStyle::None,
+                                underline_color: None,
+                                strikethrough: false,
                             },
                             inner,
                         );
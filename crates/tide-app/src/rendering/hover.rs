@@ -54,8 +54,8 @@ pub(crate) fn render_hover(
                         renderer.draw_rect(close_rect, p.hover_close);
                     }
                 }
-                drag_drop::HoverTarget::FileFinderItem(_) => {
-                    // File finder hover — rendered inline in overlays
+                drag_drop::HoverTarget::FileFinderItem(_) | drag_drop::HoverTarget::ProblemsItem(_) => {
+                    // Popup list hover — rendered inline in overlays
                 }
                 drag_drop::HoverTarget::EditorScrollbar(_) => {
                     // Scrollbar hover expansion handled in render_scrollbar
@@ -1,6 +1,6 @@
 use unicode_width::UnicodeWidthChar;
 
-use tide_core::{Rect, Renderer, TerminalBackend, TextStyle, Vec2};
+use tide_core::{Rect, Renderer, TerminalBackend, TextStyle, UnderlineStyle, Vec2};
 
 use crate::drag_drop::{DropDestination, PaneDragState};
 use crate::pane::PaneKind;
@@ -65,7 +65,9 @@ pub(crate) fn render_ime_and_drop_preview(
                         bold: false,
                         dim: false,
                         italic: false,
-                        underline: true,
+                        underline: UnderlineStyle::Single,
+                        underline_color: None,
+                        strikethrough: false,
                     };
                     let mut col_offset = 0usize;
                     for &ch in preedit_chars.iter() {
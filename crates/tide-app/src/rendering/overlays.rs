@@ -1,6 +1,6 @@
 use unicode_width::UnicodeWidthChar;
 
-use tide_core::{Color, Rect, Renderer, TextStyle, Vec2};
+use tide_core::{Color, Rect, Renderer, TextStyle, UnderlineStyle, Vec2};
 
 use crate::pane::PaneKind;
 use crate::theme::*;
@@ -53,7 +53,9 @@ fn text_style(color: tide_core::Color) -> TextStyle {
         bold: false,
         dim: false,
         italic: false,
-        underline: false,
+        underline: UnderlineStyle::None,
+        underline_color: None,
+        strikethrough: false,
     }
 }
 
@@ -65,7 +67,9 @@ fn bold_style(color: tide_core::Color) -> TextStyle {
         bold: true,
         dim: false,
         italic: false,
-        underline: false,
+        underline: UnderlineStyle::None,
+        underline_color: None,
+        strikethrough: false,
     }
 }
 
@@ -83,13 +87,77 @@ pub(crate) fn render_overlays(
     p: &ThemePalette,
     visual_pane_rects: &[(u64, Rect)],
 ) {
+    render_visual_bells(app, renderer, p, visual_pane_rects);
     render_search_bars(app, renderer, p, visual_pane_rects);
+    render_copy_mode_status(app, renderer, p, visual_pane_rects);
     render_notification_bars(app, renderer, p, visual_pane_rects);
     render_save_as(app, renderer, p, visual_pane_rects);
     render_file_finder(app, renderer, p);
+    render_problems(app, renderer, p);
     render_git_switcher(app, renderer, p);
     render_context_menu(app, renderer, p);
     render_config_page(app, renderer, p);
+    render_toast(app, renderer, p);
+}
+
+/// Flash the content area of panes that just rang the bell.
+fn render_visual_bells(
+    app: &App,
+    renderer: &mut tide_renderer::WgpuRenderer,
+    p: &ThemePalette,
+    visual_pane_rects: &[(u64, Rect)],
+) {
+    for &(id, rect) in visual_pane_rects {
+        if app.visual_bells.contains_key(&id) {
+            let content = Rect::new(rect.x, rect.y + TAB_BAR_HEIGHT, rect.width, rect.height - TAB_BAR_HEIGHT);
+            renderer.draw_top_rect(content, p.visual_bell);
+        }
+    }
+}
+
+/// Render the toast, centred near the bottom of the window.
+fn render_toast(app: &App, renderer: &mut tide_renderer::WgpuRenderer, p: &ThemePalette) {
+    let Some(ref toast) = app.toast else { return };
+    let cell_size = renderer.cell_size();
+    let logical = app.logical_size();
+    let pad = 12.0;
+    let w = visual_width(&toast.message) as f32 * cell_size.width + pad * 2.0;
+    let h = cell_size.height + pad;
+    let rect = Rect::new((logical.width - w) / 2.0, logical.height - h - 24.0, w, h);
+    draw_popup_rounded_bg(renderer, rect, p.popup_bg, p.popup_border, POPUP_CORNER_RADIUS);
+    let text_pos = Vec2::new(rect.x + pad, rect.y + pad / 2.0);
+    renderer.draw_top_text(&toast.message, text_pos, text_style(p.tab_text_focused), rect);
+}
+
+/// Badge in the bottom-right corner of terminals in copy mode: the mode and
+/// count, or the `/` `?` search prompt being typed.
+fn render_copy_mode_status(
+    app: &App,
+    renderer: &mut tide_renderer::WgpuRenderer,
+    p: &ThemePalette,
+    visual_pane_rects: &[(u64, Rect)],
+) {
+    let cell_size = renderer.cell_size();
+    for &(id, rect) in visual_pane_rects {
+        let Some(PaneKind::Terminal(pane)) = app.panes.get(&id) else { continue };
+        let Some(ref state) = pane.copy_mode else { continue };
+        let status = state.status(pane.backend.copy_mode().and_then(|v| v.selection));
+        let pad = 6.0;
+        let w = (visual_width(&status) + 1) as f32 * cell_size.width + pad * 2.0;
+        let h = cell_size.height + pad;
+        let badge = Rect::new(rect.x + rect.width - w - 8.0, rect.y + rect.height - h - 8.0, w, h);
+        if badge.x < rect.x || badge.y < rect.y + TAB_BAR_HEIGHT {
+            continue;
+        }
+        renderer.draw_top_rect(badge, p.search_bar_bg);
+        draw_popup_border(renderer, badge, p.search_bar_border);
+        let text_pos = Vec2::new(badge.x + pad, badge.y + pad / 2.0);
+        renderer.draw_top_text(&status, text_pos, text_style(p.search_bar_text), badge);
+        if let Some((ref input, _)) = state.prompt {
+            let cx = text_pos.x + (1 + visual_width(&input.text[..input.cursor])) as f32 * cell_size.width;
+            draw_cursor_beam(renderer, cx, text_pos.y, cell_size.height, p.cursor_accent);
+        }
+    }
 }
 
 /// Render search bar UI for panes that have search visible.
@@ -103,27 +171,24 @@ fn render_search_bars(
     let cell_size = renderer.cell_size();
 
     // Helper: render a search bar floating at top-right of a given rect
-    let mut search_bars: Vec<(tide_core::PaneId, Rect, String, String, usize, bool)> = Vec::new();
+    let mut search_bars: Vec<(Rect, &crate::search::SearchState, bool)> = Vec::new();
     for &(id, rect) in visual_pane_rects {
-        let (query, display, cursor_pos, visible) = match app.panes.get(&id) {
-            Some(PaneKind::Terminal(pane)) => match &pane.search {
-                Some(s) if s.visible => (s.input.text.clone(), s.current_display(), s.input.cursor, true),
-                _ => continue,
-            },
-            Some(PaneKind::Editor(pane)) => match &pane.search {
-                Some(s) if s.visible => (s.input.text.clone(), s.current_display(), s.input.cursor, true),
-                _ => continue,
-            },
-            _ => continue,
+        let search = match app.panes.get(&id) {
+            Some(PaneKind::Terminal(pane)) => pane.search.as_ref(),
+            Some(PaneKind::Editor(pane)) => pane.search.as_ref(),
+            _ => None,
         };
-        if visible {
-            search_bars.push((id, rect, query, display, cursor_pos, search_focus == Some(id)));
+        if let Some(s) = search.filter(|s| s.visible) {
+            search_bars.push((rect, s, search_focus == Some(id)));
         }
     }
 
-    for (_id, rect, query, display, cursor_pos, is_focused) in &search_bars {
+    for (rect, search, is_focused) in &search_bars {
+        let query = &search.input.text;
+        let display = search.current_display();
+        let cursor_pos = search.input.cursor;
         let bar_w = SEARCH_BAR_WIDTH.min(rect.width - 16.0);
-        if bar_w < 80.0 { continue; } // too narrow to render
+        if bar_w < SEARCH_BAR_MIN_WIDTH { continue; } // too narrow to render
         let bar_h = SEARCH_BAR_HEIGHT;
         let bar_x = rect.x + rect.width - bar_w - 8.0;
         let bar_y = rect.y + TAB_BAR_HEIGHT + 4.0;
@@ -137,15 +202,16 @@ fn render_search_bars(
 
         let text_x = bar_x + 6.0;
         let text_y = bar_y + (bar_h - cell_size.height) / 2.0;
-        let ts = text_style(p.search_bar_text);
+        let ts = text_style(if search.invalid { p.search_bar_error } else { p.search_bar_text });
         let muted_style = text_style(p.tab_text);
         let counter_style = text_style(p.search_bar_counter);
 
-        // Layout: [query text] [counter] [close button]
+        // Layout: [query text] [counter] [Aa][ab][.*] [close button]
         let close_area_w = SEARCH_BAR_CLOSE_SIZE;
         let close_x = bar_x + bar_w - close_area_w;
+        let toggles = crate::search::search_toggle_rects(bar_rect);
         let counter_w = display.len() as f32 * cell_size.width;
-        let counter_x = close_x - counter_w - 4.0;
+        let counter_x = toggles[0].1.x - counter_w - 4.0;
         let text_clip_w = (counter_x - text_x - 4.0).max(0.0);
 
         // Query text (top layer) or placeholder
@@ -158,13 +224,25 @@ fn render_search_bars(
 
         // Text cursor (beam) — only when focused
         if *is_focused {
-            let cx = text_x + visual_width(&query[..*cursor_pos]) as f32 * cell_size.width;
+            let cx = text_x + visual_width(&query[..cursor_pos]) as f32 * cell_size.width;
             draw_cursor_beam(renderer, cx, text_y, cell_size.height, p.cursor_accent);
         }
 
         // Counter text
         let counter_clip = Rect::new(counter_x, bar_y, counter_w + 4.0, bar_h);
-        renderer.draw_top_text(display, Vec2::new(counter_x, text_y), counter_style, counter_clip);
+        renderer.draw_top_text(&display, Vec2::new(counter_x, text_y), counter_style, counter_clip);
+
+        // Option toggles: highlighted background + full-strength label when on
+        for (toggle, toggle_rect) in toggles {
+            let on = search.is_enabled(toggle);
+            if on {
+                renderer.draw_top_rect(toggle_rect, p.search_toggle_on);
+            }
+            let label = toggle.label();
+            let label_x = toggle_rect.x + (toggle_rect.width - label.len() as f32 * cell_size.width) / 2.0;
+            let style = if on { text_style(p.search_bar_text) } else { counter_style };
+            renderer.draw_top_text(label, Vec2::new(label_x, text_y), style, toggle_rect);
+        }
 
         // Close button
         let close_icon_x = close_x + (close_area_w - cell_size.width) / 2.0;
@@ -241,6 +319,47 @@ fn render_notification_bars(
             }
         }
 
+        // Check for clipboard bar
+        if let Some(ref prompt) = app.modal.clipboard_prompt {
+            if prompt.pane_id == pane_id {
+                renderer.draw_top_rect(bar_rect, p.conflict_bar_bg);
+                let text_y = bar_rect.y + (CONFLICT_BAR_HEIGHT - cell_size.height) / 2.0;
+                let ts = text_style(p.conflict_bar_text);
+                renderer.draw_top_text(&prompt.message, Vec2::new(bar_rect.x + 8.0, text_y), ts, bar_rect);
+
+                let btn_style = bold_style(p.conflict_bar_btn_text);
+                let btn_pad = 8.0;
+                let btn_h = CONFLICT_BAR_HEIGHT - 6.0;
+                let btn_y = bar_rect.y + 3.0;
+
+                // Deny button (rightmost)
+                let deny_text = "Deny";
+                let deny_w = deny_text.len() as f32 * cell_size.width + btn_pad * 2.0;
+                let deny_x = bar_rect.x + bar_rect.width - deny_w - 4.0;
+                let deny_rect = Rect::new(deny_x, btn_y, deny_w, btn_h);
+                renderer.draw_top_rect(deny_rect, p.conflict_bar_btn);
+                renderer.draw_top_text(deny_text, Vec2::new(deny_x + btn_pad, text_y), btn_style, deny_rect);
+
+                // Always button (allow for this terminal from now on)
+                let always_text = "Always";
+                let always_w = always_text.len() as f32 * cell_size.width + btn_pad * 2.0;
+                let always_x = deny_x - always_w - 4.0;
+                let always_rect = Rect::new(always_x, btn_y, always_w, btn_h);
+                renderer.draw_top_rect(always_rect, p.conflict_bar_btn);
+                renderer.draw_top_text(always_text, Vec2::new(always_x + btn_pad, text_y), btn_style, always_rect);
+
+                // Allow button (this request only)
+                let allow_text = "Allow";
+                let allow_w = allow_text.len() as f32 * cell_size.width + btn_pad * 2.0;
+                let allow_x = always_x - allow_w - 4.0;
+                let allow_rect = Rect::new(allow_x, btn_y, allow_w, btn_h);
+                renderer.draw_top_rect(allow_rect, p.conflict_bar_btn);
+                renderer.draw_top_text(allow_text, Vec2::new(allow_x + btn_pad, text_y), btn_style, allow_rect);
+
+                continue;
+            }
+        }
+
         // Check for save confirm bar first
         if let Some(ref sc) = app.modal.save_confirm {
             if sc.pane_id == pane_id {
@@ -572,7 +691,9 @@ fn render_file_finder(
             bold: fi == finder.selected,
             dim: false,
             italic: false,
-            underline: false,
+            underline: UnderlineStyle::None,
+            underline_color: None,
+            strikethrough: false,
         };
         renderer.draw_top_text(
             &display_path,
@@ -583,6 +704,121 @@ fn render_file_finder(
     }
 }
 
+/// Render the problems panel: a title row, then one row per problem
+/// (severity icon, location, message).
+fn render_problems(
+    app: &App,
+    renderer: &mut tide_renderer::WgpuRenderer,
+    p: &ThemePalette,
+) {
+    let panel = match app.modal.problems {
+        Some(ref panel) => panel,
+        None => return,
+    };
+    let problems = &app.problems.items;
+
+    // Dim overlay (scrim)
+    draw_popup_scrim(renderer, app.logical_size(), p.popup_scrim);
+
+    let cell_size = renderer.cell_size();
+    let cell_height = cell_size.height;
+    let logical = app.logical_size();
+    let geo = panel.geometry(problems.len(), cell_height, logical.width, logical.height);
+
+    let line_height = geo.line_height;
+    let popup_x = geo.popup_x;
+    let popup_w = geo.popup_w;
+    let popup_rect = Rect::new(popup_x, geo.popup_y, popup_w, geo.popup_h);
+
+    // Shadow
+    let shadow_color = Color::new(0.0, 0.0, 0.0, 0.25);
+    renderer.draw_top_shadow(popup_rect, shadow_color, 8.0, 40.0, 0.0);
+
+    // Background + border (rounded)
+    draw_popup_rounded_bg(renderer, popup_rect, p.popup_bg, p.popup_border, POPUP_CORNER_RADIUS);
+
+    let muted_style = text_style(p.tab_text);
+    let item_pad = 12.0_f32;
+
+    // Title row with counts
+    let title_y = geo.popup_y + 2.0;
+    let title_clip = Rect::new(popup_x + item_pad, title_y, popup_w - 2.0 * item_pad, geo.title_h);
+    let text_y = title_y + (geo.title_h - cell_height) / 2.0;
+    renderer.draw_top_text(
+        "Problems",
+        Vec2::new(popup_x + item_pad, text_y),
+        TextStyle { bold: true, ..text_style(p.tab_text_focused) },
+        title_clip,
+    );
+    let errors = problems.iter().filter(|pr| pr.severity == crate::problems::Severity::Error).count();
+    let count_text = format!("{} errors, {} other", errors, problems.len() - errors);
+    let count_w = count_text.len() as f32 * cell_size.width;
+    renderer.draw_top_text(
+        &count_text,
+        Vec2::new(popup_x + popup_w - count_w - item_pad, text_y),
+        muted_style,
+        title_clip,
+    );
+
+    // Separator line below title
+    let sep_y = title_y + geo.title_h;
+    let sep_rect = Rect::new(popup_x + POPUP_SEPARATOR_INSET, sep_y, popup_w - 2.0 * POPUP_SEPARATOR_INSET, POPUP_SEPARATOR);
+    renderer.draw_top_rect(sep_rect, p.popup_border);
+
+    let list_top = geo.list_top;
+    let list_clip = Rect::new(
+        popup_x + item_pad,
+        list_top,
+        popup_w - 2.0 * item_pad,
+        geo.max_visible as f32 * line_height,
+    );
+    let text_offset_y = (line_height - cell_height) / 2.0;
+
+    if problems.is_empty() {
+        renderer.draw_top_text(
+            "No problems found in the terminal's output",
+            Vec2::new(popup_x + item_pad + 4.0, list_top + text_offset_y),
+            muted_style,
+            list_clip,
+        );
+        return;
+    }
+
+    for vi in 0..geo.max_visible {
+        let idx = panel.scroll_offset + vi;
+        let Some(problem) = problems.get(idx) else { break };
+        let y = list_top + vi as f32 * line_height;
+        let selected = idx == panel.selected;
+
+        // Selected item highlight
+        if selected {
+            renderer.draw_top_rect(Rect::new(popup_x + 2.0, y, popup_w - 4.0, line_height), p.popup_selected);
+        }
+
+        // Severity icon
+        let (icon, color) = match problem.severity {
+            crate::problems::Severity::Error => ("\u{f057}", p.command_failed),
+            crate::problems::Severity::Warning => ("\u{f071}", p.git_modified),
+            crate::problems::Severity::Info => ("\u{f05a}", p.tab_text),
+        };
+        let icon_x = popup_x + item_pad + 4.0;
+        renderer.draw_top_text(icon, Vec2::new(icon_x, y + text_offset_y), text_style(color), list_clip);
+
+        // Location, then the message
+        let location = app.problem_location(problem);
+        let loc_x = icon_x + 2.0 * cell_size.width;
+        let loc_color = if selected { p.tab_text_focused } else { p.tree_text };
+        renderer.draw_top_text(
+            &location,
+            Vec2::new(loc_x, y + text_offset_y),
+            TextStyle { bold: selected, ..text_style(loc_color) },
+            list_clip,
+        );
+        let msg_x = loc_x + (visual_width(&location) + 2) as f32 * cell_size.width;
+        renderer.draw_top_text(&problem.message, Vec2::new(msg_x, y + text_offset_y), muted_style, list_clip);
+    }
+}
+
 /// Render git switcher popup overlay (integrated branch + worktree popup).
 fn render_git_switcher(
     app: &App,
@@ -681,7 +917,9 @@ fn render_git_switcher(
         bold: gs.mode == crate::GitSwitcherMode::Branches,
         dim: false,
         italic: false,
-        underline: false,
+        underline: UnderlineStyle::None,
+        underline_color: None,
+        strikethrough: false,
     };
     let worktrees_style = TextStyle {
         foreground: if gs.mode == crate::GitSwitcherMode::Worktrees { tab_active_color } else { tab_inactive_color },
@@ -689,7 +927,9 @@ fn render_git_switcher(
         bold: gs.mode == crate::GitSwitcherMode::Worktrees,
         dim: false,
         italic: false,
-        underline: false,
+        underline: UnderlineStyle::None,
+        underline_color: None,
+        strikethrough: false,
     };
     let tab_clip = Rect::new(popup_x, tab_y, popup_w, tab_h);
     // Center each label in its half
@@ -766,7 +1006,9 @@ fn render_git_switcher(
                 bold: true,
                 dim: false,
                 italic: false,
-                underline: false,
+                underline: UnderlineStyle::None,
+                underline_color: None,
+                strikethrough: false,
             };
             renderer.draw_top_text(label, Vec2::new(x + btn_pad_h, btn_text_y), style, list_clip);
 
@@ -785,7 +1027,7 @@ fn render_git_switcher(
                     let del_style = TextStyle {
                         foreground: Color::new(1.0, 1.0, 1.0, 1.0),
                         background: None,
-                        bold: true, dim: false, italic: false, underline: false,
+                        bold: true, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     };
                     renderer.draw_top_text(del_label, Vec2::new(del_x + btn_pad_h, btn_text_y), del_style, list_clip);
                 } else {
@@ -843,7 +1085,9 @@ fn render_git_switcher(
                     bold: true,
                     dim: false,
                     italic: false,
-                    underline: false,
+                    underline: UnderlineStyle::None,
+                    underline_color: None,
+                    strikethrough: false,
                 };
                 renderer.draw_top_text(switch_label, Vec2::new(switch_x + btn_pad_h, btn_text_y), switch_style, list_clip);
                 cur_right = switch_x - gap;
@@ -862,7 +1106,7 @@ fn render_git_switcher(
                         let del_style = TextStyle {
                             foreground: Color::new(1.0, 1.0, 1.0, 1.0),
                             background: None,
-                            bold: true, dim: false, italic: false, underline: false,
+                            bold: true, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                         };
                         renderer.draw_top_text(del_label, Vec2::new(del_x + btn_pad_h, btn_text_y), del_style, list_clip);
                     } else {
@@ -930,7 +1174,9 @@ fn render_git_switcher(
                         bold: fi == gs.selected,
                         dim: false,
                         italic: false,
-                        underline: false,
+                        underline: UnderlineStyle::None,
+                        underline_color: None,
+                        strikethrough: false,
                     };
                     renderer.draw_top_text(&branch.name, Vec2::new(name_x, item_y), name_style, list_clip);
 
@@ -951,7 +1197,9 @@ fn render_git_switcher(
                         bold: false,
                         dim: false,
                         italic: false,
-                        underline: false,
+                        underline: UnderlineStyle::None,
+                        underline_color: None,
+                        strikethrough: false,
                     };
                     renderer.draw_top_text(badge_label, Vec2::new(badge_x + 4.0, item_y), badge_style, list_clip);
                 } else {
@@ -966,7 +1214,9 @@ fn render_git_switcher(
                         bold: fi == gs.selected,
                         dim: false,
                         italic: false,
-                        underline: false,
+                        underline: UnderlineStyle::None,
+                        underline_color: None,
+                        strikethrough: false,
                     };
                     renderer.draw_top_text(&branch.name, Vec2::new(name_x, item_y), name_style, text_clip);
 
@@ -1016,7 +1266,9 @@ fn render_git_switcher(
                         bold: fi == gs.selected,
                         dim: false,
                         italic: false,
-                        underline: false,
+                        underline: UnderlineStyle::None,
+                        underline_color: None,
+                        strikethrough: false,
                     };
                     renderer.draw_top_text(name, Vec2::new(name_x, item_y), name_style, list_clip);
 
@@ -1037,7 +1289,9 @@ fn render_git_switcher(
                         bold: false,
                         dim: false,
                         italic: false,
-                        underline: false,
+                        underline: UnderlineStyle::None,
+                        underline_color: None,
+                        strikethrough: false,
                     };
                     renderer.draw_top_text(badge_label, Vec2::new(badge_x + 4.0, item_y), badge_style, list_clip);
                 } else {
@@ -1052,7 +1306,9 @@ fn render_git_switcher(
                         bold: fi == gs.selected,
                         dim: false,
                         italic: false,
-                        underline: false,
+                        underline: UnderlineStyle::None,
+                        underline_color: None,
+                        strikethrough: false,
                     };
                     renderer.draw_top_text(name, Vec2::new(name_x, item_y), name_style, text_clip);
 
@@ -1093,7 +1349,9 @@ fn render_git_switcher(
                 bold: create_fi == gs.selected,
                 dim: false,
                 italic: false,
-                underline: false,
+                underline: UnderlineStyle::None,
+                underline_color: None,
+                strikethrough: false,
             };
             renderer.draw_top_text(gs.input.text.trim(), Vec2::new(name_x, item_y), create_name_style, list_clip);
 
@@ -1121,7 +1379,9 @@ fn render_git_switcher(
         bold: false,
         dim: false,
         italic: false,
-        underline: false,
+        underline: UnderlineStyle::None,
+        underline_color: None,
+        strikethrough: false,
     };
     let hint_clip = Rect::new(popup_x, hint_bar_y, popup_w, hint_bar_h);
     renderer.draw_top_text(hint_text, Vec2::new(hint_text_x, hint_text_y), hint_style, hint_clip);
@@ -1183,7 +1443,9 @@ fn render_context_menu(
             bold: i == menu.selected,
             dim: false,
             italic: false,
-            underline: false,
+            underline: UnderlineStyle::None,
+            underline_color: None,
+            strikethrough: false,
         };
         renderer.draw_top_text(action.label(), Vec2::new(label_x, item_y), label_style, item_clip);
     }
@@ -1256,7 +1518,9 @@ fn render_config_page(
         bold: kb_active,
         dim: false,
         italic: false,
-        underline: false,
+        underline: UnderlineStyle::None,
+        underline_color: None,
+        strikethrough: false,
     };
     let wt_style = TextStyle {
         foreground: if !kb_active { tab_active_color } else { tab_inactive_color },
@@ -1264,7 +1528,9 @@ fn render_config_page(
         bold: !kb_active,
         dim: false,
         italic: false,
-        underline: false,
+        underline: UnderlineStyle::None,
+        underline_color: None,
+        strikethrough: false,
     };
     let tab_clip = Rect::new(popup_x, tab_y, popup_w, tab_h);
     let kb_text_w = keybindings_label.len() as f32 * cell_size.width;
@@ -1320,7 +1586,9 @@ fn render_config_page(
                     bold: fi == page.selected,
                     dim: false,
                     italic: false,
-                    underline: false,
+                    underline: UnderlineStyle::None,
+                    underline_color: None,
+                    strikethrough: false,
                 };
                 let label_clip = Rect::new(popup_x + item_pad, y, popup_w * 0.55, line_height);
                 renderer.draw_top_text(label, Vec2::new(popup_x + item_pad, item_y), label_style, label_clip);
@@ -1337,7 +1605,9 @@ fn render_config_page(
                         bold: true,
                         dim: false,
                         italic: false,
-                        underline: false,
+                        underline: UnderlineStyle::None,
+                        underline_color: None,
+                        strikethrough: false,
                     };
                     renderer.draw_top_text("Press key...", Vec2::new(hotkey_x, item_y), recording_style, hotkey_clip);
                 } else {
@@ -0,0 +1,129 @@
+// Replay pane: plays back an asciicast (.cast) recording in a read-only grid.
+
+use std::path::{Path, PathBuf};
+
+use tide_core::{Color, Key, Rect, Renderer, TerminalBackend};
+use tide_renderer::WgpuRenderer;
+use tide_terminal::CastPlayer;
+
+/// Seconds skipped by Left/Right.
+const SEEK_STEP: f64 = 5.0;
+
+/// Height of the progress bar drawn along the bottom of the grid.
+const PROGRESS_BAR_HEIGHT: f32 = 2.0;
+
+pub struct ReplayPane {
+    pub path: PathBuf,
+    pub player: CastPlayer,
+}
+
+impl ReplayPane {
+    /// Load a recording; playback starts immediately.
+    pub fn open(path: PathBuf, dark_mode: bool) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut player = CastPlayer::open(&path, dark_mode)?;
+        player.toggle_pause();
+        Ok(Self { path, player })
+    }
+
+    /// Whether `path` looks like an asciicast recording.
+    pub fn is_cast_file(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "cast")
+    }
+
+    pub fn title(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Replay".to_string())
+    }
+
+    /// Header status: play state, position / duration, speed.
+    pub fn status_text(&self) -> String {
+        let icon = if self.player.is_paused() { "\u{f04c}" } else { "\u{f04b}" };
+        format!(
+            "{} {} / {}  {}\u{00d7}",
+            icon,
+            format_clock(self.player.position()),
+            format_clock(self.player.duration()),
+            self.player.speed(),
+        )
+    }
+
+    /// Advance playback. True when the header status changed.
+    pub fn process(&mut self) -> bool {
+        let before = self.status_state();
+        self.player.process();
+        self.status_state() != before
+    }
+
+    /// What `status_text` shows: the clock only moves in whole seconds.
+    fn status_state(&self) -> (bool, u64, u64) {
+        (self.player.is_paused(), self.player.position().max(0.0) as u64, self.player.speed().to_bits())
+    }
+
+    /// Playback controls. Space: play/pause, Left/Right: seek ±5s,
+    /// Up/Down: double/halve speed, Home/End: jump to start/end.
+    /// Returns false for keys that aren't controls.
+    pub fn handle_key(&mut self, key: &Key) -> bool {
+        let player = &mut self.player;
+        match key {
+            Key::Char(' ') => player.toggle_pause(),
+            Key::Left => player.seek(player.position() - SEEK_STEP),
+            Key::Right => player.seek(player.position() + SEEK_STEP),
+            Key::Up => player.set_speed(player.speed() * 2.0),
+            Key::Down => player.set_speed(player.speed() / 2.0),
+            Key::Home => player.seek(0.0),
+            Key::End => player.seek(player.duration()),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Render the grid, then a progress bar along the bottom edge.
+    pub fn render_grid(&self, rect: Rect, renderer: &mut WgpuRenderer, track: Color, fill: Color) {
+        crate::pane::render_terminal_grid(self.player.grid(), rect, renderer);
+        let duration = self.player.duration();
+        let progress = if duration > 0.0 { (self.player.position() / duration) as f32 } else { 1.0 };
+        let y = rect.y + rect.height - PROGRESS_BAR_HEIGHT;
+        renderer.draw_rect(Rect::new(rect.x, y, rect.width, PROGRESS_BAR_HEIGHT), track);
+        renderer.draw_rect(Rect::new(rect.x, y, rect.width * progress.clamp(0.0, 1.0), PROGRESS_BAR_HEIGHT), fill);
+    }
+
+    /// Changes with the grid and with the play state shown in the header.
+    pub fn generation(&self) -> u64 {
+        // Position is folded in at 10 Hz so the progress bar advances
+        let tenths = (self.player.position() * 10.0) as u64;
+        self.player.grid_generation().wrapping_mul(31).wrapping_add(tenths)
+            ^ ((self.player.is_paused() as u64) << 63)
+    }
+}
+
+/// "m:ss" (or "h:mm:ss") playback clock.
+pub(crate) fn format_clock(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_clock_pads_seconds() {
+        assert_eq!(format_clock(0.0), "0:00");
+        assert_eq!(format_clock(65.9), "1:05");
+        assert_eq!(format_clock(3_725.0), "1:02:05");
+    }
+
+    #[test]
+    fn only_cast_files_open_as_replays() {
+        assert!(ReplayPane::is_cast_file(Path::new("/tmp/demo.cast")));
+        assert!(!ReplayPane::is_cast_file(Path::new("/tmp/demo.txt")));
+        assert!(!ReplayPane::is_cast_file(Path::new("/tmp/cast")));
+    }
+}
@@ -0,0 +1,135 @@
+// Scrollback limits: each terminal keeps `TideSettings::scrollback_lines` of
+// history (or its profile override), and an optional global memory budget
+// caps the total. Over budget, the oldest history of background terminals —
+// panes not on screen, including every terminal in inactive workspaces — is
+// trimmed, largest first. Visible terminals are never trimmed.
+
+use std::time::{Duration, Instant};
+
+use tide_core::PaneId;
+
+use crate::pane::PaneKind;
+use crate::App;
+
+/// How often the memory budget is checked (reading history sizes takes each term lock).
+const BUDGET_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Background terminals are never trimmed below this many lines.
+const MIN_TRIMMED_HISTORY: usize = 1_000;
+
+/// History usage of one terminal, for budget planning.
+#[derive(Debug, Clone, Copy)]
+struct HistoryUsage {
+    id: PaneId,
+    lines: usize,
+    line_bytes: usize,
+    background: bool,
+}
+
+/// Lines of history each background terminal should keep so the total fits
+/// in `budget` bytes. Terminals that need no trimming are left out.
+fn plan_history_trim(usages: &[HistoryUsage], budget: usize) -> Vec<(PaneId, usize)> {
+    let total: usize = usages.iter().map(|u| u.lines * u.line_bytes).sum();
+    let mut excess = total.saturating_sub(budget);
+    let mut candidates: Vec<&HistoryUsage> = usages
+        .iter()
+        .filter(|u| u.background && u.lines > MIN_TRIMMED_HISTORY && u.line_bytes > 0)
+        .collect();
+    candidates.sort_by_key(|u| std::cmp::Reverse(u.lines * u.line_bytes));
+
+    let mut plan = Vec::new();
+    for u in candidates {
+        if excess == 0 {
+            break;
+        }
+        let drop = excess.div_ceil(u.line_bytes).min(u.lines - MIN_TRIMMED_HISTORY);
+        plan.push((u.id, u.lines - drop));
+        excess = excess.saturating_sub(drop * u.line_bytes);
+    }
+    plan
+}
+
+impl App {
+    /// Apply the configured scrollback limit to every terminal and, at most
+    /// every few seconds, enforce the memory budget.
+    pub(crate) fn enforce_scrollback_limits(&mut self) {
+        let default_lines = self.settings.scrollback_lines;
+        let all_panes = self.panes.iter_mut()
+            .chain(self.ws.workspaces.iter_mut().flat_map(|ws| ws.panes.iter_mut()));
+        for (_, pane) in all_panes {
+            if let PaneKind::Terminal(t) = pane {
+                t.backend.set_scrollback_lines(t.scrollback_lines.unwrap_or(default_lines));
+            }
+        }
+
+        let Some(budget_mb) = self.settings.scrollback_memory_mb else {
+            return;
+        };
+        let now = Instant::now();
+        if now.duration_since(self.last_scrollback_check) < BUDGET_CHECK_INTERVAL {
+            return;
+        }
+        self.last_scrollback_check = now;
+
+        let visible: Vec<PaneId> = self.visual_pane_rects.iter().map(|&(id, _)| id).collect();
+        let all_panes = self.panes.iter_mut()
+            .chain(self.ws.workspaces.iter_mut().flat_map(|ws| ws.panes.iter_mut()));
+        let mut terminals: Vec<(PaneId, &mut crate::pane::TerminalPane)> = all_panes
+            .filter_map(|(&id, pane)| match pane {
+                PaneKind::Terminal(t) => Some((id, t)),
+                _ => None,
+            })
+            .collect();
+        let usages: Vec<HistoryUsage> = terminals
+            .iter()
+            .map(|(id, t)| HistoryUsage {
+                id: *id,
+                lines: t.backend.history_size(),
+                line_bytes: t.backend.history_line_bytes(),
+                background: !visible.contains(id),
+            })
+            .collect();
+
+        for (id, keep) in plan_history_trim(&usages, budget_mb * 1024 * 1024) {
+            if let Some((_, t)) = terminals.iter_mut().find(|(tid, _)| *tid == id) {
+                log::info!("Scrollback budget: trimming terminal {} to {} lines", id, keep);
+                t.backend.trim_history(keep);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(id: PaneId, lines: usize, background: bool) -> HistoryUsage {
+        HistoryUsage { id, lines, line_bytes: 100, background }
+    }
+
+    #[test]
+    fn under_budget_trims_nothing() {
+        let usages = [usage(1, 5_000, true), usage(2, 5_000, true)];
+        assert!(plan_history_trim(&usages, 1_000_000).is_empty());
+    }
+
+    #[test]
+    fn trims_largest_background_terminal_first() {
+        // 30k lines * 100 B = 3 MB; budget 2.5 MB → drop 5k lines
+        let usages = [usage(1, 10_000, true), usage(2, 20_000, true)];
+        assert_eq!(plan_history_trim(&usages, 2_500_000), vec![(2, 15_000)]);
+    }
+
+    #[test]
+    fn visible_terminals_are_never_trimmed() {
+        let usages = [usage(1, 100_000, false), usage(2, 4_000, true)];
+        assert_eq!(plan_history_trim(&usages, 0), vec![(2, MIN_TRIMMED_HISTORY)]);
+    }
+
+    #[test]
+    fn spills_over_to_next_terminal_and_respects_floor() {
+        let usages = [usage(1, 3_000, true), usage(2, 5_000, true)];
+        // Excess 500 KB = 5k lines: terminal 2 gives 4k, terminal 1 the remaining 1k
+        assert_eq!(plan_history_trim(&usages, 300_000), vec![(2, 1_000), (1, 2_000)]);
+    }
+}
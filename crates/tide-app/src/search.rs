@@ -1,7 +1,8 @@
 // Search state and logic for Cmd+F search in terminal and editor panes.
 
 use crate::ui_state::InputLine;
-use tide_terminal::Terminal;
+use tide_core::Rect;
+use tide_terminal::{SearchOptions, Terminal, MAX_SEARCH_MATCHES};
 
 /// A single match location.
 #[derive(Debug, Clone)]
@@ -11,24 +12,89 @@ pub struct SearchMatch {
     pub len: usize,
 }
 
+/// A search bar toggle button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchToggle {
+    CaseSensitive,
+    WholeWord,
+    Regex,
+}
+
+impl SearchToggle {
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchToggle::CaseSensitive => "Aa",
+            SearchToggle::WholeWord => "ab",
+            SearchToggle::Regex => ".*",
+        }
+    }
+
+    /// Toggle for an Alt+key shortcut while the search bar is focused.
+    pub fn from_alt_key(ch: char) -> Option<Self> {
+        match ch.to_ascii_lowercase() {
+            'c' => Some(SearchToggle::CaseSensitive),
+            'w' => Some(SearchToggle::WholeWord),
+            'r' => Some(SearchToggle::Regex),
+            _ => None,
+        }
+    }
+}
+
+/// Toggle button rects inside a search bar, packed right-aligned against the
+/// close button.
+pub fn search_toggle_rects(bar: Rect) -> [(SearchToggle, Rect); 3] {
+    use crate::theme::{SEARCH_BAR_CLOSE_SIZE, SEARCH_BAR_TOGGLE_SIZE};
+    let size = SEARCH_BAR_TOGGLE_SIZE;
+    let left = bar.x + bar.width - SEARCH_BAR_CLOSE_SIZE - 3.0 * size;
+    let y = bar.y + (bar.height - size) / 2.0;
+    let rect = |i: f32| Rect::new(left + i * size, y, size, size);
+    [
+        (SearchToggle::CaseSensitive, rect(0.0)),
+        (SearchToggle::WholeWord, rect(1.0)),
+        (SearchToggle::Regex, rect(2.0)),
+    ]
+}
+
 /// Search state for a single pane.
 pub struct SearchState {
     pub input: InputLine,
+    pub options: SearchOptions,
     pub matches: Vec<SearchMatch>,
     pub current: Option<usize>,   // index into matches
     pub visible: bool,
+    /// The query failed to compile as a regex (shown in the error color).
+    pub invalid: bool,
 }
 
 impl SearchState {
     pub fn new() -> Self {
         Self {
             input: InputLine::new(),
+            options: SearchOptions::default(),
             matches: Vec::new(),
             current: None,
             visible: true,
+            invalid: false,
         }
     }
 
+    pub fn is_enabled(&self, toggle: SearchToggle) -> bool {
+        match toggle {
+            SearchToggle::CaseSensitive => self.options.case_sensitive,
+            SearchToggle::WholeWord => self.options.whole_word,
+            SearchToggle::Regex => self.options.regex,
+        }
+    }
+
+    pub fn toggle(&mut self, toggle: SearchToggle) {
+        let flag = match toggle {
+            SearchToggle::CaseSensitive => &mut self.options.case_sensitive,
+            SearchToggle::WholeWord => &mut self.options.whole_word,
+            SearchToggle::Regex => &mut self.options.regex,
+        };
+        *flag = !*flag;
+    }
+
     pub fn next_match(&mut self) {
         if self.matches.is_empty() {
             self.current = None;
@@ -72,19 +138,39 @@ pub fn execute_search_terminal(state: &mut SearchState, terminal: &Terminal) {
 
     state.matches.clear();
     state.current = None;
+    state.invalid = false;
 
     if state.input.is_empty() {
         return;
     }
 
-    let results = terminal.search_buffer(&state.input.text);
-    state.matches = results
-        .into_iter()
-        .map(|(line, col, len)| SearchMatch { line, col, len })
-        .collect();
+    match terminal.search_buffer(&state.input.text, state.options) {
+        Ok(results) => {
+            state.matches = results
+                .into_iter()
+                .map(|(line, col, len)| SearchMatch { line, col, len })
+                .collect();
+        }
+        Err(_) => state.invalid = true,
+    }
+
+    restore_current(state, prev_pos);
+}
+
+/// Compile the query under the state's options, flagging an invalid regex.
+fn compile_query(state: &mut SearchState) -> Option<regex::Regex> {
+    state.invalid = false;
+    if state.input.is_empty() {
+        return None;
+    }
+    let re = regex::Regex::new(&state.options.pattern(&state.input.text));
+    state.invalid = re.is_err();
+    re.ok()
+}
 
+/// Point `current` at the match at `prev_pos` if it still exists, else the first.
+fn restore_current(state: &mut SearchState, prev_pos: Option<(usize, usize)>) {
     if !state.matches.is_empty() {
-        // Try to restore current match to the same position
         state.current = if let Some((line, col)) = prev_pos {
             state.matches.iter()
                 .position(|m| m.line == line && m.col == col)
@@ -95,7 +181,7 @@ pub fn execute_search_terminal(state: &mut SearchState, terminal: &Terminal) {
     }
 }
 
-/// Execute search over preview lines (case-insensitive).
+/// Execute search over preview lines.
 /// Each preview line's text is the concatenation of its span texts (excluding newlines).
 /// Match coordinates are in preview-line space so highlights and navigation work directly.
 pub fn execute_search_preview(state: &mut SearchState, preview_lines: &[tide_editor::markdown::PreviewLine]) {
@@ -108,49 +194,35 @@ pub fn execute_search_preview(state: &mut SearchState, preview_lines: &[tide_edi
     state.matches.clear();
     state.current = None;
 
-    if state.input.is_empty() {
+    let Some(re) = compile_query(state) else {
         return;
-    }
-
-    let query_lower = state.input.text.to_lowercase();
-    let query_display_width: usize = state.input.text.chars().map(|c| c.width().unwrap_or(1)).sum();
+    };
+    let display_width = |s: &str| -> usize { s.chars().map(|c| c.width().unwrap_or(1)).sum() };
 
-    for (line_idx, pline) in preview_lines.iter().enumerate() {
+    'lines: for (line_idx, pline) in preview_lines.iter().enumerate() {
         // Build the full text of this preview line from spans
         let line_text: String = pline.spans.iter()
             .flat_map(|s| s.text.chars())
             .filter(|c| *c != '\n')
             .collect();
-        let line_lower = line_text.to_lowercase();
-
-        let mut start = 0;
-        while let Some(byte_pos) = line_lower[start..].find(&query_lower) {
-            let byte_col = start + byte_pos;
-            // Convert byte offset to display-cell column
-            let display_col: usize = line_text[..byte_col].chars()
-                .map(|c| c.width().unwrap_or(1))
-                .sum();
+
+        for m in re.find_iter(&line_text).filter(|m| !m.is_empty()) {
+            if state.matches.len() >= MAX_SEARCH_MATCHES {
+                break 'lines;
+            }
+            // Convert byte offsets to display-cell columns
             state.matches.push(SearchMatch {
                 line: line_idx,
-                col: display_col,
-                len: query_display_width,
+                col: display_width(&line_text[..m.start()]),
+                len: display_width(m.as_str()),
             });
-            start = byte_col + line_lower[byte_col..].chars().next().map_or(1, |c| c.len_utf8());
         }
     }
 
-    if !state.matches.is_empty() {
-        state.current = if let Some((line, col)) = prev_pos {
-            state.matches.iter()
-                .position(|m| m.line == line && m.col == col)
-                .or(Some(0))
-        } else {
-            Some(0)
-        };
-    }
+    restore_current(state, prev_pos);
 }
 
-/// Execute search over an editor buffer's lines (case-insensitive).
+/// Execute search over an editor buffer's lines.
 /// Preserves the current match position across re-executions.
 pub fn execute_search_editor(state: &mut SearchState, lines: &[String]) {
     let prev_pos = state.current
@@ -160,38 +232,25 @@ pub fn execute_search_editor(state: &mut SearchState, lines: &[String]) {
     state.matches.clear();
     state.current = None;
 
-    if state.input.is_empty() {
+    let Some(re) = compile_query(state) else {
         return;
-    }
-
-    let query_lower = state.input.text.to_lowercase();
-    let query_char_len = state.input.text.chars().count();
-    for (line_idx, line) in lines.iter().enumerate() {
-        let line_lower = line.to_lowercase();
-        let mut start = 0;
-        while let Some(byte_pos) = line_lower[start..].find(&query_lower) {
-            let byte_col = start + byte_pos;
-            // Convert byte offset to char column for rendering
-            let char_col = line[..byte_col].chars().count();
+    };
+
+    'lines: for (line_idx, line) in lines.iter().enumerate() {
+        for m in re.find_iter(line).filter(|m| !m.is_empty()) {
+            if state.matches.len() >= MAX_SEARCH_MATCHES {
+                break 'lines;
+            }
+            // Convert byte offsets to char columns for rendering
             state.matches.push(SearchMatch {
                 line: line_idx,
-                col: char_col,
-                len: query_char_len,
+                col: line[..m.start()].chars().count(),
+                len: m.as_str().chars().count(),
             });
-            // Advance by one character (not one byte) to find overlapping matches
-            start = byte_col + line_lower[byte_col..].chars().next().map_or(1, |c| c.len_utf8());
         }
     }
 
-    if !state.matches.is_empty() {
-        state.current = if let Some((line, col)) = prev_pos {
-            state.matches.iter()
-                .position(|m| m.line == line && m.col == col)
-                .or(Some(0))
-        } else {
-            Some(0)
-        };
-    }
+    restore_current(state, prev_pos);
 }
 
 #[cfg(test)]
@@ -326,4 +385,84 @@ mod tests {
         assert_eq!(s.matches[1].col, 3);
         assert_eq!(s.matches[2].col, 6);
     }
+
+    #[test]
+    fn search_editor_case_sensitive_toggle() {
+        let mut s = SearchState::new();
+        s.input = InputLine::with_text("ABC".into());
+        s.toggle(SearchToggle::CaseSensitive);
+        let l = lines(&["abc ABC aBc"]);
+        execute_search_editor(&mut s, &l);
+
+        assert_eq!(s.matches.len(), 1);
+        assert_eq!(s.matches[0].col, 4);
+    }
+
+    #[test]
+    fn search_editor_whole_word() {
+        let mut s = SearchState::new();
+        s.input = InputLine::with_text("foo".into());
+        s.toggle(SearchToggle::WholeWord);
+        let l = lines(&["foo food foo_bar (foo)"]);
+        execute_search_editor(&mut s, &l);
+
+        let cols: Vec<usize> = s.matches.iter().map(|m| m.col).collect();
+        assert_eq!(cols, vec![0, 18]);
+    }
+
+    #[test]
+    fn search_editor_regex_and_plain_escaping() {
+        let mut s = SearchState::new();
+        s.input = InputLine::with_text("a.c".into());
+        let l = lines(&["abc a.c", "héllo wörld"]);
+        execute_search_editor(&mut s, &l);
+        assert_eq!(s.matches.len(), 1); // literal dot
+        assert_eq!(s.matches[0].col, 4);
+
+        s.toggle(SearchToggle::Regex);
+        execute_search_editor(&mut s, &l);
+        assert_eq!(s.matches.len(), 2);
+
+        // Columns and lengths are in chars, not bytes
+        s.input = InputLine::with_text(r"w\S+".into());
+        execute_search_editor(&mut s, &l);
+        assert_eq!((s.matches[0].line, s.matches[0].col, s.matches[0].len), (1, 6, 5));
+    }
+
+    #[test]
+    fn search_editor_invalid_regex() {
+        let mut s = SearchState::new();
+        s.toggle(SearchToggle::Regex);
+        s.input = InputLine::with_text("(foo".into());
+        execute_search_editor(&mut s, &lines(&["(foo"]));
+        assert!(s.invalid);
+        assert!(s.matches.is_empty());
+
+        // The same text is fine as a literal
+        s.toggle(SearchToggle::Regex);
+        execute_search_editor(&mut s, &lines(&["(foo"]));
+        assert!(!s.invalid);
+        assert_eq!(s.matches.len(), 1);
+    }
+
+    #[test]
+    fn search_editor_skips_empty_regex_matches() {
+        let mut s = SearchState::new();
+        s.toggle(SearchToggle::Regex);
+        s.input = InputLine::with_text("x*".into());
+        execute_search_editor(&mut s, &lines(&["ab xx"]));
+        assert_eq!(s.matches.len(), 1);
+        assert_eq!((s.matches[0].col, s.matches[0].len), (3, 2));
+    }
+
+    #[test]
+    fn toggle_rects_sit_left_of_close_button() {
+        use crate::theme::SEARCH_BAR_CLOSE_SIZE;
+        let bar = Rect::new(100.0, 10.0, 320.0, 28.0);
+        let rects = search_toggle_rects(bar);
+        assert_eq!(rects.map(|(t, _)| t), [SearchToggle::CaseSensitive, SearchToggle::WholeWord, SearchToggle::Regex]);
+        let last = rects[2].1;
+        assert_eq!(last.x + last.width, bar.x + bar.width - SEARCH_BAR_CLOSE_SIZE);
+        assert!(rects.iter().all(|(_, r)| r.y >= bar.y && r.y + r.height <= bar.y + bar.height));
+    }
 }
@@ -1,7 +1,7 @@
 // Session persistence: save/restore workspace state across app restarts.
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tide_core::{PaneId, SplitDirection};
 use tide_layout::{LayoutSnapshot, SplitLayout};
 
@@ -46,6 +46,9 @@ pub enum SessionLayout {
     Leaf {
         pane_id: u64,
         cwd: Option<PathBuf>,
+        /// Settings profile the terminal was started with.
+        #[serde(default)]
+        profile: Option<String>,
     },
     Split {
         direction: String, // "horizontal" or "vertical"
@@ -98,6 +101,54 @@ pub fn load_session() -> Option<Session> {
     serde_json::from_str(&data).ok()
 }
 
+// ──────────────────────────────────────────────
+// Saved terminal output (opt-in: settings.persist_scrollback)
+// ──────────────────────────────────────────────
+
+/// Written between replayed output and the new shell's first prompt.
+const RESTORED_DIVIDER: &str = "\x1b[0;2m──────── restored session ────────\x1b[0m\r\n";
+
+fn scrollback_dir() -> Option<PathBuf> {
+    let config_dir = dirs::config_dir()?;
+    Some(config_dir.join("tide").join("scrollback"))
+}
+
+fn scrollback_path(pane_id: PaneId) -> Option<PathBuf> {
+    Some(scrollback_dir()?.join(format!("{}.ansi", pane_id)))
+}
+
+/// Delete all saved terminal output.
+pub fn purge_saved_scrollback() {
+    if let Some(dir) = scrollback_dir() {
+        match std::fs::remove_dir_all(&dir) {
+            Ok(()) => log::info!("Purged saved scrollback in {}", dir.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::error!("Failed to purge saved scrollback: {}", e),
+        }
+    }
+}
+
+/// Write saved output readable only by the user: it can hold anything that
+/// was on screen, passwords and tokens included.
+fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let mut file = std::fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+    // `mode` only applies to new files
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    file.write_all(data)
+}
+
+/// Saved output for a pane, followed by the restored-session divider.
+fn load_saved_scrollback(pane_id: PaneId) -> Option<Vec<u8>> {
+    let mut data = std::fs::read(scrollback_path(pane_id)?).ok()?;
+    if data.is_empty() {
+        return None;
+    }
+    data.extend_from_slice(RESTORED_DIVIDER.as_bytes());
+    Some(data)
+}
+
 // ──────────────────────────────────────────────
 // Running marker for crash recovery
 // ──────────────────────────────────────────────
@@ -139,6 +190,7 @@ impl Session {
             None => SessionLayout::Leaf {
                 pane_id: 1,
                 cwd: std::env::current_dir().ok(),
+                profile: None,
             },
         };
 
@@ -167,13 +219,14 @@ fn snapshot_to_session(snap: &LayoutSnapshot, app: &App) -> SessionLayout {
     match snap {
         LayoutSnapshot::Leaf { tabs, active } => {
             let id = tabs[*active];
-            let cwd = match app.panes.get(&id) {
-                Some(PaneKind::Terminal(pane)) => pane.backend.detect_cwd_fallback(),
-                _ => None,
+            let (cwd, profile) = match app.panes.get(&id) {
+                Some(PaneKind::Terminal(pane)) => (pane.backend.detect_cwd_fallback(), pane.profile.clone()),
+                _ => (None, None),
             };
             SessionLayout::Leaf {
                 pane_id: id,
                 cwd,
+                profile,
             }
         }
         LayoutSnapshot::Split {
@@ -193,6 +246,37 @@ fn snapshot_to_session(snap: &LayoutSnapshot, app: &App) -> SessionLayout {
     }
 }
 
+impl App {
+    /// Save the session before exiting. With `persist_scrollback`, each
+    /// terminal's output is written alongside it; otherwise any previously
+    /// saved output is removed.
+    pub(crate) fn save_session_on_exit(&self) {
+        save_session(&Session::from_app(self));
+
+        purge_saved_scrollback();
+        if !self.settings.persist_scrollback {
+            return;
+        }
+        let Some(dir) = scrollback_dir() else {
+            return;
+        };
+        use std::os::unix::fs::DirBuilderExt;
+        if let Err(e) = std::fs::DirBuilder::new().recursive(true).mode(0o700).create(&dir) {
+            log::error!("Failed to create scrollback directory: {}", e);
+            return;
+        }
+        let max_bytes = self.settings.persist_scrollback_kb * 1024;
+        for (&id, pane) in &self.panes {
+            if let PaneKind::Terminal(t) = pane {
+                let Some(path) = scrollback_path(id) else { continue };
+                if let Err(e) = write_private(&path, &t.backend.dump_ansi(max_bytes)) {
+                    log::error!("Failed to save scrollback for pane {}: {}", id, e);
+                }
+            }
+        }
+    }
+}
+
 // ──────────────────────────────────────────────
 // Restore session into app
 // ──────────────────────────────────────────────
@@ -200,7 +284,7 @@ fn snapshot_to_session(snap: &LayoutSnapshot, app: &App) -> SessionLayout {
 impl App {
     pub(crate) fn restore_from_session(&mut self, session: Session) -> bool {
         // Rebuild layout tree from session, collecting pane info
-        let mut pane_infos: Vec<(PaneId, Option<PathBuf>)> = Vec::new();
+        let mut pane_infos: Vec<(PaneId, Option<PathBuf>, Option<String>)> = Vec::new();
         let snap = match session_to_snapshot(&session.layout, &mut pane_infos) {
             Some(s) => s,
             None => return false,
@@ -225,8 +309,15 @@ impl App {
             24
         };
 
-        for (pane_id, cwd) in &pane_infos {
-            match crate::pane::TerminalPane::with_cwd(*pane_id, cols, rows, cwd.clone(), self.dark_mode) {
+        for (pane_id, cwd, profile) in &pane_infos {
+            let saved = if self.settings.persist_scrollback {
+                load_saved_scrollback(*pane_id)
+            } else {
+                None
+            };
+            let created =
+                self.spawn_terminal_pane(*pane_id, cols, rows, cwd.clone(), profile.as_deref(), saved.as_deref());
+            match created {
                 Ok(pane) => {
                     self.install_pty_waker(&pane);
                     self.panes.insert(*pane_id, PaneKind::Terminal(pane));
@@ -274,7 +365,7 @@ impl App {
         // Initialize file tree
         let cwd = pane_infos
             .first()
-            .and_then(|(_, c)| c.clone())
+            .and_then(|(_, c, _)| c.clone())
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
         let tree = tide_tree::FsTree::new(cwd.clone());
         self.ft.tree = Some(tree);
@@ -308,11 +399,11 @@ impl App {
 /// Public for testing.
 fn session_to_snapshot(
     layout: &SessionLayout,
-    pane_infos: &mut Vec<(PaneId, Option<PathBuf>)>,
+    pane_infos: &mut Vec<(PaneId, Option<PathBuf>, Option<String>)>,
 ) -> Option<LayoutSnapshot> {
     match layout {
-        SessionLayout::Leaf { pane_id, cwd } => {
-            pane_infos.push((*pane_id, cwd.clone()));
+        SessionLayout::Leaf { pane_id, cwd, profile } => {
+            pane_infos.push((*pane_id, cwd.clone(), profile.clone()));
             Some(LayoutSnapshot::Leaf { tabs: vec![*pane_id], active: 0 })
         }
         SessionLayout::Split {
@@ -347,14 +438,29 @@ mod tests {
         let layout = SessionLayout::Leaf {
             pane_id: 42,
             cwd: Some(PathBuf::from("/home/user")),
+            profile: Some("Python REPL".to_string()),
         };
         let json = serde_json::to_string(&layout).unwrap();
         let restored: SessionLayout = serde_json::from_str(&json).unwrap();
 
         match restored {
-            SessionLayout::Leaf { pane_id, cwd } => {
+            SessionLayout::Leaf { pane_id, cwd, profile } => {
                 assert_eq!(pane_id, 42);
                 assert_eq!(cwd, Some(PathBuf::from("/home/user")));
+                assert_eq!(profile.as_deref(), Some("Python REPL"));
+            }
+            _ => panic!("expected Leaf"),
+        }
+    }
+
+    #[test]
+    fn session_layout_leaf_without_profile_parses() {
+        // Sessions saved before profiles existed
+        let json = r#"{"Leaf":{"pane_id":7,"cwd":null}}"#;
+        match serde_json::from_str::<SessionLayout>(json).unwrap() {
+            SessionLayout::Leaf { pane_id, profile, .. } => {
+                assert_eq!(pane_id, 7);
+                assert_eq!(profile, None);
             }
             _ => panic!("expected Leaf"),
         }
@@ -368,10 +474,12 @@ mod tests {
             left: Box::new(SessionLayout::Leaf {
                 pane_id: 1,
                 cwd: None,
+                profile: None,
             }),
             right: Box::new(SessionLayout::Leaf {
                 pane_id: 2,
                 cwd: Some(PathBuf::from("/tmp")),
+                profile: None,
             }),
         };
         let json = serde_json::to_string(&layout).unwrap();
@@ -386,7 +494,7 @@ mod tests {
                     _ => panic!("expected Leaf"),
                 }
                 match *right {
-                    SessionLayout::Leaf { pane_id, cwd } => {
+                    SessionLayout::Leaf { pane_id, cwd, .. } => {
                         assert_eq!(pane_id, 2);
                         assert_eq!(cwd, Some(PathBuf::from("/tmp")));
                     }
@@ -400,7 +508,7 @@ mod tests {
     #[test]
     fn session_full_roundtrip() {
         let session = Session {
-            layout: SessionLayout::Leaf { pane_id: 1, cwd: None },
+            layout: SessionLayout::Leaf { pane_id: 1, cwd: None, profile: None },
             focused_pane_id: Some(1),
             show_file_tree: true,
             file_tree_width: 250.0,
@@ -426,6 +534,7 @@ mod tests {
         let layout = SessionLayout::Leaf {
             pane_id: 10,
             cwd: Some(PathBuf::from("/home")),
+            profile: None,
         };
         let mut pane_infos = Vec::new();
         let snap = session_to_snapshot(&layout, &mut pane_infos).unwrap();
@@ -448,8 +557,8 @@ mod tests {
         let layout = SessionLayout::Split {
             direction: "vertical".to_string(),
             ratio: 0.6,
-            left: Box::new(SessionLayout::Leaf { pane_id: 1, cwd: None }),
-            right: Box::new(SessionLayout::Leaf { pane_id: 2, cwd: None }),
+            left: Box::new(SessionLayout::Leaf { pane_id: 1, cwd: None, profile: None }),
+            right: Box::new(SessionLayout::Leaf { pane_id: 2, cwd: None, profile: None }),
         };
         let mut pane_infos = Vec::new();
         let snap = session_to_snapshot(&layout, &mut pane_infos).unwrap();
@@ -470,8 +579,8 @@ mod tests {
         let layout = SessionLayout::Split {
             direction: "diagonal".to_string(),
             ratio: 0.5,
-            left: Box::new(SessionLayout::Leaf { pane_id: 1, cwd: None }),
-            right: Box::new(SessionLayout::Leaf { pane_id: 2, cwd: None }),
+            left: Box::new(SessionLayout::Leaf { pane_id: 1, cwd: None, profile: None }),
+            right: Box::new(SessionLayout::Leaf { pane_id: 2, cwd: None, profile: None }),
         };
         let mut pane_infos = Vec::new();
         assert!(session_to_snapshot(&layout, &mut pane_infos).is_none());
@@ -493,4 +602,16 @@ mod tests {
         assert_eq!(session.sidebar_side, "left");
         assert!(session.sidebar_outer);
     }
+
+    #[test]
+    fn saved_scrollback_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("tide-scrollback-{}.ansi", std::process::id()));
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_private(&path, b"secret").unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(std::fs::read(&path).unwrap(), b"secret");
+        let _ = std::fs::remove_file(&path);
+    }
 }
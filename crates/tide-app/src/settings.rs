@@ -3,7 +3,14 @@
 // on macOS, ~/.config/tide/settings.json on Linux.
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use tide_terminal::{parse_hex_color, ColorScheme, SchemePair};
+
+/// Names of the built-in palettes, selectable like any other scheme.
+const BUILTIN_SCHEMES: [(&str, ColorScheme); 2] =
+    [("Tide Dark", ColorScheme::TIDE_DARK), ("Tide Light", ColorScheme::TIDE_LIGHT)];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TideSettings {
@@ -11,6 +18,120 @@ pub struct TideSettings {
     pub worktree: WorktreeSettings,
     #[serde(default)]
     pub keybindings: Vec<KeybindingOverride>,
+    /// Scrollback history lines per terminal.
+    #[serde(default = "default_scrollback_lines")]
+    pub scrollback_lines: usize,
+    /// Total scrollback budget across all terminals, in MiB. When exceeded,
+    /// the oldest history of background terminals is trimmed. None = unlimited.
+    #[serde(default)]
+    pub scrollback_memory_mb: Option<usize>,
+    /// Save terminal output on quit and replay it when the session is restored.
+    #[serde(default)]
+    pub persist_scrollback: bool,
+    /// Cap on saved output per terminal, in KiB (the newest lines are kept).
+    #[serde(default = "default_persist_scrollback_kb")]
+    pub persist_scrollback_kb: usize,
+    /// Terminal tab title format. Placeholders: `{title}` (set by the
+    /// program, OSC 0/2), `{icon}` (OSC 1), `{process}`, `{cwd}`, `{dir}`.
+    /// When all are empty, the tab shows the working directory.
+    #[serde(default = "default_terminal_title")]
+    pub terminal_title: String,
+    /// What a terminal bell does: flash the pane, play the system sound, or nothing.
+    #[serde(default)]
+    pub bell: BellMode,
+    /// Characters that end a word when double-clicking in a terminal.
+    #[serde(default = "default_word_separators")]
+    pub word_separators: String,
+    /// Whether programs may set the clipboard (OSC 52).
+    #[serde(default = "default_clipboard_write")]
+    pub clipboard_write: ClipboardPolicy,
+    /// Whether programs may read the clipboard (OSC 52).
+    #[serde(default = "default_clipboard_read")]
+    pub clipboard_read: ClipboardPolicy,
+    /// Named terminal setups offered in the launcher.
+    #[serde(default)]
+    pub profiles: Vec<TerminalProfile>,
+    /// Inline color schemes. They win over theme files of the same name.
+    #[serde(default)]
+    pub color_schemes: BTreeMap<String, ColorSchemeSettings>,
+    /// Color scheme for terminals in both modes. None = the built-in palette.
+    #[serde(default)]
+    pub color_scheme: Option<String>,
+    /// Color scheme in dark mode; overrides `color_scheme`.
+    #[serde(default)]
+    pub color_scheme_dark: Option<String>,
+    /// Color scheme in light mode; overrides `color_scheme`.
+    #[serde(default)]
+    pub color_scheme_light: Option<String>,
+    /// Export `EDITOR` and `GIT_EDITOR` to shells so `git commit` and the
+    /// like edit in a Tide tab, waiting until it closes.
+    #[serde(default = "default_use_as_editor")]
+    pub use_as_editor: bool,
+    /// Regex rules run against each new line of terminal output.
+    #[serde(default)]
+    pub triggers: Vec<TriggerSettings>,
+    /// Problem matchers run over terminal output for the problems list:
+    /// built-in names (`rustc`, `tsc`, `eslint`, `gcc`, `pytest`, `go`) or
+    /// custom regexes.
+    #[serde(default = "default_problem_matchers")]
+    pub problem_matchers: Vec<ProblemMatcherSettings>,
+    /// Schemes loaded from the themes directory (see `with_theme_files`).
+    #[serde(skip)]
+    pub theme_schemes: BTreeMap<String, ColorScheme>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BellMode {
+    #[default]
+    Visual,
+    Sound,
+    None,
+}
+
+/// How a program's OSC 52 clipboard request is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardPolicy {
+    Allow,
+    /// Prompt over the terminal; "Always" allows it for that terminal from then on.
+    Ask,
+    Deny,
+}
+
+fn default_clipboard_write() -> ClipboardPolicy {
+    ClipboardPolicy::Allow
+}
+
+fn default_clipboard_read() -> ClipboardPolicy {
+    ClipboardPolicy::Ask
+}
+
+fn default_terminal_title() -> String {
+    "{title}".to_string()
+}
+
+fn default_word_separators() -> String {
+    tide_terminal::DEFAULT_WORD_SEPARATORS.to_string()
+}
+
+fn default_problem_matchers() -> Vec<ProblemMatcherSettings> {
+    crate::problems::BUILTIN_MATCHERS
+        .iter()
+        .map(|name| ProblemMatcherSettings::Builtin(name.to_string()))
+        .collect()
+}
+
+fn default_use_as_editor() -> bool {
+    true
+}
+
+fn default_persist_scrollback_kb() -> usize {
+    1024
+}
+
+fn default_scrollback_lines() -> usize {
+    tide_terminal::DEFAULT_SCROLLBACK_LINES
 }
 
 impl Default for TideSettings {
@@ -18,6 +139,279 @@ impl Default for TideSettings {
         Self {
             worktree: WorktreeSettings::default(),
             keybindings: Vec::new(),
+            scrollback_lines: default_scrollback_lines(),
+            scrollback_memory_mb: None,
+            persist_scrollback: false,
+            persist_scrollback_kb: default_persist_scrollback_kb(),
+            terminal_title: default_terminal_title(),
+            bell: BellMode::default(),
+            word_separators: default_word_separators(),
+            clipboard_write: default_clipboard_write(),
+            clipboard_read: default_clipboard_read(),
+            profiles: Vec::new(),
+            color_schemes: BTreeMap::new(),
+            color_scheme: None,
+            color_scheme_dark: None,
+            color_scheme_light: None,
+            use_as_editor: default_use_as_editor(),
+            triggers: Vec::new(),
+            problem_matchers: default_problem_matchers(),
+            theme_schemes: BTreeMap::new(),
+        }
+    }
+}
+
+impl TideSettings {
+    /// Look up a profile by name.
+    pub fn profile(&self, name: &str) -> Option<&TerminalProfile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// The dark- and light-mode schemes for a terminal running `profile`
+    /// (None = no profile). For each mode the first name found wins:
+    /// profile mode-specific, profile `color_scheme`, then the same two
+    /// global settings.
+    pub fn color_schemes_for(&self, profile: Option<&TerminalProfile>) -> SchemePair {
+        let pick = |dark_mode: bool| {
+            let name = profile
+                .and_then(|p| scheme_choice(&p.color_scheme, &p.color_scheme_dark, &p.color_scheme_light, dark_mode))
+                .or_else(|| {
+                    scheme_choice(&self.color_scheme, &self.color_scheme_dark, &self.color_scheme_light, dark_mode)
+                })?;
+            self.color_scheme_named(name)
+        };
+        SchemePair { dark: pick(true), light: pick(false) }
+    }
+
+    /// Look up a scheme by name: inline `color_schemes`, then theme files,
+    /// then the built-in "Tide Dark" / "Tide Light".
+    pub fn color_scheme_named(&self, name: &str) -> Option<ColorScheme> {
+        if let Some(scheme) = self.color_schemes.get(name) {
+            let parsed = scheme.to_scheme();
+            if parsed.is_none() {
+                log::warn!("Color scheme {:?}: expected #rrggbb colors and 16 ansi entries", name);
+            }
+            return parsed;
+        }
+        let found = self
+            .theme_schemes
+            .get(name)
+            .copied()
+            .or_else(|| BUILTIN_SCHEMES.iter().find(|(n, _)| *n == name).map(|(_, s)| *s));
+        if found.is_none() {
+            log::warn!("Unknown color scheme {:?}", name);
+        }
+        found
+    }
+
+    /// Load the schemes in the themes directory (`<config>/tide/themes`).
+    pub fn with_theme_files(mut self) -> Self {
+        if let Some(dir) = themes_dir() {
+            self.theme_schemes = load_theme_files(&dir);
+        }
+        self
+    }
+
+    /// The trigger rules, compiled. Rules with a bad regex or highlight color
+    /// are skipped with a warning.
+    pub fn compiled_triggers(&self) -> Vec<tide_terminal::Trigger> {
+        self.triggers
+            .iter()
+            .filter_map(|t| {
+                let trigger = t.to_trigger();
+                if trigger.is_none() {
+                    log::warn!("Ignoring invalid trigger {:?}", t.regex);
+                }
+                trigger
+            })
+            .collect()
+    }
+
+    /// The problem matchers, compiled. Unknown built-ins and bad regexes are
+    /// skipped with a warning.
+    pub(crate) fn compiled_problem_matchers(&self) -> Vec<crate::problems::ProblemMatcher> {
+        self.problem_matchers
+            .iter()
+            .flat_map(|m| {
+                let matchers = m.to_matchers();
+                if matchers.is_none() {
+                    log::warn!("Ignoring invalid problem matcher {:?}", m);
+                }
+                matchers.unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+/// The scheme name chosen for a mode: the mode-specific one, else `any`.
+fn scheme_choice<'a>(
+    any: &'a Option<String>,
+    dark: &'a Option<String>,
+    light: &'a Option<String>,
+    dark_mode: bool,
+) -> Option<&'a str> {
+    let specific = if dark_mode { dark } else { light };
+    specific.as_deref().or(any.as_deref())
+}
+
+/// A named terminal setup: what to run, where, and how it looks.
+/// `OpenProfile1`..`OpenProfile9` keybindings open the profiles in order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TerminalProfile {
+    pub name: String,
+    /// Program to run. None = the login shell.
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Extra environment variables.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Starting directory (`~` expands to home). None = the focused
+    /// terminal's directory.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Overrides `scrollback_lines` for this profile's terminals.
+    #[serde(default)]
+    pub scrollback_lines: Option<usize>,
+    /// Color scheme name (inline, theme file or built-in) for both modes.
+    /// None = the global `color_scheme` settings.
+    #[serde(default)]
+    pub color_scheme: Option<String>,
+    /// Color scheme in dark mode; overrides `color_scheme`.
+    #[serde(default)]
+    pub color_scheme_dark: Option<String>,
+    /// Color scheme in light mode; overrides `color_scheme`.
+    #[serde(default)]
+    pub color_scheme_light: Option<String>,
+}
+
+impl TerminalProfile {
+    /// The command and environment to spawn the terminal with.
+    pub fn shell_command(&self) -> tide_terminal::ShellCommand {
+        tide_terminal::ShellCommand {
+            program: self.command.clone(),
+            args: self.args.clone(),
+            env: self.env.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+        }
+    }
+
+    /// Starting directory with `~` expanded.
+    pub fn initial_cwd(&self) -> Option<PathBuf> {
+        let cwd = self.cwd.as_deref()?;
+        match cwd.strip_prefix('~') {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+                let home = dirs::home_dir()?;
+                Some(home.join(rest.trim_start_matches('/')))
+            }
+            _ => Some(PathBuf::from(cwd)),
+        }
+    }
+}
+
+/// Terminal colors as `#rrggbb` strings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorSchemeSettings {
+    pub foreground: String,
+    pub background: String,
+    /// Cursor color. None = the foreground.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// The 16 ANSI colors: black, red, green, yellow, blue, magenta, cyan,
+    /// white, then the bright variants.
+    pub ansi: Vec<String>,
+}
+
+impl ColorSchemeSettings {
+    /// Parse into a terminal color scheme. None if a color is malformed or
+    /// `ansi` doesn't have exactly 16 entries.
+    pub fn to_scheme(&self) -> Option<ColorScheme> {
+        let ansi: Vec<tide_core::Color> = self.ansi.iter().map(|c| parse_hex_color(c)).collect::<Option<_>>()?;
+        Some(ColorScheme {
+            foreground: parse_hex_color(&self.foreground)?,
+            background: parse_hex_color(&self.background)?,
+            cursor: match &self.cursor {
+                Some(c) => Some(parse_hex_color(c)?),
+                None => None,
+            },
+            ansi: ansi.try_into().ok()?,
+        })
+    }
+}
+
+/// An output trigger as stored in settings.json.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerSettings {
+    pub regex: String,
+    pub action: TriggerKind,
+    /// Highlight color (`#rrggbb`); required for `highlight`.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Notification text, URL/path to open, or reply to send. `$1` and
+    /// `${name}` expand to capture groups. Empty = the matched line (notify)
+    /// or the match (open).
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerKind {
+    Highlight,
+    Badge,
+    Notify,
+    Open,
+    Reply,
+}
+
+impl TriggerSettings {
+    /// Compile into a terminal trigger. None if the regex doesn't compile
+    /// or a highlight has no valid color.
+    pub fn to_trigger(&self) -> Option<tide_terminal::Trigger> {
+        use tide_terminal::TriggerAction;
+        let action = match self.action {
+            TriggerKind::Highlight => TriggerAction::Highlight(parse_hex_color(self.color.as_deref()?)?),
+            TriggerKind::Badge => TriggerAction::Badge,
+            TriggerKind::Notify => TriggerAction::Notify(self.text.clone()),
+            TriggerKind::Open => TriggerAction::Open(self.text.clone()),
+            TriggerKind::Reply => TriggerAction::Reply(self.text.clone()),
+        };
+        let regex = regex::Regex::new(&self.regex).ok()?;
+        Some(tide_terminal::Trigger { regex, action })
+    }
+}
+
+/// A problem matcher as stored in settings.json: a built-in name, or a
+/// regex with the named groups `file`, `line`, `col`, `severity` and
+/// `message`. Groups missing from `regex` come from the last line matching
+/// `header`; `severity` is the default when the line names none.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProblemMatcherSettings {
+    Builtin(String),
+    Custom {
+        regex: String,
+        #[serde(default)]
+        header: Option<String>,
+        #[serde(default)]
+        severity: Option<String>,
+    },
+}
+
+impl ProblemMatcherSettings {
+    /// Compile into matchers. None for an unknown built-in, a bad regex or
+    /// an unknown severity.
+    pub(crate) fn to_matchers(&self) -> Option<Vec<crate::problems::ProblemMatcher>> {
+        use crate::problems::{ProblemMatcher, Severity};
+        match self {
+            ProblemMatcherSettings::Builtin(name) => ProblemMatcher::builtin(name),
+            ProblemMatcherSettings::Custom { regex, header, severity } => {
+                let severity = match severity {
+                    Some(word) => Severity::parse(word)?,
+                    None => Severity::Error,
+                };
+                ProblemMatcher::new(regex, header.as_deref(), severity).ok().map(|m| vec![m])
+            }
         }
     }
 }
@@ -131,6 +525,35 @@ fn settings_path() -> Option<PathBuf> {
     Some(config_dir.join("tide").join("settings.json"))
 }
 
+fn themes_dir() -> Option<PathBuf> {
+    let config_dir = dirs::config_dir()?;
+    Some(config_dir.join("tide").join("themes"))
+}
+
+/// Parse every theme file in `dir` (iTerm2, Alacritty, Windows Terminal,
+/// base16). Files that fail to parse are logged and skipped; on a name
+/// clash the file that sorts last wins.
+pub fn load_theme_files(dir: &Path) -> BTreeMap<String, ColorScheme> {
+    let mut schemes = BTreeMap::new();
+    let Ok(entries) = std::fs::read_dir(dir) else { return schemes };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_file()).collect();
+    paths.sort();
+    for path in paths {
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+        if file_name.starts_with('.') {
+            continue;
+        }
+        let parsed = std::fs::read(&path)
+            .map_err(|e| e.into())
+            .and_then(|data| tide_terminal::parse_scheme_file(file_name, &data));
+        match parsed {
+            Ok(found) => schemes.extend(found),
+            Err(e) => log::warn!("Skipping theme {}: {}", path.display(), e),
+        }
+    }
+    schemes
+}
+
 pub fn load_settings() -> TideSettings {
     let path = match settings_path() {
         Some(p) => p,
@@ -190,3 +613,183 @@ pub fn build_keybinding_map(settings: &TideSettings) -> tide_input::KeybindingMa
     tide_input::KeybindingMap::with_overrides(overrides)
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_and_color_schemes_parse() {
+        let json = r##"{
+            "profiles": [
+                {"name": "bash --norc", "command": "bash", "args": ["--norc"],
+                 "env": {"PS1": "$ "}, "cwd": "~/src", "color_scheme": "Mono"},
+                {"name": "Default shell"}
+            ],
+            "color_schemes": {
+                "Mono": {"foreground": "#ffffff", "background": "000000",
+                         "ansi": ["#000000", "#ff0000", "#00ff00", "#ffff00", "#0000ff", "#ff00ff",
+                                  "#00ffff", "#c0c0c0", "#808080", "#ff8080", "#80ff80", "#ffff80",
+                                  "#8080ff", "#ff80ff", "#80ffff", "#ffffff"]}
+            }
+        }"##;
+        let settings: TideSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.scrollback_lines, tide_terminal::DEFAULT_SCROLLBACK_LINES);
+        assert_eq!(settings.word_separators, tide_terminal::DEFAULT_WORD_SEPARATORS);
+
+        let norc = settings.profile("bash --norc").unwrap();
+        let command = norc.shell_command();
+        assert_eq!(command.program.as_deref(), Some("bash"));
+        assert_eq!(command.args, ["--norc"]);
+        assert_eq!(command.env, [("PS1".to_string(), "$ ".to_string())]);
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(norc.initial_cwd(), Some(home.join("src")));
+        }
+
+        let scheme = settings.color_schemes_for(Some(norc)).dark.unwrap();
+        assert_eq!(scheme.background, tide_core::Color::rgb(0.0, 0.0, 0.0));
+        assert_eq!(scheme.ansi[1], tide_core::Color::rgb(1.0, 0.0, 0.0));
+        assert_eq!(scheme.cursor_color(), scheme.foreground);
+
+        // No command = the login shell; no scheme = the theme palette
+        let default = settings.profile("Default shell").unwrap();
+        assert_eq!(default.shell_command(), tide_terminal::ShellCommand::default());
+        assert_eq!(settings.color_schemes_for(Some(default)), SchemePair::default());
+    }
+
+    #[test]
+    fn color_scheme_choice_prefers_profile_then_mode() {
+        let json = r##"{
+            "color_scheme": "Tide Light",
+            "color_scheme_dark": "Tide Dark",
+            "profiles": [
+                {"name": "Solar", "color_scheme_light": "Solarized Light"},
+                {"name": "Both", "color_scheme": "Tide Dark"}
+            ]
+        }"##;
+        let mut settings: TideSettings = serde_json::from_str(json).unwrap();
+        let mut solarized = ColorScheme::TIDE_LIGHT;
+        solarized.background = tide_core::Color::rgb(0.99, 0.96, 0.89);
+        settings.theme_schemes.insert("Solarized Light".to_string(), solarized);
+
+        // No profile: the global dark/light choices
+        let global = settings.color_schemes_for(None);
+        assert_eq!(global.dark, Some(ColorScheme::TIDE_DARK));
+        assert_eq!(global.light, Some(ColorScheme::TIDE_LIGHT));
+
+        // A profile's light choice overrides the global one; dark falls through
+        let solar = settings.color_schemes_for(settings.profile("Solar"));
+        assert_eq!(solar.light, Some(solarized));
+        assert_eq!(solar.dark, Some(ColorScheme::TIDE_DARK));
+
+        // A profile's `color_scheme` beats the global mode-specific choice
+        let both = settings.color_schemes_for(settings.profile("Both"));
+        assert_eq!(both, SchemePair::both(ColorScheme::TIDE_DARK));
+
+        // Unknown names leave the built-in palette
+        settings.color_scheme_dark = Some("Missing".to_string());
+        assert_eq!(settings.color_schemes_for(None).dark, None);
+    }
+
+    #[test]
+    fn inline_color_scheme_wins_over_theme_file() {
+        let mut settings = TideSettings::default();
+        settings.theme_schemes.insert("Mono".to_string(), ColorScheme::TIDE_LIGHT);
+        settings.color_schemes.insert(
+            "Mono".to_string(),
+            ColorSchemeSettings {
+                foreground: "#ffffff".to_string(),
+                background: "#000000".to_string(),
+                cursor: None,
+                ansi: vec!["#000000".to_string(); 16],
+            },
+        );
+        let scheme = settings.color_scheme_named("Mono").unwrap();
+        assert_eq!(scheme.background, tide_core::Color::rgb(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn theme_directory_loads_known_formats() {
+        let dir = std::env::temp_dir().join(format!("tide-themes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut base16 = String::from("scheme: \"Ocean\"\n");
+        for i in 0..16 {
+            base16.push_str(&format!("base0{:X}: \"{:02x}{:02x}{:02x}\"\n", i, i * 16, i * 16, i * 16));
+        }
+        std::fs::write(dir.join("ocean.yaml"), base16).unwrap();
+        std::fs::write(dir.join("broken.json"), "{").unwrap();
+        std::fs::write(dir.join(".DS_Store"), [0u8; 4]).unwrap();
+
+        let schemes = load_theme_files(&dir);
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(schemes.keys().collect::<Vec<_>>(), ["Ocean"]);
+        assert_eq!(schemes["Ocean"].background, tide_core::Color::rgb(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn malformed_color_scheme_is_rejected() {
+        let mut scheme = ColorSchemeSettings {
+            foreground: "#fff".to_string(),
+            background: "#000000".to_string(),
+            cursor: None,
+            ansi: vec!["#000000".to_string(); 16],
+        };
+        assert!(scheme.to_scheme().is_none(), "3-digit hex");
+        scheme.foreground = "#ffffff".to_string();
+        assert!(scheme.to_scheme().is_some());
+        scheme.ansi.pop();
+        assert!(scheme.to_scheme().is_none(), "15 ansi colors");
+    }
+
+    #[test]
+    fn triggers_parse_and_bad_rules_are_skipped() {
+        let json = r##"{
+            "triggers": [
+                {"regex": "ERROR", "action": "highlight", "color": "#ff0000"},
+                {"regex": "Password:", "action": "reply", "text": "hunter2\r"},
+                {"regex": "(?P<file>\\S+):(\\d+)", "action": "open", "text": "${file}:$2"},
+                {"regex": "done", "action": "notify"},
+                {"regex": "(", "action": "badge"},
+                {"regex": "WARN", "action": "highlight"}
+            ]
+        }"##;
+        let settings: TideSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.triggers.len(), 6);
+        assert_eq!(settings.triggers[1].action, TriggerKind::Reply);
+
+        let compiled = settings.compiled_triggers();
+        let actions: Vec<_> = compiled.iter().map(|t| t.action.clone()).collect();
+        use tide_terminal::TriggerAction;
+        assert_eq!(
+            actions,
+            [
+                TriggerAction::Highlight(tide_core::Color::rgb(1.0, 0.0, 0.0)),
+                TriggerAction::Reply("hunter2\r".to_string()),
+                TriggerAction::Open("${file}:$2".to_string()),
+                TriggerAction::Notify(String::new()),
+            ],
+            "bad regex and colorless highlight are dropped"
+        );
+        assert!(compiled[2].regex.is_match("src/main.rs:12"));
+    }
+
+    #[test]
+    fn problem_matchers_default_to_builtins_and_bad_ones_are_skipped() {
+        let defaults: TideSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(defaults.problem_matchers.len(), crate::problems::BUILTIN_MATCHERS.len());
+        assert!(!defaults.compiled_problem_matchers().is_empty());
+
+        let json = r#"{
+            "problem_matchers": [
+                "gcc",
+                "cobol",
+                {"regex": "^(?P<file>\\S+) line (?P<line>\\d+)$", "severity": "warning"},
+                {"regex": "("},
+                {"regex": "x", "severity": "fatal"}
+            ]
+        }"#;
+        let settings: TideSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.problem_matchers.len(), 5);
+        assert_eq!(settings.compiled_problem_matchers().len(), 2, "unknown name, bad regex and severity are dropped");
+    }
+}
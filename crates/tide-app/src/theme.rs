@@ -61,6 +61,8 @@ pub struct ThemePalette {
     pub search_bar_border: Color,
     pub search_bar_text: Color,
     pub search_bar_counter: Color,
+    pub search_bar_error: Color,
+    pub search_toggle_on: Color,
 
     // IME preedit
     pub ime_preedit_bg: Color,
@@ -97,6 +99,11 @@ pub struct ThemePalette {
     pub badge_git_deletions: Color,
     pub badge_conflict: Color,
     pub badge_deleted: Color,
+    pub command_ok: Color,     // last command exit 0 (badge + prompt gutter)
+    pub command_failed: Color, // last command exit != 0
+    pub attention: Color,      // bell / notification in a background terminal
+    pub broadcast: Color,      // border + badge of terminals mirroring input
+    pub visual_bell: Color,    // flash over a pane that rang the bell
 
     // Popup (branch switcher)
     pub popup_bg: Color,
@@ -180,6 +187,8 @@ pub static DARK: ThemePalette = ThemePalette {
     search_bar_border:  Color::new(1.0, 1.0, 1.0, 0.15),
     search_bar_text:    Color::new(0.88, 0.88, 0.88, 1.0),
     search_bar_counter: Color::new(0.45, 0.45, 0.45, 1.0),
+    search_bar_error:   Color::new(0.95, 0.45, 0.45, 1.0),
+    search_toggle_on:   Color::new(1.0, 1.0, 1.0, 0.14),
 
     // IME
     ime_preedit_bg: Color::new(0.10, 0.10, 0.10, 1.0),
@@ -214,6 +223,11 @@ pub static DARK: ThemePalette = ThemePalette {
     badge_git_deletions:  Color::new(0.90, 0.35, 0.35, 1.0),
     badge_conflict:       Color::new(0.90, 0.65, 0.25, 1.0),
     badge_deleted:        Color::new(0.90, 0.35, 0.35, 1.0),
+    command_ok:           Color::new(0.133, 0.773, 0.369, 1.0),
+    command_failed:       Color::new(0.90, 0.35, 0.35, 1.0),
+    attention:            Color::new(0.40, 0.65, 1.0, 1.0),
+    broadcast:            Color::new(0.95, 0.55, 0.20, 1.0),
+    visual_bell:          Color::new(1.0, 1.0, 1.0, 0.08),
 
     // Popup
     popup_bg:       Color::new(0.067, 0.067, 0.075, 1.0),
@@ -297,6 +311,8 @@ pub static LIGHT: ThemePalette = ThemePalette {
     search_bar_border:  Color::new(0.45, 0.35, 0.20, 0.18),
     search_bar_text:    Color::new(0.10, 0.08, 0.05, 1.0),  // warm dark
     search_bar_counter: Color::new(0.40, 0.35, 0.28, 1.0),  // warm muted
+    search_bar_error:   Color::new(0.75, 0.22, 0.18, 1.0),
+    search_toggle_on:   Color::new(0.45, 0.35, 0.20, 0.16),
 
     // IME
     ime_preedit_bg: Color::new(0.88, 0.85, 0.80, 1.0),  // warm beige
@@ -331,6 +347,11 @@ pub static LIGHT: ThemePalette = ThemePalette {
     badge_git_deletions:  Color::new(0.65, 0.15, 0.15, 1.0),
     badge_conflict:       Color::new(0.75, 0.50, 0.15, 1.0),
     badge_deleted:        Color::new(0.65, 0.15, 0.15, 1.0),
+    command_ok:           Color::new(0.15, 0.55, 0.15, 1.0),
+    command_failed:       Color::new(0.65, 0.15, 0.15, 1.0),
+    attention:            Color::new(0.15, 0.40, 0.80, 1.0),
+    broadcast:            Color::new(0.85, 0.40, 0.05, 1.0),
+    visual_bell:          Color::new(0.0, 0.0, 0.0, 0.06),
 
     // Popup — pure white with gentle scrim
     popup_bg:       Color::new(1.0, 1.0, 1.0, 1.0),     // pure white
@@ -382,9 +403,12 @@ pub const SCROLLBAR_WIDTH: f32 = 6.0;
 pub const SCROLLBAR_WIDTH_HOVER: f32 = 10.0;
 
 
-pub const SEARCH_BAR_WIDTH: f32 = 260.0;
+pub const SEARCH_BAR_WIDTH: f32 = 320.0;
 pub const SEARCH_BAR_HEIGHT: f32 = 28.0;
 pub const SEARCH_BAR_CLOSE_SIZE: f32 = 20.0;
+pub const SEARCH_BAR_TOGGLE_SIZE: f32 = 22.0;
+/// Narrower panes hide the search bar (query, counter, toggles and close must fit).
+pub const SEARCH_BAR_MIN_WIDTH: f32 = 140.0;
 
 pub const CONFLICT_BAR_HEIGHT: f32 = 28.0;
 
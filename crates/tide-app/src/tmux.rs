@@ -0,0 +1,329 @@
+// tmux control mode: a terminal running `tmux -CC` becomes a gateway, and its
+// session is shown in a workspace of its own, "tmux: <session>". Each tmux
+// window becomes a tab group, or a tree of splits when it has several panes,
+// and the windows sit side by side in proportion to their widths. Pane
+// terminals are fed by the gateway (see tide-terminal's tmux.rs). Splitting or
+// closing one sends the command to tmux instead, and the workspace is rebuilt
+// from the window list tmux reports back. Tide's pane sizes go back to tmux
+// as window sizes, so programs in tmux draw for the space they get.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use tide_core::{PaneId, SplitDirection};
+use tide_layout::{LayoutSnapshot, SplitLayout};
+use tide_terminal::{TmuxEvent, TmuxHandle, TmuxLayout, TmuxWindow};
+
+use crate::clipboard::TOAST_DURATION;
+use crate::pane::{PaneKind, TerminalPane};
+use crate::ui_state::Toast;
+use crate::workspace::Workspace;
+use crate::App;
+
+/// Sizes in Tide of one session's panes, by tmux pane id.
+type PaneSizes = HashMap<u32, (u16, u16)>;
+
+/// The tmux connection and pane id of a pane showing a tmux pane.
+fn tmux_pane(pane: &PaneKind) -> Option<(TmuxHandle, u32)> {
+    match pane {
+        PaneKind::Terminal(t) => t.backend.tmux_pane(),
+        _ => None,
+    }
+}
+
+impl App {
+    /// Apply what gateway terminals report, and keep tmux window sizes in
+    /// step with the active workspace.
+    pub(crate) fn poll_tmux(&mut self) {
+        let mut events = Vec::new();
+        let background = self.ws.workspaces.iter().flat_map(|ws| ws.panes.values());
+        for pane in self.panes.values().chain(background) {
+            if let PaneKind::Terminal(t) = pane {
+                events.extend(t.backend.tmux_events().into_iter().map(|e| (t.backend.tmux_gateway(), e)));
+            }
+        }
+        for (gateway, event) in events {
+            match event {
+                TmuxEvent::Windows { session, windows } => self.sync_tmux_workspace(&gateway, &session, &windows),
+                TmuxEvent::CommandFailed(message) => self.tmux_toast(format!("tmux: {}", message)),
+                TmuxEvent::Exited => self.close_tmux_workspace(&gateway),
+            }
+        }
+        self.resize_tmux_windows();
+    }
+
+    /// Split a tmux pane in tmux. Returns false for other panes.
+    pub(crate) fn split_tmux_pane(&mut self, pane: PaneId, direction: SplitDirection) -> bool {
+        let Some((gateway, id)) = self.live_tmux_pane(pane) else { return false };
+        gateway.split(id, direction == SplitDirection::Horizontal);
+        true
+    }
+
+    /// Close a tmux pane in tmux. Returns false for other panes.
+    pub(crate) fn kill_tmux_pane(&mut self, pane: PaneId) -> bool {
+        let Some((gateway, id)) = self.live_tmux_pane(pane) else { return false };
+        gateway.kill_pane(id);
+        true
+    }
+
+    /// Detach from the tmux sessions shown in the active workspace.
+    pub(crate) fn detach_tmux_sessions(&self) {
+        let mut gateways: Vec<TmuxHandle> = Vec::new();
+        for (gateway, _) in self.panes.values().filter_map(tmux_pane) {
+            if !gateways.contains(&gateway) {
+                gateway.detach();
+                gateways.push(gateway);
+            }
+        }
+    }
+
+    fn live_tmux_pane(&self, pane: PaneId) -> Option<(TmuxHandle, u32)> {
+        match self.panes.get(&pane) {
+            Some(PaneKind::Terminal(t)) if t.backend.is_child_alive() => t.backend.tmux_pane(),
+            _ => None,
+        }
+    }
+
+    /// Index of the workspace showing `gateway`'s session.
+    fn tmux_workspace(&self, gateway: &TmuxHandle) -> Option<usize> {
+        let shows = |panes: &HashMap<PaneId, PaneKind>| {
+            panes.values().filter_map(tmux_pane).any(|(handle, _)| &handle == gateway)
+        };
+        if shows(&self.panes) {
+            return Some(self.ws.active);
+        }
+        self.ws.workspaces.iter().enumerate()
+            .find(|(idx, ws)| *idx != self.ws.active && shows(&ws.panes))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Rebuild the session's workspace from tmux's window list, keeping the
+    /// terminals of panes that still exist. The first list opens the
+    /// workspace and switches to it.
+    fn sync_tmux_workspace(&mut self, gateway: &TmuxHandle, session: &str, windows: &[TmuxWindow]) {
+        if windows.is_empty() || self.ws.workspaces.is_empty() {
+            return;
+        }
+        let (idx, created) = match self.tmux_workspace(gateway) {
+            Some(idx) => (idx, false),
+            None => {
+                self.ws.workspaces.push(Workspace::new(String::new(), SplitLayout::new()));
+                (self.ws.workspaces.len() - 1, true)
+            }
+        };
+        self.ws.workspaces[idx].name = format!("tmux: {}", session);
+
+        let is_active = idx == self.ws.active;
+        let (mut layout, mut panes, mut focused) = if is_active {
+            (std::mem::take(&mut self.layout), std::mem::take(&mut self.panes), self.focused.take())
+        } else {
+            let ws = &mut self.ws.workspaces[idx];
+            (std::mem::take(&mut ws.layout), std::mem::take(&mut ws.panes), ws.focused.take())
+        };
+
+        let mut existing: HashMap<u32, PaneId> = panes.iter()
+            .filter_map(|(&id, pane)| tmux_pane(pane).filter(|(h, _)| h == gateway).map(|(_, n)| (n, id)))
+            .collect();
+        // Panes the user opened here themselves stay, in a group on the right
+        let others: Vec<PaneId> = layout.pane_ids().into_iter()
+            .filter(|id| !existing.values().any(|v| v == id))
+            .collect();
+
+        let mut created_ids = Vec::new();
+        let mut shown: HashMap<u32, PaneId> = HashMap::new();
+        let mut parts = Vec::new();
+        {
+            let mut pane_for = |tmux_id: u32, cols: u16, rows: u16| -> Option<PaneId> {
+                let id = match existing.remove(&tmux_id) {
+                    Some(id) => id,
+                    None => {
+                        let backend = match gateway.open_pane(tmux_id, cols, rows, self.dark_mode) {
+                            Ok(backend) => backend,
+                            Err(e) => {
+                                log::error!("Failed to open tmux pane %{}: {}", tmux_id, e);
+                                return None;
+                            }
+                        };
+                        let id = layout.alloc_id();
+                        let mut pane = TerminalPane::with_terminal(id, backend);
+                        self.apply_terminal_settings(&mut pane, None);
+                        self.install_pty_waker(&pane);
+                        panes.insert(id, PaneKind::Terminal(pane));
+                        created_ids.push(id);
+                        id
+                    }
+                };
+                shown.insert(tmux_id, id);
+                Some(id)
+            };
+            for window in windows {
+                let width = window.layout.size().0 as f32;
+                if let Some(snapshot) = window_snapshot(&window.layout, &mut pane_for) {
+                    parts.push((width, snapshot));
+                }
+            }
+        }
+        if !others.is_empty() {
+            let width = parts.iter().map(|(w, _)| w).sum::<f32>() / parts.len().max(1) as f32;
+            parts.push((width.max(1.0), LayoutSnapshot::Leaf { tabs: others, active: 0 }));
+        }
+
+        // Panes tmux no longer has
+        let removed: Vec<PaneId> = existing.into_values().collect();
+        for id in &removed {
+            panes.remove(id);
+        }
+        let layout = match chain(parts, SplitDirection::Horizontal) {
+            Some(snapshot) => SplitLayout::from_snapshot(snapshot),
+            None => SplitLayout::new(),
+        };
+        let in_layout = layout.pane_ids();
+        if !focused.is_some_and(|id| in_layout.contains(&id)) {
+            // The active window's pane, as tmux has it
+            focused = windows.iter()
+                .find(|w| w.active)
+                .and_then(|w| w.layout.panes().first().and_then(|n| shown.get(n)).copied())
+                .or_else(|| in_layout.first().copied());
+        }
+
+        self.ime.pending_creates.extend(created_ids);
+        if is_active {
+            for id in removed {
+                self.cleanup_closed_pane_state(id);
+            }
+            self.layout = layout;
+            self.panes = panes;
+            self.focused = focused;
+            if self.zoomed_pane.is_some_and(|id| !in_layout.contains(&id)) {
+                self.zoomed_pane = None;
+            }
+            if let Some(id) = focused {
+                self.router.set_focused(id);
+            }
+            self.cache.pane_generations.clear();
+            self.compute_layout();
+        } else {
+            let ws = &mut self.ws.workspaces[idx];
+            ws.layout = layout;
+            ws.panes = panes;
+            ws.focused = focused;
+        }
+        self.cache.invalidate_chrome();
+        if created {
+            self.switch_workspace(idx);
+        }
+    }
+
+    /// tmux exited: close the session's workspace, unless it is the last
+    /// one or holds other panes, where the exited terminals stay.
+    fn close_tmux_workspace(&mut self, gateway: &TmuxHandle) {
+        let Some(idx) = self.tmux_workspace(gateway) else { return };
+        self.tmux_toast("tmux detached".to_string());
+        let panes = if idx == self.ws.active { &self.panes } else { &self.ws.workspaces[idx].panes };
+        let only_tmux = panes.values().all(|pane| tmux_pane(pane).is_some_and(|(h, _)| &h == gateway));
+        if !only_tmux || self.ws.workspaces.len() <= 1 {
+            return;
+        }
+        if idx == self.ws.active {
+            self.close_workspace();
+            return;
+        }
+        // Pane ids are per workspace, so the active one's caches stay
+        self.ws.workspaces.remove(idx);
+        if idx < self.ws.active {
+            self.ws.active -= 1;
+        }
+        self.cache.invalidate_chrome();
+    }
+
+    /// Ask tmux to size each window of the active workspace to what its
+    /// panes have in Tide.
+    fn resize_tmux_windows(&self) {
+        let mut sessions: Vec<(TmuxHandle, PaneSizes)> = Vec::new();
+        for pane in self.panes.values() {
+            let PaneKind::Terminal(t) = pane else { continue };
+            let Some((gateway, id)) = t.backend.tmux_pane() else { continue };
+            let size = (t.backend.current_cols(), t.backend.current_rows());
+            match sessions.iter_mut().find(|(g, _)| *g == gateway) {
+                Some((_, sizes)) => {
+                    sizes.insert(id, size);
+                }
+                None => sessions.push((gateway, HashMap::from([(id, size)]))),
+            }
+        }
+        for (gateway, sizes) in sessions {
+            for window in gateway.windows() {
+                let Some((cols, rows)) = tide_size(&window.layout, &sizes) else { continue };
+                if (cols, rows) != window.layout.size() {
+                    gateway.resize_window(window.id, cols, rows);
+                }
+            }
+        }
+    }
+
+    fn tmux_toast(&mut self, message: String) {
+        self.toast = Some(Toast { message, until: Instant::now() + TOAST_DURATION });
+        self.cache.needs_redraw = true;
+    }
+}
+
+/// Tide layout for a window's pane tree; `pane_for` gives (or creates) the
+/// Tide pane for a tmux pane.
+fn window_snapshot(
+    layout: &TmuxLayout,
+    pane_for: &mut dyn FnMut(u32, u16, u16) -> Option<PaneId>,
+) -> Option<LayoutSnapshot> {
+    match layout {
+        TmuxLayout::Pane { id, cols, rows } => {
+            pane_for(*id, *cols, *rows).map(|pane| LayoutSnapshot::Leaf { tabs: vec![pane], active: 0 })
+        }
+        TmuxLayout::Split { horizontal, children, .. } => {
+            let parts = children.iter()
+                .filter_map(|child| {
+                    let (cols, rows) = child.size();
+                    let weight = if *horizontal { cols } else { rows } as f32;
+                    window_snapshot(child, pane_for).map(|snapshot| (weight, snapshot))
+                })
+                .collect();
+            let direction = if *horizontal { SplitDirection::Horizontal } else { SplitDirection::Vertical };
+            chain(parts, direction)
+        }
+    }
+}
+
+/// Nest `parts` into splits along `direction`, each sized by its weight.
+fn chain(mut parts: Vec<(f32, LayoutSnapshot)>, direction: SplitDirection) -> Option<LayoutSnapshot> {
+    if parts.is_empty() {
+        return None;
+    }
+    let (weight, first) = parts.remove(0);
+    if parts.is_empty() {
+        return Some(first);
+    }
+    let rest: f32 = parts.iter().map(|(w, _)| w).sum();
+    let ratio = (weight / (weight + rest)).clamp(0.05, 0.95);
+    Some(LayoutSnapshot::Split {
+        direction,
+        ratio,
+        left: Box::new(first),
+        right: Box::new(chain(parts, direction)?),
+    })
+}
+
+/// A window's size in cells from its panes' sizes in Tide, counting the
+/// one-cell borders tmux draws between panes.
+fn tide_size(layout: &TmuxLayout, sizes: &PaneSizes) -> Option<(u16, u16)> {
+    match layout {
+        TmuxLayout::Pane { id, .. } => sizes.get(id).copied(),
+        TmuxLayout::Split { horizontal, children, .. } => {
+            let children: Vec<(u16, u16)> = children.iter().map(|c| tide_size(c, sizes)).collect::<Option<_>>()?;
+            let borders = children.len().saturating_sub(1) as u16;
+            let (cols, rows): (Vec<u16>, Vec<u16>) = children.into_iter().unzip();
+            Some(if *horizontal {
+                (cols.iter().sum::<u16>() + borders, rows.into_iter().max()?)
+            } else {
+                (cols.into_iter().max()?, rows.iter().sum::<u16>() + borders)
+            })
+        }
+    }
+}
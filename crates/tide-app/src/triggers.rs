@@ -0,0 +1,100 @@
+// Output triggers: `TideSettings::triggers` are handed to every terminal, whose
+// sync thread matches new output and paints highlights itself. The other
+// actions arrive here as `TriggerEvent`s: raise an attention badge, send a
+// desktop notification, open a URL or file, or write a reply to the program.
+
+use std::path::{Path, PathBuf};
+
+use tide_core::TerminalBackend;
+use tide_terminal::TriggerAction;
+
+use crate::action::text_extract::file_uri_to_path;
+use crate::pane::PaneKind;
+use crate::App;
+
+impl App {
+    /// Carry out the triggers fired in every terminal (all workspaces).
+    /// Opens only come from the active workspace, so a background build
+    /// can't pull an editor into view.
+    pub(crate) fn poll_triggers(&mut self) {
+        let focused = self.focused.filter(|_| self.window_focused);
+        let mut opens: Vec<(String, Option<PathBuf>)> = Vec::new();
+        let mut changed = false;
+        let active = self.panes.iter_mut().map(|(id, pane)| (true, id, pane));
+        let background = self.ws.workspaces.iter_mut()
+            .flat_map(|ws| ws.panes.iter_mut())
+            .map(|(id, pane)| (false, id, pane));
+        for (in_active_ws, &id, pane) in active.chain(background) {
+            let PaneKind::Terminal(t) = pane else { continue };
+            let mut badge = false;
+            for event in t.backend.take_trigger_events() {
+                match event.action {
+                    TriggerAction::Badge => badge = true,
+                    TriggerAction::Notify(text) => {
+                        send_desktop_notification(t.context.display_title.as_deref().unwrap_or("Tide"), &text);
+                        badge = true;
+                    }
+                    TriggerAction::Open(target) if in_active_ws => {
+                        opens.push((target, t.backend.detect_cwd_fallback()));
+                    }
+                    TriggerAction::Reply(text) => t.backend.write(text.as_bytes()),
+                    TriggerAction::Open(_) | TriggerAction::Highlight(_) => {}
+                }
+            }
+            // Like a bell: the badge stays until the terminal is focused
+            let is_focused = in_active_ws && focused == Some(id);
+            if badge && !is_focused && !t.context.attention {
+                t.context.attention = true;
+                changed = true;
+            }
+        }
+        if changed {
+            self.cache.invalidate_chrome();
+        }
+        for (target, cwd) in opens {
+            self.open_trigger_target(&target, cwd.as_deref());
+        }
+    }
+
+    /// Open a trigger's target: http(s) URLs in a browser pane, files
+    /// (`file://` URIs or paths with an optional `:line`, relative to the
+    /// terminal's cwd) in an editor.
+    fn open_trigger_target(&mut self, target: &str, cwd: Option<&Path>) {
+        let target = target.trim();
+        if target.starts_with("http://") || target.starts_with("https://") {
+            self.open_browser_pane(Some(target.to_string()));
+            return;
+        }
+        let (path, line) = match file_uri_to_path(target) {
+            Some(path) => (path, None),
+            None => match target.rsplit_once(':') {
+                Some((path, line)) if line.parse::<usize>().is_ok() => (PathBuf::from(path), line.parse().ok()),
+                _ => (PathBuf::from(target), None),
+            },
+        };
+        let path = match cwd {
+            Some(cwd) if path.is_relative() => cwd.join(path),
+            _ => path,
+        };
+        if path.is_file() {
+            self.open_editor_pane_at_line(path, line);
+        } else {
+            log::warn!("Trigger target {:?} is not a URL or file", target);
+        }
+    }
+}
+
+/// Post a desktop notification. Best effort: failures are only logged.
+fn send_desktop_notification(title: &str, body: &str) {
+    #[cfg(target_os = "macos")]
+    let result = {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let script = format!("display notification {} with title {}", quote(body), quote(title));
+        std::process::Command::new("osascript").arg("-e").arg(script).spawn()
+    };
+    #[cfg(not(target_os = "macos"))]
+    let result = std::process::Command::new("notify-send").arg("--").arg(title).arg(body).spawn();
+    if let Err(e) = result {
+        log::warn!("Failed to send notification: {}", e);
+    }
+}
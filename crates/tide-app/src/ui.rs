@@ -11,6 +11,9 @@ use crate::pane::PaneKind;
 pub(crate) fn pane_title(panes: &HashMap<PaneId, PaneKind>, id: PaneId) -> String {
     match panes.get(&id) {
         Some(PaneKind::Terminal(pane)) => {
+            if let Some(ref title) = pane.context.display_title {
+                return title.clone();
+            }
             if let Some(cwd) = pane.backend.detect_cwd_fallback() {
                 let components: Vec<_> = cwd.components().collect();
                 if components.len() <= 2 {
@@ -26,6 +29,7 @@ pub(crate) fn pane_title(panes: &HashMap<PaneId, PaneKind>, id: PaneId) -> Strin
         Some(PaneKind::Editor(pane)) => pane.title(),
         Some(PaneKind::Diff(dp)) => format!("Git Changes ({})", dp.files.len()),
         Some(PaneKind::Browser(bp)) => bp.title(),
+        Some(PaneKind::Replay(rp)) => rp.title(),
         Some(PaneKind::Launcher(_)) => "New Tab".to_string(),
         None => format!("Pane {}", id),
     }
@@ -1,6 +1,7 @@
 // UI state structs extracted from main.rs
 
 use std::path::PathBuf;
+use std::time::Instant;
 
 use tide_core::{PaneId, Rect, Vec2};
 use crate::theme::{POPUP_INPUT_PADDING, POPUP_LINE_EXTRA, CONTEXT_MENU_W};
@@ -367,6 +368,81 @@ impl FileFinderState {
     }
 }
 
+// ──────────────────────────────────────────────
+// Problems panel state (floating list of parsed compiler/test problems)
+// ──────────────────────────────────────────────
+
+pub(crate) const PROBLEMS_POPUP_W: f32 = 640.0;
+pub(crate) const PROBLEMS_MAX_VISIBLE: usize = 14;
+
+/// Pre-computed popup geometry for the problems panel, shared between rendering and hit-testing.
+pub(crate) struct ProblemsGeometry {
+    pub popup_x: f32,
+    pub popup_y: f32,
+    pub popup_w: f32,
+    pub popup_h: f32,
+    pub title_h: f32,
+    pub line_height: f32,
+    pub list_top: f32,
+    pub max_visible: usize,
+}
+
+/// The list itself lives in `App::problems`; this is only the selection.
+pub(crate) struct ProblemsPanelState {
+    pub selected: usize,
+    pub scroll_offset: usize,
+}
+
+impl ProblemsPanelState {
+    pub fn new(selected: usize) -> Self {
+        Self {
+            selected,
+            scroll_offset: selected.saturating_sub(PROBLEMS_MAX_VISIBLE - 1),
+        }
+    }
+
+    /// Compute popup geometry for `count` problems (an empty list keeps one row for the notice).
+    pub fn geometry(&self, count: usize, cell_height: f32, logical_width: f32, logical_height: f32) -> ProblemsGeometry {
+        let line_height = cell_height * crate::theme::FILE_TREE_LINE_SPACING;
+        let title_h = cell_height + POPUP_INPUT_PADDING;
+        let popup_w = PROBLEMS_POPUP_W.min(logical_width - 32.0);
+        let popup_x = (logical_width - popup_w) / 2.0;
+        let popup_y = 120.0_f32.min(logical_height * 0.15);
+        let max_visible = PROBLEMS_MAX_VISIBLE.min(count.max(1));
+        let popup_h = title_h + 8.0 + max_visible as f32 * line_height + 8.0;
+        let list_top = popup_y + 2.0 + title_h + 8.0;
+
+        ProblemsGeometry {
+            popup_x,
+            popup_y,
+            popup_w,
+            popup_h,
+            title_h,
+            line_height,
+            list_top,
+            max_visible,
+        }
+    }
+
+    pub fn select_up(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+            if self.selected < self.scroll_offset {
+                self.scroll_offset = self.selected;
+            }
+        }
+    }
+
+    pub fn select_down(&mut self, count: usize) {
+        if self.selected + 1 < count {
+            self.selected += 1;
+            if self.selected >= self.scroll_offset + PROBLEMS_MAX_VISIBLE {
+                self.scroll_offset = self.selected.saturating_sub(PROBLEMS_MAX_VISIBLE - 1);
+            }
+        }
+    }
+}
+
 // ──────────────────────────────────────────────
 // Git switcher popup state (integrated branch + worktree)
 // ──────────────────────────────────────────────
@@ -755,6 +831,25 @@ pub(crate) struct BranchCleanupState {
     pub cwd: PathBuf,
 }
 
+// ──────────────────────────────────────────────
+// Clipboard prompt state (OSC 52 request waiting for the user)
+// ──────────────────────────────────────────────
+
+pub(crate) struct ClipboardPromptState {
+    pub pane_id: PaneId,
+    pub request: tide_terminal::ClipboardRequest,
+    pub message: String,
+}
+
+// ──────────────────────────────────────────────
+// Toast (short-lived message at the bottom of the window)
+// ──────────────────────────────────────────────
+
+pub(crate) struct Toast {
+    pub message: String,
+    pub until: Instant,
+}
+
 // ──────────────────────────────────────────────
 // File tree inline rename state
 // ──────────────────────────────────────────────
@@ -814,6 +909,8 @@ pub(crate) struct ModalStack {
     pub context_menu: Option<ContextMenuState>,
     pub file_tree_rename: Option<FileTreeRenameState>,
     pub branch_cleanup: Option<BranchCleanupState>,
+    pub clipboard_prompt: Option<ClipboardPromptState>,
+    pub problems: Option<ProblemsPanelState>,
 }
 
 impl ModalStack {
@@ -827,6 +924,8 @@ impl ModalStack {
             context_menu: None,
             file_tree_rename: None,
             branch_cleanup: None,
+            clipboard_prompt: None,
+            problems: None,
         }
     }
 
@@ -841,6 +940,8 @@ impl ModalStack {
             || self.context_menu.is_some()
             || self.file_tree_rename.is_some()
             || self.branch_cleanup.is_some()
+            || self.clipboard_prompt.is_some()
+            || self.problems.is_some()
     }
 
     /// Close all popups/modals.
@@ -854,9 +955,16 @@ impl ModalStack {
         self.context_menu = None;
         self.file_tree_rename = None;
         self.branch_cleanup = None;
+        self.clipboard_prompt = None;
+        self.problems = None;
     }
 }
 
+/// Clicks closer together than this (in time and logical pixels) count as
+/// one double / triple / quadruple click.
+const MULTI_CLICK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(400);
+const MULTI_CLICK_DISTANCE: f32 = 4.0;
+
 /// Mouse/drag/scroll interaction state.
 pub(crate) struct InteractionState {
     pub pane_drag: super::PaneDragState,
@@ -865,6 +973,14 @@ pub(crate) struct InteractionState {
     pub scrollbar_dragging: Option<PaneId>,
     pub scrollbar_drag_rect: Option<Rect>,
     pub hover_target: Option<super::HoverTarget>,
+    /// Button press forwarded to a mouse-reporting terminal app (pane, button).
+    pub mouse_report: Option<(PaneId, tide_core::MouseButton)>,
+    /// Last cell reported for motion, so moves within a cell aren't re-sent.
+    pub mouse_report_cell: Option<(usize, usize)>,
+    /// Time, position and click count of the last left click.
+    pub last_click: Option<(Instant, Vec2, u8)>,
+    /// Terminal whose selection follows the mouse while the left button is down.
+    pub selecting: Option<PaneId>,
 }
 
 impl InteractionState {
@@ -876,8 +992,29 @@ impl InteractionState {
             scrollbar_dragging: None,
             scrollbar_drag_rect: None,
             hover_target: None,
+            mouse_report: None,
+            mouse_report_cell: None,
+            last_click: None,
+            selecting: None,
         }
     }
+
+    /// Count a left click at `pos`: 1 for a single click, up to 4 for a
+    /// quadruple click, then back to 1.
+    pub fn register_click(&mut self, pos: Vec2, now: Instant) -> u8 {
+        let count = match self.last_click {
+            Some((at, last_pos, count))
+                if now.duration_since(at) <= MULTI_CLICK_INTERVAL
+                    && (pos.x - last_pos.x).abs() <= MULTI_CLICK_DISTANCE
+                    && (pos.y - last_pos.y).abs() <= MULTI_CLICK_DISTANCE =>
+            {
+                count % 4 + 1
+            }
+            _ => 1,
+        };
+        self.last_click = Some((now, pos, count));
+        count
+    }
 }
 
 /// Render generation tracking and dirty flags.
@@ -1376,6 +1513,22 @@ mod tests {
         assert!(is.scroll_accumulator.is_empty());
     }
 
+    #[test]
+    fn register_click_counts_quick_nearby_clicks() {
+        let mut is = InteractionState::new();
+        let t0 = Instant::now();
+        let pos = Vec2::new(10.0, 10.0);
+        let ms = std::time::Duration::from_millis;
+        assert_eq!(is.register_click(pos, t0), 1);
+        assert_eq!(is.register_click(Vec2::new(12.0, 9.0), t0 + ms(200)), 2);
+        assert_eq!(is.register_click(pos, t0 + ms(400)), 3);
+        assert_eq!(is.register_click(pos, t0 + ms(600)), 4);
+        assert_eq!(is.register_click(pos, t0 + ms(800)), 1);
+        // Too slow, or too far away
+        assert_eq!(is.register_click(pos, t0 + ms(1500)), 1);
+        assert_eq!(is.register_click(Vec2::new(40.0, 10.0), t0 + ms(1600)), 1);
+    }
+
     // ── FileTreeModel ──
 
     #[test]
@@ -95,6 +95,18 @@ impl App {
             }
         }
 
+        // Advance recording playback; keep frames coming while a replay plays
+        for pane in self.panes.values_mut() {
+            if let PaneKind::Replay(rp) = pane {
+                if rp.process() {
+                    self.cache.invalidate_chrome();
+                }
+                if !rp.player.is_paused() {
+                    self.cache.needs_redraw = true;
+                }
+            }
+        }
+
         // Keep file tree/CWD in sync with terminal output (works for RedrawRequested path too).
         // Skip during rapid updates — these are non-critical and can run on the next calm frame.
         if had_terminal_output && !is_rapid {
@@ -298,6 +310,7 @@ impl App {
         // Skip during rapid updates — badge refresh is cosmetic, not critical.
         if !is_rapid {
             self.update_terminal_badges();
+            self.enforce_scrollback_limits();
         }
 
         // Start git poller if not yet running
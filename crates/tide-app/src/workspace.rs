@@ -5,6 +5,7 @@ use std::collections::HashMap;
 use tide_core::{DropZone, LayoutEngine, PaneId};
 use tide_layout::SplitLayout;
 
+use crate::broadcast::BroadcastScope;
 use crate::pane::PaneKind;
 use crate::ui_state::FocusArea;
 use crate::App;
@@ -16,6 +17,14 @@ pub(crate) struct Workspace {
     pub layout: SplitLayout,
     pub focused: Option<PaneId>,
     pub panes: HashMap<PaneId, PaneKind>,
+    pub broadcast: Option<BroadcastScope>,
+}
+
+impl Workspace {
+    /// A workspace with nothing in it yet but `layout`.
+    pub(crate) fn new(name: impl Into<String>, layout: SplitLayout) -> Self {
+        Self { name: name.into(), layout, focused: None, panes: HashMap::new(), broadcast: None }
+    }
 }
 
 impl App {
@@ -26,6 +35,7 @@ impl App {
         std::mem::swap(&mut self.layout, &mut ws.layout);
         std::mem::swap(&mut self.focused, &mut ws.focused);
         std::mem::swap(&mut self.panes, &mut ws.panes);
+        std::mem::swap(&mut self.broadcast, &mut ws.broadcast);
     }
 
     /// Load the active workspace's state from the workspaces vec into App fields.
@@ -35,6 +45,7 @@ impl App {
         std::mem::swap(&mut self.layout, &mut ws.layout);
         std::mem::swap(&mut self.focused, &mut ws.focused);
         std::mem::swap(&mut self.panes, &mut ws.panes);
+        std::mem::swap(&mut self.broadcast, &mut ws.broadcast);
     }
 
     /// Switch to workspace at the given 0-based index.
@@ -93,12 +104,7 @@ impl App {
         self.panes = HashMap::new();
 
         let ws_name = format!("Workspace {}", self.ws.workspaces.len() + 1);
-        self.ws.workspaces.push(Workspace {
-            name: ws_name,
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        self.ws.workspaces.push(Workspace::new(ws_name, SplitLayout::new()));
         self.ws.active = self.ws.workspaces.len() - 1;
 
         self.create_terminal_pane(pane_id, None);
@@ -224,6 +230,16 @@ impl App {
     /// Close the current workspace (only if more than one exists).
     pub(crate) fn close_workspace(&mut self) {
         if self.ws.workspaces.len() <= 1 { return; }
+        // A tmux session shown here keeps running on the server
+        self.detach_tmux_sessions();
+
+        // `tide open --wait` clients of its editors get each one's saved state
+        let waited: Vec<(std::path::PathBuf, bool)> = self.panes.values()
+            .filter_map(|pane| match pane {
+                PaneKind::Editor(ep) => ep.editor.file_path().map(|p| (p.to_path_buf(), !ep.editor.is_modified())),
+                _ => None,
+            })
+            .collect();
 
         // Destroy all panes in the current workspace
         let pane_ids: Vec<PaneId> = self.panes.keys().copied().collect();
@@ -260,6 +276,9 @@ impl App {
         self.compute_layout();
         self.update_file_tree_cwd();
         self.sync_browser_webview_frames();
+        for (path, saved) in waited {
+            self.finish_ipc_waits(&path, saved);
+        }
     }
 }
 
@@ -278,12 +297,7 @@ mod tests {
     #[test]
     fn save_load_roundtrip() {
         let mut app = test_app();
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
         app.ws.active = 0;
         app.focused = Some(42);
 
@@ -315,18 +329,8 @@ mod tests {
         let mut app = test_app();
 
         // Create two workspaces with different focused pane IDs
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
-        app.ws.workspaces.push(Workspace {
-            name: "WS2".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
+        app.ws.workspaces.push(Workspace::new("WS2", SplitLayout::new()));
 
         // Set up WS1 as active with focused pane 100
         app.ws.active = 0;
@@ -358,12 +362,7 @@ mod tests {
     #[test]
     fn switch_workspace_same_index_is_noop() {
         let mut app = test_app();
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
         app.ws.active = 0;
         app.focused = Some(42);
 
@@ -377,12 +376,7 @@ mod tests {
     #[test]
     fn switch_workspace_out_of_bounds_is_noop() {
         let mut app = test_app();
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
         app.ws.active = 0;
         app.focused = Some(42);
 
@@ -394,12 +388,7 @@ mod tests {
     #[test]
     fn close_workspace_with_single_workspace_is_noop() {
         let mut app = test_app();
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
 
         app.close_workspace();
         assert_eq!(app.ws.workspaces.len(), 1); // still 1
@@ -410,18 +399,8 @@ mod tests {
         let mut app = test_app();
 
         // Set up two workspaces
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
-        app.ws.workspaces.push(Workspace {
-            name: "WS2".into(),
-            layout: SplitLayout::new(),
-            focused: Some(200),
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
+        app.ws.workspaces.push(Workspace { focused: Some(200), ..Workspace::new("WS2", SplitLayout::new()) });
         app.ws.active = 0;
         app.focused = Some(100);
 
@@ -446,20 +425,10 @@ mod tests {
         let pane = crate::editor_pane::EditorPane::new_empty(pane_id);
         app.panes.insert(pane_id, PaneKind::Editor(pane));
 
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
 
         // Create WS2 (empty, stored)
-        app.ws.workspaces.push(Workspace {
-            name: "WS2".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS2", SplitLayout::new()));
         app.ws.active = 0;
 
         let gen_before = app.cache.chrome_generation;
@@ -492,18 +461,8 @@ mod tests {
         // Simulate scroll state
         app.interaction.scroll_accumulator.insert(pane_id, 3.5);
 
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
-        app.ws.workspaces.push(Workspace {
-            name: "WS2".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
+        app.ws.workspaces.push(Workspace::new("WS2", SplitLayout::new()));
         app.ws.active = 0;
 
         app.move_pane_to_workspace(pane_id, 1);
@@ -529,18 +488,8 @@ mod tests {
         app.panes.insert(pane_b, PaneKind::Editor(editor_b));
         app.focused = Some(pane_a);
 
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
-        app.ws.workspaces.push(Workspace {
-            name: "WS2".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
+        app.ws.workspaces.push(Workspace::new("WS2", SplitLayout::new()));
         app.ws.active = 0;
 
         // Move pane_a to WS2
@@ -568,12 +517,7 @@ mod tests {
         let pane = crate::editor_pane::EditorPane::new_empty(pane_id);
         app.panes.insert(pane_id, PaneKind::Editor(pane));
 
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
         app.ws.active = 0;
 
         let gen_before = app.cache.chrome_generation;
@@ -589,18 +533,8 @@ mod tests {
     fn switch_workspace_sets_needs_redraw() {
         let mut app = test_app();
 
-        app.ws.workspaces.push(Workspace {
-            name: "WS1".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
-        app.ws.workspaces.push(Workspace {
-            name: "WS2".into(),
-            layout: SplitLayout::new(),
-            focused: None,
-            panes: HashMap::new(),
-        });
+        app.ws.workspaces.push(Workspace::new("WS1", SplitLayout::new()));
+        app.ws.workspaces.push(Workspace::new("WS2", SplitLayout::new()));
         app.ws.active = 0;
         app.focused = Some(100);
         app.save_active_workspace();
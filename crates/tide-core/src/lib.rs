@@ -93,6 +93,18 @@ impl Color {
 // Text Styling
 // ──────────────────────────────────────────────
 
+/// Underline shape (SGR 4, 4:1–4:5).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnderlineStyle {
+    #[default]
+    None,
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct TextStyle {
     pub foreground: Color,
@@ -100,7 +112,10 @@ pub struct TextStyle {
     pub bold: bool,
     pub dim: bool,
     pub italic: bool,
-    pub underline: bool,
+    pub underline: UnderlineStyle,
+    /// SGR 58 underline color (None = foreground).
+    pub underline_color: Option<Color>,
+    pub strikethrough: bool,
 }
 
 impl Default for TextStyle {
@@ -111,11 +126,20 @@ impl Default for TextStyle {
             bold: false,
             dim: false,
             italic: false,
-            underline: false,
+            underline: UnderlineStyle::None,
+            underline_color: None,
+            strikethrough: false,
         }
     }
 }
 
+impl TextStyle {
+    /// Whether an underline or strikethrough is drawn, even on a blank cell.
+    pub fn has_decoration(&self) -> bool {
+        self.underline != UnderlineStyle::None || self.strikethrough
+    }
+}
+
 // ──────────────────────────────────────────────
 // Input
 // ──────────────────────────────────────────────
@@ -202,12 +226,27 @@ pub struct TerminalGrid {
     pub cols: u16,
     pub rows: u16,
     pub cells: Vec<Vec<TerminalCell>>,
+    /// OSC 8 hyperlink URIs. A cell's `link` id N refers to `links[N - 1]`.
+    pub links: Vec<String>,
+}
+
+impl TerminalGrid {
+    /// URI of the OSC 8 hyperlink at the given cell, if any.
+    pub fn link_at(&self, row: usize, col: usize) -> Option<&str> {
+        let id = self.cells.get(row)?.get(col)?.link as usize;
+        if id == 0 {
+            return None;
+        }
+        self.links.get(id - 1).map(String::as_str)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct TerminalCell {
     pub character: char,
     pub style: TextStyle,
+    /// OSC 8 hyperlink id into `TerminalGrid::links` (0 = no link).
+    pub link: u32,
 }
 
 impl Default for TerminalCell {
@@ -215,6 +254,7 @@ impl Default for TerminalCell {
         Self {
             character: ' ',
             style: TextStyle::default(),
+            link: 0,
         }
     }
 }
@@ -9,7 +9,7 @@ use syntect::highlighting::{
 };
 use syntect::parsing::{ParseState, ScopeStack, SyntaxDefinition, SyntaxReference, SyntaxSet};
 
-use tide_core::{Color, TextStyle};
+use tide_core::{Color, TextStyle, UnderlineStyle};
 
 /// A styled span of text produced by syntax highlighting.
 pub struct StyledSpan {
@@ -238,9 +238,16 @@ impl Highlighter {
                                     .font_style
                                     .contains(syntect::highlighting::FontStyle::ITALIC),
                                 dim: false,
-                                underline: style
+                                underline: if style
                                     .font_style
-                                    .contains(syntect::highlighting::FontStyle::UNDERLINE),
+                                    .contains(syntect::highlighting::FontStyle::UNDERLINE)
+                                {
+                                    UnderlineStyle::Single
+                                } else {
+                                    UnderlineStyle::None
+                                },
+                                underline_color: None,
+                                strikethrough: false,
                             },
                         }
                     })
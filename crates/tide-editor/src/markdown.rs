@@ -3,7 +3,7 @@
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use pulldown_cmark::Alignment;
-use tide_core::{Color, TextStyle};
+use tide_core::{Color, TextStyle, UnderlineStyle};
 
 use crate::highlight::StyledSpan;
 
@@ -141,22 +141,22 @@ fn render_table(
     let border_style = TextStyle {
         foreground: theme.blockquote,
         background: None,
-        bold: false, dim: false, italic: false, underline: false,
+        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
     };
     let header_style = TextStyle {
         foreground: theme.bold,
         background: None,
-        bold: true, dim: false, italic: false, underline: false,
+        bold: true, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
     };
     let cell_style = TextStyle {
         foreground: theme.body,
         background: None,
-        bold: false, dim: false, italic: false, underline: false,
+        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
     };
     let indent_style = TextStyle {
         foreground: theme.body,
         background: None,
-        bold: false, dim: false, italic: false, underline: false,
+        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
     };
 
     // Helper: build a horizontal rule line
@@ -275,7 +275,7 @@ pub fn render_markdown_preview(
             style: TextStyle {
                 foreground: theme.body,
                 background: None,
-                bold: false, dim: false, italic: false, underline: false,
+                bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
             },
         }];
         line_spans.append(spans);
@@ -298,14 +298,14 @@ pub fn render_markdown_preview(
             return TextStyle {
                 foreground: theme.code_fg,
                 background: None,
-                bold: false, dim: false, italic: false, underline: false,
+                bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
             };
         }
         if in_link {
             return TextStyle {
                 foreground: theme.link,
                 background: None,
-                bold: false, dim: false, italic: false, underline: true,
+                bold: false, dim: false, italic: false, underline: UnderlineStyle::Single, underline_color: None, strikethrough: false,
             };
         }
         if let Some(level) = heading {
@@ -320,41 +320,43 @@ pub fn render_markdown_preview(
                 background: None,
                 bold: true, dim: false,
                 italic: matches!(level, HeadingLevel::H4 | HeadingLevel::H5 | HeadingLevel::H6),
-                underline: false,
+                underline: UnderlineStyle::None,
+                underline_color: None,
+                strikethrough: false,
             };
         }
         if in_blockquote {
             return TextStyle {
                 foreground: theme.blockquote,
                 background: None,
-                bold, dim: false, italic: true, underline: false,
+                bold, dim: false, italic: true, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
             };
         }
         if bold && italic {
             return TextStyle {
                 foreground: theme.bold,
                 background: None,
-                bold: true, dim: false, italic: true, underline: false,
+                bold: true, dim: false, italic: true, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
             };
         }
         if bold {
             return TextStyle {
                 foreground: theme.bold,
                 background: None,
-                bold: true, dim: false, italic: false, underline: false,
+                bold: true, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
             };
         }
         if italic {
             return TextStyle {
                 foreground: theme.italic,
                 background: None,
-                bold: false, dim: false, italic: true, underline: false,
+                bold: false, dim: false, italic: true, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
             };
         }
         TextStyle {
             foreground: theme.body,
             background: None,
-            bold: false, dim: false, italic: false, underline: false,
+            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
         }
     };
 
@@ -472,7 +474,7 @@ pub fn render_markdown_preview(
                         style: TextStyle {
                             foreground: theme.body,
                             background: None,
-                            bold: false, dim: false, italic: false, underline: false,
+                            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                         },
                     }],
                     bg_color: current_bg,
@@ -486,7 +488,7 @@ pub fn render_markdown_preview(
                             style: TextStyle {
                                 foreground: theme.blockquote,
                                 background: None,
-                                bold: false, dim: true, italic: true, underline: false,
+                                bold: false, dim: true, italic: true, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                             },
                         });
                         flush_line(&mut current_spans, &current_bg, &mut result, &mut current_col);
@@ -504,7 +506,7 @@ pub fn render_markdown_preview(
                         style: TextStyle {
                             foreground: theme.body,
                             background: None,
-                            bold: false, dim: false, italic: false, underline: false,
+                            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                         },
                     }],
                     bg_color: current_bg,
@@ -581,7 +583,7 @@ pub fn render_markdown_preview(
                         style: TextStyle {
                             foreground: theme.list_marker,
                             background: None,
-                            bold: false, dim: false, italic: false, underline: false,
+                            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                         },
                     });
                     current_col += mw;
@@ -617,7 +619,7 @@ pub fn render_markdown_preview(
                                     style: TextStyle {
                                         foreground: theme.body,
                                         background: None,
-                                        bold: false, dim: false, italic: false, underline: false,
+                                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                                     },
                                 }],
                                 bg_color: current_bg,
@@ -635,7 +637,7 @@ pub fn render_markdown_preview(
                             style: TextStyle {
                                 foreground: theme.blockquote,
                                 background: None,
-                                bold: false, dim: false, italic: false, underline: false,
+                                bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                             },
                         });
                         current_col += prefix_len;
@@ -655,7 +657,7 @@ pub fn render_markdown_preview(
                                     style: TextStyle {
                                         foreground: theme.blockquote,
                                         background: None,
-                                        bold: false, dim: false, italic: false, underline: false,
+                                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                                     },
                                 });
                                 current_col += prefix_len;
@@ -683,7 +685,7 @@ pub fn render_markdown_preview(
                                             style: TextStyle {
                                                 foreground: theme.blockquote,
                                                 background: None,
-                                                bold: false, dim: false, italic: false, underline: false,
+                                                bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                                             },
                                         });
                                         current_col += prefix_len;
@@ -716,7 +718,7 @@ pub fn render_markdown_preview(
                         style: TextStyle {
                             foreground: theme.list_marker,
                             background: None,
-                            bold: false, dim: false, italic: false, underline: false,
+                            bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                         },
                     });
                     current_col += marker.width();
@@ -732,7 +734,7 @@ pub fn render_markdown_preview(
                     style: TextStyle {
                         foreground: theme.code_fg,
                         background: Some(theme.code_bg),
-                        bold: false, dim: false, italic: false, underline: false,
+                        bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                     },
                 });
                 current_col += code_len;
@@ -760,7 +762,7 @@ pub fn render_markdown_preview(
                             style: TextStyle {
                                 foreground: theme.body,
                                 background: None,
-                                bold: false, dim: false, italic: false, underline: false,
+                                bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                             },
                         },
                         StyledSpan {
@@ -768,7 +770,7 @@ pub fn render_markdown_preview(
                             style: TextStyle {
                                 foreground: theme.rule,
                                 background: None,
-                                bold: false, dim: false, italic: false, underline: false,
+                                bold: false, dim: false, italic: false, underline: UnderlineStyle::None, underline_color: None, strikethrough: false,
                             },
                         },
                     ],
@@ -60,12 +60,30 @@ pub enum GlobalAction {
     BrowserForward,
     ScrollHalfPageUp,
     ScrollHalfPageDown,
+    PrevPrompt,
+    NextPrompt,
+    SelectLastOutput,
+    CopyLastOutput,
+    PurgeSavedScrollback,
+    ToggleRecording,
     WorkspacePrev,
     WorkspaceNext,
     NewWorkspace,
     CloseWorkspace,
     ToggleFileTree,
     ToggleWorkspaceSidebar,
+    ShowProblems,
+    NextError,
+    PrevError,
+    CopyMode,
+    /// Mirror input to every terminal in the focused tab group (toggle).
+    BroadcastTabGroup,
+    /// Mirror input to every terminal in the workspace (toggle).
+    BroadcastWorkspace,
+    /// Add the focused terminal to the hand-picked broadcast set, or remove it.
+    BroadcastPane,
+    /// Open a terminal tab with the Nth profile from settings (1-9).
+    OpenProfile(u8),
 }
 
 impl GlobalAction {
@@ -105,12 +123,35 @@ impl GlobalAction {
             GlobalAction::BrowserForward => "Browser Forward",
             GlobalAction::ScrollHalfPageUp => "Scroll Half Page Up",
             GlobalAction::ScrollHalfPageDown => "Scroll Half Page Down",
+            GlobalAction::PrevPrompt => "Previous Prompt",
+            GlobalAction::NextPrompt => "Next Prompt",
+            GlobalAction::SelectLastOutput => "Select Last Output",
+            GlobalAction::CopyLastOutput => "Copy Last Output",
+            GlobalAction::PurgeSavedScrollback => "Purge Saved Scrollback",
+            GlobalAction::ToggleRecording => "Toggle Terminal Recording",
             GlobalAction::WorkspacePrev => "Workspace Prev",
             GlobalAction::WorkspaceNext => "Workspace Next",
             GlobalAction::NewWorkspace => "New Workspace",
             GlobalAction::CloseWorkspace => "Close Workspace",
             GlobalAction::ToggleFileTree => "Toggle File Tree",
             GlobalAction::ToggleWorkspaceSidebar => "Toggle Workspace Sidebar",
+            GlobalAction::ShowProblems => "Show Problems",
+            GlobalAction::NextError => "Next Error",
+            GlobalAction::PrevError => "Previous Error",
+            GlobalAction::CopyMode => "Copy Mode",
+            GlobalAction::BroadcastTabGroup => "Broadcast to Tab Group",
+            GlobalAction::BroadcastWorkspace => "Broadcast to Workspace",
+            GlobalAction::BroadcastPane => "Broadcast to This Pane",
+            GlobalAction::OpenProfile(1) => "Open Profile 1",
+            GlobalAction::OpenProfile(2) => "Open Profile 2",
+            GlobalAction::OpenProfile(3) => "Open Profile 3",
+            GlobalAction::OpenProfile(4) => "Open Profile 4",
+            GlobalAction::OpenProfile(5) => "Open Profile 5",
+            GlobalAction::OpenProfile(6) => "Open Profile 6",
+            GlobalAction::OpenProfile(7) => "Open Profile 7",
+            GlobalAction::OpenProfile(8) => "Open Profile 8",
+            GlobalAction::OpenProfile(9) => "Open Profile 9",
+            GlobalAction::OpenProfile(_) => "Open Profile",
         }
     }
 
@@ -150,12 +191,35 @@ impl GlobalAction {
             GlobalAction::BrowserForward => "BrowserForward",
             GlobalAction::ScrollHalfPageUp => "ScrollHalfPageUp",
             GlobalAction::ScrollHalfPageDown => "ScrollHalfPageDown",
+            GlobalAction::PrevPrompt => "PrevPrompt",
+            GlobalAction::NextPrompt => "NextPrompt",
+            GlobalAction::SelectLastOutput => "SelectLastOutput",
+            GlobalAction::CopyLastOutput => "CopyLastOutput",
+            GlobalAction::PurgeSavedScrollback => "PurgeSavedScrollback",
+            GlobalAction::ToggleRecording => "ToggleRecording",
             GlobalAction::WorkspacePrev => "WorkspacePrev",
             GlobalAction::WorkspaceNext => "WorkspaceNext",
             GlobalAction::NewWorkspace => "NewWorkspace",
             GlobalAction::CloseWorkspace => "CloseWorkspace",
             GlobalAction::ToggleFileTree => "ToggleFileTree",
             GlobalAction::ToggleWorkspaceSidebar => "ToggleWorkspaceSidebar",
+            GlobalAction::ShowProblems => "ShowProblems",
+            GlobalAction::NextError => "NextError",
+            GlobalAction::PrevError => "PrevError",
+            GlobalAction::CopyMode => "CopyMode",
+            GlobalAction::BroadcastTabGroup => "BroadcastTabGroup",
+            GlobalAction::BroadcastWorkspace => "BroadcastWorkspace",
+            GlobalAction::BroadcastPane => "BroadcastPane",
+            GlobalAction::OpenProfile(1) => "OpenProfile1",
+            GlobalAction::OpenProfile(2) => "OpenProfile2",
+            GlobalAction::OpenProfile(3) => "OpenProfile3",
+            GlobalAction::OpenProfile(4) => "OpenProfile4",
+            GlobalAction::OpenProfile(5) => "OpenProfile5",
+            GlobalAction::OpenProfile(6) => "OpenProfile6",
+            GlobalAction::OpenProfile(7) => "OpenProfile7",
+            GlobalAction::OpenProfile(8) => "OpenProfile8",
+            GlobalAction::OpenProfile(9) => "OpenProfile9",
+            GlobalAction::OpenProfile(_) => "OpenProfile",
         }
     }
 
@@ -195,12 +259,34 @@ impl GlobalAction {
             "BrowserForward" => Some(GlobalAction::BrowserForward),
             "ScrollHalfPageUp" => Some(GlobalAction::ScrollHalfPageUp),
             "ScrollHalfPageDown" => Some(GlobalAction::ScrollHalfPageDown),
+            "PrevPrompt" => Some(GlobalAction::PrevPrompt),
+            "NextPrompt" => Some(GlobalAction::NextPrompt),
+            "SelectLastOutput" => Some(GlobalAction::SelectLastOutput),
+            "CopyLastOutput" => Some(GlobalAction::CopyLastOutput),
+            "PurgeSavedScrollback" => Some(GlobalAction::PurgeSavedScrollback),
+            "ToggleRecording" => Some(GlobalAction::ToggleRecording),
             "WorkspacePrev" => Some(GlobalAction::WorkspacePrev),
             "WorkspaceNext" => Some(GlobalAction::WorkspaceNext),
             "NewWorkspace" => Some(GlobalAction::NewWorkspace),
             "CloseWorkspace" => Some(GlobalAction::CloseWorkspace),
             "ToggleFileTree" => Some(GlobalAction::ToggleFileTree),
             "ToggleWorkspaceSidebar" => Some(GlobalAction::ToggleWorkspaceSidebar),
+            "ShowProblems" => Some(GlobalAction::ShowProblems),
+            "NextError" => Some(GlobalAction::NextError),
+            "PrevError" => Some(GlobalAction::PrevError),
+            "CopyMode" => Some(GlobalAction::CopyMode),
+            "BroadcastTabGroup" => Some(GlobalAction::BroadcastTabGroup),
+            "BroadcastWorkspace" => Some(GlobalAction::BroadcastWorkspace),
+            "BroadcastPane" => Some(GlobalAction::BroadcastPane),
+            "OpenProfile1" => Some(GlobalAction::OpenProfile(1)),
+            "OpenProfile2" => Some(GlobalAction::OpenProfile(2)),
+            "OpenProfile3" => Some(GlobalAction::OpenProfile(3)),
+            "OpenProfile4" => Some(GlobalAction::OpenProfile(4)),
+            "OpenProfile5" => Some(GlobalAction::OpenProfile(5)),
+            "OpenProfile6" => Some(GlobalAction::OpenProfile(6)),
+            "OpenProfile7" => Some(GlobalAction::OpenProfile(7)),
+            "OpenProfile8" => Some(GlobalAction::OpenProfile(8)),
+            "OpenProfile9" => Some(GlobalAction::OpenProfile(9)),
             _ => None,
         }
     }
@@ -243,8 +329,24 @@ impl GlobalAction {
             GlobalAction::BrowserForward,
             GlobalAction::ScrollHalfPageUp,
             GlobalAction::ScrollHalfPageDown,
+            GlobalAction::PrevPrompt,
+            GlobalAction::NextPrompt,
+            GlobalAction::SelectLastOutput,
+            GlobalAction::CopyLastOutput,
+            GlobalAction::PurgeSavedScrollback,
+            GlobalAction::ToggleRecording,
             GlobalAction::ToggleWorkspaceSidebar,
+            GlobalAction::ShowProblems,
+            GlobalAction::NextError,
+            GlobalAction::PrevError,
+            GlobalAction::CopyMode,
+            GlobalAction::BroadcastTabGroup,
+            GlobalAction::BroadcastWorkspace,
+            GlobalAction::BroadcastPane,
         ]
+        .into_iter()
+        .chain((1..=9).map(GlobalAction::OpenProfile))
+        .collect()
     }
 }
 
@@ -318,6 +420,7 @@ impl Hotkey {
             Key::End => "End".to_string(),
             Key::PageUp => "PageUp".to_string(),
             Key::PageDown => "PageDown".to_string(),
+            Key::F(n) => format!("F{}", n),
             _ => format!("{:?}", self.key),
         }
     }
@@ -338,6 +441,7 @@ impl Hotkey {
             "End" => Some(Key::End),
             "PageUp" => Some(Key::PageUp),
             "PageDown" => Some(Key::PageDown),
+            _ if s.len() > 1 && s.starts_with('F') => s[1..].parse().ok().map(Key::F),
             _ => {
                 let mut chars = s.chars();
                 let c = chars.next()?;
@@ -376,6 +480,7 @@ pub fn display_key(key: &Key) -> String {
         Key::End => "End".to_string(),
         Key::PageUp => "PgUp".to_string(),
         Key::PageDown => "PgDn".to_string(),
+        Key::F(n) => format!("F{}", n),
         _ => "?".to_string(),
     }
 }
@@ -397,6 +502,7 @@ impl KeybindingMap {
             (Hotkey::new(Key::Char('w'), true, false, true, false), GlobalAction::CloseWorkspace),
             (Hotkey::new(Key::Char('v'), false, false, true, false), GlobalAction::Paste),
             (Hotkey::new(Key::Char('c'), false, false, true, false), GlobalAction::Copy),
+            (Hotkey::new(Key::Char('c'), true, false, true, false), GlobalAction::CopyMode),
             (Hotkey::new(Key::Char('f'), false, true, true, false), GlobalAction::ToggleFullscreen),
             (Hotkey::new(Key::Char('f'), false, false, true, false), GlobalAction::Find),
             (Hotkey::new(Key::Enter, false, false, true, false), GlobalAction::ToggleZoom),
@@ -414,6 +520,9 @@ impl KeybindingMap {
             (Hotkey::new(Key::Char('k'), false, false, true, false), GlobalAction::Navigate(Direction::Up)),
             (Hotkey::new(Key::Char('l'), false, false, true, false), GlobalAction::Navigate(Direction::Right)),
             (Hotkey::new(Key::Char('i'), false, false, true, false), GlobalAction::TabPrev),
+            (Hotkey::new(Key::Char('i'), false, false, true, true), GlobalAction::BroadcastTabGroup),
+            (Hotkey::new(Key::Char('i'), true, false, true, false), GlobalAction::BroadcastWorkspace),
+            (Hotkey::new(Key::Char('i'), true, false, true, true), GlobalAction::BroadcastPane),
             (Hotkey::new(Key::Char('o'), false, false, true, false), GlobalAction::TabNext),
             (Hotkey::new(Key::Char('o'), true, false, true, false), GlobalAction::FileFinder),
             (Hotkey::new(Key::Char('n'), false, false, true, false), GlobalAction::NewWindow),
@@ -428,6 +537,11 @@ impl KeybindingMap {
             (Hotkey::new(Key::Char(']'), false, false, true, false), GlobalAction::BrowserForward),
             (Hotkey::new(Key::Char('u'), false, false, true, false), GlobalAction::ScrollHalfPageUp),
             (Hotkey::new(Key::Char('d'), false, false, true, false), GlobalAction::ScrollHalfPageDown),
+            (Hotkey::new(Key::Up, true, false, true, false), GlobalAction::PrevPrompt),
+            (Hotkey::new(Key::Down, true, false, true, false), GlobalAction::NextPrompt),
+            (Hotkey::new(Key::Char('m'), true, false, true, false), GlobalAction::ShowProblems),
+            (Hotkey::new(Key::F(8), false, false, true, false), GlobalAction::NextError),
+            (Hotkey::new(Key::F(8), true, false, true, false), GlobalAction::PrevError),
         ]
     }
 
@@ -628,9 +742,11 @@ impl Router {
                     None // Ctrl+V → terminal 0x16
                 }
             }
-            // Cmd+C (macOS) / Ctrl+Shift+C (Linux) -> copy
+            // Cmd+C (macOS) / Ctrl+Shift+C (Linux) -> copy, Cmd+Shift+C -> copy mode
             Key::Char('c') | Key::Char('C') => {
-                if modifiers.meta {
+                if modifiers.meta && modifiers.shift {
+                    Some(GlobalAction::CopyMode)
+                } else if modifiers.meta {
                     Some(GlobalAction::Copy)
                 } else if modifiers.ctrl && modifiers.shift {
                     Some(GlobalAction::Copy)
@@ -675,8 +791,14 @@ impl Router {
             Key::Char('j') | Key::Char('J') => Some(GlobalAction::Navigate(Direction::Down)),
             Key::Char('k') | Key::Char('K') => Some(GlobalAction::Navigate(Direction::Up)),
             Key::Char('l') | Key::Char('L') => Some(GlobalAction::Navigate(Direction::Right)),
-            // Cmd+I -> tab prev
-            Key::Char('i') | Key::Char('I') => Some(GlobalAction::TabPrev),
+            // Cmd+I -> tab prev; with Alt / Shift / both -> broadcast to the
+            // tab group / workspace / this pane
+            Key::Char('i') | Key::Char('I') => match (modifiers.alt, modifiers.shift) {
+                (true, true) => Some(GlobalAction::BroadcastPane),
+                (true, false) => Some(GlobalAction::BroadcastTabGroup),
+                (false, true) => Some(GlobalAction::BroadcastWorkspace),
+                (false, false) => Some(GlobalAction::TabPrev),
+            },
             // Cmd+O -> tab next, Cmd+Shift+O -> file finder
             Key::Char('o') | Key::Char('O') => {
                 if modifiers.shift {
@@ -695,6 +817,13 @@ impl Router {
             }
             // Cmd+U -> scroll half page up
             Key::Char('u') | Key::Char('U') => Some(GlobalAction::ScrollHalfPageUp),
+            // Cmd+Shift+Up / Cmd+Shift+Down -> previous/next shell prompt
+            Key::Up if modifiers.shift => Some(GlobalAction::PrevPrompt),
+            Key::Down if modifiers.shift => Some(GlobalAction::NextPrompt),
+            // Cmd+Shift+M -> problems list, Cmd+F8 / Cmd+Shift+F8 -> next/previous error
+            Key::Char('m') | Key::Char('M') if modifiers.shift => Some(GlobalAction::ShowProblems),
+            Key::F(8) if modifiers.shift => Some(GlobalAction::PrevError),
+            Key::F(8) => Some(GlobalAction::NextError),
             // Cmd+= / Cmd++ -> font size up, Cmd+- -> font size down, Cmd+0 -> reset
             Key::Char('+') | Key::Char('=') => Some(GlobalAction::FontSizeUp),
             Key::Char('-') | Key::Char('_') => Some(GlobalAction::FontSizeDown),
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::{Action, AreaSlot, Direction, GlobalAction, Router};
+    use crate::{Action, AreaSlot, Direction, GlobalAction, Hotkey, KeybindingMap, Router};
     use tide_core::{InputEvent, Key, Modifiers, MouseButton, Rect, Size, Vec2};
 
     /// Helper: creates a set of two side-by-side pane rects.
@@ -351,6 +351,100 @@ mod tests {
         assert_eq!(action, Action::GlobalAction(GlobalAction::NewWorkspace));
     }
 
+    #[test]
+    fn meta_shift_arrows_trigger_prompt_navigation() {
+        let mut router = Router::new();
+        router.set_focused(1);
+        let panes = two_panes_horizontal();
+
+        for (key, expected) in [(Key::Up, GlobalAction::PrevPrompt), (Key::Down, GlobalAction::NextPrompt)] {
+            let action = router.process(InputEvent::KeyPress { key, modifiers: meta_shift() }, &panes);
+            assert_eq!(action, Action::GlobalAction(expected.clone()));
+            // Same binding through the default keybinding map
+            assert_eq!(KeybindingMap::new().lookup(&key, &meta_shift()), Some(expected.clone()));
+            assert_eq!(GlobalAction::from_action_key(expected.action_key()), Some(expected));
+        }
+    }
+
+    #[test]
+    fn problem_hotkeys_use_meta_f8_and_survive_key_names() {
+        let mut router = Router::new();
+        router.set_focused(1);
+        let panes = two_panes_horizontal();
+        let meta = Modifiers { meta: true, ..Default::default() };
+        for (key, modifiers, expected) in [
+            (Key::F(8), meta, GlobalAction::NextError),
+            (Key::F(8), meta_shift(), GlobalAction::PrevError),
+            (Key::Char('m'), meta_shift(), GlobalAction::ShowProblems),
+        ] {
+            let action = router.process(InputEvent::KeyPress { key, modifiers }, &panes);
+            assert_eq!(action, Action::GlobalAction(expected.clone()));
+            assert_eq!(KeybindingMap::new().lookup(&key, &modifiers), Some(expected));
+        }
+        // Plain F8 still goes to the pane
+        let action = router.process(InputEvent::KeyPress { key: Key::F(8), modifiers: Modifiers::default() }, &panes);
+        assert_eq!(action, Action::RouteToPane(1));
+
+        let hotkey = Hotkey::new(Key::F(12), false, false, true, false);
+        assert_eq!(hotkey.key_name(), "F12");
+        assert_eq!(hotkey.display(), "Cmd+F12");
+        assert_eq!(Hotkey::key_from_name("F12"), Some(Key::F(12)));
+        assert_eq!(Hotkey::key_from_name("F"), Some(Key::Char('f')));
+    }
+
+    #[test]
+    fn copy_mode_is_cmd_shift_c_and_ctrl_shift_c_still_copies() {
+        let mut router = Router::new();
+        router.set_focused(1);
+        let panes = two_panes_horizontal();
+        let action = router.process(InputEvent::KeyPress { key: Key::Char('c'), modifiers: meta_shift() }, &panes);
+        assert_eq!(action, Action::GlobalAction(GlobalAction::CopyMode));
+        assert_eq!(KeybindingMap::new().lookup(&Key::Char('c'), &meta_shift()), Some(GlobalAction::CopyMode));
+
+        let ctrl_shift = Modifiers { ctrl: true, shift: true, ..Default::default() };
+        let action = router.process(InputEvent::KeyPress { key: Key::Char('c'), modifiers: ctrl_shift }, &panes);
+        assert_eq!(action, Action::GlobalAction(GlobalAction::Copy));
+    }
+
+    #[test]
+    fn broadcast_hotkeys_share_cmd_i_with_tab_prev() {
+        let mut router = Router::new();
+        router.set_focused(1);
+        let panes = two_panes_horizontal();
+        let meta_alt = Modifiers { meta: true, alt: true, ..Default::default() };
+        let meta_alt_shift = Modifiers { meta: true, alt: true, shift: true, ..Default::default() };
+        for (modifiers, expected) in [
+            (meta(), GlobalAction::TabPrev),
+            (meta_alt, GlobalAction::BroadcastTabGroup),
+            (meta_shift(), GlobalAction::BroadcastWorkspace),
+            (meta_alt_shift, GlobalAction::BroadcastPane),
+        ] {
+            let action = router.process(InputEvent::KeyPress { key: Key::Char('i'), modifiers }, &panes);
+            assert_eq!(action, Action::GlobalAction(expected.clone()));
+            assert_eq!(KeybindingMap::new().lookup(&Key::Char('i'), &modifiers), Some(expected.clone()));
+            assert_eq!(GlobalAction::from_action_key(expected.action_key()), Some(expected));
+        }
+    }
+
+    #[test]
+    fn profile_hotkeys_bind_through_overrides() {
+        // Profiles have no default hotkey; settings bind them by action key
+        assert_eq!(KeybindingMap::new().hotkey_for(&GlobalAction::OpenProfile(1)), None);
+        let action = GlobalAction::from_action_key("OpenProfile3").unwrap();
+        assert_eq!(action, GlobalAction::OpenProfile(3));
+        assert_eq!(action.label(), "Open Profile 3");
+
+        let hotkey = Hotkey::new(Key::Char('3'), false, false, true, true);
+        let map = KeybindingMap::with_overrides(vec![(hotkey, action)]);
+        let modifiers = Modifiers { meta: true, alt: true, ..Default::default() };
+        assert_eq!(map.lookup(&Key::Char('3'), &modifiers), Some(GlobalAction::OpenProfile(3)));
+
+        // Every listed action survives the settings round trip
+        for action in GlobalAction::all_actions() {
+            assert_eq!(GlobalAction::from_action_key(action.action_key()), Some(action));
+        }
+    }
+
     #[test]
     fn hotkey_is_not_routed_to_pane() {
         let mut router = Router::new();
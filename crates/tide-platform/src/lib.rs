@@ -139,6 +139,9 @@ pub trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
     /// Update the IME candidate window position for a specific pane's proxy.
     fn set_ime_proxy_cursor_area(&self, pane_id: u64, x: f64, y: f64, w: f64, h: f64);
 
+    /// Play the system alert sound. No-op where unsupported.
+    fn beep(&self) {}
+
     /// Return a raw pointer to the content NSView (macOS) for subview management.
     /// Returns `None` on platforms that don't support native subviews.
     fn content_view_ptr(&self) -> Option<*mut std::ffi::c_void> {
@@ -210,6 +213,7 @@ pub enum WindowCommand {
     CreateImeProxy(u64),
     RemoveImeProxy(u64),
     FocusImeProxy(u64),
+    Beep,
     SetImeCursorArea {
         pane_id: u64,
         x: f64,
@@ -229,6 +233,7 @@ pub fn execute_window_command(window: &dyn PlatformWindow, cmd: WindowCommand) {
         WindowCommand::CreateImeProxy(id) => window.create_ime_proxy(id),
         WindowCommand::RemoveImeProxy(id) => window.remove_ime_proxy(id),
         WindowCommand::FocusImeProxy(id) => window.focus_ime_proxy(id),
+        WindowCommand::Beep => window.beep(),
         WindowCommand::SetImeCursorArea { pane_id, x, y, w, h } => {
             window.set_ime_proxy_cursor_area(pane_id, x, y, w, h);
         }
@@ -296,4 +301,8 @@ impl WindowProxy {
     pub fn set_ime_proxy_cursor_area(&self, pane_id: u64, x: f64, y: f64, w: f64, h: f64) {
         self.send(WindowCommand::SetImeCursorArea { pane_id, x, y, w, h });
     }
+
+    pub fn beep(&self) {
+        self.send_and_wake(WindowCommand::Beep);
+    }
 }
@@ -326,6 +326,10 @@ impl PlatformWindow for MacosWindow {
         }
     }
 
+    fn beep(&self) {
+        unsafe { objc2_app_kit::NSBeep() };
+    }
+
     fn content_view_ptr(&self) -> Option<*mut std::ffi::c_void> {
         Some(Retained::as_ptr(&self.view) as *mut std::ffi::c_void)
     }
@@ -1,7 +1,8 @@
 use std::collections::HashSet;
 
-use tide_core::{Color, Rect, Size, TextStyle, Vec2};
+use tide_core::{Color, Rect, Size, TextStyle, UnderlineStyle, Vec2};
 
+use crate::image::ImageQuad;
 use crate::vertex::{GridBgInstance, GridGlyphInstance};
 use crate::WgpuRenderer;
 
@@ -9,12 +10,17 @@ use crate::WgpuRenderer;
 pub struct PaneGridCache {
     pub bg_instances: Vec<GridBgInstance>,
     pub glyph_instances: Vec<GridGlyphInstance>,
+    pub decoration_instances: Vec<GridBgInstance>,
+    /// Inline images; drawn every frame, outside the assembled instance arrays.
+    pub images: Vec<ImageQuad>,
 }
 
 impl PaneGridCache {
     fn clear(&mut self) {
         self.bg_instances.clear();
         self.glyph_instances.clear();
+        self.decoration_instances.clear();
+        self.images.clear();
     }
 }
 
@@ -25,6 +31,8 @@ pub(crate) struct PaneGridRange {
     pub bg_inst_count: usize,
     pub glyph_inst_start: usize,
     pub glyph_inst_count: usize,
+    pub decoration_inst_start: usize,
+    pub decoration_inst_count: usize,
 }
 
 impl WgpuRenderer {
@@ -82,6 +90,7 @@ impl WgpuRenderer {
                     (Some(cache), Some(range)) => {
                         cache.bg_instances.len() == range.bg_inst_count
                             && cache.glyph_instances.len() == range.glyph_inst_count
+                            && cache.decoration_instances.len() == range.decoration_inst_count
                     }
                     _ => false,
                 }
@@ -96,21 +105,26 @@ impl WgpuRenderer {
         // Full assembly
         self.grid_bg_instances.clear();
         self.grid_glyph_instances.clear();
+        self.grid_decoration_instances.clear();
         self.pane_grid_ranges.clear();
 
         for &id in pane_order {
             if let Some(cache) = self.pane_grid_caches.get(&id) {
                 let bg_inst_start = self.grid_bg_instances.len();
                 let glyph_inst_start = self.grid_glyph_instances.len();
+                let decoration_inst_start = self.grid_decoration_instances.len();
 
                 self.grid_bg_instances.extend_from_slice(&cache.bg_instances);
                 self.grid_glyph_instances.extend_from_slice(&cache.glyph_instances);
+                self.grid_decoration_instances.extend_from_slice(&cache.decoration_instances);
 
                 self.pane_grid_ranges.insert(id, PaneGridRange {
                     bg_inst_start,
                     bg_inst_count: cache.bg_instances.len(),
                     glyph_inst_start,
                     glyph_inst_count: cache.glyph_instances.len(),
+                    decoration_inst_start,
+                    decoration_inst_count: cache.decoration_instances.len(),
                 });
             }
         }
@@ -144,6 +158,8 @@ impl WgpuRenderer {
                 .copy_from_slice(&cache.bg_instances);
             self.grid_glyph_instances[range.glyph_inst_start..range.glyph_inst_start + range.glyph_inst_count]
                 .copy_from_slice(&cache.glyph_instances);
+            self.grid_decoration_instances[range.decoration_inst_start..range.decoration_inst_start + range.decoration_inst_count]
+                .copy_from_slice(&cache.decoration_instances);
 
             self.grid_partial_uploads.push(range);
         }
@@ -182,6 +198,7 @@ impl WgpuRenderer {
     pub fn invalidate_grid(&mut self) {
         self.grid_bg_instances.clear();
         self.grid_glyph_instances.clear();
+        self.grid_decoration_instances.clear();
         self.grid_needs_upload = true;
     }
 
@@ -216,10 +233,11 @@ impl WgpuRenderer {
         };
 
         // Determine target arrays
-        let (bg, gl) = if self.active_pane_id.is_some() {
-            (&mut self.active_pane_cache.bg_instances, &mut self.active_pane_cache.glyph_instances)
+        let (bg, gl, deco) = if self.active_pane_id.is_some() {
+            let cache = &mut self.active_pane_cache;
+            (&mut cache.bg_instances, &mut cache.glyph_instances, &mut cache.decoration_instances)
         } else {
-            (&mut self.grid_bg_instances, &mut self.grid_glyph_instances)
+            (&mut self.grid_bg_instances, &mut self.grid_glyph_instances, &mut self.grid_decoration_instances)
         };
 
         // Background instance
@@ -246,5 +264,62 @@ impl WgpuRenderer {
                 color: [style.foreground.r, style.foreground.g, style.foreground.b, style.foreground.a],
             });
         }
+
+        if style.has_decoration() {
+            let cell = Rect::new(px, py, cw, ch);
+            push_decorations(deco, &style, cell, baseline_y, em_scale);
+        }
+    }
+}
+
+/// Underline and strikethrough rects for one cell (physical pixels).
+/// Dots, dashes and waves are phased on the cell edges so they run on
+/// seamlessly across a span of cells.
+fn push_decorations(out: &mut Vec<GridBgInstance>, style: &TextStyle, cell: Rect, baseline_y: f32, em_scale: f32) {
+    let thickness = (em_scale * 0.06).round().max(1.0);
+    let mut push = |x: f32, y: f32, w: f32, h: f32, color: Color| {
+        out.push(GridBgInstance { position: [x, y], size: [w, h], color: [color.r, color.g, color.b, color.a] });
+    };
+
+    let Rect { x, y, width: cw, height: ch } = cell;
+    let bottom = y + ch;
+    let color = style.underline_color.unwrap_or(style.foreground);
+    // Just below the baseline, kept inside the cell
+    let under_y = (y + baseline_y + thickness).min(bottom - thickness);
+    match style.underline {
+        UnderlineStyle::None => {}
+        UnderlineStyle::Single => push(x, under_y, cw, thickness, color),
+        UnderlineStyle::Double => {
+            let top = under_y.min(bottom - 3.0 * thickness);
+            push(x, top, cw, thickness, color);
+            push(x, top + 2.0 * thickness, cw, thickness, color);
+        }
+        UnderlineStyle::Dotted => {
+            let dots = (cw / (2.0 * thickness)).round().max(1.0);
+            let step = cw / dots;
+            for i in 0..dots as usize {
+                push(x + i as f32 * step, under_y, thickness, thickness, color);
+            }
+        }
+        UnderlineStyle::Dashed => push(x + cw * 0.25, under_y, cw * 0.5, thickness, color),
+        UnderlineStyle::Curly => {
+            // One sine period per cell, drawn as 1px columns
+            let amplitude = (em_scale * 0.07).max(thickness);
+            let center = under_y.min(bottom - amplitude - thickness);
+            let wave = |dx: f32| center - amplitude * (dx / cw * std::f32::consts::TAU).sin();
+            let columns = cw.ceil() as usize;
+            for i in 0..columns {
+                let (x0, x1) = (i as f32, (i as f32 + 1.0).min(cw));
+                let (a, b) = (wave(x0), wave(x1));
+                let top = a.min(b) - thickness * 0.5;
+                push(x + x0, top, x1 - x0, (a - b).abs() + thickness, color);
+            }
+        }
+    }
+
+    if style.strikethrough {
+        // Through the middle of lowercase letters
+        let strike_y = y + baseline_y - em_scale * 0.27 - thickness * 0.5;
+        push(x, strike_y.max(y), cw, thickness, style.foreground);
     }
 }
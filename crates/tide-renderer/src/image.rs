@@ -0,0 +1,183 @@
+// Inline terminal images (kitty graphics, sixel).
+// One texture per image, uploaded the first time it is drawn; quads are
+// recorded per pane next to its grid instances and drawn around the grid glyphs.
+
+use std::collections::HashSet;
+
+use tide_core::Rect;
+
+use crate::vertex::GlyphVertex;
+use crate::WgpuRenderer;
+
+/// An image to draw into the active pane's grid layer.
+pub struct GridImage<'a> {
+    /// Identifies the pixels: the texture is uploaded once per id.
+    pub id: u64,
+    pub width: u32,
+    pub height: u32,
+    /// RGBA, `width * height * 4` bytes.
+    pub rgba: &'a [u8],
+    /// Where the `uv` part of the image goes (logical pixels).
+    pub rect: Rect,
+    /// Part of the image to show: `[u0, v0, u1, v1]`.
+    pub uv: [f32; 4],
+    /// Nothing outside this rect is drawn (logical pixels).
+    pub clip: Rect,
+    /// Draw under the grid glyphs instead of over them.
+    pub below_text: bool,
+}
+
+#[derive(Clone, Copy)]
+pub struct ImageQuad {
+    pub image_id: u64,
+    pub vertices: [GlyphVertex; 4],
+    pub below_text: bool,
+}
+
+/// One indexed quad in the frame's image buffers.
+pub(crate) struct ImageDraw {
+    image_id: u64,
+    first_index: u32,
+    below_text: bool,
+}
+
+pub(crate) struct ImageTexture {
+    _texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+impl WgpuRenderer {
+    /// Record an image quad for the pane between `begin_pane_grid` and `end_pane_grid`.
+    pub fn draw_grid_image(&mut self, image: &GridImage) {
+        if self.active_pane_id.is_none() {
+            return;
+        }
+        let Some(vertices) = clipped_quad(image, self.scale_factor) else { return };
+        if !self.image_textures.contains_key(&image.id) {
+            let Some(texture) = self.upload_image(image) else { return };
+            self.image_textures.insert(image.id, texture);
+        }
+        self.active_pane_cache.images.push(ImageQuad { image_id: image.id, vertices, below_text: image.below_text });
+    }
+
+    fn upload_image(&self, image: &GridImage) -> Option<ImageTexture> {
+        let max_side = self.device.limits().max_texture_dimension_2d;
+        if image.width == 0 || image.height == 0 || image.width > max_side || image.height > max_side {
+            return None;
+        }
+        if image.rgba.len() < image.width as usize * image.height as usize * 4 {
+            return None;
+        }
+        let size = wgpu::Extent3d { width: image.width, height: image.height, depth_or_array_layers: 1 };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("terminal_image"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            image.rgba,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(image.width * 4),
+                rows_per_image: Some(image.height),
+            },
+            size,
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("terminal_image_bg"),
+            layout: &self.texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.image_sampler) },
+            ],
+        });
+        Some(ImageTexture { _texture: texture, bind_group })
+    }
+
+    /// Gather the image quads of the panes in draw order into the frame's
+    /// buffers, and free textures no pane shows anymore.
+    pub(crate) fn prepare_images(&mut self) {
+        self.image_vertices.clear();
+        self.image_indices.clear();
+        self.image_draws.clear();
+
+        let mut used = HashSet::new();
+        for below_text in [true, false] {
+            for id in &self.last_pane_order {
+                let Some(cache) = self.pane_grid_caches.get(id) else { continue };
+                for quad in cache.images.iter().filter(|q| q.below_text == below_text) {
+                    let base = self.image_vertices.len() as u32;
+                    let first_index = self.image_indices.len() as u32;
+                    self.image_draws.push(ImageDraw { image_id: quad.image_id, first_index, below_text });
+                    self.image_vertices.extend_from_slice(&quad.vertices);
+                    self.image_indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+                    used.insert(quad.image_id);
+                }
+            }
+        }
+        self.image_textures.retain(|id, _| used.contains(id));
+
+        if self.image_vertices.is_empty() {
+            return;
+        }
+        let vb_usage = wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST;
+        let ib_usage = wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST;
+        let vb_bytes = bytemuck::cast_slice(&self.image_vertices);
+        Self::ensure_buffer_capacity(&self.device, &mut self.image_vb, &mut self.image_vb_capacity, vb_bytes.len(), vb_usage, "image_vb");
+        self.queue.write_buffer(&self.image_vb, 0, vb_bytes);
+        let ib_bytes = bytemuck::cast_slice(&self.image_indices);
+        Self::ensure_buffer_capacity(&self.device, &mut self.image_ib, &mut self.image_ib_capacity, ib_bytes.len(), ib_usage, "image_ib");
+        self.queue.write_buffer(&self.image_ib, 0, ib_bytes);
+    }
+
+    /// Draw the images prepared for this frame that go below or above the text.
+    pub(crate) fn draw_images<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>, below_text: bool) {
+        let mut draws = self.image_draws.iter().filter(|d| d.below_text == below_text).peekable();
+        if draws.peek().is_none() {
+            return;
+        }
+        pass.set_pipeline(&self.image_pipeline);
+        pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+        pass.set_vertex_buffer(0, self.image_vb.slice(..));
+        pass.set_index_buffer(self.image_ib.slice(..), wgpu::IndexFormat::Uint32);
+        for draw in draws {
+            let Some(texture) = self.image_textures.get(&draw.image_id) else { continue };
+            pass.set_bind_group(1, &texture.bind_group, &[]);
+            pass.draw_indexed(draw.first_index..draw.first_index + 6, 0, 0..1);
+        }
+    }
+}
+
+/// The image's quad in physical pixels, cut to its clip rect with the
+/// texture coordinates cut to match. `None` if nothing is left.
+fn clipped_quad(image: &GridImage, scale: f32) -> Option<[GlyphVertex; 4]> {
+    let Rect { x, y, width, height } = image.rect;
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+    let clip = image.clip;
+    let x0 = x.max(clip.x);
+    let y0 = y.max(clip.y);
+    let x1 = (x + width).min(clip.x + clip.width);
+    let y1 = (y + height).min(clip.y + clip.height);
+    if x1 <= x0 || y1 <= y0 {
+        return None;
+    }
+    let [u0, v0, u1, v1] = image.uv;
+    let u = |px: f32| u0 + (px - x) / width * (u1 - u0);
+    let v = |py: f32| v0 + (py - y) / height * (v1 - v0);
+    let vertex = |px: f32, py: f32| GlyphVertex { position: [px * scale, py * scale], uv: [u(px), v(py)], color: [1.0; 4] };
+    Some([vertex(x0, y0), vertex(x1, y0), vertex(x1, y1), vertex(x0, y1)])
+}
@@ -6,7 +6,7 @@ use tide_core::{Color, Size};
 use crate::atlas::GlyphAtlas;
 use crate::grid::PaneGridCache;
 use crate::msdf::MsdfFontStore;
-use crate::shaders::{CHROME_RECT_SHADER, GRID_BG_INSTANCED_SHADER, RECT_SHADER};
+use crate::shaders::{CHROME_RECT_SHADER, GRID_BG_INSTANCED_SHADER, IMAGE_SHADER, RECT_SHADER};
 use crate::vertex::{ChromeRectVertex, GlyphVertex, GridBgInstance, GridGlyphInstance, RectVertex};
 use crate::WgpuRenderer;
 
@@ -243,6 +243,62 @@ impl WgpuRenderer {
             cache: None,
         });
 
+        // --- Inline image pipeline (one texture per image, same layout as the atlas) ---
+        let image_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("image_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let image_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("image_shader"),
+            source: wgpu::ShaderSource::Wgsl(IMAGE_SHADER.into()),
+        });
+
+        let image_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("image_pipeline_layout"),
+                bind_group_layouts: &[&uniform_bind_group_layout, &atlas_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let image_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("image_pipeline"),
+            layout: Some(&image_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &image_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[GlyphVertex::LAYOUT],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &image_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
         // --- Instanced grid bg pipeline ---
         let grid_bg_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("grid_bg_instanced_shader"),
@@ -404,12 +460,23 @@ impl WgpuRenderer {
             rect_pipeline,
             chrome_rounded_pipeline,
             glyph_pipeline,
+            image_pipeline,
             grid_bg_pipeline,
             grid_glyph_pipeline,
             uniform_buffer,
             uniform_bind_group,
             atlas,
             atlas_bind_group,
+            texture_bind_group_layout: atlas_bind_group_layout,
+            image_sampler,
+            image_textures: HashMap::new(),
+            image_vertices: Vec::new(),
+            image_indices: Vec::new(),
+            image_draws: Vec::new(),
+            image_vb: create_buf("image_vb", vb_usage),
+            image_ib: create_buf("image_ib", ib_usage),
+            image_vb_capacity: initial_buf_size as usize,
+            image_ib_capacity: initial_buf_size as usize,
             font_system,
             msdf_font_store,
             // Per-pane grid caching
@@ -419,11 +486,14 @@ impl WgpuRenderer {
             // Grid layer (instanced)
             grid_bg_instances: Vec::with_capacity(4096),
             grid_glyph_instances: Vec::with_capacity(8192),
+            grid_decoration_instances: Vec::new(),
             grid_needs_upload: true,
             grid_bg_inst_buf: create_buf("grid_bg_inst_buf", vb_usage),
             grid_glyph_inst_buf: create_buf("grid_glyph_inst_buf", vb_usage),
+            grid_decoration_inst_buf: create_buf("grid_decoration_inst_buf", vb_usage),
             grid_bg_inst_buf_capacity: initial_buf_size as usize,
             grid_glyph_inst_buf_capacity: initial_buf_size as usize,
+            grid_decoration_inst_buf_capacity: initial_buf_size as usize,
             // Chrome layer (cached for borders and file tree)
             chrome_rect_vertices: Vec::with_capacity(4096),
             chrome_rect_indices: Vec::with_capacity(6144),
@@ -5,6 +5,7 @@ mod atlas;
 mod chrome;
 mod font;
 mod grid;
+mod image;
 mod init;
 mod msdf;
 mod overlay;
@@ -19,6 +20,8 @@ use tide_core::{Color, Rect, Renderer, Size, TextStyle, Vec2};
 
 use atlas::GlyphAtlas;
 use grid::PaneGridCache;
+pub use image::GridImage;
+use image::{ImageDraw, ImageTexture};
 use msdf::MsdfFontStore;
 use vertex::{ChromeRectVertex, GlyphVertex, GridBgInstance, GridGlyphInstance, RectVertex};
 
@@ -31,6 +34,7 @@ pub struct WgpuRenderer {
     pub(crate) rect_pipeline: wgpu::RenderPipeline,
     pub(crate) chrome_rounded_pipeline: wgpu::RenderPipeline,
     pub(crate) glyph_pipeline: wgpu::RenderPipeline,
+    pub(crate) image_pipeline: wgpu::RenderPipeline,
 
     // Uniform buffer (screen size)
     pub(crate) uniform_buffer: wgpu::Buffer,
@@ -40,6 +44,18 @@ pub struct WgpuRenderer {
     pub(crate) atlas: GlyphAtlas,
     pub(crate) atlas_bind_group: wgpu::BindGroup,
 
+    // Inline terminal images: texture + sampler bind group per image
+    pub(crate) texture_bind_group_layout: wgpu::BindGroupLayout,
+    pub(crate) image_sampler: wgpu::Sampler,
+    pub(crate) image_textures: HashMap<u64, ImageTexture>,
+    pub(crate) image_vertices: Vec<GlyphVertex>,
+    pub(crate) image_indices: Vec<u32>,
+    pub(crate) image_draws: Vec<ImageDraw>,
+    pub(crate) image_vb: wgpu::Buffer,
+    pub(crate) image_ib: wgpu::Buffer,
+    pub(crate) image_vb_capacity: usize,
+    pub(crate) image_ib_capacity: usize,
+
     // Text subsystem
     pub(crate) font_system: FontSystem,
     pub(crate) msdf_font_store: MsdfFontStore,
@@ -56,13 +72,17 @@ pub struct WgpuRenderer {
     // Cached grid layer — only rebuilt when grid content changes (instanced)
     pub(crate) grid_bg_instances: Vec<GridBgInstance>,
     pub(crate) grid_glyph_instances: Vec<GridGlyphInstance>,
+    /// Underlines and strikethroughs: bg-pipeline rects drawn over the glyphs.
+    pub(crate) grid_decoration_instances: Vec<GridBgInstance>,
     pub(crate) grid_needs_upload: bool,
 
     // Grid GPU instance buffers
     pub(crate) grid_bg_inst_buf: wgpu::Buffer,
     pub(crate) grid_glyph_inst_buf: wgpu::Buffer,
+    pub(crate) grid_decoration_inst_buf: wgpu::Buffer,
     pub(crate) grid_bg_inst_buf_capacity: usize,
     pub(crate) grid_glyph_inst_buf_capacity: usize,
+    pub(crate) grid_decoration_inst_buf_capacity: usize,
 
     // Chrome layer — cached for panel backgrounds and file tree
     pub(crate) chrome_rect_vertices: Vec<ChromeRectVertex>,
@@ -79,7 +79,7 @@ impl WgpuRenderer {
     }
 
     /// Ensure a GPU buffer is large enough; grow if needed.
-    fn ensure_buffer_capacity(
+    pub(crate) fn ensure_buffer_capacity(
         device: &wgpu::Device,
         buf: &mut wgpu::Buffer,
         capacity: &mut usize,
@@ -134,6 +134,11 @@ impl WgpuRenderer {
                 Self::ensure_buffer_capacity(&self.device, &mut self.grid_glyph_inst_buf, &mut self.grid_glyph_inst_buf_capacity, data.len(), vb_usage, "grid_glyph_inst_buf");
                 self.queue.write_buffer(&self.grid_glyph_inst_buf, 0, data);
             }
+            if !self.grid_decoration_instances.is_empty() {
+                let data = bytemuck::cast_slice(&self.grid_decoration_instances);
+                Self::ensure_buffer_capacity(&self.device, &mut self.grid_decoration_inst_buf, &mut self.grid_decoration_inst_buf_capacity, data.len(), vb_usage, "grid_decoration_inst_buf");
+                self.queue.write_buffer(&self.grid_decoration_inst_buf, 0, data);
+            }
             self.grid_needs_upload = false;
         } else if !self.grid_partial_uploads.is_empty() {
             // Partial upload: only dirty panes' instance ranges
@@ -152,6 +157,12 @@ impl WgpuRenderer {
                     let data = bytemuck::cast_slice(&self.grid_glyph_instances[start..end]);
                     self.queue.write_buffer(&self.grid_glyph_inst_buf, (start * glyph_stride) as u64, data);
                 }
+                if range.decoration_inst_count > 0 {
+                    let start = range.decoration_inst_start;
+                    let end = start + range.decoration_inst_count;
+                    let data = bytemuck::cast_slice(&self.grid_decoration_instances[start..end]);
+                    self.queue.write_buffer(&self.grid_decoration_inst_buf, (start * bg_stride) as u64, data);
+                }
             }
             self.grid_partial_uploads.clear();
         }
@@ -231,8 +242,12 @@ impl WgpuRenderer {
             self.queue.write_buffer(&self.top_glyph_ib, 0, ib_bytes);
         }
 
+        // ── Image quads of the panes (every frame; textures are cached) ──
+        self.prepare_images();
+
         let grid_bg_instance_count = self.grid_bg_instances.len() as u32;
         let grid_glyph_instance_count = self.grid_glyph_instances.len() as u32;
+        let grid_decoration_instance_count = self.grid_decoration_instances.len() as u32;
         let chrome_rect_count = self.chrome_rect_indices.len() as u32;
         let chrome_glyph_count = self.chrome_glyph_indices.len() as u32;
         let overlay_rect_count = self.rect_indices.len() as u32;
@@ -262,8 +277,9 @@ impl WgpuRenderer {
                 occlusion_query_set: None,
             });
 
-            // Draw order: chrome rects → grid bg (instanced) → overlay rects →
-            //             chrome glyphs → grid glyphs (instanced) → overlay glyphs
+            // Draw order: chrome rects → grid bg (instanced) → images below text →
+            //             overlay rects → chrome glyphs → grid glyphs (instanced) →
+            //             grid decorations (instanced) → images above text → overlay glyphs
             // Chrome rects (pane backgrounds, panel backgrounds) are drawn first so that
             // grid cell backgrounds (e.g. INVERSE/standout for paste highlighting) show on top.
 
@@ -284,6 +300,9 @@ impl WgpuRenderer {
                 pass.draw(0..6, 0..grid_bg_instance_count);
             }
 
+            // Inline images with a negative z-index sit between cell backgrounds and text
+            self.draw_images(&mut pass, true);
+
             // Overlay rects — indexed (traditional)
             if overlay_rect_count > 0 {
                 pass.set_pipeline(&self.rect_pipeline);
@@ -312,6 +331,17 @@ impl WgpuRenderer {
                 pass.draw(0..6, 0..grid_glyph_instance_count);
             }
 
+            // Underlines and strikethroughs — bg pipeline, over the text
+            if grid_decoration_instance_count > 0 {
+                pass.set_pipeline(&self.grid_bg_pipeline);
+                pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                pass.set_vertex_buffer(0, self.grid_decoration_inst_buf.slice(..));
+                pass.draw(0..6, 0..grid_decoration_instance_count);
+            }
+
+            // Other inline images cover the text they were printed over
+            self.draw_images(&mut pass, false);
+
             // Overlay glyphs — indexed (traditional)
             if overlay_glyph_count > 0 {
                 pass.set_pipeline(&self.glyph_pipeline);
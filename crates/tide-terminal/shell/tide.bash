@@ -0,0 +1,40 @@
+# Tide shell integration for bash: OSC 133 prompt/command/output marks.
+#
+# Tide starts bash with --rcfile pointing here, which replaces the usual
+# startup files, so run them the way a login shell would.
+
+if [ -r /etc/profile ]; then
+    . /etc/profile
+fi
+for __tideterm_rc in ~/.bash_profile ~/.bash_login ~/.profile; do
+    if [ -r "$__tideterm_rc" ]; then
+        . "$__tideterm_rc"
+        break
+    fi
+done
+unset __tideterm_rc
+
+if [ -z "${__tideterm_loaded}" ]; then
+    __tideterm_loaded=1
+    __tideterm_prompted=
+
+    __tideterm_prompt_command() {
+        local ret=$?
+        if [ -n "${__tideterm_prompted}" ]; then
+            builtin printf '\e]133;D;%s\a' "$ret"
+        fi
+        __tideterm_prompted=1
+        builtin printf '\e]133;A\a'
+        # Mark the end of the prompt. Prompt tools may rebuild PS1, so re-check.
+        case "$PS1" in
+            *$'\e]133;B\a'*) ;;
+            *) PS1="${PS1}"'\['$'\e]133;B\a''\]' ;;
+        esac
+        return $ret
+    }
+
+    # Must run first to see the command's exit status.
+    PROMPT_COMMAND="__tideterm_prompt_command${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+    # PS0 (bash 4.4+) is printed after a command line is read, before it runs.
+    PS0="${PS0}"$'\e]133;C\a'
+fi
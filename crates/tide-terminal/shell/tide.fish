@@ -0,0 +1,21 @@
+# Tide shell integration for fish: OSC 133 prompt/command/output marks.
+
+if status is-interactive; and not set -q __tideterm_loaded
+    set -g __tideterm_loaded 1
+
+    function __tideterm_prompt --on-event fish_prompt
+        if set -q __tideterm_status
+            printf '\e]133;D;%s\a' $__tideterm_status
+            set -e __tideterm_status
+        end
+        printf '\e]133;A\a'
+    end
+
+    function __tideterm_preexec --on-event fish_preexec
+        printf '\e]133;C\a'
+    end
+
+    function __tideterm_postexec --on-event fish_postexec
+        set -g __tideterm_status $status
+    end
+end
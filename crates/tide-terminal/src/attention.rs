@@ -0,0 +1,54 @@
+// Bell and desktop notifications
+//
+// BEL arrives as `Event::Bell`; OSC 9 (iTerm2 `9;message`) and OSC 777
+// (urxvt `777;notify;title;body`) as unhandled OSCs. Each only bumps a
+// counter: the app polls the counters to raise attention on the terminal's
+// tab and to ring the bell.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::Terminal;
+
+/// Bell and notification counters, shared between listener and `Terminal`.
+#[derive(Debug, Default)]
+pub(crate) struct Attention {
+    pub(crate) bells: AtomicU64,
+    pub(crate) notifications: AtomicU64,
+}
+
+impl Attention {
+    pub(crate) fn bell(&self) {
+        self.bells.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Apply an OSC that vte doesn't handle. Returns false if it isn't a
+    /// desktop notification.
+    pub(crate) fn apply_osc(&self, payload: &str) -> bool {
+        if !is_notification(payload) {
+            return false;
+        }
+        self.notifications.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+}
+
+fn is_notification(payload: &str) -> bool {
+    if let Some(message) = payload.strip_prefix("9;") {
+        // ConEmu reuses OSC 9 for numbered sub-commands (9;4;... is progress)
+        let command = message.split(';').next().unwrap_or_default();
+        return !message.is_empty() && !command.bytes().all(|b| b.is_ascii_digit());
+    }
+    payload.starts_with("777;notify;")
+}
+
+impl Terminal {
+    /// Number of times the program rang the bell.
+    pub fn bell_count(&self) -> u64 {
+        self.attention.bells.load(Ordering::Relaxed)
+    }
+
+    /// Number of desktop notifications (OSC 9 / OSC 777) the program sent.
+    pub fn notification_count(&self) -> u64 {
+        self.attention.notifications.load(Ordering::Relaxed)
+    }
+}
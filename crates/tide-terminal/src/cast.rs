@@ -0,0 +1,302 @@
+// Asciicast v2 recording
+//
+// The vendored PTY event loop hands every chunk of output to an `OutputTap`
+// before parsing it. The tap installed by `Terminal::spawn` timestamps the
+// bytes and queues them for the active `Recording`, whose writer thread owns
+// the `CastRecorder` that appends `[time, "o", data]` lines to a `.cast` file;
+// resizes add `"r"` lines. The tap never touches the file: it runs with the
+// term lock held. The format is asciinema's:
+// https://docs.asciinema.org/manual/asciicast/v2/
+//
+// Lock order: the term must never be locked while holding `recording`.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use alacritty_terminal::event_loop::OutputTap;
+
+use super::Terminal;
+
+// ──────────────────────────────────────────────
+// Recording
+// ──────────────────────────────────────────────
+
+/// Writes PTY output to an asciicast v2 file.
+pub(crate) struct CastRecorder {
+    path: PathBuf,
+    out: BufWriter<File>,
+    start: Instant,
+    /// Incomplete UTF-8 sequence at the end of the last chunk.
+    pending: Vec<u8>,
+}
+
+impl CastRecorder {
+    /// Create `path` (and its parent directory) and write the header.
+    pub(crate) fn create(path: &Path, cols: u16, rows: u16) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = BufWriter::new(File::create(path)?);
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let header = serde_json::json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": timestamp,
+            "env": {
+                "TERM": "xterm-256color",
+                "SHELL": std::env::var("SHELL").unwrap_or_default(),
+            },
+        });
+        writeln!(out, "{header}")?;
+        Ok(Self { path: path.to_path_buf(), out, start: Instant::now(), pending: Vec::new() })
+    }
+
+    /// Record a chunk of PTY output read at `at`. Event data must be a JSON
+    /// string, so a UTF-8 sequence split across chunks is held back until it
+    /// completes.
+    pub(crate) fn output(&mut self, at: Instant, bytes: &[u8]) -> io::Result<()> {
+        self.pending.extend_from_slice(bytes);
+        let split = self.pending.len() - incomplete_utf8_tail(&self.pending);
+        if split == 0 {
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(&self.pending[..split]).into_owned();
+        self.pending.drain(..split);
+        self.event(at, "o", &text)
+    }
+
+    pub(crate) fn resize(&mut self, at: Instant, cols: u16, rows: u16) -> io::Result<()> {
+        self.event(at, "r", &format!("{cols}x{rows}"))
+    }
+
+    /// Flush any held-back bytes and the file buffer.
+    pub(crate) fn finish(mut self) -> io::Result<PathBuf> {
+        if !self.pending.is_empty() {
+            let text = String::from_utf8_lossy(&self.pending).into_owned();
+            self.event(Instant::now(), "o", &text)?;
+        }
+        self.out.flush()?;
+        Ok(self.path)
+    }
+
+    /// Write queued events until the sender hangs up, then finish.
+    fn run(mut self, queue: mpsc::Receiver<CastMsg>) -> io::Result<PathBuf> {
+        for msg in queue {
+            match msg {
+                CastMsg::Output(at, bytes) => self.output(at, &bytes)?,
+                CastMsg::Resize(at, cols, rows) => self.resize(at, cols, rows)?,
+            }
+        }
+        self.finish()
+    }
+
+    fn event(&mut self, at: Instant, code: &str, data: &str) -> io::Result<()> {
+        // Microsecond precision, like asciinema
+        let time = (at.saturating_duration_since(self.start).as_secs_f64() * 1e6).round() / 1e6;
+        let line = serde_json::to_string(&(time, code, data))?;
+        writeln!(self.out, "{line}")
+    }
+}
+
+/// Length of an unfinished UTF-8 sequence at the end of `bytes` (0..=3).
+fn incomplete_utf8_tail(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let b = bytes[bytes.len() - back];
+        if b & 0xC0 != 0x80 {
+            let len = match b {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => 1,
+            };
+            return if len > back { back } else { 0 };
+        }
+    }
+    0
+}
+
+/// An event queued for the writer thread, stamped when it happened.
+enum CastMsg {
+    Output(Instant, Vec<u8>),
+    Resize(Instant, u16, u16),
+}
+
+/// A recording in progress: events go to a writer thread that owns the file.
+pub(crate) struct Recording {
+    queue: mpsc::Sender<CastMsg>,
+    writer: JoinHandle<io::Result<PathBuf>>,
+}
+
+impl Recording {
+    /// Create the file and header here, so errors reach the caller, then hand
+    /// the recorder to its writer thread.
+    fn start(path: &Path, cols: u16, rows: u16) -> io::Result<Self> {
+        let recorder = CastRecorder::create(path, cols, rows)?;
+        let (queue, events) = mpsc::channel();
+        let writer = std::thread::Builder::new()
+            .name("cast-writer".to_string())
+            .spawn(move || {
+                let path = recorder.path.clone();
+                let result = recorder.run(events);
+                if let Err(e) = &result {
+                    log::warn!("Recording to {} failed: {}", path.display(), e);
+                }
+                result
+            })?;
+        Ok(Self { queue, writer })
+    }
+
+    /// Queue an event. False once the writer has stopped after an error.
+    fn send(&self, msg: CastMsg) -> bool {
+        self.queue.send(msg).is_ok()
+    }
+
+    /// Let the writer drain its queue and flush the file.
+    fn finish(self) -> io::Result<PathBuf> {
+        drop(self.queue);
+        self.writer.join().unwrap_or_else(|_| Err(io::Error::other("cast writer panicked")))
+    }
+}
+
+/// PTY output tap feeding the active recording. A writer that stopped after
+/// a failed write ends the recording instead of queueing every chunk.
+pub(crate) fn recording_tap(recording: Arc<Mutex<Option<Recording>>>) -> OutputTap {
+    Box::new(move |bytes| {
+        let Ok(mut guard) = recording.lock() else { return };
+        if let Some(active) = guard.as_ref() {
+            if !active.send(CastMsg::Output(Instant::now(), bytes.to_vec())) {
+                *guard = None;
+            }
+        }
+    })
+}
+
+impl Terminal {
+    /// Start recording PTY output to an asciicast v2 file at `path`,
+    /// replacing any recording in progress.
+    pub fn start_recording(&mut self, path: &Path) -> io::Result<()> {
+        let recording = Recording::start(path, self.cols, self.rows)?;
+        let previous = self.recording.lock().ok().and_then(|mut guard| guard.replace(recording));
+        if let Some(previous) = previous {
+            let _ = previous.finish();
+        }
+        Ok(())
+    }
+
+    /// Stop recording. Returns the finished file.
+    pub fn stop_recording(&mut self) -> Option<PathBuf> {
+        let recording = self.recording.lock().ok()?.take()?;
+        match recording.finish() {
+            Ok(path) => Some(path),
+            Err(e) => {
+                log::warn!("Failed to finish recording: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.lock().is_ok_and(|guard| guard.is_some())
+    }
+
+    /// Add a resize event to the active recording.
+    pub(crate) fn record_resize(&self, cols: u16, rows: u16) {
+        let Ok(mut guard) = self.recording.lock() else { return };
+        if let Some(active) = guard.as_ref() {
+            if !active.send(CastMsg::Resize(Instant::now(), cols, rows)) {
+                *guard = None;
+            }
+        }
+    }
+}
+
+// ──────────────────────────────────────────────
+// Parsing
+// ──────────────────────────────────────────────
+
+/// One playable event of a recording. Input (`"i"`) and marker (`"m"`)
+/// events are skipped when parsing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CastEvent {
+    Output { time: f64, data: String },
+    Resize { time: f64, cols: u16, rows: u16 },
+}
+
+impl CastEvent {
+    /// Seconds since the start of the recording.
+    pub fn time(&self) -> f64 {
+        match self {
+            CastEvent::Output { time, .. } | CastEvent::Resize { time, .. } => *time,
+        }
+    }
+}
+
+/// A parsed asciicast v2 recording.
+#[derive(Debug, Clone)]
+pub struct Cast {
+    pub width: u16,
+    pub height: u16,
+    pub events: Vec<CastEvent>,
+}
+
+impl Cast {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse a `.cast` file. Pauses longer than the header's
+    /// `idle_time_limit` are shortened to it.
+    pub fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        let (_, header) = lines.next().ok_or("empty cast file")?;
+        let header: serde_json::Value = serde_json::from_str(header)?;
+        if header["version"].as_u64() != Some(2) {
+            return Err("unsupported asciicast version (expected 2)".into());
+        }
+        let dimension = |key: &str| -> Result<u16, Box<dyn Error>> {
+            let n = header[key].as_u64().ok_or_else(|| format!("header has no {key}"))?;
+            Ok(n.clamp(1, 1000) as u16)
+        };
+        let (width, height) = (dimension("width")?, dimension("height")?);
+        let idle_limit = header["idle_time_limit"].as_f64();
+
+        let mut events = Vec::new();
+        let (mut last_raw, mut shift) = (0.0, 0.0);
+        for (i, line) in lines {
+            let bad = || format!("line {}: malformed event", i + 1);
+            let (raw_time, code, data): (f64, String, String) =
+                serde_json::from_str(line).map_err(|_| bad())?;
+            // Compress long pauses: shift every later event back by the excess
+            let gap = raw_time - last_raw;
+            if let Some(limit) = idle_limit {
+                if gap > limit {
+                    shift += gap - limit;
+                }
+            }
+            last_raw = raw_time;
+            let time = raw_time - shift;
+            match code.as_str() {
+                "o" => events.push(CastEvent::Output { time, data }),
+                "r" => {
+                    let (cols, rows) = data.split_once('x').ok_or_else(bad)?;
+                    let cols: u16 = cols.parse().map_err(|_| bad())?;
+                    let rows: u16 = rows.parse().map_err(|_| bad())?;
+                    events.push(CastEvent::Resize { time, cols, rows });
+                }
+                _ => {}
+            }
+        }
+        Ok(Self { width, height, events })
+    }
+
+    /// Time of the last event, in seconds.
+    pub fn duration(&self) -> f64 {
+        self.events.last().map_or(0.0, CastEvent::time)
+    }
+}
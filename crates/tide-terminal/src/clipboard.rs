@@ -0,0 +1,67 @@
+// OSC 52 clipboard
+//
+// vte decodes OSC 52 into `Event::ClipboardStore` (the program sets the
+// clipboard) and `Event::ClipboardLoad` (the program asks for its contents).
+// The listener only queues them: whether a program may touch the system
+// clipboard is the app's call, made on the main thread. Both the clipboard
+// (`c`) and selection (`p`/`s`) targets map to the one system clipboard.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+
+use tide_core::TerminalBackend;
+
+use super::Terminal;
+
+/// Requests kept until the app picks them up; older ones are dropped.
+const MAX_PENDING_REQUESTS: usize = 16;
+
+/// A program's OSC 52 clipboard request.
+#[derive(Debug)]
+pub enum ClipboardRequest {
+    /// Set the system clipboard to this text.
+    Store(String),
+    /// Send the clipboard contents back to the program.
+    Load(ClipboardReply),
+}
+
+/// Formats clipboard text as the OSC 52 response the program expects.
+#[derive(Clone)]
+pub struct ClipboardReply(pub(crate) Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl fmt::Debug for ClipboardReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ClipboardReply")
+    }
+}
+
+/// Pending clipboard requests, shared between listener and `Terminal`.
+#[derive(Debug, Default)]
+pub(crate) struct ClipboardQueue(VecDeque<ClipboardRequest>);
+
+impl ClipboardQueue {
+    pub(crate) fn push(&mut self, request: ClipboardRequest) {
+        if self.0.len() >= MAX_PENDING_REQUESTS {
+            self.0.pop_front();
+        }
+        self.0.push_back(request);
+    }
+
+    pub(crate) fn take(&mut self) -> Vec<ClipboardRequest> {
+        self.0.drain(..).collect()
+    }
+}
+
+impl Terminal {
+    /// Take the clipboard requests the program sent since the last call.
+    pub fn take_clipboard_requests(&self) -> Vec<ClipboardRequest> {
+        self.clipboard.lock().map(|mut queue| queue.take()).unwrap_or_default()
+    }
+
+    /// Answer a `ClipboardRequest::Load` with the clipboard's text.
+    pub fn reply_clipboard(&mut self, reply: &ClipboardReply, text: &str) {
+        let response = (reply.0)(text);
+        self.write(response.as_bytes());
+    }
+}
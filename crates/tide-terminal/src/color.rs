@@ -4,76 +4,20 @@ use alacritty_terminal::vte::ansi::{Color as AnsiColor, NamedColor, Rgb as AnsiR
 
 use tide_core::Color;
 
-use super::Terminal;
+use super::{ColorScheme, Terminal};
 
 impl Terminal {
-    /// Convert a named ANSI color to RGB, respecting dark/light mode.
+    /// Convert a named ANSI color to RGB with the built-in dark/light palette.
     pub(crate) fn named_color_to_rgb(dark_mode: bool, named: NamedColor) -> Color {
-        if dark_mode {
-            Self::named_color_dark(named)
-        } else {
-            Self::named_color_light(named)
-        }
+        ColorScheme::builtin(dark_mode).named(named)
     }
 
-    /// Dark mode ANSI palette
-    fn named_color_dark(named: NamedColor) -> Color {
-        match named {
-            // Normal colors
-            NamedColor::Black => Color::rgb(0.1, 0.1, 0.14),
-            NamedColor::Red => Color::rgb(1.0, 0.33, 0.33),       // #FF5555
-            NamedColor::Green => Color::rgb(0.31, 0.98, 0.48),    // #50FA7B
-            NamedColor::Yellow => Color::rgb(0.94, 0.9, 0.55),    // #F0E68D
-            NamedColor::Blue => Color::rgb(0.39, 0.58, 1.0),      // #6495FF
-            NamedColor::Magenta => Color::rgb(0.74, 0.45, 1.0),   // #BD73FF
-            NamedColor::Cyan => Color::rgb(0.35, 0.87, 0.93),     // #59DEED
-            NamedColor::White => Color::rgb(0.78, 0.8, 0.87),     // #C7CCDE
-
-            // Bright colors
-            NamedColor::BrightBlack => Color::rgb(0.4, 0.42, 0.53),  // #676B87
-            NamedColor::BrightRed => Color::rgb(1.0, 0.47, 0.42),    // #FF786B
-            NamedColor::BrightGreen => Color::rgb(0.45, 1.0, 0.6),   // #73FF99
-            NamedColor::BrightYellow => Color::rgb(1.0, 0.98, 0.55), // #FFFA8D
-            NamedColor::BrightBlue => Color::rgb(0.53, 0.7, 1.0),    // #87B3FF
-            NamedColor::BrightMagenta => Color::rgb(0.85, 0.6, 1.0), // #D999FF
-            NamedColor::BrightCyan => Color::rgb(0.47, 0.94, 1.0),   // #78F0FF
-            NamedColor::BrightWhite => Color::rgb(0.95, 0.96, 0.98), // #F2F5FA
-
-            // Special
-            NamedColor::Foreground => Color::rgb(0.9, 0.91, 0.95),   // #E6E8F2
-            NamedColor::Background => Color::rgb(0.0, 0.0, 0.0),     // Transparent → pane BG shows
-            _ => Color::rgb(0.9, 0.91, 0.95),
-        }
-    }
-
-    /// Light mode ANSI palette — dark text on light background.
-    /// White/BrightWhite stay light so they work as background colors.
-    fn named_color_light(named: NamedColor) -> Color {
-        match named {
-            // Normal colors — high-contrast variants for warm beige bg
-            NamedColor::Black => Color::rgb(0.0, 0.0, 0.0),
-            NamedColor::Red => Color::rgb(0.68, 0.08, 0.08),
-            NamedColor::Green => Color::rgb(0.05, 0.40, 0.10),
-            NamedColor::Yellow => Color::rgb(0.45, 0.35, 0.0),
-            NamedColor::Blue => Color::rgb(0.10, 0.22, 0.65),
-            NamedColor::Magenta => Color::rgb(0.48, 0.15, 0.65),
-            NamedColor::Cyan => Color::rgb(0.0, 0.35, 0.42),
-            NamedColor::White => Color::rgb(0.85, 0.83, 0.80),
-
-            // Bright colors
-            NamedColor::BrightBlack => Color::rgb(0.40, 0.38, 0.35),
-            NamedColor::BrightRed => Color::rgb(0.75, 0.12, 0.10),
-            NamedColor::BrightGreen => Color::rgb(0.08, 0.48, 0.12),
-            NamedColor::BrightYellow => Color::rgb(0.52, 0.40, 0.0),
-            NamedColor::BrightBlue => Color::rgb(0.12, 0.30, 0.75),
-            NamedColor::BrightMagenta => Color::rgb(0.55, 0.22, 0.75),
-            NamedColor::BrightCyan => Color::rgb(0.05, 0.45, 0.50),
-            NamedColor::BrightWhite => Color::rgb(0.95, 0.93, 0.90),
-
-            // Special
-            NamedColor::Foreground => Color::rgb(0.10, 0.08, 0.05),  // Warm near-black
-            NamedColor::Background => Color::rgb(0.0, 0.0, 0.0),     // Transparent → pane BG shows
-            _ => Color::rgb(0.12, 0.12, 0.12),
+    /// Named color from the terminal's scheme, or the built-in palette for
+    /// the mode when it has none.
+    pub(crate) fn scheme_color(dark_mode: bool, scheme: Option<&ColorScheme>, named: NamedColor) -> Color {
+        match scheme {
+            Some(scheme) => scheme.named(named),
+            None => Self::named_color_to_rgb(dark_mode, named),
         }
     }
 
@@ -117,9 +61,14 @@ impl Terminal {
     }
 
     /// Convert color using pre-copied palette (no lock needed)
-    pub(crate) fn convert_color(dark_mode: bool, color: &AnsiColor, palette: &[Option<AnsiRgb>; 256]) -> Color {
+    pub(crate) fn convert_color(
+        dark_mode: bool,
+        scheme: Option<&ColorScheme>,
+        color: &AnsiColor,
+        palette: &[Option<AnsiRgb>; 256],
+    ) -> Color {
         match color {
-            AnsiColor::Named(named) => Self::named_color_to_rgb(dark_mode, *named),
+            AnsiColor::Named(named) => Self::scheme_color(dark_mode, scheme, *named),
             AnsiColor::Spec(rgb) => Color::rgb(
                 rgb.r as f32 / 255.0,
                 rgb.g as f32 / 255.0,
@@ -129,7 +78,7 @@ impl Terminal {
                 // Indices 0-15 → route through our named palette (respects dark/light)
                 if *idx < 16 {
                     let named = Self::index_to_named(*idx);
-                    return Self::named_color_to_rgb(dark_mode, named);
+                    return Self::scheme_color(dark_mode, scheme, named);
                 }
                 if let Some(rgb) = palette[*idx as usize] {
                     Color::rgb(
@@ -0,0 +1,266 @@
+// Copy mode: keyboard navigation and selection over the scrollback
+//
+// Built on alacritty's vi mode, so the cursor and selection live in grid
+// coordinates inside `Term` and stay attached to their text while output
+// scrolls the screen. The sync thread publishes a `CopyModeView` with the
+// cursor in viewport coordinates (the selection is published like any other,
+// see `selection.rs`); the vi key bindings themselves live in tide-app.
+
+use std::error::Error;
+use std::sync::atomic::Ordering;
+
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::index::{Boundary, Column, Direction, Point, Side};
+use alacritty_terminal::selection::{Selection, SelectionType};
+use alacritty_terminal::term::search::RegexSearch;
+use alacritty_terminal::term::{Term, TermMode};
+use alacritty_terminal::vi_mode::ViMotion;
+
+use super::Terminal;
+
+/// Cursor movements in copy mode (named after the vi keys in the comments).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMotion {
+    /// `h` / `j` / `k` / `l`
+    Left,
+    Down,
+    Up,
+    Right,
+    /// `0`
+    LineStart,
+    /// `$`
+    LineEnd,
+    /// `^`
+    FirstNonBlank,
+    /// `w` / `b` / `e`: words split at punctuation and whitespace.
+    WordRight,
+    WordLeft,
+    WordEnd,
+    /// `W` / `B` / `E`: words split at whitespace only.
+    BigWordRight,
+    BigWordLeft,
+    BigWordEnd,
+    /// `{` / `}`
+    ParagraphUp,
+    ParagraphDown,
+    /// `H` / `M` / `L`
+    ScreenTop,
+    ScreenMiddle,
+    ScreenBottom,
+    /// `%`
+    Bracket,
+    /// `gg` / `G`: first / last line of the buffer.
+    Top,
+    Bottom,
+    /// `Ctrl+U` / `Ctrl+D`
+    HalfPageUp,
+    HalfPageDown,
+}
+
+impl CopyMotion {
+    fn vi_motion(self) -> Option<ViMotion> {
+        Some(match self {
+            Self::Left => ViMotion::Left,
+            Self::Down => ViMotion::Down,
+            Self::Up => ViMotion::Up,
+            Self::Right => ViMotion::Right,
+            Self::LineStart => ViMotion::First,
+            Self::LineEnd => ViMotion::Last,
+            Self::FirstNonBlank => ViMotion::FirstOccupied,
+            Self::WordRight => ViMotion::SemanticRight,
+            Self::WordLeft => ViMotion::SemanticLeft,
+            Self::WordEnd => ViMotion::SemanticRightEnd,
+            Self::BigWordRight => ViMotion::WordRight,
+            Self::BigWordLeft => ViMotion::WordLeft,
+            Self::BigWordEnd => ViMotion::WordRightEnd,
+            Self::ParagraphUp => ViMotion::ParagraphUp,
+            Self::ParagraphDown => ViMotion::ParagraphDown,
+            Self::ScreenTop => ViMotion::High,
+            Self::ScreenMiddle => ViMotion::Middle,
+            Self::ScreenBottom => ViMotion::Low,
+            Self::Bracket => ViMotion::Bracket,
+            Self::Top | Self::Bottom | Self::HalfPageUp | Self::HalfPageDown => return None,
+        })
+    }
+}
+
+/// Selection kinds: `v`, `V` and `Ctrl+V`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopySelection {
+    Chars,
+    Lines,
+    Block,
+}
+
+impl CopySelection {
+    fn selection_type(self) -> SelectionType {
+        match self {
+            Self::Chars => SelectionType::Simple,
+            Self::Lines => SelectionType::Lines,
+            Self::Block => SelectionType::Block,
+        }
+    }
+
+    fn from_type(ty: SelectionType) -> Self {
+        match ty {
+            SelectionType::Lines => Self::Lines,
+            SelectionType::Block => Self::Block,
+            SelectionType::Simple | SelectionType::Semantic => Self::Chars,
+        }
+    }
+}
+
+/// Copy mode state in viewport coordinates, published with each snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CopyModeView {
+    /// Cursor `(row, col)`; always inside the viewport.
+    pub cursor: (usize, usize),
+    pub selection: Option<CopySelection>,
+}
+
+impl Terminal {
+    /// Enter copy mode with the cursor on the terminal cursor (or the top of
+    /// the viewport when scrolled away from it). Output keeps flowing, but the
+    /// view no longer follows it.
+    pub fn enter_copy_mode(&mut self) {
+        self.stay_at_bottom.store(false, Ordering::Relaxed);
+        enter(&mut self.term.lock());
+        self.mark_dirty();
+    }
+
+    /// Leave copy mode, dropping its selection, and scroll back to the bottom.
+    pub fn exit_copy_mode(&mut self) {
+        exit(&mut self.term.lock());
+        self.copy_mode = None;
+        self.request_scroll_to_bottom();
+    }
+
+    /// Copy mode state from the latest snapshot; None outside copy mode.
+    pub fn copy_mode(&self) -> Option<&CopyModeView> {
+        self.copy_mode.as_ref()
+    }
+
+    pub fn copy_mode_motion(&mut self, motion: CopyMotion) {
+        apply_motion(&mut self.term.lock(), motion);
+        self.mark_dirty();
+    }
+
+    /// Start a selection of `kind` at the cursor, switch the current one to
+    /// `kind`, or clear it when it already is `kind`.
+    pub fn toggle_copy_selection(&mut self, kind: CopySelection) {
+        toggle_selection(&mut self.term.lock(), kind);
+        self.mark_dirty();
+    }
+
+    /// Clear the selection, staying in copy mode. Returns false if there was none.
+    pub fn clear_copy_selection(&mut self) -> bool {
+        self.clear_selection()
+    }
+
+    /// Text of the copy mode selection, if any.
+    pub fn copy_mode_yank(&self) -> Option<String> {
+        yank(&self.term.lock())
+    }
+
+    /// Move the cursor to the next match of `pattern` (a regex, e.g. from
+    /// `SearchOptions::pattern`), wrapping around the buffer. Returns false if
+    /// nothing matched.
+    pub fn copy_mode_search(&mut self, pattern: &str, forward: bool) -> Result<bool, Box<dyn Error>> {
+        let mut regex = RegexSearch::new(pattern)?;
+        let found = search(&mut self.term.lock(), &mut regex, forward);
+        self.mark_dirty();
+        Ok(found)
+    }
+}
+
+pub(crate) fn enter<T: EventListener>(term: &mut Term<T>) {
+    if !term.mode().contains(TermMode::VI) {
+        term.toggle_vi_mode();
+    }
+    term.selection = None;
+}
+
+pub(crate) fn exit<T: EventListener>(term: &mut Term<T>) {
+    if term.mode().contains(TermMode::VI) {
+        term.toggle_vi_mode();
+    }
+    term.selection = None;
+}
+
+pub(crate) fn apply_motion<T: EventListener>(term: &mut Term<T>, motion: CopyMotion) {
+    if !term.mode().contains(TermMode::VI) {
+        return;
+    }
+    if let Some(vi_motion) = motion.vi_motion() {
+        term.vi_motion(vi_motion);
+        let point = term.vi_mode_cursor.point;
+        term.scroll_to_point(point);
+        return;
+    }
+    match motion {
+        CopyMotion::Top => term.vi_goto_point(Point::new(term.topmost_line(), Column(0))),
+        CopyMotion::Bottom => term.vi_goto_point(Point::new(term.bottommost_line(), Column(0))),
+        CopyMotion::HalfPageUp | CopyMotion::HalfPageDown => {
+            let half = (term.screen_lines() / 2).max(1) as i32;
+            let lines = if motion == CopyMotion::HalfPageUp { half } else { -half };
+            let target = term.vi_mode_cursor.scroll(term, lines).point;
+            term.scroll_display(Scroll::Delta(lines));
+            term.vi_goto_point(target);
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn toggle_selection<T: EventListener>(term: &mut Term<T>, kind: CopySelection) {
+    if !term.mode().contains(TermMode::VI) {
+        return;
+    }
+    let ty = kind.selection_type();
+    term.selection = match term.selection.take() {
+        Some(selection) if selection.ty == ty => None,
+        Some(mut selection) => {
+            selection.ty = ty;
+            selection.include_all();
+            Some(selection)
+        }
+        None => {
+            let mut selection = Selection::new(ty, term.vi_mode_cursor.point, Side::Left);
+            selection.include_all();
+            Some(selection)
+        }
+    };
+}
+
+pub(crate) fn yank<T>(term: &Term<T>) -> Option<String> {
+    term.selection_to_string().filter(|text| !text.is_empty())
+}
+
+pub(crate) fn search<T: EventListener>(term: &mut Term<T>, regex: &mut RegexSearch, forward: bool) -> bool {
+    let cursor = term.vi_mode_cursor.point;
+    let (origin, direction) = if forward {
+        (cursor.add(term, Boundary::None, 1), Direction::Right)
+    } else {
+        (cursor.sub(term, Boundary::None, 1), Direction::Left)
+    };
+    match term.search_next(regex, origin, direction, Side::Left, None) {
+        Some(found) => {
+            term.vi_goto_point(*found.start());
+            true
+        }
+        None => false,
+    }
+}
+
+/// Viewport view of copy mode; None outside it. Called with the term lock held.
+pub(crate) fn view<T>(term: &Term<T>) -> Option<CopyModeView> {
+    if !term.mode().contains(TermMode::VI) {
+        return None;
+    }
+    let offset = term.grid().display_offset() as i32;
+    let rows = term.screen_lines() as i32;
+    let point = term.vi_mode_cursor.point;
+    let cursor = ((point.line.0 + offset).clamp(0, rows - 1) as usize, point.column.0);
+    let selection = term.selection.as_ref().map(|s| CopySelection::from_type(s.ty));
+    Some(CopyModeView { cursor, selection })
+}
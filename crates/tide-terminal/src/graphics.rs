@@ -0,0 +1,552 @@
+// Inline images: kitty graphics protocol and sixel
+//
+// The vendored scanner hands `APC G ... ST` and `DCS ... q ... ST` bodies to
+// the listener (`EventListener::graphics`) with the cursor position in stable
+// line coordinates, like OSC 133 marks. Decoded images are anchored to that
+// line and column, so they scroll with the text and go away with it when
+// they fall out of scrollback or the screen is cleared.
+//
+// A placement is sized in cells from the image's pixel size and the cell
+// size in device pixels (`Terminal::set_cell_pixel_size`), unless the
+// program asks for a size in cells. The sync thread publishes the placements
+// in the viewport as `VisibleImage`s; drawing them is the renderer's job.
+//
+// Decoding (and reading kitty's file media) happens before that, in
+// `EventListener::prepare_graphics`, which the PTY thread calls as it reads a
+// sequence and before it locks the terminal to parse it. The store picks up
+// the result when the parser reaches the same sequence.
+
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use alacritty_terminal::event::{CursorAdvance, Graphics, GraphicsCursor};
+
+use super::kitty::Command;
+use super::{sixel, Terminal};
+
+/// Longest side of a decoded image, in pixels.
+pub(crate) const MAX_IMAGE_SIDE: usize = 4096;
+
+/// Pixel data kept per terminal; the oldest images are dropped beyond it.
+const MAX_STORE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Longest base64 text collected from a chunked kitty transmission.
+const MAX_TRANSFER_BYTES: usize = 96 * 1024 * 1024;
+
+/// Decoded images waiting for the parser to reach their sequence.
+const MAX_READY: usize = 16;
+
+/// Kitty ids handed out to images transmitted without one.
+const FIRST_INTERNAL_ID: u32 = 1 << 31;
+
+/// Image ids are unique across terminals so the renderer can key textures by them.
+static NEXT_IMAGE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// RGBA pixels straight out of a decoder.
+#[derive(Debug)]
+pub(crate) struct DecodedImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// A decoded image, shared by its placements and the renderer.
+#[derive(Debug)]
+pub struct TermImage {
+    /// Unique for the life of the process.
+    pub id: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Unpremultiplied RGBA, row by row.
+    pub rgba: Vec<u8>,
+}
+
+impl TermImage {
+    fn new(decoded: DecodedImage) -> Arc<Self> {
+        Arc::new(Self {
+            id: NEXT_IMAGE_ID.fetch_add(1, Ordering::Relaxed),
+            width: decoded.width,
+            height: decoded.height,
+            rgba: decoded.rgba,
+        })
+    }
+}
+
+/// An image placement in the viewport.
+#[derive(Debug, Clone)]
+pub struct VisibleImage {
+    pub image: Arc<TermImage>,
+    /// Viewport row of the top edge; negative when it starts above the viewport.
+    pub row: i32,
+    pub col: usize,
+    /// Size in cells, possibly fractional.
+    pub cols: f32,
+    pub rows: f32,
+    /// Part of the image to show, in texture coordinates `[u0, v0, u1, v1]`.
+    pub uv: [f32; 4],
+    /// Kitty z-index below zero: drawn under the text.
+    pub below_text: bool,
+}
+
+impl PartialEq for VisibleImage {
+    fn eq(&self, other: &Self) -> bool {
+        self.image.id == other.image.id
+            && self.row == other.row
+            && self.col == other.col
+            && self.cols == other.cols
+            && self.rows == other.rows
+            && self.uv == other.uv
+            && self.below_text == other.below_text
+    }
+}
+
+#[derive(Debug)]
+struct Placement {
+    image: Arc<TermImage>,
+    /// Kitty image and placement ids; 0 for sixel.
+    kitty_id: u32,
+    placement_id: u32,
+    alt_screen: bool,
+    /// Stable line and column of the top-left cell.
+    line: u64,
+    column: usize,
+    cols: f32,
+    rows: f32,
+    uv: [f32; 4],
+    z: i32,
+}
+
+impl Placement {
+    fn bottom(&self) -> u64 {
+        self.line + self.rows.ceil() as u64
+    }
+
+    fn covers(&self, line: u64, column: usize) -> bool {
+        (self.line..self.bottom()).contains(&line)
+            && (self.column..self.column + self.cols.ceil() as usize).contains(&column)
+    }
+}
+
+#[derive(Debug)]
+struct KittyImage {
+    image: Arc<TermImage>,
+    /// Image number (`I=`), 0 if none.
+    number: u32,
+}
+
+/// A chunked kitty transmission waiting for its last chunk.
+#[derive(Debug)]
+struct Transfer {
+    command: Command,
+    payload: Vec<u8>,
+}
+
+enum Gathered {
+    /// More chunks to come.
+    Pending,
+    /// The transmission outgrew `MAX_TRANSFER_BYTES` and was dropped.
+    TooLarge(Command),
+    /// A whole command, with the payload of all its chunks.
+    Whole(Command, Vec<u8>),
+}
+
+/// Add a kitty command to a chunked transmission. Chunks after the first
+/// carry only `m` and data.
+fn gather(transfer: &mut Option<Transfer>, mut command: Command) -> Gathered {
+    let payload = std::mem::take(&mut command.payload);
+    if let Some(mut pending) = transfer.take() {
+        if pending.payload.len() + payload.len() > MAX_TRANSFER_BYTES {
+            return Gathered::TooLarge(pending.command);
+        }
+        pending.payload.extend_from_slice(&payload);
+        if command.more {
+            *transfer = Some(pending);
+            return Gathered::Pending;
+        }
+        return Gathered::Whole(pending.command, pending.payload);
+    }
+    if command.more && matches!(command.action, b't' | b'T' | b'q') {
+        *transfer = Some(Transfer { command, payload });
+        return Gathered::Pending;
+    }
+    Gathered::Whole(command, payload)
+}
+
+/// Decodes image sequences ahead of the terminal lock. It sees the same
+/// sequences as the store, in the same order, and keeps its own chunk state.
+#[derive(Debug, Default)]
+pub(crate) struct ImageDecoder {
+    transfer: Option<Transfer>,
+    ready: VecDeque<(Graphics, Result<DecodedImage, &'static str>)>,
+}
+
+impl ImageDecoder {
+    /// Decode the image `graphics` transmits, if any.
+    pub(crate) fn prepare(&mut self, graphics: &Graphics) {
+        let decoded = match graphics {
+            Graphics::Sixel(data) => sixel::decode(data).ok_or("EINVAL:bad sixel data"),
+            Graphics::Kitty(data) => {
+                let Some(command) = Command::parse(data) else { return };
+                match gather(&mut self.transfer, command) {
+                    Gathered::Whole(command, payload) if matches!(command.action, b't' | b'T' | b'q') => {
+                        command.decode(&payload)
+                    }
+                    _ => return,
+                }
+            }
+        };
+        if self.ready.len() >= MAX_READY {
+            self.ready.pop_front();
+        }
+        self.ready.push_back((graphics.clone(), decoded));
+    }
+
+    /// The image decoded for `graphics`, dropping older ones nobody claimed.
+    pub(crate) fn take(&mut self, graphics: &Graphics) -> Option<Result<DecodedImage, &'static str>> {
+        let index = self.ready.iter().position(|(g, _)| g == graphics)?;
+        self.ready.drain(..index);
+        self.ready.pop_front().map(|(_, decoded)| decoded)
+    }
+}
+
+/// Per-terminal images and placements, shared between the event listener
+/// (writer, PTY thread), the sync thread and `Terminal`.
+#[derive(Debug)]
+pub(crate) struct ImageStore {
+    kitty_images: HashMap<u32, KittyImage>,
+    /// In the order they were placed.
+    placements: Vec<Placement>,
+    transfer: Option<Transfer>,
+    next_internal_id: u32,
+    /// Cell size in device pixels.
+    cell_width: f32,
+    cell_height: f32,
+}
+
+impl Default for ImageStore {
+    fn default() -> Self {
+        Self {
+            kitty_images: HashMap::new(),
+            placements: Vec::new(),
+            transfer: None,
+            next_internal_id: FIRST_INTERNAL_ID,
+            cell_width: 8.0,
+            cell_height: 16.0,
+        }
+    }
+}
+
+impl ImageStore {
+    pub(crate) fn set_cell_size(&mut self, width: f32, height: f32) {
+        self.cell_width = width.max(1.0);
+        self.cell_height = height.max(1.0);
+    }
+
+    /// Handle an image sequence. `decoded` is its image if `ImageDecoder`
+    /// got to it first; otherwise it's decoded here. Returns the cursor
+    /// movement and, for kitty, the reply to send to the program.
+    pub(crate) fn apply(
+        &mut self,
+        graphics: Graphics,
+        at: GraphicsCursor,
+        decoded: Option<Result<DecodedImage, &'static str>>,
+    ) -> (Option<CursorAdvance>, Option<String>) {
+        match graphics {
+            Graphics::Kitty(data) => self.kitty(&data, at, decoded),
+            Graphics::Sixel(data) => (self.sixel(&data, at, decoded), None),
+        }
+    }
+
+    fn sixel(
+        &mut self,
+        data: &[u8],
+        at: GraphicsCursor,
+        decoded: Option<Result<DecodedImage, &'static str>>,
+    ) -> Option<CursorAdvance> {
+        let decoded = match decoded {
+            Some(decoded) => decoded.ok()?,
+            None => sixel::decode(data)?,
+        };
+        let image = TermImage::new(decoded);
+        let cols = image.width as f32 / self.cell_width;
+        let rows = image.height as f32 / self.cell_height;
+        self.placements.push(Placement {
+            image,
+            kitty_id: 0,
+            placement_id: 0,
+            alt_screen: at.alt_screen,
+            line: at.line,
+            column: at.column,
+            cols,
+            rows,
+            uv: [0.0, 0.0, 1.0, 1.0],
+            z: 0,
+        });
+        self.evict();
+        // The cursor ends up on the line below the image, in the same column.
+        Some(CursorAdvance { lines: rows.ceil() as usize, columns: 0 })
+    }
+
+    fn kitty(
+        &mut self,
+        data: &[u8],
+        at: GraphicsCursor,
+        decoded: Option<Result<DecodedImage, &'static str>>,
+    ) -> (Option<CursorAdvance>, Option<String>) {
+        let Some(command) = Command::parse(data) else { return (None, None) };
+        let (mut command, payload) = match gather(&mut self.transfer, command) {
+            Gathered::Pending => return (None, None),
+            Gathered::TooLarge(command) => return (None, reply(&command, Err("EFBIG:image data too large"))),
+            Gathered::Whole(command, payload) => (command, payload),
+        };
+
+        // Images sent without an id or number get one, but no reply.
+        let wants_reply = command.id > 0 || command.number > 0;
+        let result = match command.action {
+            b't' | b'T' | b'q' => self.transmit(&mut command, &payload, at, decoded),
+            b'p' => self.place(&command, at),
+            b'd' => {
+                self.delete(&command, at);
+                return (None, None);
+            }
+            _ => Err("EINVAL:unsupported action"),
+        };
+        match result {
+            Ok(advance) => (advance, reply(&command, Ok(())).filter(|_| wants_reply)),
+            Err(error) => (None, reply(&command, Err(error)).filter(|_| wants_reply)),
+        }
+    }
+
+    fn transmit(
+        &mut self,
+        command: &mut Command,
+        payload: &[u8],
+        at: GraphicsCursor,
+        decoded: Option<Result<DecodedImage, &'static str>>,
+    ) -> Result<Option<CursorAdvance>, &'static str> {
+        let decoded = match decoded {
+            Some(decoded) => decoded?,
+            None => command.decode(payload)?,
+        };
+        if command.action == b'q' {
+            return Ok(None);
+        }
+        if command.id == 0 {
+            command.id = self.next_internal_id;
+            self.next_internal_id = self.next_internal_id.checked_add(1).unwrap_or(FIRST_INTERNAL_ID);
+        }
+        // New data for an id replaces the image and its placements.
+        self.placements.retain(|p| p.kitty_id != command.id);
+        let image = TermImage::new(decoded);
+        self.kitty_images.insert(command.id, KittyImage { image, number: command.number });
+        self.evict();
+        if command.action == b'T' {
+            self.place(command, at)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn place(&mut self, command: &Command, at: GraphicsCursor) -> Result<Option<CursorAdvance>, &'static str> {
+        let id = self.resolve(command).ok_or("ENOENT:image not found")?;
+        let image = self.kitty_images[&id].image.clone();
+
+        let (width, height) = (image.width, image.height);
+        let x = command.source_x.min(width);
+        let y = command.source_y.min(height);
+        let w = if command.source_w == 0 { width - x } else { command.source_w.min(width - x) };
+        let h = if command.source_h == 0 { height - y } else { command.source_h.min(height - y) };
+        if w == 0 || h == 0 {
+            return Err("EINVAL:empty source rectangle");
+        }
+        let uv = [
+            x as f32 / width as f32,
+            y as f32 / height as f32,
+            (x + w) as f32 / width as f32,
+            (y + h) as f32 / height as f32,
+        ];
+
+        // Natural size in cells; a size given for one side keeps the aspect ratio.
+        let natural_cols = w as f32 / self.cell_width;
+        let natural_rows = h as f32 / self.cell_height;
+        let (cols, rows) = match (command.cols, command.rows) {
+            (0, 0) => (natural_cols, natural_rows),
+            (c, 0) => (c as f32, natural_rows * c as f32 / natural_cols),
+            (0, r) => (natural_cols * r as f32 / natural_rows, r as f32),
+            (c, r) => (c as f32, r as f32),
+        };
+
+        if command.placement > 0 {
+            self.placements.retain(|p| !(p.kitty_id == id && p.placement_id == command.placement));
+        }
+        self.placements.push(Placement {
+            image,
+            kitty_id: id,
+            placement_id: command.placement,
+            alt_screen: at.alt_screen,
+            line: at.line,
+            column: at.column,
+            cols,
+            rows,
+            uv,
+            z: command.z,
+        });
+
+        if command.keep_cursor {
+            return Ok(None);
+        }
+        // The cursor ends up just right of the image, on its last row.
+        let lines = (rows.ceil() as usize).saturating_sub(1);
+        Ok(Some(CursorAdvance { lines, columns: cols.ceil() as usize }))
+    }
+
+    /// Image id a command refers to: `i=`, or the newest image with `I=`.
+    fn resolve(&self, command: &Command) -> Option<u32> {
+        if command.id > 0 {
+            return self.kitty_images.contains_key(&command.id).then_some(command.id);
+        }
+        if command.number == 0 {
+            return None;
+        }
+        self.kitty_images
+            .iter()
+            .filter(|(_, k)| k.number == command.number)
+            .max_by_key(|(_, k)| k.image.id)
+            .map(|(&id, _)| id)
+    }
+
+    /// `a=d`. Lowercase `d=` values remove placements, uppercase ones also
+    /// free the images left without any.
+    fn delete(&mut self, command: &Command, at: GraphicsCursor) {
+        let target = match command.delete.to_ascii_lowercase() {
+            b'i' | b'n' => match self.resolve(command) {
+                Some(id) => Some(id),
+                None => return,
+            },
+            _ => None,
+        };
+        let mut affected = Vec::new();
+        self.placements.retain(|p| {
+            let hit = p.kitty_id != 0
+                && match command.delete.to_ascii_lowercase() {
+                    b'a' => p.alt_screen == at.alt_screen,
+                    b'i' | b'n' => {
+                        Some(p.kitty_id) == target && (command.placement == 0 || p.placement_id == command.placement)
+                    }
+                    b'c' => p.alt_screen == at.alt_screen && p.covers(at.line, at.column),
+                    b'z' => p.alt_screen == at.alt_screen && p.z == command.z,
+                    _ => false,
+                };
+            if hit {
+                affected.push(p.kitty_id);
+            }
+            !hit
+        });
+        if command.delete.is_ascii_uppercase() {
+            affected.extend(target);
+            for id in affected {
+                if !self.placements.iter().any(|p| p.kitty_id == id) {
+                    self.kitty_images.remove(&id);
+                }
+            }
+        }
+    }
+
+    /// Remove placements on `lines` of the primary or alternate screen.
+    pub(crate) fn clear(&mut self, alt_screen: bool, lines: Range<u64>) {
+        self.placements.retain(|p| p.alt_screen != alt_screen || !lines.contains(&p.line));
+    }
+
+    /// Drop placements that scrolled out of the history buffer. `first_line`
+    /// is the stable line of the oldest line still in that screen's grid.
+    pub(crate) fn prune(&mut self, alt_screen: bool, first_line: u64) {
+        self.placements.retain(|p| p.alt_screen != alt_screen || p.bottom() > first_line);
+    }
+
+    /// Drop the oldest images while the store is over its byte budget.
+    fn evict(&mut self) {
+        loop {
+            let kitty = self.kitty_images.values().map(|k| &k.image);
+            let sixel = self.placements.iter().filter(|p| p.kitty_id == 0).map(|p| &p.image);
+            let images: Vec<&Arc<TermImage>> = kitty.chain(sixel).collect();
+            let bytes: usize = images.iter().map(|i| i.rgba.len()).sum();
+            // Never evict the image that was just added.
+            if bytes <= MAX_STORE_BYTES || images.len() <= 1 {
+                return;
+            }
+            let Some(oldest) = images.iter().map(|i| i.id).min() else { return };
+            self.kitty_images.retain(|_, k| k.image.id != oldest);
+            self.placements.retain(|p| p.image.id != oldest);
+        }
+    }
+
+    /// Placements of one screen overlapping the `rows` lines starting at
+    /// `viewport_top`, in drawing order.
+    pub(crate) fn visible(&self, viewport_top: u64, rows: usize, alt_screen: bool, out: &mut Vec<VisibleImage>) {
+        out.clear();
+        let bottom = viewport_top + rows as u64;
+        let mut placements: Vec<&Placement> = self
+            .placements
+            .iter()
+            .filter(|p| p.alt_screen == alt_screen && p.line < bottom && p.bottom() > viewport_top)
+            .collect();
+        placements.sort_by_key(|p| p.z);
+        out.extend(placements.into_iter().map(|p| VisibleImage {
+            image: p.image.clone(),
+            row: (p.line as i64 - viewport_top as i64) as i32,
+            col: p.column,
+            cols: p.cols,
+            rows: p.rows,
+            uv: p.uv,
+            below_text: p.z < 0,
+        }));
+    }
+}
+
+/// Kitty's reply to a command, honoring `q=`. Commands without an id or
+/// number get none.
+fn reply(command: &Command, result: Result<(), &str>) -> Option<String> {
+    if command.id == 0 && command.number == 0 {
+        return None;
+    }
+    let message = match result {
+        Ok(()) if command.quiet == 0 => "OK",
+        Err(error) if command.quiet < 2 => error,
+        _ => return None,
+    };
+    let mut keys = format!("i={}", command.id);
+    if command.number > 0 {
+        keys.push_str(&format!(",I={}", command.number));
+    }
+    if command.placement > 0 {
+        keys.push_str(&format!(",p={}", command.placement));
+    }
+    Some(format!("\x1b_G{keys};{message}\x1b\\"))
+}
+
+// ──────────────────────────────────────────────
+// Terminal API
+// ──────────────────────────────────────────────
+
+impl Terminal {
+    /// Images in the viewport, in drawing order (read from snapshot).
+    pub fn visible_images(&self) -> &[VisibleImage] {
+        &self.visible_images
+    }
+
+    /// Tell the terminal how large a cell is in device pixels. Images are
+    /// sized in cells with it, and programs see it in the PTY window size.
+    pub fn set_cell_pixel_size(&mut self, width: u16, height: u16) {
+        if width == 0 || height == 0 || self.cell_pixels == (width, height) {
+            return;
+        }
+        self.cell_pixels = (width, height);
+        if let Ok(mut images) = self.images.lock() {
+            images.set_cell_size(width as f32, height as f32);
+        }
+        self.pending_pty_resize = Some((self.window_size(), Instant::now()));
+    }
+}
@@ -0,0 +1,165 @@
+// Headless scripted terminals
+//
+// `HeadlessTerminal` drives a `Terminal` without a window: spawn a command on a
+// real PTY, type into it, wait for the screen to show something, then inspect
+// the grid. Meant for expect-style integration tests of CLI programs (and of
+// Tide itself).
+//
+// There is no event loop, so every read forces a grid sync and swaps the
+// snapshot in on the calling thread. Matching runs against the visible
+// screen, so output that scrolls off before a wait polls it is not seen.
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+
+use tide_core::{CursorState, Key, Modifiers, TerminalBackend, TerminalGrid};
+
+use super::{KeyEventKind, ShellCommand, Terminal};
+
+/// How often `wait_for_text` / `wait_for_exit` re-check the terminal.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A terminal running one command, scripted from code.
+pub struct HeadlessTerminal {
+    terminal: Terminal,
+}
+
+/// A copy of the screen: cells with their resolved colors and attributes,
+/// plus the cursor.
+#[derive(Clone)]
+pub struct HeadlessSnapshot {
+    pub grid: TerminalGrid,
+    pub cursor: CursorState,
+}
+
+impl HeadlessSnapshot {
+    /// The screen as text: one line per row, trailing blanks trimmed.
+    pub fn text(&self) -> String {
+        grid_text(&self.grid)
+    }
+}
+
+impl HeadlessTerminal {
+    /// Run `program` with `args` on an 80x24 terminal in the current directory.
+    pub fn spawn(program: &str, args: &[&str]) -> Result<Self, Box<dyn Error>> {
+        let command = ShellCommand {
+            program: Some(program.to_string()),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: Vec::new(),
+        };
+        Self::with_command(80, 24, std::env::current_dir().ok(), &command)
+    }
+
+    /// Run `command` on a `cols` x `rows` terminal. A bare zsh/bash/fish gets
+    /// shell integration, as in the app.
+    pub fn with_command(
+        cols: u16,
+        rows: u16,
+        cwd: Option<PathBuf>,
+        command: &ShellCommand,
+    ) -> Result<Self, Box<dyn Error>> {
+        let terminal = Terminal::with_command(cols, rows, cwd, true, command, None)?;
+        Ok(Self { terminal })
+    }
+
+    /// Type `text` as if pasted without bracketing: `\r` is Enter, `\x03` is
+    /// Ctrl-C, and so on.
+    pub fn send_keys(&mut self, text: &str) {
+        self.terminal.write(text.as_bytes());
+    }
+
+    /// Press a key, encoded for the keyboard mode the program asked for.
+    pub fn send_key(&mut self, key: Key, modifiers: Modifiers) {
+        let text = match key {
+            Key::Char(c) if !modifiers.ctrl && !modifiers.meta => Some(c.to_string()),
+            _ => None,
+        };
+        let flags = self.terminal.keyboard_flags();
+        let bytes = Terminal::encode_key(&key, &modifiers, KeyEventKind::Press, text.as_deref(), flags);
+        self.terminal.write(&bytes);
+    }
+
+    /// Wait until the screen text matches `pattern` (a regex; `(?m)` makes
+    /// `^`/`$` match per line) and return the matched text. The error on
+    /// timeout includes the screen, so a failing test shows what was there.
+    pub fn wait_for_text(&mut self, pattern: &str, timeout: Duration) -> Result<String, Box<dyn Error>> {
+        let regex = Regex::new(pattern)?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            let text = self.screen_text();
+            if let Some(found) = regex.find(&text) {
+                return Ok(found.as_str().to_string());
+            }
+            if Instant::now() >= deadline {
+                return Err(format!("timed out after {:?} waiting for /{}/; screen:\n{}", timeout, pattern, text).into());
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Wait for the command to exit. Returns false on timeout.
+    pub fn wait_for_exit(&mut self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        // The PTY loop stops after reading what the command wrote last
+        while self.terminal.is_child_alive() || !self.terminal.pty_loop_finished() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        true
+    }
+
+    /// The screen as text: one line per row, trailing blanks trimmed.
+    pub fn screen_text(&mut self) -> String {
+        self.refresh();
+        grid_text(self.terminal.grid())
+    }
+
+    /// The screen with colors and attributes, after a fresh sync.
+    pub fn snapshot(&mut self) -> HeadlessSnapshot {
+        self.refresh();
+        HeadlessSnapshot { grid: self.terminal.grid().clone(), cursor: self.terminal.cursor() }
+    }
+
+    /// Resize the grid. The program gets SIGWINCH on the first read after
+    /// the 50 ms resize debounce.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.terminal.resize(cols, rows);
+    }
+
+    /// The underlying terminal, for everything else (search, marks, titles).
+    pub fn terminal(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    pub fn terminal_mut(&mut self) -> &mut Terminal {
+        &mut self.terminal
+    }
+
+    /// What the app's event loop does each frame: flush a due PTY resize,
+    /// then sync and swap in the grid.
+    fn refresh(&mut self) {
+        self.terminal.process();
+        self.terminal.sync_now();
+    }
+}
+
+/// Rows joined with `\n`. Wide characters' spacer cells are skipped.
+fn grid_text(grid: &TerminalGrid) -> String {
+    let mut out = String::new();
+    for (i, row) in grid.cells.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let line: String = row.iter().map(|c| c.character).filter(|&c| c != '\0').collect();
+        out.push_str(line.trim_end());
+    }
+    // Blank rows below the output
+    let trimmed = out.trim_end_matches('\n').len();
+    out.truncate(trimmed);
+    out
+}
@@ -1,10 +1,185 @@
 // Keyboard event to byte conversion for Terminal
+//
+// Legacy xterm encoding by default. Applications that push kitty keyboard
+// protocol flags (`CSI > flags u`, tracked per screen by alacritty's `Term`)
+// get progressive enhancements: `CSI code ; modifiers[:event] [; text] u`
+// for keys that are ambiguous in legacy mode, key release events, shifted
+// alternates, and optionally every key as an escape code.
+// Spec: https://sw.kovidgoyal.net/kitty/keyboard-protocol/
 
+use std::fmt::Write as _;
+
+use alacritty_terminal::term::TermMode;
 use tide_core::{Key, Modifiers};
 
 use super::Terminal;
 
+/// Kitty keyboard protocol enhancement flags requested by the application.
+/// All off means legacy encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyboardFlags {
+    /// 0b1: Esc and modified keys as CSI u, so Ctrl+I differs from Tab.
+    pub disambiguate: bool,
+    /// 0b10: report key releases.
+    pub report_events: bool,
+    /// 0b100: add the shifted key to CSI u codes.
+    pub report_alternates: bool,
+    /// 0b1000: encode every key, including plain text, as an escape code.
+    pub report_all_keys: bool,
+    /// 0b10000: append the text a key produces (with `report_all_keys`).
+    pub report_text: bool,
+}
+
+impl KeyboardFlags {
+    pub fn is_legacy(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Text keys must go through `encode_key` instead of being sent as text
+    /// (to encode them, or to pair their release events).
+    pub fn wants_text_keys(&self) -> bool {
+        self.report_all_keys || self.report_events
+    }
+}
+
+/// Press or release (the platform layer doesn't mark repeats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEventKind {
+    Press,
+    Release,
+}
+
+/// How a key is written in kitty encoding.
+enum KittyKey {
+    /// `CSI code ... u`
+    Code(u32),
+    /// `CSI number ; modifiers final`, e.g. `CSI 1;5A` or `CSI 3;2~`.
+    Legacy(u32, char),
+}
+
 impl Terminal {
+    /// Kitty keyboard flags in effect for the active screen.
+    pub fn keyboard_flags(&self) -> KeyboardFlags {
+        let mode = *self.term.lock().mode();
+        KeyboardFlags {
+            disambiguate: mode.contains(TermMode::DISAMBIGUATE_ESC_CODES),
+            report_events: mode.contains(TermMode::REPORT_EVENT_TYPES),
+            report_alternates: mode.contains(TermMode::REPORT_ALTERNATE_KEYS),
+            report_all_keys: mode.contains(TermMode::REPORT_ALL_KEYS_AS_ESC),
+            report_text: mode.contains(TermMode::REPORT_ASSOCIATED_TEXT),
+        }
+    }
+
+    /// Encode a key event under `flags`. `text` is what the key typed, if
+    /// anything. Returns nothing for events the application didn't ask for.
+    pub fn encode_key(
+        key: &Key,
+        modifiers: &Modifiers,
+        kind: KeyEventKind,
+        text: Option<&str>,
+        flags: KeyboardFlags,
+    ) -> Vec<u8> {
+        let release = kind == KeyEventKind::Release;
+        if flags.is_legacy() || (release && !flags.report_events) {
+            return if release { Vec::new() } else { Self::key_to_bytes(key, modifiers) };
+        }
+
+        let mods = 1
+            + u32::from(modifiers.shift)
+            + 2 * u32::from(modifiers.alt)
+            + 4 * u32::from(modifiers.ctrl)
+            + 8 * u32::from(modifiers.meta);
+        let kitty_key = match key {
+            Key::Char(c) => KittyKey::Code(c.to_lowercase().next().unwrap_or(*c) as u32),
+            Key::Enter => KittyKey::Code(13),
+            Key::Tab => KittyKey::Code(9),
+            Key::Backspace => KittyKey::Code(127),
+            Key::Escape => KittyKey::Code(27),
+            Key::Up => KittyKey::Legacy(1, 'A'),
+            Key::Down => KittyKey::Legacy(1, 'B'),
+            Key::Right => KittyKey::Legacy(1, 'C'),
+            Key::Left => KittyKey::Legacy(1, 'D'),
+            Key::Home => KittyKey::Legacy(1, 'H'),
+            Key::End => KittyKey::Legacy(1, 'F'),
+            Key::Insert => KittyKey::Legacy(2, '~'),
+            Key::Delete => KittyKey::Legacy(3, '~'),
+            Key::PageUp => KittyKey::Legacy(5, '~'),
+            Key::PageDown => KittyKey::Legacy(6, '~'),
+            Key::F(n) => match n {
+                1 => KittyKey::Legacy(1, 'P'),
+                2 => KittyKey::Legacy(1, 'Q'),
+                3 => KittyKey::Legacy(13, '~'),
+                4 => KittyKey::Legacy(1, 'S'),
+                5 => KittyKey::Legacy(15, '~'),
+                6..=10 => KittyKey::Legacy(*n as u32 + 11, '~'),
+                11 | 12 => KittyKey::Legacy(*n as u32 + 12, '~'),
+                // F13.. live in kitty's private-use range
+                13..=35 => KittyKey::Code(57376 + *n as u32 - 13),
+                _ => return Vec::new(),
+            },
+        };
+
+        // Keys that keep their legacy bytes on press
+        if !release && !flags.report_all_keys {
+            let plain = !modifiers.ctrl && !modifiers.alt && !modifiers.meta;
+            match (&kitty_key, key) {
+                (KittyKey::Code(_), Key::Char(c)) if plain => {
+                    return match text {
+                        Some(text) => text.as_bytes().to_vec(),
+                        None => c.to_string().into_bytes(),
+                    };
+                }
+                (KittyKey::Code(_), Key::Enter | Key::Tab | Key::Backspace) if mods == 1 => {
+                    return Self::key_to_bytes(key, modifiers);
+                }
+                (KittyKey::Legacy(..), _) if mods == 1 => return Self::key_to_bytes(key, modifiers),
+                _ => {}
+            }
+        }
+        // Enter/Tab/Backspace releases only with all keys as escape codes
+        if release && !flags.report_all_keys && matches!(key, Key::Enter | Key::Tab | Key::Backspace) {
+            return Vec::new();
+        }
+
+        let mut out = String::from("\x1b[");
+        let event = if release { ":3" } else { "" };
+        match kitty_key {
+            KittyKey::Code(code) => {
+                let _ = write!(out, "{}", code);
+                if let Key::Char(c) = key {
+                    let shifted = c.to_uppercase().next().unwrap_or(*c) as u32;
+                    if flags.report_alternates && modifiers.shift && shifted != code {
+                        let _ = write!(out, ":{}", shifted);
+                    }
+                }
+                let text = text
+                    .filter(|t| flags.report_text && flags.report_all_keys && !release && !t.chars().any(char::is_control))
+                    .filter(|t| !t.is_empty());
+                if mods > 1 || release || text.is_some() {
+                    out.push(';');
+                    if mods > 1 || release {
+                        let _ = write!(out, "{}{}", mods, event);
+                    }
+                }
+                if let Some(text) = text {
+                    out.push(';');
+                    let codepoints: Vec<String> = text.chars().map(|c| (c as u32).to_string()).collect();
+                    out.push_str(&codepoints.join(":"));
+                }
+                out.push('u');
+            }
+            KittyKey::Legacy(number, final_char) => {
+                if mods > 1 || release {
+                    let _ = write!(out, "{};{}{}", number, mods, event);
+                } else if number != 1 {
+                    let _ = write!(out, "{}", number);
+                }
+                out.push(final_char);
+            }
+        }
+        out.into_bytes()
+    }
+
     /// Convert a key event to the byte sequence that should be sent to the PTY
     pub fn key_to_bytes(key: &Key, modifiers: &Modifiers) -> Vec<u8> {
         match key {
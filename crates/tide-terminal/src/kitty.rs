@@ -0,0 +1,220 @@
+// Kitty graphics protocol: command parsing and pixel decoding
+//
+// `APC G <key>=<value>,... ; <base64 payload> ST`. Keys used here:
+//
+//   a  action: t transmit, T transmit and display, p display, d delete, q query
+//   f  format: 24 RGB, 32 RGBA (default), 100 PNG;   o=z  zlib-compressed
+//   t  medium: d direct (default), f file, t temporary file (deleted after)
+//   s v  pixel size of raw data;   S O  size and offset within a file
+//   i I p  image id, image number, placement id
+//   m  1 while more chunks follow;   q  1 suppresses OK, 2 also errors
+//   x y w h  source rectangle;   c r  display size in cells
+//   C  1 keeps the cursor in place;   z  z-index (< 0 draws below text)
+//   d  what to delete (see `graphics::ImageStore::delete`)
+//
+// Only `a`, `d`, `f`, `o`, `t` are letters; everything else is a number.
+
+use std::io::Read;
+
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+
+use super::graphics::{DecodedImage, MAX_IMAGE_SIDE};
+
+/// Accepts payloads with or without `=` padding, as kitty does.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &base64::alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Largest compressed or file payload accepted.
+const MAX_PAYLOAD: usize = 64 * 1024 * 1024;
+
+/// One parsed `APC G` command.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Command {
+    pub action: u8,
+    pub format: u32,
+    pub medium: u8,
+    pub compressed: bool,
+    pub width: u32,
+    pub height: u32,
+    pub file_size: usize,
+    pub file_offset: usize,
+    pub id: u32,
+    pub number: u32,
+    pub placement: u32,
+    pub more: bool,
+    pub quiet: u32,
+    pub source_x: u32,
+    pub source_y: u32,
+    pub source_w: u32,
+    pub source_h: u32,
+    pub cols: u32,
+    pub rows: u32,
+    pub keep_cursor: bool,
+    pub z: i32,
+    pub delete: u8,
+    /// Base64 text after the `;`.
+    pub payload: Vec<u8>,
+}
+
+impl Command {
+    /// Parse the APC body, starting with `G`. Unknown keys are ignored.
+    pub(crate) fn parse(data: &[u8]) -> Option<Self> {
+        let body = data.strip_prefix(b"G")?;
+        let (control, payload) = match body.iter().position(|&b| b == b';') {
+            Some(i) => (&body[..i], &body[i + 1..]),
+            None => (body, &[][..]),
+        };
+        let mut command = Command {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            delete: b'a',
+            payload: payload.to_vec(),
+            ..Default::default()
+        };
+        for pair in control.split(|&b| b == b',') {
+            let [key, b'=', value @ ..] = pair else { continue };
+            let letter = value.first().copied().unwrap_or(0);
+            let number = || std::str::from_utf8(value).ok().and_then(|v| v.parse::<i64>().ok()).unwrap_or(0);
+            let unsigned = || number().clamp(0, u32::MAX as i64) as u32;
+            match key {
+                b'a' => command.action = letter,
+                b'd' => command.delete = letter,
+                b't' => command.medium = letter,
+                b'o' => command.compressed = letter == b'z',
+                b'f' => command.format = unsigned(),
+                b's' => command.width = unsigned(),
+                b'v' => command.height = unsigned(),
+                b'S' => command.file_size = unsigned() as usize,
+                b'O' => command.file_offset = unsigned() as usize,
+                b'i' => command.id = unsigned(),
+                b'I' => command.number = unsigned(),
+                b'p' => command.placement = unsigned(),
+                b'm' => command.more = unsigned() == 1,
+                b'q' => command.quiet = unsigned(),
+                b'x' => command.source_x = unsigned(),
+                b'y' => command.source_y = unsigned(),
+                b'w' => command.source_w = unsigned(),
+                b'h' => command.source_h = unsigned(),
+                b'c' => command.cols = unsigned(),
+                b'r' => command.rows = unsigned(),
+                b'C' => command.keep_cursor = unsigned() == 1,
+                b'z' => command.z = number().clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+                _ => {}
+            }
+        }
+        Some(command)
+    }
+
+    /// Decode the transmitted image. `payload` is the whole base64 text when
+    /// the data came in chunks.
+    pub(crate) fn decode(&self, payload: &[u8]) -> Result<DecodedImage, &'static str> {
+        let payload: Vec<u8> = payload.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect();
+        let payload = BASE64.decode(payload).map_err(|_| "EINVAL:bad base64 data")?;
+        let data = match self.medium {
+            b'd' => payload,
+            b'f' | b't' => self.read_file(&payload)?,
+            _ => return Err("EINVAL:unsupported transmission medium"),
+        };
+        let data = if self.compressed { inflate(&data)? } else { data };
+        match self.format {
+            24 | 32 => self.raw_pixels(data),
+            100 => decode_png(&data),
+            _ => Err("EINVAL:unsupported format"),
+        }
+    }
+
+    fn read_file(&self, path: &[u8]) -> Result<Vec<u8>, &'static str> {
+        use std::io::{Seek, SeekFrom};
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let path = std::str::from_utf8(path).map_err(|_| "EINVAL:bad file name")?;
+        // Checks apply to the real file, after `..` and links are resolved.
+        let path = std::fs::canonicalize(path).map_err(|_| "EBADF:cannot open file")?;
+        if ["/proc", "/sys", "/dev"].iter().any(|dir| path.starts_with(dir)) {
+            return Err("EPERM:not a regular file");
+        }
+        // Non-blocking, so a FIFO swapped in for the file can't hang the reader.
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&path)
+            .map_err(|_| "EBADF:cannot open file")?;
+        if !file.metadata().is_ok_and(|m| m.is_file()) {
+            return Err("EPERM:not a regular file");
+        }
+        if self.file_offset > 0 {
+            file.seek(SeekFrom::Start(self.file_offset as u64)).map_err(|_| "EBADF:cannot seek")?;
+        }
+        let limit = if self.file_size > 0 { self.file_size.min(MAX_PAYLOAD) } else { MAX_PAYLOAD };
+        let mut data = Vec::new();
+        file.take(limit as u64).read_to_end(&mut data).map_err(|_| "EBADF:cannot read file")?;
+        // Temporary files are ours to remove, but only ones made for this
+        // protocol, directly in the temp directory.
+        let in_temp_dir = std::fs::canonicalize(std::env::temp_dir())
+            .is_ok_and(|temp| path.parent() == Some(temp.as_path()));
+        let protocol_file =
+            path.file_name().is_some_and(|name| name.to_string_lossy().contains("tty-graphics-protocol"));
+        if self.medium == b't' && in_temp_dir && protocol_file {
+            let _ = std::fs::remove_file(&path);
+        }
+        Ok(data)
+    }
+
+    fn raw_pixels(&self, data: Vec<u8>) -> Result<DecodedImage, &'static str> {
+        let (width, height) = (self.width, self.height);
+        if width == 0 || height == 0 {
+            return Err("EINVAL:missing image size");
+        }
+        if width as usize > MAX_IMAGE_SIDE || height as usize > MAX_IMAGE_SIDE {
+            return Err("EINVAL:image too large");
+        }
+        let channels = if self.format == 24 { 3 } else { 4 };
+        let len = width as usize * height as usize * channels;
+        if data.len() < len {
+            return Err("ENODATA:insufficient image data");
+        }
+        let rgba = if channels == 4 {
+            let mut data = data;
+            data.truncate(len);
+            data
+        } else {
+            data[..len].chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 0xff]).collect()
+        };
+        Ok(DecodedImage { width, height, rgba })
+    }
+}
+
+fn inflate(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let mut out = Vec::new();
+    flate2::read::ZlibDecoder::new(data)
+        .take(MAX_PAYLOAD as u64)
+        .read_to_end(&mut out)
+        .map_err(|_| "EINVAL:bad zlib data")?;
+    Ok(out)
+}
+
+fn decode_png(data: &[u8]) -> Result<DecodedImage, &'static str> {
+    let limits = png::Limits { bytes: MAX_PAYLOAD };
+    let mut decoder = png::Decoder::new_with_limits(std::io::Cursor::new(data), limits);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|_| "EINVAL:bad png data")?;
+    let (width, height) = reader.info().size();
+    if width as usize > MAX_IMAGE_SIDE || height as usize > MAX_IMAGE_SIDE {
+        return Err("EINVAL:image too large");
+    }
+    let mut buf = vec![0; reader.output_buffer_size().ok_or("EINVAL:image too large")?];
+    let info = reader.next_frame(&mut buf).map_err(|_| "EINVAL:bad png data")?;
+    buf.truncate(info.buffer_size());
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 0xff]).collect(),
+        png::ColorType::GrayscaleAlpha => buf.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&v| [v, v, v, 0xff]).collect(),
+        png::ColorType::Indexed => return Err("EINVAL:unsupported png"),
+    };
+    Ok(DecodedImage { width: info.width, height: info.height, rgba })
+}
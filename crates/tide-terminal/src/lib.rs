@@ -10,33 +10,90 @@
 // so input events are never blocked by terminal output processing.
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Instant;
 
-use alacritty_terminal::event::{Event, EventListener, WindowSize};
+use alacritty_terminal::event::{CursorAdvance, Event, EventListener, Graphics, GraphicsCursor, WindowSize};
 use alacritty_terminal::event_loop::{EventLoop, Msg, Notifier};
 use alacritty_terminal::grid::{Dimensions, Scroll};
 use alacritty_terminal::index::{Column, Line, Point};
 use alacritty_terminal::sync::FairMutex;
-use alacritty_terminal::term::cell::Flags as CellFlags;
-use alacritty_terminal::term::{Config as TermConfig, Term, TermMode};
+use alacritty_terminal::term::cell::{Flags as CellFlags, Hyperlink};
+use alacritty_terminal::term::{Config as TermConfig, Osc52, Term, TermMode};
 use alacritty_terminal::tty;
 use alacritty_terminal::vte::ansi::{Color as AnsiColor, NamedColor, Rgb as AnsiRgb};
 
 pub mod git;
+mod attention;
+mod cast;
+mod clipboard;
 mod color;
+mod copy_mode;
+mod graphics;
+mod headless;
 mod key_input;
+mod kitty;
+mod mouse;
+mod replay;
+mod restore;
+mod scheme;
+mod search;
+mod selection;
+mod shell_integration;
+mod sixel;
+mod title;
+mod tmux;
+mod triggers;
+
+pub use cast::{Cast, CastEvent};
+use attention::Attention;
+use cast::Recording;
+pub use clipboard::{ClipboardReply, ClipboardRequest};
+use clipboard::ClipboardQueue;
+pub use copy_mode::{CopyModeView, CopyMotion, CopySelection};
+pub use selection::{SelectionKind, DEFAULT_WORD_SEPARATORS};
+pub use graphics::{TermImage, VisibleImage};
+use graphics::{ImageDecoder, ImageStore};
+pub use headless::{HeadlessSnapshot, HeadlessTerminal};
+pub use key_input::{KeyEventKind, KeyboardFlags};
+pub use mouse::{MouseEncoding, MouseProtocol, MouseReport, MouseTracking};
+pub use replay::CastPlayer;
+pub use scheme::{parse_hex_color, parse_scheme_file, ColorScheme, SchemePair};
+pub use search::{BufferMatch, SearchOptions, MAX_SEARCH_MATCHES};
+pub use shell_integration::{CommandRegion, CommandStatus, PromptMark};
+use shell_integration::ShellMarks;
+use title::TermTitles;
+pub use triggers::{Trigger, TriggerAction, TriggerEvent};
+use triggers::{TriggerScanner, TriggerSet};
+pub use tmux::{TmuxEvent, TmuxHandle, TmuxLayout, TmuxWindow};
+use tmux::{TmuxControl, TmuxPaneLink};
 
 use tide_core::{
-    Color, CursorShape, CursorState, TerminalBackend, TerminalCell, TerminalGrid,
+    Color, CursorShape, CursorState, TerminalBackend, TerminalCell, TerminalGrid, TextStyle, UnderlineStyle,
 };
 
-/// Number of scrollback history lines to keep.
-const SCROLLBACK_LINES: usize = 10_000;
+/// Scrollback history lines kept when no setting overrides it.
+pub const DEFAULT_SCROLLBACK_LINES: usize = 10_000;
+
+/// Cell size in device pixels reported to the PTY until the app sets one.
+const DEFAULT_CELL_PIXELS: (u16, u16) = (8, 16);
+
+/// Upper bound for `set_scrollback_lines` (~1 GB of cells at 200 columns).
+pub const MAX_SCROLLBACK_LINES: usize = 1_000_000;
+
+/// Environment variable holding a shell's `terminal_id`, so programs run in
+/// it can tell the app which terminal they came from.
+pub const TERMINAL_ID_ENV: &str = "TIDE_TERMINAL_ID";
+
+/// Source of `terminal_id`s, unique within the process.
+static NEXT_TERMINAL_ID: AtomicU64 = AtomicU64::new(1);
 
 /// Simple dimensions struct that implements alacritty_terminal's Dimensions trait.
+/// Only the screen size matters here: alacritty sizes history from
+/// `TermConfig::scrolling_history`.
 struct TermDimensions {
     cols: usize,
     rows: usize,
@@ -58,7 +115,7 @@ impl Dimensions for TermDimensions {
     }
 
     fn total_lines(&self) -> usize {
-        self.rows + SCROLLBACK_LINES
+        self.rows
     }
 }
 
@@ -70,8 +127,12 @@ struct SharedSnapshot {
     grid: TerminalGrid,
     inverse_cursor: Option<(u16, u16)>,
     url_ranges: Vec<Vec<(usize, usize)>>,
+    prompt_marks: Vec<PromptMark>,
+    images: Vec<VisibleImage>,
     generation: u64,
     cursor: CursorState,
+    copy_mode: Option<CopyModeView>,
+    selection_spans: Vec<(usize, usize, usize)>,
 }
 
 // ──────────────────────────────────────────────
@@ -80,7 +141,7 @@ struct SharedSnapshot {
 
 /// Event listener that sets a dirty flag when the terminal has new output,
 /// forwards PtyWrite events back to the PTY, and wakes the sync thread.
-#[derive(Clone)]
+#[derive(Clone, Default)]
 struct TermEventListener {
     dirty: Arc<AtomicBool>,
     /// Lazily initialized after EventLoop creation so PtyWrite can be forwarded.
@@ -89,11 +150,41 @@ struct TermEventListener {
     sync_thread: Arc<Mutex<Option<std::thread::Thread>>>,
     /// Dark/light mode — used to resolve OSC 10/11 color queries.
     dark_mode: Arc<AtomicBool>,
+    /// Dark/light color schemes — answer OSC 4/10/11/12 queries.
+    schemes: Arc<Mutex<SchemePair>>,
     /// Mode 2031: app opted in to dark/light color-scheme notifications.
     mode_2031: Arc<AtomicBool>,
+    /// Mode 1015: urxvt mouse encoding (not a named mode in vte).
+    urxvt_mouse: Arc<AtomicBool>,
+    /// OSC 133 shell integration marks (command regions).
+    shell_marks: Arc<Mutex<ShellMarks>>,
+    /// Program-set title and icon name (OSC 0/1/2).
+    titles: Arc<Mutex<TermTitles>>,
+    /// Bell and desktop notification counters.
+    attention: Arc<Attention>,
+    /// OSC 52 clipboard requests waiting for the app.
+    clipboard: Arc<Mutex<ClipboardQueue>>,
+    /// Kitty graphics and sixel images.
+    images: Arc<Mutex<ImageStore>>,
+    /// Images decoded ahead of the term lock, waiting for `graphics`.
+    decoder: Arc<Mutex<ImageDecoder>>,
+    /// A tmux pane's output comes from tmux, which answers queries itself.
+    suppress_replies: bool,
 }
 
 impl TermEventListener {
+    /// Answer a query from the program (device attributes, colors, ...).
+    fn reply(&self, bytes: Vec<u8>) {
+        if self.suppress_replies {
+            return;
+        }
+        if let Ok(guard) = self.pty_writer.lock() {
+            if let Some(notifier) = guard.as_ref() {
+                let _ = notifier.0.send(Msg::Input(Cow::Owned(bytes)));
+            }
+        }
+    }
+
     /// Resolve a color index to an RGB value for OSC 10/11/12 responses.
     ///
     /// Index mapping (from vte/alacritty_terminal):
@@ -104,50 +195,26 @@ impl TermEventListener {
     ///   258    = Cursor     (OSC 12)
     fn resolve_color(&self, index: usize) -> AnsiRgb {
         let dark = self.dark_mode.load(Ordering::Relaxed);
-        match index {
+        let chosen = self.schemes.lock().ok().and_then(|s| s.for_mode(dark));
+        // Without a chosen scheme, OSC 11 reports the visible pane background
+        let scheme = chosen.as_ref().unwrap_or(ColorScheme::builtin(dark));
+        let color = match index {
             // Foreground (OSC 10)
-            256 => {
-                if dark {
-                    AnsiRgb { r: 230, g: 232, b: 242 } // dark fg: (0.9, 0.91, 0.95)
-                } else {
-                    AnsiRgb { r: 26, g: 20, b: 13 }    // light fg: (0.10, 0.08, 0.05)
-                }
-            }
-            // Background (OSC 11) — report the actual visible pane background
-            257 => {
-                if dark {
-                    AnsiRgb { r: 14, g: 14, b: 16 }    // dark pane_bg: (0.055, 0.055, 0.063)
-                } else {
-                    AnsiRgb { r: 240, g: 235, b: 227 }  // light pane_bg: (0.94, 0.92, 0.89)
-                }
-            }
-            // Cursor (OSC 12) — use foreground color
-            258 => {
-                if dark {
-                    AnsiRgb { r: 230, g: 232, b: 242 }
-                } else {
-                    AnsiRgb { r: 26, g: 20, b: 13 }
-                }
-            }
+            256 => scheme.foreground,
+            // Background (OSC 11)
+            257 => scheme.background,
+            // Cursor (OSC 12)
+            258 => scheme.cursor_color(),
             // Named ANSI colors (0-15)
-            0..=15 => {
-                let color = Terminal::named_color_to_rgb(dark, Terminal::index_to_named(index as u8));
-                AnsiRgb {
-                    r: (color.r * 255.0) as u8,
-                    g: (color.g * 255.0) as u8,
-                    b: (color.b * 255.0) as u8,
-                }
-            }
+            0..=15 => scheme.ansi[index],
             // 256-color palette (16-255)
-            16..=255 => {
-                let color = Terminal::indexed_color_fallback(index as u8);
-                AnsiRgb {
-                    r: (color.r * 255.0) as u8,
-                    g: (color.g * 255.0) as u8,
-                    b: (color.b * 255.0) as u8,
-                }
-            }
-            _ => AnsiRgb { r: 0, g: 0, b: 0 },
+            16..=255 => Terminal::indexed_color_fallback(index as u8),
+            _ => Color::BLACK,
+        };
+        AnsiRgb {
+            r: (color.r * 255.0).round() as u8,
+            g: (color.g * 255.0).round() as u8,
+            b: (color.b * 255.0).round() as u8,
         }
     }
 }
@@ -156,20 +223,11 @@ impl EventListener for TermEventListener {
     fn send_event(&self, event: Event) {
         match &event {
             Event::PtyWrite(text) => {
-                if let Ok(guard) = self.pty_writer.lock() {
-                    if let Some(notifier) = guard.as_ref() {
-                        let _ = notifier.0.send(Msg::Input(Cow::Owned(text.clone().into_bytes())));
-                    }
-                }
+                self.reply(text.clone().into_bytes());
             }
             Event::ColorRequest(index, formatter) => {
                 let rgb = self.resolve_color(*index);
-                let response = formatter(rgb);
-                if let Ok(guard) = self.pty_writer.lock() {
-                    if let Some(notifier) = guard.as_ref() {
-                        let _ = notifier.0.send(Msg::Input(Cow::Owned(response.into_bytes())));
-                    }
-                }
+                self.reply(formatter(rgb).into_bytes());
                 return; // No need to mark dirty or wake sync thread
             }
             Event::PrivateModeUpdate(2031, enabled) => {
@@ -178,15 +236,53 @@ impl EventListener for TermEventListener {
                 // Immediately report current mode: CSI ? 997 ; N n (1=dark, 2=light)
                 if *enabled {
                     let mode = if self.dark_mode.load(Ordering::Relaxed) { 1 } else { 2 };
-                    let response = format!("\x1b[?997;{}n", mode);
-                    if let Ok(guard) = self.pty_writer.lock() {
-                        if let Some(notifier) = guard.as_ref() {
-                            let _ = notifier.0.send(Msg::Input(Cow::Owned(response.into_bytes())));
-                        }
-                    }
+                    self.reply(format!("\x1b[?997;{}n", mode).into_bytes());
                 }
                 return;
             }
+            Event::PrivateModeUpdate(1015, enabled) => {
+                self.urxvt_mouse.store(*enabled, Ordering::Relaxed);
+                return;
+            }
+            Event::Bell => self.attention.bell(),
+            Event::ClipboardStore(_, text) => {
+                if let Ok(mut queue) = self.clipboard.lock() {
+                    queue.push(ClipboardRequest::Store(text.clone()));
+                }
+            }
+            Event::ClipboardLoad(_, formatter) => {
+                if let Ok(mut queue) = self.clipboard.lock() {
+                    queue.push(ClipboardRequest::Load(ClipboardReply(formatter.clone())));
+                }
+            }
+            Event::Title(title) => {
+                if let Ok(mut titles) = self.titles.lock() {
+                    titles.set_title(Some(title));
+                }
+                return;
+            }
+            Event::ResetTitle => {
+                if let Ok(mut titles) = self.titles.lock() {
+                    titles.set_title(None);
+                }
+                return;
+            }
+            Event::UnhandledOsc { payload, line, column } => {
+                if self.titles.lock().is_ok_and(|mut titles| titles.apply_osc(payload))
+                    || self.attention.apply_osc(payload)
+                {
+                    return;
+                }
+                if let Ok(mut marks) = self.shell_marks.lock() {
+                    marks.apply(payload, *line, *column);
+                }
+                return;
+            }
+            Event::GraphicsCleared { alt_screen, lines } => {
+                if let Ok(mut images) = self.images.lock() {
+                    images.clear(*alt_screen, lines.clone());
+                }
+            }
             _ => {}
         }
         self.dirty.store(true, Ordering::Relaxed);
@@ -197,16 +293,47 @@ impl EventListener for TermEventListener {
             }
         }
     }
+
+    fn prepare_graphics(&self, graphics: &Graphics) {
+        if let Ok(mut decoder) = self.decoder.lock() {
+            decoder.prepare(graphics);
+        }
+    }
+
+    fn graphics(&self, graphics: Graphics, at: GraphicsCursor) -> Option<CursorAdvance> {
+        let decoded = self.decoder.lock().ok().and_then(|mut decoder| decoder.take(&graphics));
+        let (advance, reply) = match self.images.lock() {
+            Ok(mut images) => images.apply(graphics, at, decoded),
+            Err(_) => return None,
+        };
+        if let Some(reply) = reply {
+            self.reply(reply.into_bytes());
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+        if let Ok(guard) = self.sync_thread.lock() {
+            if let Some(ref thread) = *guard {
+                thread.unpark();
+            }
+        }
+        advance
+    }
 }
 
 // ──────────────────────────────────────────────
 // GridSyncer: owns all state for grid synchronization (runs on sync thread)
 // ──────────────────────────────────────────────
 
+/// Per-cell (char, fg, bg, flags, link id, underline color) copied under the lock.
+type RawCell = (char, AnsiColor, AnsiColor, CellFlags, u32, Option<AnsiColor>);
+
 struct GridSyncer {
     term: Arc<FairMutex<Term<TermEventListener>>>,
-    raw_buf: Vec<(char, AnsiColor, AnsiColor, CellFlags)>,
-    prev_raw_buf: Vec<(char, AnsiColor, AnsiColor, CellFlags)>,
+    raw_buf: Vec<RawCell>,
+    prev_raw_buf: Vec<RawCell>,
+    /// OSC 8 hyperlinks seen this frame; link id N = `link_buf[N - 1]`.
+    link_buf: Vec<Hyperlink>,
+    /// Id of each hyperlink in `link_buf`, so interning a cell's link is a lookup.
+    link_ids: HashMap<Hyperlink, u32>,
     palette_buf: [Option<AnsiRgb>; 256],
     grid: TerminalGrid,
     inverse_cursor: Option<(u16, u16)>,
@@ -216,24 +343,88 @@ struct GridSyncer {
     url_row_buf: String,
     dark_mode: Arc<AtomicBool>,
     dark_mode_changed: Arc<AtomicBool>,
+    /// Dark/light color schemes; None = the built-in palette for that mode.
+    schemes: Arc<Mutex<SchemePair>>,
     stay_at_bottom: Arc<AtomicBool>,
+    /// OSC 133 regions, read to place prompt markers in the viewport.
+    shell_marks: Arc<Mutex<ShellMarks>>,
+    prompt_marks: Vec<PromptMark>,
+    prompt_marks_buf: Vec<PromptMark>,
+    /// Inline images, read to find the ones in the viewport.
+    images: Arc<Mutex<ImageStore>>,
+    visible_images: Vec<VisibleImage>,
+    visible_images_buf: Vec<VisibleImage>,
+    /// Output trigger rules and fired events (shared with `Terminal`).
+    triggers: Arc<Mutex<TriggerSet>>,
+    trigger_scan: TriggerScanner,
+    /// Copy mode cursor, read under the term lock.
+    copy_mode: Option<CopyModeView>,
+    /// Selected cells of the viewport, read under the term lock.
+    selection_spans: Vec<(usize, usize, usize)>,
+    selection_spans_buf: Vec<(usize, usize, usize)>,
 }
 
 impl GridSyncer {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        term: Arc<FairMutex<Term<TermEventListener>>>,
+        cols: u16,
+        rows: u16,
+        dark_mode: Arc<AtomicBool>,
+        dark_mode_changed: Arc<AtomicBool>,
+        schemes: Arc<Mutex<SchemePair>>,
+        stay_at_bottom: Arc<AtomicBool>,
+        shell_marks: Arc<Mutex<ShellMarks>>,
+        images: Arc<Mutex<ImageStore>>,
+        triggers: Arc<Mutex<TriggerSet>>,
+    ) -> Self {
+        Self {
+            term,
+            raw_buf: Vec::new(),
+            prev_raw_buf: Vec::new(),
+            palette_buf: [None; 256],
+            grid: Terminal::build_empty_grid(cols, rows),
+            inverse_cursor: None,
+            cached_cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block },
+            url_ranges: Vec::new(),
+            grid_generation: 0,
+            url_row_buf: String::new(),
+            link_buf: Vec::new(),
+            link_ids: HashMap::new(),
+            dark_mode,
+            dark_mode_changed,
+            schemes,
+            stay_at_bottom,
+            shell_marks,
+            prompt_marks: Vec::new(),
+            prompt_marks_buf: Vec::new(),
+            images,
+            visible_images: Vec::new(),
+            visible_images_buf: Vec::new(),
+            triggers,
+            trigger_scan: TriggerScanner::default(),
+            copy_mode: None,
+            selection_spans: Vec::new(),
+            selection_spans_buf: Vec::new(),
+        }
+    }
+
     /// Run one grid synchronization cycle.
     /// Phase 1: Lock Term briefly to copy raw cell data + palette.
     /// Phase 2: Convert colors and diff against previous frame (no lock held).
     fn sync(&mut self) {
-        // Check if dark mode changed — force full re-render
+        // Check if dark mode or the color scheme changed — force full re-render
         if self.dark_mode_changed.swap(false, Ordering::Relaxed) {
             self.prev_raw_buf.clear();
         }
 
         let dark_mode = self.dark_mode.load(Ordering::Relaxed);
+        let scheme = self.schemes.lock().ok().and_then(|s| s.for_mode(dark_mode));
+        let scheme = scheme.as_ref();
         let stay_at_bottom = self.stay_at_bottom.load(Ordering::Relaxed);
 
         // Phase 1: Hold lock briefly — copy raw cell data + palette + cursor
-        let (cols, total_lines) = {
+        let (cols, total_lines, viewport_top, alt_screen, first_line, selection_changed) = {
             let mut term = self.term.lock();
 
             if stay_at_bottom {
@@ -255,15 +446,30 @@ impl GridSyncer {
             // Copy raw cell data into flat buffer
             self.raw_buf.resize(
                 total_cells,
-                (' ', AnsiColor::Named(NamedColor::Foreground), AnsiColor::Named(NamedColor::Background), CellFlags::empty()),
+                (' ', AnsiColor::Named(NamedColor::Foreground), AnsiColor::Named(NamedColor::Background), CellFlags::empty(), 0, None),
             );
+            self.link_buf.clear();
+            self.link_ids.clear();
             for line_idx in 0..total_lines {
                 let line = Line(line_idx as i32 - display_offset as i32);
                 let base = line_idx * cols;
                 for col_idx in 0..cols {
                     let point = Point::new(line, Column(col_idx));
                     let cell = &grid[point];
-                    self.raw_buf[base + col_idx] = (cell.c, cell.fg, cell.bg, cell.flags);
+                    // Intern OSC 8 hyperlinks so cells carry a small id instead of the URI
+                    let link = match cell.hyperlink() {
+                        Some(hyperlink) => match self.link_ids.get(&hyperlink) {
+                            Some(&id) => id,
+                            None => {
+                                self.link_buf.push(hyperlink.clone());
+                                let id = self.link_buf.len() as u32;
+                                self.link_ids.insert(hyperlink, id);
+                                id
+                            }
+                        },
+                        None => 0,
+                    };
+                    self.raw_buf[base + col_idx] = (cell.c, cell.fg, cell.bg, cell.flags, link, cell.underline_color());
                 }
             }
 
@@ -284,9 +490,49 @@ impl GridSyncer {
                 shape: cursor_shape,
             };
 
-            (cols, total_lines)
+            let viewport_top = grid.scrolled_lines().saturating_sub(display_offset as u64);
+            let first_line = grid.scrolled_lines().saturating_sub(grid.history_size() as u64);
+            let alt_screen = term.mode().contains(TermMode::ALT_SCREEN);
+
+            let copy_mode = copy_mode::view(&term);
+            selection::viewport_spans(&term, &mut self.selection_spans_buf);
+            let selection_changed = copy_mode != self.copy_mode || self.selection_spans_buf != self.selection_spans;
+            self.copy_mode = copy_mode;
+            std::mem::swap(&mut self.selection_spans, &mut self.selection_spans_buf);
+
+            // Lines finished since the last sync, for the output triggers
+            if let Ok(mut triggers) = self.triggers.lock() {
+                self.trigger_scan.collect(&term, &mut triggers);
+            }
+            (cols, total_lines, viewport_top, alt_screen, first_line, selection_changed)
         }; // Lock released here!
 
+        // Match them now that the PTY thread can run again
+        if let Ok(mut triggers) = self.triggers.lock() {
+            self.trigger_scan.match_lines(&mut triggers);
+        }
+        self.trigger_scan.prune(first_line);
+
+        // Prompt markers: taken after the term lock is released (the PTY
+        // thread locks shell_marks while holding the term lock).
+        if let Ok(marks) = self.shell_marks.lock() {
+            marks.visible_marks(viewport_top, total_lines, &mut self.prompt_marks_buf);
+        }
+        let marks_changed = self.prompt_marks_buf != self.prompt_marks;
+        if marks_changed {
+            std::mem::swap(&mut self.prompt_marks, &mut self.prompt_marks_buf);
+        }
+
+        // Inline images, likewise outside the term lock.
+        if let Ok(mut images) = self.images.lock() {
+            images.prune(alt_screen, first_line);
+            images.visible(viewport_top, total_lines, alt_screen, &mut self.visible_images_buf);
+        }
+        let images_changed = self.visible_images_buf != self.visible_images;
+        if images_changed {
+            std::mem::swap(&mut self.visible_images, &mut self.visible_images_buf);
+        }
+
         // Phase 2: Diff with previous frame — only convert changed cells
         let total_cells = cols * total_lines;
         let same_size = self.prev_raw_buf.len() == total_cells;
@@ -318,6 +564,9 @@ impl GridSyncer {
         cells.resize_with(total_lines, || vec![TerminalCell::default(); cols]);
 
         let mut any_changed = false;
+        // Cells painted by triggers last time (sorted) are re-converted even
+        // when unchanged; `paint` redoes the highlights that are still in view.
+        let mut painted = self.trigger_scan.painted.iter().peekable();
 
         for (line_idx, row) in cells.iter_mut().enumerate().take(total_lines) {
             row.resize_with(cols, TerminalCell::default);
@@ -327,28 +576,35 @@ impl GridSyncer {
                 let idx = base + col_idx;
                 let raw = self.raw_buf[idx];
 
-                // Skip unchanged cells (same char, fg, bg, flags)
-                if same_size && self.prev_raw_buf[idx] == raw {
+                // Skip unchanged cells (same char, fg, bg, flags, link, underline color)
+                let unchanged = same_size && self.prev_raw_buf[idx] == raw;
+                let repaint = painted.next_if_eq(&&idx).is_some();
+                if unchanged && !repaint {
                     continue;
                 }
-                any_changed = true;
+                any_changed |= !unchanged;
 
-                let (c, fg, bg, flags) = raw;
+                let (c, fg, bg, flags, link, underline_color) = raw;
+                tc.link = link;
+                // Wide-char spacers carry the decorations too, so lines span both cells
+                let underline_color =
+                    underline_color.map(|color| Terminal::convert_color(dark_mode, scheme, &color, &self.palette_buf));
+                set_decorations(&mut tc.style, flags, underline_color);
 
                 if flags.contains(CellFlags::WIDE_CHAR_SPACER) {
                     tc.character = '\0';
                     // Preserve background for selection/ANSI highlights on
                     // the second half of wide characters (Korean, CJK, etc.).
-                    let mut bg_color = Terminal::convert_color(dark_mode, &bg, &self.palette_buf);
+                    let mut bg_color = Terminal::convert_color(dark_mode, scheme, &bg, &self.palette_buf);
                     let mut bg_is_default = matches!(bg, AnsiColor::Named(NamedColor::Background));
                     if flags.contains(CellFlags::INVERSE) {
-                        let fg_color = Terminal::convert_color(dark_mode, &fg, &self.palette_buf);
+                        let fg_color = Terminal::convert_color(dark_mode, scheme, &fg, &self.palette_buf);
                         bg_color = fg_color;
                         bg_is_default = false;
                     }
                     // Remap mismatched true-color backgrounds (see main cell path below).
                     let effective_bg = if flags.contains(CellFlags::INVERSE) { &fg } else { &bg };
-                    if !bg_is_default {
+                    if !bg_is_default && scheme.is_none() {
                         if let AnsiColor::Spec(_) = effective_bg {
                             let bg_lum = 0.2126 * bg_color.r + 0.7152 * bg_color.g + 0.0722 * bg_color.b;
                             if !dark_mode && bg_lum < 0.5 {
@@ -362,8 +618,8 @@ impl GridSyncer {
                     continue;
                 }
 
-                let mut fg_color = Terminal::convert_color(dark_mode, &fg, &self.palette_buf);
-                let mut bg_color = Terminal::convert_color(dark_mode, &bg, &self.palette_buf);
+                let mut fg_color = Terminal::convert_color(dark_mode, scheme, &fg, &self.palette_buf);
+                let mut bg_color = Terminal::convert_color(dark_mode, scheme, &bg, &self.palette_buf);
                 let mut bg_is_default = matches!(bg, AnsiColor::Named(NamedColor::Background));
 
                 // SGR 7: swap foreground and background
@@ -377,8 +633,10 @@ impl GridSyncer {
                 // or Mode 2031 send dark bgs in light mode (or bright bgs in dark
                 // mode). Remap them to theme-appropriate equivalents.
                 // Named/indexed colors are already mode-aware via our palette.
+                // A color scheme brings its own background, so leave colors
+                // as the program sent them.
                 let effective_bg = if flags.contains(CellFlags::INVERSE) { &fg } else { &bg };
-                if !bg_is_default {
+                if !bg_is_default && scheme.is_none() {
                     if let AnsiColor::Spec(_) = effective_bg {
                         let bg_lum = 0.2126 * bg_color.r + 0.7152 * bg_color.g + 0.0722 * bg_color.b;
                         if !dark_mode && bg_lum < 0.5 {
@@ -389,10 +647,13 @@ impl GridSyncer {
                     }
                 }
 
-                if dark_mode {
-                    fg_color = Terminal::ensure_dark_fg_contrast(fg_color);
-                } else {
-                    fg_color = Terminal::ensure_light_fg_contrast(fg_color);
+                // Scheme colors are already chosen against the scheme's background
+                if scheme.is_none() {
+                    if dark_mode {
+                        fg_color = Terminal::ensure_dark_fg_contrast(fg_color);
+                    } else {
+                        fg_color = Terminal::ensure_light_fg_contrast(fg_color);
+                    }
                 }
 
                 let background = if bg_is_default {
@@ -405,9 +666,6 @@ impl GridSyncer {
                 tc.style.bold = flags.contains(CellFlags::BOLD);
                 tc.style.dim = flags.contains(CellFlags::DIM);
                 tc.style.italic = flags.contains(CellFlags::ITALIC);
-                tc.style.underline = flags.contains(CellFlags::UNDERLINE)
-                    || flags.contains(CellFlags::DOUBLE_UNDERLINE)
-                    || flags.contains(CellFlags::UNDERCURL);
 
                 tc.style.foreground = if tc.style.dim {
                     Color::new(fg_color.r * 0.65, fg_color.g * 0.65, fg_color.b * 0.65, fg_color.a)
@@ -421,11 +679,28 @@ impl GridSyncer {
         // Swap buffers for next frame's diff
         std::mem::swap(&mut self.prev_raw_buf, &mut self.raw_buf);
 
-        if any_changed || !same_size {
-            self.grid_generation += 1;
+        // Publish the hyperlink URI table. Ids are assigned in scan order, so an
+        // unchanged screen keeps its ids; only a changed URI forces a new frame.
+        let links_changed = self.grid.links.len() != self.link_buf.len()
+            || self.grid.links.iter().zip(&self.link_buf).any(|(uri, h)| uri != h.uri());
+        if links_changed {
+            self.grid.links.clear();
+            self.grid.links.extend(self.link_buf.iter().map(|h| h.uri().to_string()));
+            any_changed = true;
         }
 
         cells.truncate(total_lines);
+        if alt_screen {
+            any_changed |= !self.trigger_scan.painted.is_empty();
+            self.trigger_scan.painted.clear();
+        } else {
+            any_changed |= self.trigger_scan.paint(cells, viewport_top);
+        }
+
+        if any_changed || !same_size || marks_changed || images_changed || selection_changed {
+            self.grid_generation += 1;
+        }
+
         self.grid.cols = cols as u16;
         self.grid.rows = total_lines as u16;
 
@@ -436,6 +711,7 @@ impl GridSyncer {
     }
 
     /// Detect URLs in the grid and store column ranges per row.
+    /// OSC 8 hyperlink spans come first; regex matches overlapping them are skipped.
     fn detect_urls(&mut self) {
         static URL_RE: OnceLock<regex::Regex> = OnceLock::new();
         let re = URL_RE.get_or_init(|| {
@@ -446,8 +722,24 @@ impl GridSyncer {
         self.url_ranges.resize(rows, Vec::new());
 
         for (row_idx, row) in self.grid.cells.iter().enumerate() {
-            self.url_ranges[row_idx].clear();
+            let ranges = &mut self.url_ranges[row_idx];
+            ranges.clear();
             self.url_row_buf.clear();
+
+            // Explicit hyperlinks: runs of consecutive cells with the same link id
+            let mut col = 0;
+            while col < row.len() {
+                let link = row[col].link;
+                let start_col = col;
+                while col < row.len() && row[col].link == link {
+                    col += 1;
+                }
+                if link != 0 {
+                    ranges.push((start_col, col));
+                }
+            }
+            let link_spans = ranges.len();
+
             for c in row.iter() {
                 self.url_row_buf.push(if c.character == '\0' { ' ' } else { c.character });
             }
@@ -455,13 +747,40 @@ impl GridSyncer {
                 let url = trim_url_trailing(m.as_str());
                 let start_col = self.url_row_buf[..m.start()].chars().count();
                 let end_col = start_col + url.chars().count();
-                self.url_ranges[row_idx].push((start_col, end_col));
+                let overlaps_link = ranges[..link_spans]
+                    .iter()
+                    .any(|&(s, e)| start_col < e && s < end_col);
+                if !overlaps_link {
+                    ranges.push((start_col, end_col));
+                }
+            }
+            if link_spans > 0 {
+                ranges.sort_unstable();
             }
         }
         self.url_ranges.truncate(rows);
     }
 }
 
+/// SGR 4 underline shape, SGR 58 underline color and SGR 9 strikethrough.
+fn set_decorations(style: &mut TextStyle, flags: CellFlags, underline_color: Option<Color>) {
+    style.underline = if flags.contains(CellFlags::UNDERCURL) {
+        UnderlineStyle::Curly
+    } else if flags.contains(CellFlags::DOUBLE_UNDERLINE) {
+        UnderlineStyle::Double
+    } else if flags.contains(CellFlags::DOTTED_UNDERLINE) {
+        UnderlineStyle::Dotted
+    } else if flags.contains(CellFlags::DASHED_UNDERLINE) {
+        UnderlineStyle::Dashed
+    } else if flags.contains(CellFlags::UNDERLINE) {
+        UnderlineStyle::Single
+    } else {
+        UnderlineStyle::None
+    };
+    style.underline_color = underline_color;
+    style.strikethrough = flags.contains(CellFlags::STRIKEOUT);
+}
+
 /// Trim unbalanced trailing parentheses and punctuation from a URL match.
 /// Preserves balanced parens (e.g. Wikipedia URLs like `https://en.wikipedia.org/wiki/Foo_(bar)`).
 fn trim_url_trailing(url: &str) -> &str {
@@ -525,8 +844,12 @@ fn grid_sync_thread_main(
                 snap.grid.clone_from(&syncer.grid);
                 snap.inverse_cursor = syncer.inverse_cursor;
                 snap.url_ranges.clone_from(&syncer.url_ranges);
+                snap.prompt_marks.clone_from(&syncer.prompt_marks);
+                snap.images.clone_from(&syncer.visible_images);
                 snap.generation = syncer.grid_generation;
                 snap.cursor = syncer.cached_cursor;
+                snap.copy_mode.clone_from(&syncer.copy_mode);
+                snap.selection_spans.clone_from(&syncer.selection_spans);
             }
             snapshot_ready.store(true, Ordering::Relaxed);
 
@@ -551,6 +874,19 @@ fn grid_sync_thread_main(
 // Terminal backend
 // ──────────────────────────────────────────────
 
+/// What a new terminal runs. The default is the user's login shell with
+/// shell integration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShellCommand {
+    /// Program to run instead of `$SHELL`.
+    pub program: Option<String>,
+    /// Arguments for `program`. Without any, a zsh/bash/fish program still
+    /// gets shell integration.
+    pub args: Vec<String>,
+    /// Extra environment variables, set over the defaults (`TERM`, ...).
+    pub env: Vec<(String, String)>,
+}
+
 /// Terminal backend using alacritty_terminal for PTY management and terminal emulation.
 pub struct Terminal {
     /// The alacritty terminal emulator state, wrapped in a FairMutex for thread safety
@@ -567,6 +903,8 @@ pub struct Terminal {
     rows: u16,
     /// The child process ID for CWD detection fallback
     child_pid: Option<u32>,
+    /// Id exported to the shell as `TIDE_TERMINAL_ID` (None for tmux panes)
+    terminal_id: Option<u64>,
     /// Atomic flag: sync thread has a new snapshot ready to consume
     snapshot_ready: Arc<AtomicBool>,
     /// Shared snapshot for grid exchange with sync thread
@@ -577,16 +915,50 @@ pub struct Terminal {
     cached_cursor: CursorState,
     /// Detected URL ranges per row (read from snapshot)
     url_ranges: Vec<Vec<(usize, usize)>>,
+    /// Finished prompts in the viewport (read from snapshot)
+    prompt_marks: Vec<PromptMark>,
+    /// Inline images in the viewport (read from snapshot)
+    visible_images: Vec<VisibleImage>,
+    /// Copy mode cursor and selection (read from snapshot)
+    copy_mode: Option<CopyModeView>,
+    /// Selected cells of the viewport (read from snapshot)
+    selection_spans: Vec<(usize, usize, usize)>,
     /// Grid generation counter
     grid_generation: u64,
     /// Stay-at-bottom mode (shared with sync thread via atomic)
     stay_at_bottom: Arc<AtomicBool>,
     /// Dark/light mode (shared with sync thread via atomic)
     dark_mode: Arc<AtomicBool>,
-    /// Signal to sync thread: dark mode changed, force full re-render
+    /// Signal to sync thread: dark mode or color scheme changed, force full re-render
     dark_mode_changed: Arc<AtomicBool>,
+    /// Dark/light color schemes (shared with sync thread and listener)
+    schemes: Arc<Mutex<SchemePair>>,
     /// Mode 2031: app opted in to color-scheme notifications (shared with listener)
     mode_2031: Arc<AtomicBool>,
+    /// Mode 1015: urxvt mouse encoding requested (shared with listener)
+    urxvt_mouse: Arc<AtomicBool>,
+    /// OSC 133 command regions (shared with listener)
+    shell_marks: Arc<Mutex<ShellMarks>>,
+    /// Program-set title and icon name (shared with listener)
+    titles: Arc<Mutex<TermTitles>>,
+    /// Bell and notification counters (shared with listener)
+    attention: Arc<Attention>,
+    /// OSC 52 clipboard requests (shared with listener)
+    clipboard: Arc<Mutex<ClipboardQueue>>,
+    /// Kitty graphics and sixel images (shared with listener and sync thread)
+    images: Arc<Mutex<ImageStore>>,
+    /// Output trigger rules and fired events (shared with sync thread)
+    triggers: Arc<Mutex<TriggerSet>>,
+    /// Cell size in device pixels, reported in the PTY window size
+    cell_pixels: (u16, u16),
+    /// Scrollback limit in lines (mirrors `TermConfig::scrolling_history`)
+    scrollback_lines: usize,
+    /// Active asciicast recording (shared with the PTY thread's output tap)
+    recording: Arc<Mutex<Option<Recording>>>,
+    /// tmux control mode running in this terminal (shared with the output tap)
+    tmux: Arc<Mutex<TmuxControl>>,
+    /// The tmux pane this terminal shows, if it is one
+    tmux_pane: Option<TmuxPaneLink>,
     /// Dirty flag (shared with PTY thread and sync thread)
     dirty: Arc<AtomicBool>,
     /// Shared waker callback — installed by main thread, called by sync thread
@@ -599,6 +971,8 @@ pub struct Terminal {
     sync_shutdown: Arc<AtomicBool>,
     /// Sync thread join handle (joined on Drop)
     _sync_join: Option<std::thread::JoinHandle<()>>,
+    /// Whether the PTY thread has stopped (it is generic over the PTY type)
+    pty_finished: Box<dyn Fn() -> bool + Send + Sync>,
 }
 
 impl Terminal {
@@ -609,8 +983,43 @@ impl Terminal {
 
     /// Create a new terminal backend, optionally starting in the given directory.
     pub fn with_cwd(cols: u16, rows: u16, cwd: Option<PathBuf>, dark_mode: bool) -> Result<Self, Box<dyn std::error::Error>> {
-        let cell_width = 8;
-        let cell_height = 16;
+        Self::spawn(cols, rows, cwd, dark_mode, &ShellCommand::default(), None)
+    }
+
+    /// Like `with_cwd`, but first replays `output` (an ANSI stream, see
+    /// `dump_ansi`) into the grid so it sits above the new shell's prompt.
+    pub fn with_restored_output(
+        cols: u16,
+        rows: u16,
+        cwd: Option<PathBuf>,
+        dark_mode: bool,
+        output: &[u8],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::spawn(cols, rows, cwd, dark_mode, &ShellCommand::default(), Some(output))
+    }
+
+    /// Create a terminal running `command` instead of the login shell,
+    /// optionally replaying `restored_output` first (see `with_restored_output`).
+    pub fn with_command(
+        cols: u16,
+        rows: u16,
+        cwd: Option<PathBuf>,
+        dark_mode: bool,
+        command: &ShellCommand,
+        restored_output: Option<&[u8]>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::spawn(cols, rows, cwd, dark_mode, command, restored_output)
+    }
+
+    fn spawn(
+        cols: u16,
+        rows: u16,
+        cwd: Option<PathBuf>,
+        dark_mode: bool,
+        command: &ShellCommand,
+        restored_output: Option<&[u8]>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let (cell_width, cell_height) = DEFAULT_CELL_PIXELS;
 
         let window_size = WindowSize {
             num_cols: cols,
@@ -619,28 +1028,6 @@ impl Terminal {
             cell_height,
         };
 
-        let term_size = TermDimensions::new(cols as usize, rows as usize);
-
-        let dirty = Arc::new(AtomicBool::new(true));
-        let pty_writer = Arc::new(Mutex::new(None));
-        let sync_thread_handle: Arc<Mutex<Option<std::thread::Thread>>> = Arc::new(Mutex::new(None));
-        let dark_mode_flag = Arc::new(AtomicBool::new(dark_mode));
-        let mode_2031_flag = Arc::new(AtomicBool::new(false));
-        let listener = TermEventListener {
-            dirty: dirty.clone(),
-            pty_writer: pty_writer.clone(),
-            sync_thread: sync_thread_handle.clone(),
-            dark_mode: dark_mode_flag.clone(),
-            mode_2031: mode_2031_flag.clone(),
-        };
-
-        let config = TermConfig::default();
-        let term = Term::new(config, &term_size, listener.clone());
-        let term = Arc::new(FairMutex::new(term));
-
-        // Determine the shell to use
-        let shell = Self::detect_shell();
-
         // Use provided cwd, or fall back to $HOME so .app bundles don't land in /
         let working_directory = cwd.or_else(|| std::env::var("HOME").ok().map(PathBuf::from));
         let mut env = std::collections::HashMap::new();
@@ -652,8 +1039,24 @@ impl Terminal {
         } else {
             env.insert(String::from("COLORFGBG"), String::from("0;15"));
         }
+        let terminal_id = NEXT_TERMINAL_ID.fetch_add(1, Ordering::Relaxed);
+        env.insert(String::from(TERMINAL_ID_ENV), terminal_id.to_string());
+        env.extend(command.env.iter().cloned());
+
+        // Determine the program to run. Explicit args are passed as-is; a bare
+        // zsh/bash/fish gets the same integration as the login shell.
+        let (shell, shell_args) = match &command.program {
+            Some(program) if !command.args.is_empty() || !shell_integration::is_supported(program) => {
+                (program.clone(), command.args.clone())
+            }
+            program => {
+                let shell = program.clone().unwrap_or_else(Self::detect_shell);
+                let args = shell_integration::shell_args(&shell, &mut env);
+                (shell, args)
+            }
+        };
         let pty_config = tty::Options {
-            shell: Some(tty::Shell::new(shell, vec![String::from("--login")])),
+            shell: Some(tty::Shell::new(shell, shell_args)),
             working_directory,
             env,
             ..tty::Options::default()
@@ -665,13 +1068,105 @@ impl Terminal {
         // Get child PID before moving pty into the event loop
         let child_pid = pty.child().id();
 
+        let mut terminal = Self::start(cols, rows, dark_mode, pty, Some(child_pid), restored_output, false)?;
+        terminal.terminal_id = Some(terminal_id);
+        Ok(terminal)
+    }
+
+    /// Run the emulator, event loop and grid sync thread over `pty`: a real
+    /// PTY, or a tmux pane's socket pair (see tmux.rs). With
+    /// `suppress_replies`, the emulator's answers to queries are dropped.
+    fn start<P>(
+        cols: u16,
+        rows: u16,
+        dark_mode: bool,
+        pty: P,
+        child_pid: Option<u32>,
+        restored_output: Option<&[u8]>,
+        suppress_replies: bool,
+    ) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        P: tty::EventedPty + alacritty_terminal::event::OnResize + Send + 'static,
+    {
+        let term_size = TermDimensions::new(cols as usize, rows as usize);
+
+        let dirty = Arc::new(AtomicBool::new(true));
+        let pty_writer = Arc::new(Mutex::new(None));
+        let sync_thread_handle: Arc<Mutex<Option<std::thread::Thread>>> = Arc::new(Mutex::new(None));
+        let dark_mode_flag = Arc::new(AtomicBool::new(dark_mode));
+        let schemes = Arc::new(Mutex::new(SchemePair::default()));
+        let mode_2031_flag = Arc::new(AtomicBool::new(false));
+        let urxvt_mouse_flag = Arc::new(AtomicBool::new(false));
+        let shell_marks = Arc::new(Mutex::new(ShellMarks::default()));
+        let titles = Arc::new(Mutex::new(TermTitles::default()));
+        let attention = Arc::new(Attention::default());
+        let clipboard = Arc::new(Mutex::new(ClipboardQueue::default()));
+        let images = Arc::new(Mutex::new(ImageStore::default()));
+        let listener = TermEventListener {
+            dirty: dirty.clone(),
+            pty_writer: pty_writer.clone(),
+            sync_thread: sync_thread_handle.clone(),
+            dark_mode: dark_mode_flag.clone(),
+            schemes: schemes.clone(),
+            mode_2031: mode_2031_flag.clone(),
+            urxvt_mouse: urxvt_mouse_flag.clone(),
+            shell_marks: shell_marks.clone(),
+            titles: titles.clone(),
+            attention: attention.clone(),
+            clipboard: clipboard.clone(),
+            images: images.clone(),
+            suppress_replies,
+            ..Default::default()
+        };
+
+        // OSC 52 reads and writes are both accepted here; the app applies the
+        // user's clipboard policy to each request.
+        let config = TermConfig {
+            scrolling_history: DEFAULT_SCROLLBACK_LINES,
+            kitty_keyboard: true,
+            osc52: Osc52::CopyPaste,
+            sixel: true,
+            ..TermConfig::default()
+        };
+        let mut term = Term::new(config, &term_size, listener.clone());
+        // Replay before the PTY thread starts so the shell's output lands below it
+        if let Some(output) = restored_output {
+            use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+            let mut processor: Processor<StdSyncHandler> = Processor::new();
+            processor.advance(&mut term, output);
+        }
+        let output_start = term.grid().scrolled_lines() + term.grid().cursor.point.line.0 as u64;
+        let triggers = Arc::new(Mutex::new(TriggerSet::starting_at(output_start)));
+        let term = Arc::new(FairMutex::new(term));
+
         // Create the event loop that bridges PTY I/O with the terminal emulator
-        let event_loop = EventLoop::new(term.clone(), listener, pty, false, false)?;
+        let recording = Arc::new(Mutex::new(None));
+        // Drain on exit, for every terminal: output written just before the
+        // child exits would otherwise be lost with the PTY loop. That is a
+        // profile command's last lines, and tmux's `%exit`, the only way a
+        // control mode gateway learns that tmux detached. The final read stops
+        // at `MAX_LOCKED_READ`, so a leftover writer can't hold the loop open.
+        let mut event_loop = EventLoop::new(term.clone(), listener, pty, true, false)?;
+        let tmux = Arc::new(Mutex::new(TmuxControl::default()));
+        let mut record = cast::recording_tap(recording.clone());
+        let mut control = tmux::control_tap(tmux.clone());
+        event_loop.set_output_tap(Box::new(move |bytes| {
+            record(bytes);
+            control(bytes);
+        }));
+        if let Ok(mut control) = tmux.lock() {
+            control.set_writer(Notifier(event_loop.channel()));
+            if let Some(pid) = child_pid {
+                let check = move || title::foreground_process_name(pid).is_some_and(|name| name.starts_with("tmux"));
+                control.set_foreground_check(Box::new(check));
+            }
+        }
         let notifier = Notifier(event_loop.channel());
         if let Ok(mut guard) = pty_writer.lock() {
             *guard = Some(Notifier(event_loop.channel()));
         }
-        event_loop.spawn();
+        let pty_join = event_loop.spawn();
+        let pty_finished = Box::new(move || pty_join.is_finished());
 
         // Initialize shared state for the sync thread
         let cached_grid = Self::build_empty_grid(cols, rows);
@@ -685,26 +1180,27 @@ impl Terminal {
             grid: Self::build_empty_grid(cols, rows),
             inverse_cursor: None,
             url_ranges: Vec::new(),
+            prompt_marks: Vec::new(),
+            images: Vec::new(),
             generation: 0,
             cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block },
+            copy_mode: None,
+            selection_spans: Vec::new(),
         }));
 
         // Create the GridSyncer with all sync-related state
-        let syncer = GridSyncer {
-            term: term.clone(),
-            raw_buf: Vec::new(),
-            prev_raw_buf: Vec::new(),
-            palette_buf: [None; 256],
-            grid: Self::build_empty_grid(cols, rows),
-            inverse_cursor: None,
-            cached_cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block },
-            url_ranges: Vec::new(),
-            grid_generation: 0,
-            url_row_buf: String::new(),
-            dark_mode: dark_mode_flag.clone(),
-            dark_mode_changed: dark_mode_changed.clone(),
-            stay_at_bottom: stay_at_bottom.clone(),
-        };
+        let syncer = GridSyncer::new(
+            term.clone(),
+            cols,
+            rows,
+            dark_mode_flag.clone(),
+            dark_mode_changed.clone(),
+            schemes.clone(),
+            stay_at_bottom.clone(),
+            shell_marks.clone(),
+            images.clone(),
+            triggers.clone(),
+        );
 
         // Spawn the grid sync thread
         let sync_join = {
@@ -729,23 +1225,42 @@ impl Terminal {
             current_dir: None,
             cols,
             rows,
-            child_pid: Some(child_pid),
+            child_pid,
+            terminal_id: None,
             snapshot_ready,
             snapshot,
             inverse_cursor: None,
             cached_cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block },
             url_ranges: Vec::new(),
+            prompt_marks: Vec::new(),
+            visible_images: Vec::new(),
+            copy_mode: None,
+            selection_spans: Vec::new(),
             grid_generation: 0,
             stay_at_bottom,
             dark_mode: dark_mode_flag,
             dark_mode_changed,
+            schemes,
             mode_2031: mode_2031_flag,
+            urxvt_mouse: urxvt_mouse_flag,
+            shell_marks,
+            titles,
+            attention,
+            clipboard,
+            images,
+            triggers,
+            cell_pixels: DEFAULT_CELL_PIXELS,
+            scrollback_lines: DEFAULT_SCROLLBACK_LINES,
+            recording,
+            tmux,
+            tmux_pane: None,
             dirty,
             waker,
             pending_pty_resize: None,
             sync_thread_handle,
             sync_shutdown,
             _sync_join: Some(sync_join),
+            pty_finished,
         })
     }
 
@@ -769,7 +1284,7 @@ impl Terminal {
                     .collect()
             })
             .collect();
-        TerminalGrid { cols, rows, cells }
+        TerminalGrid { cols, rows, cells, links: Vec::new() }
     }
 
     /// Detect the CWD of the child process using native OS APIs (no subprocess).
@@ -818,6 +1333,16 @@ impl Terminal {
         }
     }
 
+    /// Size reported to the PTY (TIOCSWINSZ).
+    fn window_size(&self) -> WindowSize {
+        WindowSize {
+            num_cols: self.cols,
+            num_lines: self.rows,
+            cell_width: self.cell_pixels.0,
+            cell_height: self.cell_pixels.1,
+        }
+    }
+
     /// Unpark the sync thread so it processes pending dirty flags.
     fn notify_sync_thread(&self) {
         if let Ok(guard) = self.sync_thread_handle.lock() {
@@ -827,6 +1352,12 @@ impl Terminal {
         }
     }
 
+    /// Flag the grid as changed and wake the sync thread.
+    fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::Relaxed);
+        self.notify_sync_thread();
+    }
+
     /// Consume the latest snapshot from the sync thread (if available).
     fn consume_snapshot(&mut self) {
         if !self.snapshot_ready.load(Ordering::Relaxed) {
@@ -836,8 +1367,12 @@ impl Terminal {
             std::mem::swap(&mut self.cached_grid, &mut snap.grid);
             self.inverse_cursor = snap.inverse_cursor;
             std::mem::swap(&mut self.url_ranges, &mut snap.url_ranges);
+            std::mem::swap(&mut self.prompt_marks, &mut snap.prompt_marks);
+            std::mem::swap(&mut self.visible_images, &mut snap.images);
             self.grid_generation = snap.generation;
             self.cached_cursor = snap.cursor;
+            std::mem::swap(&mut self.copy_mode, &mut snap.copy_mode);
+            std::mem::swap(&mut self.selection_spans, &mut snap.selection_spans);
         }
         self.snapshot_ready.store(false, Ordering::Relaxed);
     }
@@ -858,8 +1393,22 @@ impl Terminal {
         self.child_pid
     }
 
+    /// Id of the shell's terminal, as seen in its `TIDE_TERMINAL_ID`.
+    pub fn terminal_id(&self) -> Option<u64> {
+        self.terminal_id
+    }
+
+    /// Whether the PTY loop has stopped, after the child exited and its last
+    /// output was read.
+    pub(crate) fn pty_loop_finished(&self) -> bool {
+        (self.pty_finished)()
+    }
+
     /// Check if the child shell process is still alive.
     pub fn is_child_alive(&self) -> bool {
+        if let Some(link) = &self.tmux_pane {
+            return link.alive.load(Ordering::Relaxed);
+        }
         let pid = match self.child_pid {
             Some(p) => p,
             None => return false,
@@ -869,9 +1418,18 @@ impl Terminal {
         unsafe { libc::kill(pid as i32, 0) == 0 }
     }
 
-    /// Detect whether the shell is idle (no foreground child process running).
-    #[cfg(target_os = "macos")]
+    /// Detect whether the shell is idle (no command running). Uses OSC 133
+    /// marks when the shell sends them, otherwise the foreground process.
     pub fn is_shell_idle(&self) -> bool {
+        match self.command_running() {
+            Some(running) => !running,
+            None => self.is_foreground_idle(),
+        }
+    }
+
+    /// No foreground child process running.
+    #[cfg(target_os = "macos")]
+    fn is_foreground_idle(&self) -> bool {
         let pid = match self.child_pid {
             Some(p) => p,
             None => return false,
@@ -888,7 +1446,7 @@ impl Terminal {
     }
 
     #[cfg(not(target_os = "macos"))]
-    pub fn is_shell_idle(&self) -> bool {
+    fn is_foreground_idle(&self) -> bool {
         let pid = match self.child_pid {
             Some(p) => p,
             None => return false,
@@ -917,9 +1475,17 @@ impl Terminal {
     }
 
     /// Force a sync_grid cycle for benchmarking purposes.
-    /// Sets the dirty flag, wakes the sync thread, and spins until the snapshot is ready.
     #[doc(hidden)]
     pub fn bench_sync_grid(&mut self) {
+        self.sync_now();
+    }
+
+    /// Sync the grid on demand and swap the result in, for callers without an
+    /// event loop. Sets the dirty flag, wakes the sync thread, and spins until
+    /// the snapshot is ready.
+    pub(crate) fn sync_now(&mut self) {
+        // Drop a snapshot from an earlier sync so we wait for ours
+        self.consume_snapshot();
         self.dirty.store(true, Ordering::Relaxed);
         self.notify_sync_thread();
         // Spin until snapshot is ready
@@ -954,44 +1520,6 @@ impl Terminal {
         self.rows
     }
 
-    /// Search the full scrollback + screen buffer for case-insensitive substring matches.
-    /// Returns `(absolute_line_from_top, char_col, char_len)` tuples.
-    pub fn search_buffer(&self, query: &str) -> Vec<(usize, usize, usize)> {
-        let mut results = Vec::new();
-        if query.is_empty() {
-            return results;
-        }
-
-        let query_lower = query.to_lowercase();
-        let query_char_len = query.chars().count();
-        let term = self.term.lock();
-        let grid = term.grid();
-        let total_lines = grid.screen_lines();
-        let history_len = grid.history_size();
-        let cols = grid.columns();
-
-        for abs_line in 0..(history_len + total_lines) {
-            let line_idx = Line(abs_line as i32 - history_len as i32);
-            let mut row_text = String::with_capacity(cols);
-            for col_idx in 0..cols {
-                let point = Point::new(line_idx, Column(col_idx));
-                let c = grid[point].c;
-                row_text.push(if c == '\0' { ' ' } else { c });
-            }
-
-            let row_lower = row_text.to_lowercase();
-            let mut start = 0;
-            while let Some(byte_pos) = row_lower[start..].find(&query_lower) {
-                let byte_col = start + byte_pos;
-                let char_col = row_text[..byte_col].chars().count();
-                results.push((abs_line, char_col, query_char_len));
-                start = byte_col + row_lower[byte_col..].chars().next().map_or(1, |c| c.len_utf8());
-            }
-        }
-
-        results
-    }
-
     /// Get the current display offset (how many lines scrolled up into history).
     pub fn display_offset(&self) -> usize {
         let term = self.term.lock();
@@ -1004,6 +1532,49 @@ impl Terminal {
         term.grid().history_size()
     }
 
+    /// Text of the last `max_rows` rows of scrollback and screen, with
+    /// soft-wrapped lines joined and trailing blank lines dropped.
+    pub fn recent_text(&self, max_rows: usize) -> String {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let rows = grid.screen_lines() as i32;
+        let top = (rows - max_rows.max(1) as i32).max(-(grid.history_size() as i32));
+        let start = Point::new(Line(top), Column(0));
+        let end = Point::new(Line(rows - 1), Column(grid.columns() - 1));
+        term.bounds_to_string(start, end).trim_end().to_string()
+    }
+
+    /// Maximum number of history lines this terminal keeps.
+    pub fn scrollback_lines(&self) -> usize {
+        self.scrollback_lines
+    }
+
+    /// Change the scrollback limit (clamped to `MAX_SCROLLBACK_LINES`).
+    /// Lowering it drops the oldest history immediately.
+    pub fn set_scrollback_lines(&mut self, lines: usize) {
+        let lines = lines.min(MAX_SCROLLBACK_LINES);
+        if lines == self.scrollback_lines {
+            return;
+        }
+        self.scrollback_lines = lines;
+        self.term.lock().set_scrolling_history(lines);
+        self.dirty.store(true, Ordering::Relaxed);
+        self.notify_sync_thread();
+    }
+
+    /// Drop the oldest history beyond `keep` lines. The limit is unchanged,
+    /// so history grows back as new output scrolls in.
+    pub fn trim_history(&mut self, keep: usize) {
+        self.term.lock().trim_history(keep);
+        self.dirty.store(true, Ordering::Relaxed);
+        self.notify_sync_thread();
+    }
+
+    /// Approximate heap size of one history line at the current width.
+    pub fn history_line_bytes(&self) -> usize {
+        self.cols as usize * std::mem::size_of::<alacritty_terminal::term::cell::Cell>()
+    }
+
     /// Check if the terminal has bracketed paste mode enabled.
     pub fn is_bracketed_paste_mode(&self) -> bool {
         let term = self.term.lock();
@@ -1034,6 +1605,27 @@ impl Terminal {
         }
     }
 
+    /// Set the dark- and light-mode color schemes. The one for the current
+    /// mode applies; `set_dark_mode` switches between them.
+    /// Signals the sync thread to re-convert every cell.
+    pub fn set_color_schemes(&mut self, schemes: SchemePair) {
+        let Ok(mut current) = self.schemes.lock() else { return };
+        if *current == schemes {
+            return;
+        }
+        *current = schemes;
+        drop(current);
+        self.dark_mode_changed.store(true, Ordering::Relaxed);
+        self.dirty.store(true, Ordering::Relaxed);
+        self.notify_sync_thread();
+    }
+
+    /// The color scheme in effect for the current mode, if one was set.
+    pub fn color_scheme(&self) -> Option<ColorScheme> {
+        let dark = self.dark_mode.load(Ordering::Relaxed);
+        self.schemes.lock().ok().and_then(|s| s.for_mode(dark))
+    }
+
     /// Enter stay-at-bottom mode: every sync_grid will scroll to bottom until
     /// the user explicitly scrolls away via scroll_display().
     pub fn request_scroll_to_bottom(&mut self) {
@@ -1062,6 +1654,9 @@ impl Terminal {
 
 impl TerminalBackend for Terminal {
     fn write(&mut self, data: &[u8]) {
+        if self.tmux_gateway_input(data) {
+            return;
+        }
         let _ = self.notifier.0.send(Msg::Input(Cow::Owned(data.to_vec())));
     }
 
@@ -1093,15 +1688,7 @@ impl TerminalBackend for Terminal {
         self.cols = cols;
         self.rows = rows;
 
-        let cell_width = 8;
-        let cell_height = 16;
-
-        let window_size = WindowSize {
-            num_cols: cols,
-            num_lines: rows,
-            cell_width,
-            cell_height,
-        };
+        let window_size = self.window_size();
 
         let term_size = TermDimensions::new(cols as usize, rows as usize);
 
@@ -1109,6 +1696,7 @@ impl TerminalBackend for Terminal {
             let mut term = self.term.lock();
             term.resize(term_size);
         }
+        self.record_resize(cols, rows);
 
         // Debounce PTY resize notification (SIGWINCH) to avoid prompt artifacts
         self.pending_pty_resize = Some((window_size, Instant::now()));
@@ -1175,6 +1763,11 @@ impl Drop for Terminal {
             wait_for_child_exit(pid);
         }
 
+        // tmux panes fed by this terminal end with it
+        if let Ok(mut control) = self.tmux.lock() {
+            control.close();
+        }
+
         // Signal the sync thread to shut down and wait for it
         self.sync_shutdown.store(true, Ordering::Relaxed);
         self.notify_sync_thread();
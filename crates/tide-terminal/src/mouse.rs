@@ -0,0 +1,188 @@
+// Mouse event to escape sequence conversion for Terminal (xterm mouse reporting)
+//
+// Tracking modes (which events are reported):
+//   1000 = clicks + wheel, 1002 = + drag motion, 1003 = + all motion
+// Encodings (how coordinates are written):
+//   default X10 bytes, 1005 UTF-8, 1006 SGR, 1015 urxvt
+
+use std::sync::atomic::Ordering;
+
+use alacritty_terminal::term::TermMode;
+use tide_core::{Modifiers, MouseButton, TerminalBackend};
+
+use super::Terminal;
+
+/// A mouse event to be reported to the application running in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseReport {
+    Press(MouseButton),
+    Release(MouseButton),
+    /// Motion while a button is held (reported in 1002 and 1003).
+    Drag(MouseButton),
+    /// Motion with no button held (reported in 1003 only).
+    Move,
+    WheelUp,
+    WheelDown,
+}
+
+/// Which mouse events the application asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MouseTracking {
+    #[default]
+    Off,
+    /// Mode 1000: button presses, releases and wheel.
+    Click,
+    /// Mode 1002: clicks plus motion while a button is held.
+    Drag,
+    /// Mode 1003: clicks plus all motion.
+    Motion,
+}
+
+/// How mouse coordinates are encoded in the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MouseEncoding {
+    /// Legacy `CSI M Cb Cx Cy` with single-byte coordinates (max 223).
+    #[default]
+    X10,
+    /// Mode 1005: like X10 but coordinates are UTF-8 encoded (max 2015).
+    Utf8,
+    /// Mode 1006: `CSI < Cb ; Cx ; Cy M/m` with decimal coordinates.
+    Sgr,
+    /// Mode 1015: `CSI Cb ; Cx ; Cy M` with decimal coordinates.
+    Urxvt,
+}
+
+/// Snapshot of the terminal's current mouse reporting configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MouseProtocol {
+    pub tracking: MouseTracking,
+    pub encoding: MouseEncoding,
+}
+
+impl MouseProtocol {
+    /// Whether the application wants this kind of event reported at all.
+    pub fn wants(&self, report: MouseReport) -> bool {
+        match report {
+            MouseReport::Press(_)
+            | MouseReport::Release(_)
+            | MouseReport::WheelUp
+            | MouseReport::WheelDown => self.tracking != MouseTracking::Off,
+            MouseReport::Drag(_) => {
+                matches!(self.tracking, MouseTracking::Drag | MouseTracking::Motion)
+            }
+            MouseReport::Move => self.tracking == MouseTracking::Motion,
+        }
+    }
+}
+
+impl Terminal {
+    /// Current mouse tracking mode and encoding requested by the application.
+    pub fn mouse_protocol(&self) -> MouseProtocol {
+        let mode = *self.term.lock().mode();
+        let tracking = if mode.contains(TermMode::MOUSE_MOTION) {
+            MouseTracking::Motion
+        } else if mode.contains(TermMode::MOUSE_DRAG) {
+            MouseTracking::Drag
+        } else if mode.contains(TermMode::MOUSE_REPORT_CLICK) {
+            MouseTracking::Click
+        } else {
+            MouseTracking::Off
+        };
+        let encoding = if mode.contains(TermMode::SGR_MOUSE) {
+            MouseEncoding::Sgr
+        } else if self.urxvt_mouse.load(Ordering::Relaxed) {
+            MouseEncoding::Urxvt
+        } else if mode.contains(TermMode::UTF8_MOUSE) {
+            MouseEncoding::Utf8
+        } else {
+            MouseEncoding::X10
+        };
+        MouseProtocol { tracking, encoding }
+    }
+
+    /// Whether the application has enabled any mouse tracking mode.
+    pub fn is_mouse_reporting(&self) -> bool {
+        self.term.lock().mode().intersects(TermMode::MOUSE_MODE)
+    }
+
+    /// Report a mouse event at the given viewport cell to the application.
+    /// Returns false (and writes nothing) if the current tracking mode does
+    /// not cover this event, so the caller can fall back to local handling.
+    pub fn report_mouse(&mut self, report: MouseReport, col: usize, row: usize, modifiers: &Modifiers) -> bool {
+        let protocol = self.mouse_protocol();
+        if !protocol.wants(report) {
+            return false;
+        }
+        if let Some(bytes) = Self::mouse_to_bytes(report, col, row, modifiers, protocol.encoding) {
+            self.write(&bytes);
+        }
+        true
+    }
+
+    /// Convert a mouse event to the byte sequence for the given encoding.
+    /// `col` and `row` are 0-based viewport cells. Returns None when the
+    /// position can't be represented (e.g. beyond column 223 in X10).
+    pub fn mouse_to_bytes(
+        report: MouseReport,
+        col: usize,
+        row: usize,
+        modifiers: &Modifiers,
+        encoding: MouseEncoding,
+    ) -> Option<Vec<u8>> {
+        let button_code = |button: MouseButton| -> u8 {
+            match button {
+                MouseButton::Left => 0,
+                MouseButton::Middle => 1,
+                MouseButton::Right => 2,
+            }
+        };
+        let is_release = matches!(report, MouseReport::Release(_));
+        let mut cb = match report {
+            MouseReport::Press(b) => button_code(b),
+            // Only SGR can tell which button was released.
+            MouseReport::Release(b) => {
+                if encoding == MouseEncoding::Sgr { button_code(b) } else { 3 }
+            }
+            MouseReport::Drag(b) => button_code(b) + 32,
+            MouseReport::Move => 3 + 32,
+            MouseReport::WheelUp => 64,
+            MouseReport::WheelDown => 65,
+        };
+        if modifiers.shift {
+            cb += 4;
+        }
+        if modifiers.alt || modifiers.meta {
+            cb += 8;
+        }
+        if modifiers.ctrl {
+            cb += 16;
+        }
+
+        let x = col + 1;
+        let y = row + 1;
+        match encoding {
+            MouseEncoding::Sgr => {
+                let suffix = if is_release { 'm' } else { 'M' };
+                Some(format!("\x1b[<{};{};{}{}", cb, x, y, suffix).into_bytes())
+            }
+            MouseEncoding::Urxvt => {
+                Some(format!("\x1b[{};{};{}M", 32 + cb as usize, x, y).into_bytes())
+            }
+            MouseEncoding::X10 => {
+                if x > 223 || y > 223 {
+                    return None;
+                }
+                Some(vec![0x1b, b'[', b'M', 32 + cb, 32 + x as u8, 32 + y as u8])
+            }
+            MouseEncoding::Utf8 => {
+                let mut bytes = vec![0x1b, b'[', b'M', 32 + cb];
+                for pos in [x, y] {
+                    let c = char::from_u32(32 + pos as u32).filter(|_| pos <= 2015)?;
+                    let mut buf = [0u8; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                Some(bytes)
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+// Asciicast playback
+//
+// `CastPlayer` feeds a recording into a `Term` with no PTY behind it and runs
+// the same `GridSyncer` as a live terminal — synchronously, on the calling
+// thread — so colors and attributes convert exactly as they did on screen.
+// Seeking forward replays events up to the target; seeking backward rebuilds
+// the `Term` and replays from the start.
+
+use std::error::Error;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use alacritty_terminal::sync::FairMutex;
+use alacritty_terminal::term::{Config as TermConfig, Term};
+use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+
+use tide_core::{CursorState, TerminalBackend, TerminalGrid};
+
+use super::cast::{Cast, CastEvent};
+use super::graphics::ImageStore;
+use super::shell_integration::ShellMarks;
+use super::{GridSyncer, TermDimensions, TermEventListener};
+
+/// Playback speed bounds for `set_speed`.
+const MIN_SPEED: f64 = 0.25;
+const MAX_SPEED: f64 = 16.0;
+
+/// Plays an asciicast recording into a PTY-less terminal grid.
+pub struct CastPlayer {
+    cast: Cast,
+    term: Arc<FairMutex<Term<TermEventListener>>>,
+    listener: TermEventListener,
+    processor: Processor<StdSyncHandler>,
+    syncer: GridSyncer,
+    dirty: Arc<AtomicBool>,
+    dark_mode: Arc<AtomicBool>,
+    dark_mode_changed: Arc<AtomicBool>,
+    /// Index of the next event to apply.
+    next_event: usize,
+    /// Playback position in recording seconds.
+    position: f64,
+    speed: f64,
+    paused: bool,
+    last_tick: Instant,
+}
+
+impl CastPlayer {
+    /// Load a `.cast` file. Playback starts paused at 0:00.
+    pub fn open(path: &Path, dark_mode: bool) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::new(Cast::load(path)?, dark_mode))
+    }
+
+    pub fn new(cast: Cast, dark_mode: bool) -> Self {
+        let dirty = Arc::new(AtomicBool::new(true));
+        let dark_mode = Arc::new(AtomicBool::new(dark_mode));
+        let dark_mode_changed = Arc::new(AtomicBool::new(false));
+        let shell_marks = Arc::new(Mutex::new(ShellMarks::default()));
+        let images = Arc::new(Mutex::new(ImageStore::default()));
+        // No PTY: replies the recorded apps asked for (PtyWrite) go nowhere
+        let listener = TermEventListener {
+            dirty: dirty.clone(),
+            dark_mode: dark_mode.clone(),
+            shell_marks: shell_marks.clone(),
+            images: images.clone(),
+            ..Default::default()
+        };
+        let term = Arc::new(FairMutex::new(new_term(&cast, listener.clone())));
+        let syncer = GridSyncer::new(
+            term.clone(),
+            cast.width,
+            cast.height,
+            dark_mode.clone(),
+            dark_mode_changed.clone(),
+            Default::default(),
+            Arc::new(AtomicBool::new(true)),
+            shell_marks,
+            images,
+            Default::default(),
+        );
+        Self {
+            cast,
+            term,
+            listener,
+            processor: Processor::new(),
+            syncer,
+            dirty,
+            dark_mode,
+            dark_mode_changed,
+            next_event: 0,
+            position: 0.0,
+            speed: 1.0,
+            paused: true,
+            last_tick: Instant::now(),
+        }
+    }
+
+    /// Length of the recording in seconds.
+    pub fn duration(&self) -> f64 {
+        self.cast.duration()
+    }
+
+    /// Current playback position in seconds.
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Reached the last event.
+    pub fn is_finished(&self) -> bool {
+        self.next_event >= self.cast.events.len()
+    }
+
+    /// Play or pause. Playing a finished recording restarts it.
+    pub fn toggle_pause(&mut self) {
+        if self.paused && self.is_finished() {
+            self.seek(0.0);
+        }
+        self.paused = !self.paused;
+        self.last_tick = Instant::now();
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Set the playback speed multiplier (clamped to 0.25×–16×).
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    }
+
+    /// Jump to `time` seconds into the recording.
+    pub fn seek(&mut self, time: f64) {
+        let time = time.clamp(0.0, self.duration());
+        if time < self.position {
+            *self.term.lock() = new_term(&self.cast, self.listener.clone());
+            self.processor = Processor::new();
+            self.next_event = 0;
+        }
+        self.position = time;
+        self.apply_until(time);
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Change the palette used for default and named colors.
+    pub fn set_dark_mode(&mut self, dark: bool) {
+        if self.dark_mode.swap(dark, Ordering::Relaxed) != dark {
+            self.dark_mode_changed.store(true, Ordering::Relaxed);
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Increments whenever the grid changes.
+    pub fn grid_generation(&self) -> u64 {
+        self.syncer.grid_generation
+    }
+
+    /// Apply every event at or before `time`.
+    fn apply_until(&mut self, time: f64) {
+        let mut term = self.term.lock();
+        while let Some(event) = self.cast.events.get(self.next_event) {
+            if event.time() > time {
+                break;
+            }
+            match event {
+                CastEvent::Output { data, .. } => self.processor.advance(&mut *term, data.as_bytes()),
+                CastEvent::Resize { cols, rows, .. } => {
+                    term.resize(TermDimensions::new((*cols).clamp(1, 1000) as usize, (*rows).clamp(1, 500) as usize));
+                }
+            }
+            self.next_event += 1;
+        }
+    }
+}
+
+fn new_term(cast: &Cast, listener: TermEventListener) -> Term<TermEventListener> {
+    let size = TermDimensions::new(cast.width as usize, cast.height as usize);
+    Term::new(TermConfig::default(), &size, listener)
+}
+
+impl TerminalBackend for CastPlayer {
+    /// Replay panes take no input.
+    fn write(&mut self, _data: &[u8]) {}
+
+    /// Advance playback by the wall-clock time since the last call and
+    /// refresh the grid if anything changed.
+    fn process(&mut self) {
+        let now = Instant::now();
+        if !self.paused {
+            let elapsed = now.duration_since(self.last_tick).as_secs_f64();
+            self.position = (self.position + elapsed * self.speed).min(self.duration());
+            self.apply_until(self.position);
+            if self.is_finished() {
+                self.paused = true;
+            }
+        }
+        self.last_tick = now;
+        if self.dirty.swap(false, Ordering::Relaxed) {
+            self.syncer.sync();
+        }
+    }
+
+    fn grid(&self) -> &TerminalGrid {
+        &self.syncer.grid
+    }
+
+    /// The grid keeps the recording's own size; resize events in the cast
+    /// change it, the pane size does not.
+    fn resize(&mut self, _cols: u16, _rows: u16) {}
+
+    fn cwd(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    fn cursor(&self) -> CursorState {
+        self.syncer.cached_cursor
+    }
+}
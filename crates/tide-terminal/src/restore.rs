@@ -0,0 +1,182 @@
+// Scrollback persistence
+//
+// The grid (history + screen) is dumped as a plain ANSI stream: text with SGR
+// sequences for colors and attributes. Replaying that stream into a fresh
+// Term (`Terminal::with_restored_output`) recreates the output at whatever
+// width the new terminal has — soft-wrapped rows are written without a line
+// break, so they re-wrap naturally.
+//
+// Every logical line starts from SGR defaults and ends with a reset, so the
+// stream can be cut at any line boundary to fit a size cap.
+
+use std::fmt::Write as _;
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line};
+use alacritty_terminal::term::cell::{Cell, Flags as CellFlags};
+use alacritty_terminal::term::Term;
+use alacritty_terminal::vte::ansi::{Color as AnsiColor, NamedColor};
+
+use super::Terminal;
+
+/// Cell flags that map to SGR attributes.
+const SGR_FLAGS: CellFlags = CellFlags::BOLD
+    .union(CellFlags::DIM)
+    .union(CellFlags::ITALIC)
+    .union(CellFlags::ALL_UNDERLINES)
+    .union(CellFlags::INVERSE)
+    .union(CellFlags::HIDDEN)
+    .union(CellFlags::STRIKEOUT);
+
+impl Terminal {
+    /// History + screen as an ANSI stream, keeping only the most recent lines
+    /// that fit in `max_bytes`.
+    pub fn dump_ansi(&self, max_bytes: usize) -> Vec<u8> {
+        dump_term(&self.term.lock(), max_bytes)
+    }
+}
+
+pub(crate) fn dump_term<T>(term: &Term<T>, max_bytes: usize) -> Vec<u8> {
+    let grid = term.grid();
+    let last_col = grid.columns() - 1;
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut style = Style::default();
+    for line in term.topmost_line().0..=term.bottommost_line().0 {
+        let row = &grid[Line(line)];
+        let wrapped = row[Column(last_col)].flags.contains(CellFlags::WRAPLINE);
+        // Trailing blanks only matter when the row continues on the next one
+        let end = if wrapped {
+            last_col + 1
+        } else {
+            (0..=last_col).rev().find(|&c| !is_blank(&row[Column(c)])).map_or(0, |c| c + 1)
+        };
+
+        for col in 0..end {
+            let cell = &row[Column(col)];
+            if cell.flags.intersects(CellFlags::WIDE_CHAR_SPACER | CellFlags::LEADING_WIDE_CHAR_SPACER) {
+                continue;
+            }
+            let cell_style = Style::of(cell);
+            if cell_style != style {
+                cell_style.write_sgr(&mut current);
+                style = cell_style;
+            }
+            current.push(cell.c);
+            if let Some(zerowidth) = cell.zerowidth() {
+                current.extend(zerowidth);
+            }
+        }
+
+        if !wrapped {
+            if style != Style::default() {
+                current.push_str("\x1b[0m");
+                style = Style::default();
+            }
+            current.push_str("\r\n");
+            lines.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        current.push_str("\x1b[0m\r\n");
+        lines.push(current);
+    }
+
+    // Drop blank lines below the last output (the empty part of the screen)
+    while lines.last().is_some_and(|l| l == "\r\n") {
+        lines.pop();
+    }
+
+    // Keep the newest lines that fit
+    let mut size = 0;
+    let keep = lines
+        .iter()
+        .rev()
+        .take_while(|l| {
+            size += l.len();
+            size <= max_bytes
+        })
+        .count();
+    lines[lines.len() - keep..].concat().into_bytes()
+}
+
+fn is_blank(cell: &Cell) -> bool {
+    cell.c == ' '
+        && cell.bg == AnsiColor::Named(NamedColor::Background)
+        && !cell.flags.intersects(CellFlags::INVERSE | CellFlags::ALL_UNDERLINES | CellFlags::STRIKEOUT)
+}
+
+/// The SGR-relevant part of a cell.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Style {
+    fg: AnsiColor,
+    bg: AnsiColor,
+    flags: CellFlags,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fg: AnsiColor::Named(NamedColor::Foreground),
+            bg: AnsiColor::Named(NamedColor::Background),
+            flags: CellFlags::empty(),
+        }
+    }
+}
+
+impl Style {
+    fn of(cell: &Cell) -> Self {
+        Self { fg: cell.fg, bg: cell.bg, flags: cell.flags & SGR_FLAGS }
+    }
+
+    /// Full SGR for this style, starting from a reset.
+    fn write_sgr(&self, out: &mut String) {
+        out.push_str("\x1b[0");
+        let attrs = [
+            (CellFlags::BOLD, ";1"),
+            (CellFlags::DIM, ";2"),
+            (CellFlags::ITALIC, ";3"),
+            (CellFlags::UNDERLINE, ";4"),
+            (CellFlags::DOUBLE_UNDERLINE, ";4:2"),
+            (CellFlags::UNDERCURL, ";4:3"),
+            (CellFlags::DOTTED_UNDERLINE, ";4:4"),
+            (CellFlags::DASHED_UNDERLINE, ";4:5"),
+            (CellFlags::INVERSE, ";7"),
+            (CellFlags::HIDDEN, ";8"),
+            (CellFlags::STRIKEOUT, ";9"),
+        ];
+        for (flag, code) in attrs {
+            if self.flags.contains(flag) {
+                out.push_str(code);
+            }
+        }
+        write_color(out, self.fg, 30);
+        write_color(out, self.bg, 40);
+        out.push('m');
+    }
+}
+
+/// Append `;<sgr>` for a color; `base` is 30 (foreground) or 40 (background).
+fn write_color(out: &mut String, color: AnsiColor, base: u8) {
+    match color {
+        AnsiColor::Named(named) => {
+            let idx = named as usize;
+            if idx < 8 {
+                let _ = write!(out, ";{}", base as usize + idx);
+            } else if idx < 16 {
+                let _ = write!(out, ";{}", base as usize + 60 + idx - 8);
+            } else if (NamedColor::DimBlack as usize..=NamedColor::DimWhite as usize).contains(&idx) {
+                // Dim variants come from the DIM flag; store the base color
+                let _ = write!(out, ";{}", base as usize + idx - NamedColor::DimBlack as usize);
+            }
+            // Foreground/Background/Cursor: terminal default, nothing to emit
+        }
+        AnsiColor::Indexed(idx) => {
+            let _ = write!(out, ";{};5;{}", base + 8, idx);
+        }
+        AnsiColor::Spec(rgb) => {
+            let _ = write!(out, ";{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b);
+        }
+    }
+}
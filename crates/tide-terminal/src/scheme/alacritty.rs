@@ -0,0 +1,83 @@
+// Alacritty themes: `colors.primary.{foreground,background}`,
+// `colors.cursor.cursor` and `colors.{normal,bright}.{black..white}`, in TOML
+// (current) or YAML (before 0.13). Both are flattened into dotted keys first.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use yaml_rust2::Yaml;
+
+use tide_core::Color;
+
+use super::{hex_entry, parse_hex_color, ColorScheme};
+
+const COLOR_NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+pub(super) fn parse_toml(text: &str) -> Result<ColorScheme, Box<dyn Error>> {
+    let table: toml::Table = text.parse()?;
+    let mut flat = HashMap::new();
+    for (key, value) in &table {
+        flatten_toml(value, key.clone(), &mut flat);
+    }
+    from_flat(&flat)
+}
+
+pub(super) fn parse_yaml(doc: &Yaml) -> Result<ColorScheme, Box<dyn Error>> {
+    let mut flat = HashMap::new();
+    flatten_yaml(doc, String::new(), &mut flat);
+    from_flat(&flat)
+}
+
+fn from_flat(flat: &HashMap<String, String>) -> Result<ColorScheme, Box<dyn Error>> {
+    let lookup = |key: &str| flat.get(key).map(String::as_str);
+    let mut ansi = [Color::BLACK; 16];
+    for (i, name) in COLOR_NAMES.iter().enumerate() {
+        ansi[i] = hex_entry(lookup, &format!("colors.normal.{}", name))?;
+        ansi[i + 8] = hex_entry(lookup, &format!("colors.bright.{}", name))?;
+    }
+    Ok(ColorScheme {
+        foreground: hex_entry(lookup, "colors.primary.foreground")?,
+        background: hex_entry(lookup, "colors.primary.background")?,
+        // "CellForeground"/"CellBackground" don't parse and mean the default
+        cursor: lookup("colors.cursor.cursor").and_then(parse_hex_color),
+        ansi,
+    })
+}
+
+fn flatten_yaml(value: &Yaml, prefix: String, flat: &mut HashMap<String, String>) {
+    match value {
+        Yaml::Hash(map) => {
+            for (k, v) in map {
+                if let Some(k) = k.as_str() {
+                    flatten_yaml(v, join_key(&prefix, k), flat);
+                }
+            }
+        }
+        Yaml::String(s) => {
+            flat.insert(prefix, s.clone());
+        }
+        _ => {}
+    }
+}
+
+fn flatten_toml(value: &toml::Value, prefix: String, flat: &mut HashMap<String, String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (k, v) in table {
+                flatten_toml(v, join_key(&prefix, k), flat);
+            }
+        }
+        toml::Value::String(s) => {
+            flat.insert(prefix, s.clone());
+        }
+        _ => {}
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
@@ -0,0 +1,41 @@
+// base16 schemes: YAML with `base00`..`base0F` as hex colors, either at the
+// top level (classic, named by `scheme`) or under `palette` (tinted-theming,
+// named by `name`). ANSI colors follow the base16-shell mapping.
+
+use std::error::Error;
+
+use yaml_rust2::Yaml;
+
+use tide_core::Color;
+
+use super::{hex_entry, ColorScheme};
+
+/// base16 slot for each ANSI color 0-15.
+const ANSI_SLOTS: [&str; 16] = [
+    "base00", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base05",
+    "base03", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base07",
+];
+
+/// The scheme and its name, if the file has one.
+pub(super) fn parse(doc: &Yaml) -> Result<(Option<String>, ColorScheme), Box<dyn Error>> {
+    let palette = if doc["palette"].is_badvalue() { doc } else { &doc["palette"] };
+    // Slot names are case-insensitive in the wild (base0a / base0A)
+    let lookup = |key: &str| {
+        palette[key].as_str().or_else(|| palette[key.to_ascii_lowercase().as_str()].as_str())
+    };
+    if lookup("base00").is_none() {
+        return Err("not a base16 scheme (no base00)".into());
+    }
+    let mut ansi = [Color::BLACK; 16];
+    for (slot, key) in ansi.iter_mut().zip(ANSI_SLOTS) {
+        *slot = hex_entry(lookup, key)?;
+    }
+    let name = doc["scheme"].as_str().or_else(|| doc["name"].as_str()).map(str::to_string);
+    let scheme = ColorScheme {
+        foreground: hex_entry(lookup, "base05")?,
+        background: hex_entry(lookup, "base00")?,
+        cursor: Some(hex_entry(lookup, "base05")?),
+        ansi,
+    };
+    Ok((name, scheme))
+}
@@ -0,0 +1,42 @@
+// iTerm2 `.itermcolors`: a plist dict of "Ansi 0 Color".."Ansi 15 Color",
+// "Foreground Color", "Background Color" and "Cursor Color", each a dict of
+// float "Red/Green/Blue Component" values in 0..1.
+
+use std::error::Error;
+
+use plist::{Dictionary, Value};
+
+use tide_core::Color;
+
+use super::ColorScheme;
+
+pub(super) fn parse(data: &[u8]) -> Result<ColorScheme, Box<dyn Error>> {
+    let value = Value::from_reader(std::io::Cursor::new(data))?;
+    let dict = value.as_dictionary().ok_or("itermcolors: expected a dictionary")?;
+    let mut ansi = [Color::BLACK; 16];
+    for (i, slot) in ansi.iter_mut().enumerate() {
+        *slot = color(dict, &format!("Ansi {} Color", i))?.ok_or_else(|| format!("itermcolors: missing Ansi {} Color", i))?;
+    }
+    Ok(ColorScheme {
+        foreground: color(dict, "Foreground Color")?.ok_or("itermcolors: missing Foreground Color")?,
+        background: color(dict, "Background Color")?.ok_or("itermcolors: missing Background Color")?,
+        cursor: color(dict, "Cursor Color")?,
+        ansi,
+    })
+}
+
+/// A color entry, None when the key is absent.
+fn color(dict: &Dictionary, key: &str) -> Result<Option<Color>, Box<dyn Error>> {
+    let Some(entry) = dict.get(key) else {
+        return Ok(None);
+    };
+    let entry = entry.as_dictionary().ok_or_else(|| format!("itermcolors: {} is not a dictionary", key))?;
+    let component = |name: &str| -> Result<f32, Box<dyn Error>> {
+        let value = entry
+            .get(name)
+            .and_then(|v| v.as_real().or_else(|| v.as_signed_integer().map(|i| i as f64)))
+            .ok_or_else(|| format!("itermcolors: {} has no {}", key, name))?;
+        Ok((value as f32).clamp(0.0, 1.0))
+    };
+    Ok(Some(Color::rgb(component("Red Component")?, component("Green Component")?, component("Blue Component")?)))
+}
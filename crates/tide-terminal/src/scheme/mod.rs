@@ -0,0 +1,180 @@
+// Color schemes: the 16 ANSI colors plus default foreground, background and
+// cursor. Every terminal has a scheme per app mode (dark/light); without one
+// it uses the built-in `TIDE_DARK` / `TIDE_LIGHT` palettes, which also get a
+// contrast boost against the app's pane background (see color.rs).
+//
+// The schemes are shared between the main thread (`Terminal`), the sync
+// thread (cell colors) and the event listener (OSC 4/10/11/12 replies).
+//
+// Theme files are parsed by format:
+//   .itermcolors          iTerm2 (XML or binary plist)
+//   .toml / .yml / .yaml  Alacritty (`colors.primary`, `colors.normal`, ...)
+//   .yml / .yaml          base16 (`base00`..`base0F`, flat or under `palette`)
+//   .json                 Windows Terminal scheme, list of schemes or a
+//                         settings.json with a `schemes` array
+
+use std::error::Error;
+
+use alacritty_terminal::vte::ansi::NamedColor;
+
+use tide_core::Color;
+
+mod alacritty;
+mod base16;
+mod iterm;
+mod windows_terminal;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorScheme {
+    pub foreground: Color,
+    /// Pane background; cells with the default background show it.
+    pub background: Color,
+    /// Cursor color. None = the foreground.
+    pub cursor: Option<Color>,
+    /// ANSI colors 0-15: black, red, green, yellow, blue, magenta, cyan,
+    /// white, then the bright variants in the same order.
+    pub ansi: [Color; 16],
+}
+
+impl ColorScheme {
+    /// Built-in dark palette, on the dark theme's pane background.
+    pub const TIDE_DARK: ColorScheme = ColorScheme {
+        foreground: Color::rgb(0.9, 0.91, 0.95),      // #E6E8F2
+        background: Color::rgb(0.055, 0.055, 0.063),  // dark pane_bg
+        cursor: None,
+        ansi: [
+            Color::rgb(0.1, 0.1, 0.14),
+            Color::rgb(1.0, 0.33, 0.33),   // #FF5555
+            Color::rgb(0.31, 0.98, 0.48),  // #50FA7B
+            Color::rgb(0.94, 0.9, 0.55),   // #F0E68D
+            Color::rgb(0.39, 0.58, 1.0),   // #6495FF
+            Color::rgb(0.74, 0.45, 1.0),   // #BD73FF
+            Color::rgb(0.35, 0.87, 0.93),  // #59DEED
+            Color::rgb(0.78, 0.8, 0.87),   // #C7CCDE
+            Color::rgb(0.4, 0.42, 0.53),   // #676B87
+            Color::rgb(1.0, 0.47, 0.42),   // #FF786B
+            Color::rgb(0.45, 1.0, 0.6),    // #73FF99
+            Color::rgb(1.0, 0.98, 0.55),   // #FFFA8D
+            Color::rgb(0.53, 0.7, 1.0),    // #87B3FF
+            Color::rgb(0.85, 0.6, 1.0),    // #D999FF
+            Color::rgb(0.47, 0.94, 1.0),   // #78F0FF
+            Color::rgb(0.95, 0.96, 0.98),  // #F2F5FA
+        ],
+    };
+
+    /// Built-in light palette: dark text on the warm beige pane background.
+    /// White/BrightWhite stay light so they work as background colors.
+    pub const TIDE_LIGHT: ColorScheme = ColorScheme {
+        foreground: Color::rgb(0.10, 0.08, 0.05),  // Warm near-black
+        background: Color::rgb(0.94, 0.92, 0.89),  // light pane_bg
+        cursor: None,
+        ansi: [
+            Color::rgb(0.0, 0.0, 0.0),
+            Color::rgb(0.68, 0.08, 0.08),
+            Color::rgb(0.05, 0.40, 0.10),
+            Color::rgb(0.45, 0.35, 0.0),
+            Color::rgb(0.10, 0.22, 0.65),
+            Color::rgb(0.48, 0.15, 0.65),
+            Color::rgb(0.0, 0.35, 0.42),
+            Color::rgb(0.85, 0.83, 0.80),
+            Color::rgb(0.40, 0.38, 0.35),
+            Color::rgb(0.75, 0.12, 0.10),
+            Color::rgb(0.08, 0.48, 0.12),
+            Color::rgb(0.52, 0.40, 0.0),
+            Color::rgb(0.12, 0.30, 0.75),
+            Color::rgb(0.55, 0.22, 0.75),
+            Color::rgb(0.05, 0.45, 0.50),
+            Color::rgb(0.95, 0.93, 0.90),
+        ],
+    };
+
+    /// The built-in palette for a mode.
+    pub fn builtin(dark_mode: bool) -> &'static ColorScheme {
+        if dark_mode {
+            &Self::TIDE_DARK
+        } else {
+            &Self::TIDE_LIGHT
+        }
+    }
+
+    /// Cursor color, falling back to the foreground.
+    pub fn cursor_color(&self) -> Color {
+        self.cursor.unwrap_or(self.foreground)
+    }
+
+    /// Color for a named color. Names without a scheme slot (dim variants)
+    /// use the foreground.
+    pub(crate) fn named(&self, named: NamedColor) -> Color {
+        match named {
+            NamedColor::Background => self.background,
+            NamedColor::Cursor => self.cursor_color(),
+            _ => self.ansi.get(named as usize).copied().unwrap_or(self.foreground),
+        }
+    }
+}
+
+/// A terminal's schemes for dark and light mode. None = the built-in palette.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SchemePair {
+    pub dark: Option<ColorScheme>,
+    pub light: Option<ColorScheme>,
+}
+
+impl SchemePair {
+    /// The same scheme in both modes.
+    pub fn both(scheme: ColorScheme) -> Self {
+        Self { dark: Some(scheme), light: Some(scheme) }
+    }
+
+    /// The scheme chosen for a mode, if any.
+    pub fn for_mode(&self, dark_mode: bool) -> Option<ColorScheme> {
+        if dark_mode {
+            self.dark
+        } else {
+            self.light
+        }
+    }
+}
+
+/// Parse a `#rrggbb`, `0xrrggbb` or `rrggbb` color.
+pub fn parse_hex_color(s: &str) -> Option<Color> {
+    let s = s.trim();
+    let hex = s.strip_prefix('#').or_else(|| s.strip_prefix("0x")).unwrap_or(s);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok().map(|v| v as f32 / 255.0);
+    Some(Color::rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Parse a theme file into named schemes, picking the format by extension
+/// (and, for YAML, by content). Schemes whose format carries no name are
+/// named after the file stem.
+pub fn parse_scheme_file(file_name: &str, data: &[u8]) -> Result<Vec<(String, ColorScheme)>, Box<dyn Error>> {
+    let path = std::path::Path::new(file_name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(file_name).to_string();
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_ascii_lowercase();
+    match ext.as_str() {
+        "itermcolors" => Ok(vec![(stem, iterm::parse(data)?)]),
+        "toml" => Ok(vec![(stem, alacritty::parse_toml(std::str::from_utf8(data)?)?)]),
+        "yml" | "yaml" => {
+            let text = std::str::from_utf8(data)?;
+            let docs = yaml_rust2::YamlLoader::load_from_str(text)?;
+            let doc = docs.first().ok_or("empty YAML file")?;
+            if !doc["colors"].is_badvalue() {
+                Ok(vec![(stem, alacritty::parse_yaml(doc)?)])
+            } else {
+                let (name, scheme) = base16::parse(doc)?;
+                Ok(vec![(name.unwrap_or(stem), scheme)])
+            }
+        }
+        "json" => windows_terminal::parse(std::str::from_utf8(data)?, &stem),
+        _ => Err(format!("unknown theme format: {}", file_name).into()),
+    }
+}
+
+/// Look up `key` in a name → color map, as a parsed hex color.
+fn hex_entry<'a>(lookup: impl Fn(&str) -> Option<&'a str>, key: &str) -> Result<Color, Box<dyn Error>> {
+    let value = lookup(key).ok_or_else(|| format!("missing color: {}", key))?;
+    parse_hex_color(value).ok_or_else(|| format!("bad color for {}: {}", key, value).into())
+}
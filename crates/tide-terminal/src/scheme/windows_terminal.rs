@@ -0,0 +1,50 @@
+// Windows Terminal color schemes: objects with "name", "foreground",
+// "background", "cursorColor" and the 16 colors by name ("purple" is
+// magenta). A file holds one scheme, a list of them, or a whole
+// settings.json with a "schemes" array.
+
+use std::error::Error;
+
+use serde_json::Value;
+
+use tide_core::Color;
+
+use super::{hex_entry, ColorScheme};
+
+const ANSI_KEYS: [&str; 16] = [
+    "black", "red", "green", "yellow", "blue", "purple", "cyan", "white",
+    "brightBlack", "brightRed", "brightGreen", "brightYellow", "brightBlue", "brightPurple", "brightCyan", "brightWhite",
+];
+
+pub(super) fn parse(text: &str, stem: &str) -> Result<Vec<(String, ColorScheme)>, Box<dyn Error>> {
+    let value: Value = serde_json::from_str(text)?;
+    let entries = match &value {
+        Value::Array(items) => items.as_slice(),
+        Value::Object(map) => match map.get("schemes") {
+            Some(Value::Array(items)) => items.as_slice(),
+            _ => std::slice::from_ref(&value),
+        },
+        _ => return Err("Windows Terminal scheme: expected an object or array".into()),
+    };
+    entries
+        .iter()
+        .map(|entry| {
+            let name = entry.get("name").and_then(Value::as_str).unwrap_or(stem).to_string();
+            Ok((name, scheme(entry)?))
+        })
+        .collect()
+}
+
+fn scheme(entry: &Value) -> Result<ColorScheme, Box<dyn Error>> {
+    let lookup = |key: &str| entry.get(key).and_then(Value::as_str);
+    let mut ansi = [Color::BLACK; 16];
+    for (slot, key) in ansi.iter_mut().zip(ANSI_KEYS) {
+        *slot = hex_entry(lookup, key)?;
+    }
+    Ok(ColorScheme {
+        foreground: hex_entry(lookup, "foreground")?,
+        background: hex_entry(lookup, "background")?,
+        cursor: lookup("cursorColor").and_then(super::parse_hex_color),
+        ansi,
+    })
+}
@@ -0,0 +1,91 @@
+// Scrollback search
+//
+// Queries are compiled into alacritty's `RegexSearch` (lazy DFAs that walk
+// grid cells directly), so matches follow soft-wrapped lines the same way
+// alacritty's own vi-mode search does. Plain-text queries are escaped and the
+// case / whole-word toggles are expressed as inline regex flags, which keeps
+// the editor and preview searches in tide-app on the exact same pattern.
+
+use std::error::Error;
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Direction, Point};
+use alacritty_terminal::term::cell::Flags as CellFlags;
+use alacritty_terminal::term::search::{RegexIter, RegexSearch};
+use alacritty_terminal::term::Term;
+
+use super::Terminal;
+
+/// Results are truncated beyond this many matches (e.g. a `.` regex).
+pub const MAX_SEARCH_MATCHES: usize = 10_000;
+
+/// `(absolute_line_from_top, col, cell_len)` of one match.
+pub type BufferMatch = (usize, usize, usize);
+
+/// Search bar toggles. The default is a case-insensitive plain-text search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Treat the query as a regular expression instead of literal text.
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Only match where the query starts and ends on a word boundary.
+    pub whole_word: bool,
+}
+
+impl SearchOptions {
+    /// Regex source for `query` under these options.
+    ///
+    /// Word boundaries are ASCII-only: alacritty's lazy DFA gives up on
+    /// Unicode `\b` as soon as it sees a non-ASCII byte.
+    pub fn pattern(&self, query: &str) -> String {
+        let body = if self.regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let body = if self.whole_word {
+            format!(r"(?-u:\b)(?:{body})(?-u:\b)")
+        } else {
+            body
+        };
+        let flags = if self.case_sensitive { "(?-i)" } else { "(?i)" };
+        format!("{flags}{body}")
+    }
+}
+
+impl Terminal {
+    /// Search the full scrollback + screen buffer.
+    /// Returns one `BufferMatch` per hit. A match that
+    /// continues across a soft wrap is reported once, with `col + cell_len`
+    /// running past the grid width; callers split it per row.
+    pub fn search_buffer(
+        &self,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<BufferMatch>, Box<dyn Error>> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut regex = RegexSearch::new(&options.pattern(query))?;
+        Ok(search_term(&self.term.lock(), &mut regex))
+    }
+}
+
+pub(crate) fn search_term<T>(term: &Term<T>, regex: &mut RegexSearch) -> Vec<BufferMatch> {
+    let grid = term.grid();
+    let history_len = grid.history_size() as i32;
+    let cols = grid.columns();
+    let start = Point::new(term.topmost_line(), Column(0));
+    let end = Point::new(term.bottommost_line(), term.last_column());
+
+    let mut results = Vec::new();
+    for m in RegexIter::new(start, end, Direction::Right, term, regex).take(MAX_SEARCH_MATCHES) {
+        let (first, last) = (*m.start(), *m.end());
+        // The match end is the leading cell of a wide char; cover its spacer too.
+        let last_width = if grid[last].flags.contains(CellFlags::WIDE_CHAR) { 2 } else { 1 };
+        let rows = (last.line - first.line).0 as usize;
+        let len = rows * cols + last.column.0 + last_width - first.column.0;
+        results.push(((first.line.0 + history_len) as usize, first.column.0, len));
+    }
+    results
+}
@@ -0,0 +1,231 @@
+// Mouse selection: simple, block, word, line and smart
+//
+// Selections are alacritty `Selection`s on the `Term`, in grid coordinates,
+// so they stay on their text while output scrolls and expand across
+// soft-wrapped lines. Copy mode selects through the same field. The sync
+// thread publishes the selected cells of the viewport as spans; click counts
+// and modifiers are mapped to a kind in tide-app.
+
+use std::sync::OnceLock;
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point, Side};
+use alacritty_terminal::selection::{Selection, SelectionType};
+use alacritty_terminal::term::cell::Flags as CellFlags;
+use alacritty_terminal::term::Term;
+use regex::Regex;
+
+use super::Terminal;
+
+/// Characters that end a word for double-click selection (alacritty's default).
+pub const DEFAULT_WORD_SEPARATORS: &str = alacritty_terminal::term::SEMANTIC_ESCAPE_CHARS;
+
+/// What a mouse selection grows by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionKind {
+    /// Cell by cell (click and drag).
+    Simple,
+    /// A rectangle of columns (Alt-drag).
+    Block,
+    /// Whole words (double-click).
+    Word,
+    /// Whole lines, following soft wraps (triple-click).
+    Lines,
+}
+
+impl SelectionKind {
+    pub(crate) fn selection_type(self) -> SelectionType {
+        match self {
+            Self::Simple => SelectionType::Simple,
+            Self::Block => SelectionType::Block,
+            Self::Word => SelectionType::Semantic,
+            Self::Lines => SelectionType::Lines,
+        }
+    }
+}
+
+impl Terminal {
+    /// Start a selection of `kind` at viewport cell `(row, col)`. `right_half`
+    /// says which half of the cell was clicked, so a click-drag only takes
+    /// the cells it crosses.
+    pub fn start_selection(&mut self, kind: SelectionKind, cell: (usize, usize), right_half: bool) {
+        let mut term = self.term.lock();
+        let point = viewport_point(&term, cell);
+        term.selection = Some(Selection::new(kind.selection_type(), point, side(right_half)));
+        drop(term);
+        self.mark_dirty();
+    }
+
+    /// Move the free end of the selection to viewport cell `(row, col)`.
+    pub fn update_selection(&mut self, cell: (usize, usize), right_half: bool) {
+        let mut term = self.term.lock();
+        let point = viewport_point(&term, cell);
+        let Some(selection) = term.selection.as_mut() else { return };
+        selection.update(point, side(right_half));
+        drop(term);
+        self.mark_dirty();
+    }
+
+    /// Select the URL, path, IP address or quoted string around viewport
+    /// cell `(row, col)`. Returns false (leaving the selection alone) if
+    /// the cell isn't in one.
+    pub fn select_smart(&mut self, cell: (usize, usize)) -> bool {
+        let mut term = self.term.lock();
+        let point = viewport_point(&term, cell);
+        let Some((start, end)) = smart_bounds(&term, point) else { return false };
+        term.selection = Some(range_selection(start, end));
+        drop(term);
+        self.mark_dirty();
+        true
+    }
+
+    /// Drop the selection. Returns false if there was none.
+    pub fn clear_selection(&mut self) -> bool {
+        let cleared = self.term.lock().selection.take().is_some();
+        if cleared {
+            self.mark_dirty();
+        }
+        cleared
+    }
+
+    /// Text of the selection; None when nothing is selected.
+    pub fn selection_text(&self) -> Option<String> {
+        self.term.lock().selection_to_string().filter(|text| !text.is_empty())
+    }
+
+    /// Selected cells per visible row from the latest snapshot:
+    /// `(row, first_col, end_col)`, end exclusive.
+    pub fn selection_spans(&self) -> &[(usize, usize, usize)] {
+        &self.selection_spans
+    }
+
+    /// Characters that end a word for double-click selection and the copy
+    /// mode word motions.
+    pub fn set_word_separators(&mut self, separators: &str) {
+        self.term.lock().set_semantic_escape_chars(separators);
+    }
+}
+
+fn side(right_half: bool) -> Side {
+    if right_half {
+        Side::Right
+    } else {
+        Side::Left
+    }
+}
+
+/// Grid point of a viewport cell, clamped to the screen.
+fn viewport_point<T>(term: &Term<T>, (row, col): (usize, usize)) -> Point {
+    let offset = term.grid().display_offset() as i32;
+    let row = row.min(term.screen_lines().saturating_sub(1)) as i32;
+    let col = col.min(term.columns().saturating_sub(1));
+    Point::new(Line(row - offset), Column(col))
+}
+
+/// Selection covering `start..=end`.
+pub(crate) fn range_selection(start: Point, end: Point) -> Selection {
+    let mut selection = Selection::new(SelectionType::Simple, start, Side::Left);
+    selection.update(end, Side::Right);
+    selection
+}
+
+/// Patterns for smart selection, most specific first.
+fn smart_patterns() -> &'static [Regex; 4] {
+    static PATTERNS: OnceLock<[Regex; 4]> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            // URLs, without trailing punctuation
+            Regex::new(r#"(?:https?|ftp|file|ssh|git)://[^\s<>"'`{}|\\^\[\]]*[^\s<>"'`{}|\\^\[\].,;:!?)]"#).unwrap(),
+            // IPv4 (with an optional port) and IPv6
+            Regex::new(
+                r"\b(?:\d{1,3}\.){3}\d{1,3}(?::\d{1,5})?\b|\b(?:[0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}\b|\b[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*::(?:[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*)?\b",
+            )
+            .unwrap(),
+            // Paths with a slash, or file names followed by `:line[:col]`
+            Regex::new(
+                r"(?:~|\.\.?)?(?:/[\w.@%+~-]+)+/?(?::\d+){0,2}|[\w.@%+~-]+(?:/[\w.@%+~-]+)+/?(?::\d+){0,2}|[\w.-]+\.[A-Za-z]\w*(?::\d+){1,2}",
+            )
+            .unwrap(),
+            // Quoted strings; the quotes themselves aren't selected
+            Regex::new(r#""([^"]*)"|'([^']*)'|`([^`]*)`"#).unwrap(),
+        ]
+    })
+}
+
+/// Bounds (inclusive) of the smart selection around `point`, if any.
+pub(crate) fn smart_bounds<T>(term: &Term<T>, point: Point) -> Option<(Point, Point)> {
+    // The logical line around the point, joining soft wraps
+    let first = term.line_search_left(point).line;
+    let last = term.line_search_right(point).line;
+    let grid = term.grid();
+    let mut text = String::new();
+    let mut starts: Vec<(usize, Point)> = Vec::new();
+    let mut clicked = None;
+    for line in first.0..=last.0 {
+        let line = Line(line);
+        for col in 0..term.columns() {
+            let cell_point = Point::new(line, Column(col));
+            let cell = &grid[cell_point];
+            if cell.flags.intersects(CellFlags::WIDE_CHAR_SPACER | CellFlags::LEADING_WIDE_CHAR_SPACER) {
+                continue;
+            }
+            if cell_point == point {
+                clicked = Some(text.len());
+            }
+            starts.push((text.len(), cell_point));
+            text.push(cell.c);
+        }
+    }
+    // A click on the right half of a wide character lands on its spacer
+    let clicked = clicked.or_else(|| starts.iter().rev().find(|(_, p)| *p < point).map(|&(i, _)| i))?;
+
+    let point_at = |index: usize| starts.iter().find(|(i, _)| *i == index).map(|&(_, p)| p);
+    let last_point_before = |index: usize| starts.iter().rev().find(|(i, _)| *i < index).map(|&(_, p)| p);
+    for (kind, regex) in smart_patterns().iter().enumerate() {
+        for caps in regex.captures_iter(&text) {
+            let whole = caps.get(0)?;
+            if clicked < whole.start() || clicked >= whole.end() {
+                continue;
+            }
+            let found = if kind == 3 {
+                // Quotes open and close at word boundaries, so `don't` isn't one
+                let before = text[..whole.start()].chars().next_back();
+                let after = text[whole.end()..].chars().next();
+                if before.is_some_and(char::is_alphanumeric) || after.is_some_and(char::is_alphanumeric) {
+                    continue;
+                }
+                match caps.iter().skip(1).flatten().next() {
+                    Some(inner) if !inner.is_empty() => inner,
+                    _ => continue,
+                }
+            } else {
+                whole
+            };
+            return Some((point_at(found.start())?, last_point_before(found.end())?));
+        }
+    }
+    None
+}
+
+/// Selected cells of the viewport as `(row, first_col, end_col)` spans,
+/// end exclusive. Called with the term lock held.
+pub(crate) fn viewport_spans<T>(term: &Term<T>, spans: &mut Vec<(usize, usize, usize)>) {
+    spans.clear();
+    let Some(range) = term.selection.as_ref().and_then(|s| s.to_range(term)) else { return };
+    let offset = term.grid().display_offset() as i32;
+    let rows = term.screen_lines() as i32;
+    let last_col = term.columns().saturating_sub(1);
+    let first_row = (range.start.line.0 + offset).max(0);
+    let last_row = (range.end.line.0 + offset).min(rows - 1);
+    for row in first_row..=last_row {
+        let line = Line(row - offset);
+        let (first, last) = if range.is_block {
+            (range.start.column.0, range.end.column.0)
+        } else {
+            let first = if line == range.start.line { range.start.column.0 } else { 0 };
+            let last = if line == range.end.line { range.end.column.0 } else { last_col };
+            (first, last)
+        };
+        spans.push((row as usize, first, last + 1));
+    }
+}
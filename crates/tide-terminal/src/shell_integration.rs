@@ -0,0 +1,450 @@
+// Shell integration: OSC 133 (FTCS) prompt/command/output marks
+//
+//   OSC 133 ; A        prompt starts
+//   OSC 133 ; B        prompt ends, command input starts
+//   OSC 133 ; C        command submitted, output starts
+//   OSC 133 ; D [; N]  command finished (N = exit status)
+//
+// Marks arrive on the PTY thread via Event::UnhandledOsc with the cursor
+// position, in "stable" line coordinates (lines scrolled off the top so far
+// + screen line). Stable lines don't shift as output scrolls, so a region
+// recorded once stays valid until it falls out of scrollback.
+//
+// The bundled zsh/bash/fish scripts are written to a temp directory once and
+// injected into the spawned shell by `Terminal::with_cwd`.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, SystemTime};
+
+use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::{Term, TermMode};
+
+use super::{selection, Terminal};
+
+/// Oldest regions are dropped beyond this many, even if still in scrollback.
+const MAX_REGIONS: usize = 1000;
+
+/// Outcome of a finished shell command, kept after its region is pruned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommandStatus {
+    /// None if the shell didn't report a status.
+    pub exit_code: Option<i32>,
+    pub started_at: SystemTime,
+    pub duration: Duration,
+}
+
+impl CommandStatus {
+    /// Keep at most this many finished commands per terminal.
+    pub const HISTORY_LIMIT: usize = 256;
+
+    pub fn failed(&self) -> bool {
+        self.exit_code.is_some_and(|code| code != 0)
+    }
+}
+
+/// One prompt → command → output cycle, in stable line coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandRegion {
+    /// Line where the prompt starts (OSC 133;A).
+    pub prompt_line: u64,
+    /// Where the command input starts, right after the prompt (OSC 133;B).
+    pub command_start: Option<(u64, usize)>,
+    /// Line where the command output starts (OSC 133;C).
+    pub output_line: Option<u64>,
+    /// Cursor position when the command finished (OSC 133;D); output ends
+    /// just before it.
+    pub end: Option<(u64, usize)>,
+    /// Exit status reported with OSC 133;D, if the shell sent one.
+    pub exit_code: Option<i32>,
+    /// When the command was submitted.
+    pub started_at: Option<SystemTime>,
+    /// Wall time from submission to completion.
+    pub duration: Option<Duration>,
+}
+
+impl CommandRegion {
+    fn new(prompt_line: u64) -> Self {
+        Self {
+            prompt_line,
+            command_start: None,
+            output_line: None,
+            end: None,
+            exit_code: None,
+            started_at: None,
+            duration: None,
+        }
+    }
+
+    /// A command was submitted and hasn't finished yet.
+    pub fn is_running(&self) -> bool {
+        self.output_line.is_some() && self.end.is_none()
+    }
+}
+
+/// A prompt in the viewport whose command has finished, for gutter markers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptMark {
+    /// Viewport row of the prompt.
+    pub row: usize,
+    pub exit_code: i32,
+}
+
+/// Per-terminal list of command regions, shared between the event listener
+/// (writer, PTY thread), the sync thread and `Terminal` (main thread).
+#[derive(Debug, Default)]
+pub(crate) struct ShellMarks {
+    regions: VecDeque<CommandRegion>,
+    /// Number of commands finished so far (never decreases).
+    finished: u64,
+    /// The last `CommandStatus::HISTORY_LIMIT` of them, recorded at `D`.
+    statuses: VecDeque<CommandStatus>,
+}
+
+impl ShellMarks {
+    /// Apply an OSC payload. Returns false if it isn't an OSC 133 mark.
+    pub(crate) fn apply(&mut self, payload: &str, line: u64, column: usize) -> bool {
+        let Some(mark) = payload.strip_prefix("133;") else {
+            return false;
+        };
+        match mark.split(';').next().unwrap_or_default() {
+            "A" => {
+                // A redrawn prompt (e.g. after a resize) replaces the last one.
+                match self.regions.back_mut() {
+                    Some(last) if last.prompt_line == line && last.output_line.is_none() => {
+                        *last = CommandRegion::new(line);
+                    }
+                    _ => self.regions.push_back(CommandRegion::new(line)),
+                }
+                if self.regions.len() > MAX_REGIONS {
+                    self.regions.pop_front();
+                }
+            }
+            "B" => {
+                if let Some(last) = self.regions.back_mut() {
+                    last.command_start = Some((line, column));
+                }
+            }
+            "C" => {
+                if let Some(last) = self.regions.back_mut() {
+                    last.output_line = Some(line);
+                    last.end = None;
+                    last.started_at = Some(SystemTime::now());
+                }
+            }
+            "D" => {
+                // Shells also send D after an empty command line; only a
+                // submitted command (C seen) has output to close.
+                if let Some(last) = self.regions.back_mut() {
+                    if last.is_running() {
+                        last.end = Some((line, column));
+                        last.exit_code = mark.split(';').nth(1).and_then(|code| code.parse().ok());
+                        last.duration = last.started_at.and_then(|t| t.elapsed().ok());
+                        self.finished += 1;
+                        if self.statuses.len() == CommandStatus::HISTORY_LIMIT {
+                            self.statuses.pop_front();
+                        }
+                        self.statuses.push_back(CommandStatus {
+                            exit_code: last.exit_code,
+                            started_at: last.started_at.unwrap_or_else(SystemTime::now),
+                            duration: last.duration.unwrap_or_default(),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Drop regions whose prompt has scrolled out of the history buffer.
+    /// `first_line` is the stable line of the oldest line still in the grid.
+    pub(crate) fn prune(&mut self, first_line: u64) {
+        while self.regions.front().is_some_and(|r| r.prompt_line < first_line) {
+            self.regions.pop_front();
+        }
+    }
+
+    pub(crate) fn regions(&self) -> &VecDeque<CommandRegion> {
+        &self.regions
+    }
+
+    pub(crate) fn finished(&self) -> u64 {
+        self.finished
+    }
+
+    /// Statuses of the commands finished after the first `since`, as many
+    /// as are kept.
+    pub(crate) fn finished_since(&self, since: u64) -> Vec<CommandStatus> {
+        let new = self.finished.saturating_sub(since).min(self.statuses.len() as u64) as usize;
+        self.statuses.range(self.statuses.len() - new..).copied().collect()
+    }
+
+    /// Collect finished prompts within `rows` lines from stable line
+    /// `viewport_top` into `out`.
+    pub(crate) fn visible_marks(&self, viewport_top: u64, rows: usize, out: &mut Vec<PromptMark>) {
+        out.clear();
+        let bottom = viewport_top + rows as u64;
+        for region in self.regions.iter().rev() {
+            if region.prompt_line < viewport_top {
+                break;
+            }
+            if region.prompt_line >= bottom {
+                continue;
+            }
+            if let Some(exit_code) = region.exit_code {
+                out.push(PromptMark { row: (region.prompt_line - viewport_top) as usize, exit_code });
+            }
+        }
+        out.reverse();
+    }
+}
+
+// ──────────────────────────────────────────────
+// Terminal API
+// ──────────────────────────────────────────────
+
+impl Terminal {
+    /// Prompt/command/output regions still in scrollback, oldest first.
+    /// Empty if the shell has no integration loaded.
+    pub fn command_regions(&self) -> Vec<CommandRegion> {
+        let first_line = self.first_stable_line();
+        let mut marks = self.shell_marks.lock().unwrap_or_else(|e| e.into_inner());
+        marks.prune(first_line);
+        marks.regions().iter().copied().collect()
+    }
+
+    /// Number of commands finished so far. Cheap to poll; fetch
+    /// `finished_commands()` only when it changes.
+    pub fn finished_command_count(&self) -> u64 {
+        self.shell_marks.lock().unwrap_or_else(|e| e.into_inner()).finished()
+    }
+
+    /// The number of commands finished so far, and the statuses of those
+    /// after the first `since` (at most `CommandStatus::HISTORY_LIMIT`).
+    /// Unlike `command_regions()`, these outlive the scrollback.
+    pub fn finished_commands(&self, since: u64) -> (u64, Vec<CommandStatus>) {
+        let marks = self.shell_marks.lock().unwrap_or_else(|e| e.into_inner());
+        (marks.finished(), marks.finished_since(since))
+    }
+
+    /// Finished prompts in the viewport (read from the latest snapshot).
+    pub fn prompt_marks(&self) -> &[PromptMark] {
+        &self.prompt_marks
+    }
+
+    /// Whether a command is running, according to shell integration marks.
+    /// None if the shell hasn't sent any marks.
+    pub fn command_running(&self) -> Option<bool> {
+        let marks = self.shell_marks.lock().unwrap_or_else(|e| e.into_inner());
+        marks.regions().back().map(CommandRegion::is_running)
+    }
+
+    /// Scroll so the previous (`forward = false`) or next prompt is at the top
+    /// of the viewport. Returns false if there is no such prompt.
+    pub fn scroll_to_prompt(&mut self, forward: bool) -> bool {
+        let regions = self.command_regions();
+        let mut term = self.term.lock();
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return false;
+        }
+        let scrolled = term.grid().scrolled_lines();
+        let offset = term.grid().display_offset() as u64;
+        let viewport_top = scrolled.saturating_sub(offset);
+        let target = if forward {
+            regions.iter().map(|r| r.prompt_line).find(|&l| l > viewport_top)
+        } else {
+            regions.iter().rev().map(|r| r.prompt_line).find(|&l| l < viewport_top)
+        };
+        let Some(target) = target else {
+            return false;
+        };
+
+        // Prompts on the live screen can't scroll past the bottom.
+        let new_offset = scrolled.saturating_sub(target).min(term.grid().history_size() as u64);
+        term.scroll_display(Scroll::Delta(new_offset as i32 - offset as i32));
+        drop(term);
+
+        // Back at the bottom: resume following output.
+        if new_offset == 0 {
+            self.request_scroll_to_bottom();
+        } else {
+            self.stay_at_bottom.store(false, Ordering::Relaxed);
+            self.dirty.store(true, Ordering::Relaxed);
+            self.notify_sync_thread();
+        }
+        true
+    }
+
+    /// Text output of the most recent finished command.
+    pub fn last_command_output(&self) -> Option<String> {
+        let bounds = self.last_output_bounds()?;
+        let term = self.term.lock();
+        let Some((start, end)) = output_points(&term, bounds) else { return Some(String::new()) };
+        let text = term.bounds_to_string(start, end);
+        Some(text.trim_end_matches('\n').to_string())
+    }
+
+    /// Scroll the most recent finished command's output into view and select
+    /// it. Returns false if there is none.
+    pub fn reveal_last_output(&mut self) -> bool {
+        let Some(bounds) = self.last_output_bounds() else { return false };
+        let start = bounds.0;
+        let (scrolled, offset, rows) = {
+            let term = self.term.lock();
+            let grid = term.grid();
+            (grid.scrolled_lines(), grid.display_offset() as u64, grid.screen_lines() as u64)
+        };
+        let viewport_top = scrolled.saturating_sub(offset);
+        let viewport_bottom = viewport_top + rows;
+        if start.0 < viewport_top || start.0 >= viewport_bottom {
+            let delta = offset as i64 - scrolled.saturating_sub(start.0) as i64;
+            self.scroll_display(-delta as i32);
+        }
+
+        let mut term = self.term.lock();
+        let Some((start, end)) = output_points(&term, bounds) else { return false };
+        term.selection = Some(selection::range_selection(start, end));
+        drop(term);
+        self.mark_dirty();
+        true
+    }
+
+    /// Stable (start, exclusive end) of the last finished command's output.
+    fn last_output_bounds(&self) -> Option<((u64, usize), (u64, usize))> {
+        let regions = self.command_regions();
+        let region = regions.iter().rev().find(|r| r.end.is_some())?;
+        Some(((region.output_line?, 0), region.end?))
+    }
+
+    /// Stable line of the oldest line in the grid (top of scrollback).
+    fn first_stable_line(&self) -> u64 {
+        let term = self.term.lock();
+        term.grid().scrolled_lines().saturating_sub(term.grid().history_size() as u64)
+    }
+}
+
+/// Grid points of the first and last cell of stable output bounds (the end
+/// is exclusive); None when the output is empty.
+fn output_points<T>(term: &Term<T>, (start, end): ((u64, usize), (u64, usize))) -> Option<(Point, Point)> {
+    let scrolled = term.grid().scrolled_lines();
+    let history = term.grid().history_size() as i64;
+    let to_point = |(line, col): (u64, usize)| {
+        let line = (line as i64 - scrolled as i64).max(-history);
+        Point::new(Line(line as i32), Column(col))
+    };
+    let start = to_point(start);
+    let end = to_point(end);
+    if end <= start {
+        return None;
+    }
+    // `end` is exclusive: step back one cell (wrapping to the previous line).
+    let last_col = term.grid().columns() - 1;
+    let end = if end.column.0 == 0 {
+        Point::new(end.line - 1, Column(last_col))
+    } else {
+        Point::new(end.line, end.column - 1)
+    };
+    Some((start, end))
+}
+
+// ──────────────────────────────────────────────
+// Shell script injection
+// ──────────────────────────────────────────────
+
+const ZSH_ENV: &str = include_str!("../shell/zshenv");
+const ZSH_SCRIPT: &str = include_str!("../shell/tide.zsh");
+const BASH_SCRIPT: &str = include_str!("../shell/tide.bash");
+const FISH_SCRIPT: &str = include_str!("../shell/tide.fish");
+
+/// Directory holding the integration scripts, `tide/shell-integration` in the
+/// user's config directory. Checked on every spawn, since anything may have
+/// removed or changed the files since the last one. None if it isn't safe to
+/// use or couldn't be written (the shell then starts without integration).
+fn scripts_dir() -> Option<PathBuf> {
+    let dir = dirs::config_dir()?.join("tide").join("shell-integration");
+    match write_scripts(&dir) {
+        Ok(()) => Some(dir),
+        Err(e) => {
+            log::warn!("shell integration disabled: failed to write scripts to {}: {}", dir.display(), e);
+            None
+        }
+    }
+}
+
+/// Create `dir` private to the user (0700) and bring the scripts in it up to
+/// date. Fails if it or its `zsh` subdirectory belongs to someone else.
+pub(crate) fn write_scripts(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir.join("zsh"))?;
+    let uid = unsafe { libc::getuid() };
+    for dir in [dir.to_path_buf(), dir.join("zsh")] {
+        let meta = std::fs::symlink_metadata(&dir)?;
+        if !meta.is_dir() || meta.uid() != uid {
+            let message = format!("{} is not a directory owned by this user", dir.display());
+            return Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, message));
+        }
+        if meta.mode() & 0o077 != 0 {
+            std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+    }
+    let scripts = [
+        ("zsh/.zshenv", ZSH_ENV),
+        ("zsh/tide.zsh", ZSH_SCRIPT),
+        ("tide.bash", BASH_SCRIPT),
+        ("tide.fish", FISH_SCRIPT),
+    ];
+    for (name, script) in scripts {
+        let path = dir.join(name);
+        if std::fs::read(&path).ok().as_deref() != Some(script.as_bytes()) {
+            std::fs::write(&path, script)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether `shell` (a path or bare name) is one the bundled scripts cover.
+pub(crate) fn is_supported(shell: &str) -> bool {
+    let name = Path::new(shell).file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    matches!(name, "zsh" | "bash" | "fish")
+}
+
+/// Arguments for launching `shell` with integration loaded, adding any needed
+/// variables to `env`. Unknown shells (or `TIDE_SHELL_INTEGRATION=0`) get a
+/// plain login shell.
+pub(crate) fn shell_args(shell: &str, env: &mut HashMap<String, String>) -> Vec<String> {
+    let login = vec![String::from("--login")];
+    if std::env::var("TIDE_SHELL_INTEGRATION").is_ok_and(|v| v == "0") {
+        return login;
+    }
+    if !is_supported(shell) {
+        return login;
+    }
+    let name = Path::new(shell).file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let Some(dir) = scripts_dir() else {
+        return login;
+    };
+
+    match name {
+        "zsh" => {
+            // zsh reads $ZDOTDIR/.zshenv first; ours restores the user's
+            // ZDOTDIR and sources their startup files before adding hooks.
+            if let Ok(orig) = std::env::var("ZDOTDIR") {
+                env.insert(String::from("TIDE_ORIG_ZDOTDIR"), orig);
+            }
+            env.insert(String::from("ZDOTDIR"), dir.join("zsh").to_string_lossy().into_owned());
+            login
+        }
+        // --rcfile doesn't combine with --login; the script sources the
+        // login profile files itself.
+        "bash" => vec![String::from("--rcfile"), dir.join("tide.bash").to_string_lossy().into_owned()],
+        _ => vec![
+            String::from("--login"),
+            String::from("--init-command"),
+            format!("source '{}'", dir.join("tide.fish").to_string_lossy()),
+        ],
+    }
+}
@@ -0,0 +1,227 @@
+// Sixel decoding
+//
+// `DCS P1 ; P2 ; P3 q <data> ST`. The data is a stream of sixel characters
+// (`?`..`~`, six vertical pixels each, low bit on top) in the current color,
+// plus a few controls:
+//
+//   "Pan;Pad;Ph;Pv   raster attributes: aspect ratio, image size in pixels
+//   #Pc              select color register Pc
+//   #Pc;Pu;Px;Py;Pz  define register Pc: Pu=1 HLS (degrees, %), Pu=2 RGB (%)
+//   !Pn<char>        repeat the sixel character Pn times
+//   $                back to the start of the current band
+//   -                next band (six pixel rows down)
+//
+// Pixels are square whatever P1 or Pan/Pad ask for. P2=1 leaves pixels no
+// sixel touched transparent; otherwise they take register 0's color.
+
+use super::graphics::{DecodedImage, MAX_IMAGE_SIDE};
+
+const REGISTERS: usize = 256;
+
+/// VT340 default color registers, in percent.
+const VT340_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (20, 20, 80),
+    (80, 13, 13),
+    (20, 80, 20),
+    (80, 20, 80),
+    (20, 80, 80),
+    (80, 80, 20),
+    (53, 53, 53),
+    (26, 26, 26),
+    (33, 33, 60),
+    (60, 26, 26),
+    (33, 60, 33),
+    (60, 33, 60),
+    (33, 60, 60),
+    (60, 60, 33),
+    (80, 80, 80),
+];
+
+/// Decode the body of a sixel DCS (parameters, `q`, data). `None` if it
+/// draws nothing.
+pub(crate) fn decode(data: &[u8]) -> Option<DecodedImage> {
+    let q = data.iter().position(|&b| b == b'q')?;
+    let params = parse_params(&data[..q]);
+    let transparent = params.get(1) == Some(&1);
+
+    let mut canvas = Canvas::default();
+    let mut palette = [0xff00_0000u32; REGISTERS];
+    for (register, &(r, g, b)) in palette.iter_mut().zip(&VT340_PALETTE) {
+        *register = rgba(percent(r), percent(g), percent(b));
+    }
+    let mut color = palette[0];
+    let (mut x, mut y) = (0usize, 0usize);
+
+    let mut rest = &data[q + 1..];
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'"' => {
+                let (values, tail) = take_numbers(rest);
+                rest = tail;
+                if let (Some(&w), Some(&h)) = (values.get(2), values.get(3)) {
+                    canvas.reserve(w as usize, h as usize);
+                }
+            }
+            b'#' => {
+                let (values, tail) = take_numbers(rest);
+                rest = tail;
+                let register = values.first().copied().unwrap_or(0) as usize % REGISTERS;
+                if let [_, space, a, b, c, ..] = values[..] {
+                    palette[register] = match space {
+                        1 => hls(a, b, c),
+                        2 => rgba(percent(a), percent(b), percent(c)),
+                        _ => palette[register],
+                    };
+                }
+                color = palette[register];
+            }
+            b'!' => {
+                let (values, tail) = take_numbers(rest);
+                let count = values.first().copied().unwrap_or(1).max(1) as usize;
+                let Some((&sixel, tail)) = tail.split_first() else { break };
+                rest = tail;
+                if (b'?'..=b'~').contains(&sixel) {
+                    canvas.draw(x, y, count, sixel - b'?', color);
+                    x += count;
+                }
+            }
+            b'$' => x = 0,
+            b'-' => {
+                x = 0;
+                y += 6;
+            }
+            b'?'..=b'~' => {
+                canvas.draw(x, y, 1, byte - b'?', color);
+                x += 1;
+            }
+            _ => {}
+        }
+    }
+
+    let background = if transparent { 0 } else { palette[0] };
+    canvas.finish(background)
+}
+
+/// `P1;P2;P3` before the `q`; missing values are 0.
+fn parse_params(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .split(|&b| b == b';')
+        .map(|p| std::str::from_utf8(p).ok().and_then(|p| p.parse().ok()).unwrap_or(0))
+        .collect()
+}
+
+/// Read `N;N;...` from the front of `bytes`, returning the numbers and the rest.
+fn take_numbers(bytes: &[u8]) -> (Vec<u32>, &[u8]) {
+    let end = bytes.iter().position(|b| !b.is_ascii_digit() && *b != b';').unwrap_or(bytes.len());
+    let values = bytes[..end]
+        .split(|&b| b == b';')
+        .map(|p| p.iter().fold(0u32, |n, d| n.saturating_mul(10).saturating_add((d - b'0') as u32)))
+        .collect();
+    (values, &bytes[end..])
+}
+
+fn percent(value: impl Into<u32>) -> u8 {
+    (value.into().min(100) * 255 / 100) as u8
+}
+
+fn rgba(r: u8, g: u8, b: u8) -> u32 {
+    u32::from_le_bytes([r, g, b, 0xff])
+}
+
+/// DEC HLS: hue 0 is blue and 120 red, lightness and saturation in percent.
+fn hls(hue: u32, lightness: u32, saturation: u32) -> u32 {
+    let h = ((hue + 240) % 360) as f32 / 360.0;
+    let l = lightness.min(100) as f32 / 100.0;
+    let s = saturation.min(100) as f32 / 100.0;
+    if s == 0.0 {
+        let v = (l * 255.0).round() as u8;
+        return rgba(v, v, v);
+    }
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.0);
+        let v = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+        (v * 255.0).round() as u8
+    };
+    rgba(channel(h + 1.0 / 3.0), channel(h), channel(h - 1.0 / 3.0))
+}
+
+/// Growable pixel buffer; 0 marks a pixel no sixel set.
+#[derive(Default)]
+struct Canvas {
+    pixels: Vec<u32>,
+    /// Allocated size.
+    stride: usize,
+    rows: usize,
+    /// Size declared by raster attributes or drawn so far.
+    width: usize,
+    height: usize,
+}
+
+impl Canvas {
+    /// Grow to at least `width` × `height`, within the image size limit.
+    fn reserve(&mut self, width: usize, height: usize) {
+        let width = width.min(MAX_IMAGE_SIDE);
+        let height = height.min(MAX_IMAGE_SIDE);
+        self.width = self.width.max(width);
+        self.height = self.height.max(height);
+        if width <= self.stride && height <= self.rows {
+            return;
+        }
+        let stride = if width > self.stride { width.max(self.stride * 2).min(MAX_IMAGE_SIDE) } else { self.stride };
+        let rows = if height > self.rows { height.max(self.rows * 2).min(MAX_IMAGE_SIDE) } else { self.rows };
+        let mut pixels = vec![0; stride * rows];
+        for row in 0..self.rows {
+            pixels[row * stride..row * stride + self.stride]
+                .copy_from_slice(&self.pixels[row * self.stride..(row + 1) * self.stride]);
+        }
+        self.pixels = pixels;
+        self.stride = stride;
+        self.rows = rows;
+    }
+
+    /// Draw `count` copies of the six-pixel column `bits` starting at `x`.
+    fn draw(&mut self, x: usize, y: usize, count: usize, bits: u8, color: u32) {
+        if bits == 0 || x >= MAX_IMAGE_SIDE || y >= MAX_IMAGE_SIDE {
+            // An empty sixel still widens the image.
+            self.reserve(x + count, 0);
+            return;
+        }
+        let top = (0..6).find(|i| bits & (1 << i) != 0).unwrap_or(0);
+        let bottom = (0..6).rev().find(|i| bits & (1 << i) != 0).unwrap_or(0);
+        self.reserve(x + count, y + bottom + 1);
+        let end = (x + count).min(self.stride);
+        for i in top..=bottom {
+            let row = y + i;
+            if bits & (1 << i) == 0 || row >= self.rows {
+                continue;
+            }
+            self.pixels[row * self.stride + x..row * self.stride + end].fill(color);
+        }
+    }
+
+    fn finish(self, background: u32) -> Option<DecodedImage> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let mut rgba = Vec::with_capacity(self.width * self.height * 4);
+        for row in 0..self.height {
+            for &pixel in &self.pixels[row * self.stride..row * self.stride + self.width] {
+                let pixel = if pixel == 0 { background } else { pixel };
+                rgba.extend_from_slice(&pixel.to_le_bytes());
+            }
+        }
+        Some(DecodedImage { width: self.width as u32, height: self.height as u32, rgba })
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::*;
-    use tide_core::{Key, Modifiers};
+    use tide_core::{Key, Modifiers, MouseButton};
 
     #[test]
     fn test_key_to_bytes_char() {
@@ -82,4 +82,1543 @@ mod tests {
         // No trimming needed
         assert_eq!(crate::trim_url_trailing("https://example.com/page"), "https://example.com/page");
     }
+
+    #[test]
+    fn test_mouse_to_bytes_sgr_press_release() {
+        let mods = Modifiers::default();
+        let press = Terminal::mouse_to_bytes(MouseReport::Press(MouseButton::Left), 4, 9, &mods, MouseEncoding::Sgr);
+        assert_eq!(press.unwrap(), b"\x1b[<0;5;10M".to_vec());
+        // SGR release keeps the button code and uses lowercase 'm'
+        let release = Terminal::mouse_to_bytes(MouseReport::Release(MouseButton::Right), 4, 9, &mods, MouseEncoding::Sgr);
+        assert_eq!(release.unwrap(), b"\x1b[<2;5;10m".to_vec());
+    }
+
+    #[test]
+    fn test_mouse_to_bytes_x10() {
+        let mods = Modifiers::default();
+        let press = Terminal::mouse_to_bytes(MouseReport::Press(MouseButton::Left), 0, 0, &mods, MouseEncoding::X10);
+        assert_eq!(press.unwrap(), vec![0x1b, b'[', b'M', 32, 33, 33]);
+        // Non-SGR release is button 3
+        let release = Terminal::mouse_to_bytes(MouseReport::Release(MouseButton::Left), 0, 0, &mods, MouseEncoding::X10);
+        assert_eq!(release.unwrap(), vec![0x1b, b'[', b'M', 35, 33, 33]);
+        // Coordinates beyond 223 can't be encoded
+        assert!(Terminal::mouse_to_bytes(MouseReport::Press(MouseButton::Left), 300, 0, &mods, MouseEncoding::X10).is_none());
+    }
+
+    #[test]
+    fn test_mouse_to_bytes_modifiers_wheel_and_motion() {
+        let mods = Modifiers { shift: true, ctrl: true, ..Default::default() };
+        let wheel = Terminal::mouse_to_bytes(MouseReport::WheelDown, 0, 0, &mods, MouseEncoding::Sgr);
+        assert_eq!(wheel.unwrap(), b"\x1b[<85;1;1M".to_vec()); // 65 + 4 + 16
+        let drag = Terminal::mouse_to_bytes(MouseReport::Drag(MouseButton::Left), 1, 1, &Modifiers::default(), MouseEncoding::Sgr);
+        assert_eq!(drag.unwrap(), b"\x1b[<32;2;2M".to_vec());
+        let motion = Terminal::mouse_to_bytes(MouseReport::Move, 1, 1, &Modifiers::default(), MouseEncoding::Urxvt);
+        assert_eq!(motion.unwrap(), b"\x1b[67;2;2M".to_vec()); // 32 + 35
+    }
+
+    #[test]
+    fn test_mouse_to_bytes_utf8_large_coords() {
+        let bytes = Terminal::mouse_to_bytes(MouseReport::Press(MouseButton::Left), 299, 0, &Modifiers::default(), MouseEncoding::Utf8).unwrap();
+        let mut expected = vec![0x1b, b'[', b'M', 32];
+        expected.extend_from_slice("\u{14C}".as_bytes()); // 32 + 300
+        expected.push(33);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_mouse_protocol_wants() {
+        let click = MouseProtocol { tracking: MouseTracking::Click, encoding: MouseEncoding::Sgr };
+        assert!(click.wants(MouseReport::Press(MouseButton::Left)));
+        assert!(click.wants(MouseReport::WheelUp));
+        assert!(!click.wants(MouseReport::Drag(MouseButton::Left)));
+        let drag = MouseProtocol { tracking: MouseTracking::Drag, ..click };
+        assert!(drag.wants(MouseReport::Drag(MouseButton::Left)));
+        assert!(!drag.wants(MouseReport::Move));
+        let motion = MouseProtocol { tracking: MouseTracking::Motion, ..click };
+        assert!(motion.wants(MouseReport::Move));
+        assert!(!MouseProtocol::default().wants(MouseReport::Press(MouseButton::Left)));
+    }
+
+    /// An event listener with nothing shared: no PTY, no sync thread, dark mode.
+    fn test_listener() -> TermEventListener {
+        TermEventListener { dark_mode: Arc::new(AtomicBool::new(true)), ..Default::default() }
+    }
+
+    /// GridSyncer over a PTY-less Term, fed directly through the VT parser.
+    fn test_syncer(cols: usize, rows: usize) -> GridSyncer {
+        test_syncer_with_marks(cols, rows).0
+    }
+
+    /// Like `test_syncer`, also returning the listener's OSC 133 marks.
+    fn test_syncer_with_marks(cols: usize, rows: usize) -> (GridSyncer, Arc<Mutex<ShellMarks>>) {
+        let shell_marks = Arc::new(Mutex::new(ShellMarks::default()));
+        let images = Arc::new(Mutex::new(ImageStore::default()));
+        let listener = TermEventListener {
+            shell_marks: shell_marks.clone(),
+            images: images.clone(),
+            ..test_listener()
+        };
+        let term = Term::new(TermConfig::default(), &TermDimensions::new(cols, rows), listener);
+        let syncer = GridSyncer {
+            term: Arc::new(FairMutex::new(term)),
+            raw_buf: Vec::new(),
+            prev_raw_buf: Vec::new(),
+            link_buf: Vec::new(),
+            link_ids: Default::default(),
+            palette_buf: [None; 256],
+            grid: Terminal::build_empty_grid(cols as u16, rows as u16),
+            inverse_cursor: None,
+            cached_cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block },
+            url_ranges: Vec::new(),
+            grid_generation: 0,
+            url_row_buf: String::new(),
+            dark_mode: Arc::new(AtomicBool::new(true)),
+            dark_mode_changed: Arc::new(AtomicBool::new(false)),
+            schemes: Default::default(),
+            stay_at_bottom: Arc::new(AtomicBool::new(false)),
+            shell_marks: shell_marks.clone(),
+            prompt_marks: Vec::new(),
+            prompt_marks_buf: Vec::new(),
+            images,
+            visible_images: Vec::new(),
+            visible_images_buf: Vec::new(),
+            triggers: Default::default(),
+            trigger_scan: Default::default(),
+            copy_mode: None,
+            selection_spans: Vec::new(),
+            selection_spans_buf: Vec::new(),
+        };
+        (syncer, shell_marks)
+    }
+
+    /// Feed bytes the way the PTY thread does, including OSC passthrough.
+    fn feed(syncer: &GridSyncer, data: &[u8]) {
+        use alacritty_terminal::event_loop::OscPassthrough;
+        use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+        let mut processor: Processor<StdSyncHandler> = Processor::new();
+        OscPassthrough::default().advance(&mut processor, &mut *syncer.term.lock(), data);
+    }
+
+    #[test]
+    fn test_sync_carries_osc8_hyperlinks() {
+        let mut syncer = test_syncer(40, 3);
+        feed(&syncer, b"see \x1b]8;;file:///tmp/a.rs\x1b\\a.rs\x1b]8;;\x1b\\ and https://example.com");
+        syncer.sync();
+
+        assert_eq!(syncer.grid.link_at(0, 4), Some("file:///tmp/a.rs"));
+        assert_eq!(syncer.grid.link_at(0, 7), Some("file:///tmp/a.rs"));
+        assert_eq!(syncer.grid.link_at(0, 8), None);
+        assert_eq!(syncer.grid.links.len(), 1);
+        // Hyperlink span first, then the regex-detected URL
+        assert_eq!(syncer.url_ranges[0], vec![(4, 8), (13, 32)]);
+    }
+
+    #[test]
+    fn test_sync_hyperlink_text_url_not_duplicated() {
+        let mut syncer = test_syncer(40, 3);
+        feed(&syncer, b"\x1b]8;;https://real.example\x1b\\https://shown.example\x1b]8;;\x1b\\");
+        syncer.sync();
+
+        assert_eq!(syncer.url_ranges[0], vec![(0, 21)]);
+        assert_eq!(syncer.grid.link_at(0, 0), Some("https://real.example"));
+    }
+
+    #[test]
+    fn test_sync_underline_styles_color_and_strikethrough() {
+        let mut syncer = test_syncer(40, 3);
+        feed(&syncer, b"\x1b[4ma\x1b[4:3mb\x1b[4:2mc\x1b[4:4md\x1b[4:5me\x1b[24;9mf\x1b[0m ");
+        feed(&syncer, b"\x1b[4:3;58:2::255:0:0mg\x1b[59mh\x1b[0m\r\n\x1b[4m\xe4\xbd\xa0");
+        syncer.sync();
+
+        let style = |row: usize, col: usize| syncer.grid.cells[row][col].style;
+        let underlines: Vec<UnderlineStyle> = (0..6).map(|col| style(0, col).underline).collect();
+        assert_eq!(underlines, [
+            UnderlineStyle::Single,
+            UnderlineStyle::Curly,
+            UnderlineStyle::Double,
+            UnderlineStyle::Dotted,
+            UnderlineStyle::Dashed,
+            UnderlineStyle::None,
+        ]);
+        assert!(style(0, 5).strikethrough && !style(0, 4).strikethrough);
+        assert!(!style(0, 6).has_decoration());
+
+        // SGR 58 sets the underline color, 59 goes back to the foreground
+        let red = style(0, 7).underline_color.unwrap();
+        assert!(red.r > 0.9 && red.g < 0.1 && red.b < 0.1);
+        assert_eq!(style(0, 8).underline, UnderlineStyle::Curly);
+        assert_eq!(style(0, 8).underline_color, None);
+
+        // Both halves of a wide character are underlined
+        assert_eq!(style(1, 0).underline, UnderlineStyle::Single);
+        assert_eq!(syncer.grid.cells[1][1].character, '\0');
+        assert_eq!(style(1, 1).underline, UnderlineStyle::Single);
+    }
+
+    #[test]
+    fn test_sync_applies_color_scheme() {
+        let mut syncer = test_syncer(10, 2);
+        let mut ansi = [Color::rgb(0.0, 0.0, 0.0); 16];
+        ansi[1] = Color::rgb(0.5, 0.0, 0.0);
+        let scheme = ColorScheme {
+            foreground: Color::rgb(0.2, 0.3, 0.4),
+            background: Color::rgb(0.9, 0.9, 0.8),
+            cursor: None,
+            ansi,
+        };
+        *syncer.schemes.lock().unwrap() = SchemePair::both(scheme);
+        // Named red, indexed red, default colors, then inverse video
+        feed(&syncer, b"\x1b[31ma\x1b[38;5;1mb\x1b[0mc\x1b[7md");
+        syncer.sync();
+
+        let style = |col: usize| syncer.grid.cells[0][col].style;
+        // Scheme colors are used as-is, without the contrast boost
+        assert_eq!(style(0).foreground, ansi[1]);
+        assert_eq!(style(1).foreground, ansi[1]);
+        assert_eq!(style(2).foreground, scheme.foreground);
+        assert_eq!(style(2).background, None);
+        assert_eq!(style(3).foreground, scheme.background);
+        assert_eq!(style(3).background, Some(scheme.foreground));
+        assert_eq!(scheme.cursor_color(), scheme.foreground);
+    }
+
+    #[test]
+    fn test_color_schemes_follow_dark_mode() {
+        let mut syncer = test_syncer(10, 2);
+        let mut dark = ColorScheme::TIDE_DARK;
+        dark.foreground = Color::rgb(0.1, 0.2, 0.3);
+        *syncer.schemes.lock().unwrap() = SchemePair { dark: Some(dark), light: None };
+        feed(&syncer, b"a");
+        syncer.sync();
+        assert_eq!(syncer.grid.cells[0][0].style.foreground, dark.foreground);
+
+        // Light mode has no scheme: the built-in light palette applies
+        syncer.dark_mode.store(false, Ordering::Relaxed);
+        syncer.dark_mode_changed.store(true, Ordering::Relaxed);
+        syncer.sync();
+        assert_ne!(syncer.grid.cells[0][0].style.foreground, dark.foreground);
+    }
+
+    fn hex(s: &str) -> Color {
+        parse_hex_color(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_hex_color_forms() {
+        assert_eq!(parse_hex_color("#ff0000"), Some(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(parse_hex_color("0x00FF00"), Some(Color::rgb(0.0, 1.0, 0.0)));
+        assert_eq!(parse_hex_color("0000ff"), Some(Color::rgb(0.0, 0.0, 1.0)));
+        assert_eq!(parse_hex_color("#fff"), None);
+        assert_eq!(parse_hex_color("CellForeground"), None);
+    }
+
+    const ALACRITTY_TOML: &str = r##"
+# Tokyo-ish
+[colors.primary]
+background = "#1a1b26"
+foreground = '#c0caf5'
+
+[colors.cursor]
+cursor = "#ff9e64" # orange
+text = "CellBackground"
+
+[colors.normal]
+black = "#15161e"
+red = "#f7768e"
+green = "#9ece6a"
+yellow = "#e0af68"
+blue = "#7aa2f7"
+magenta = "#bb9af7"
+cyan = "#7dcfff"
+white = "#a9b1d6"
+
+[colors]
+bright = { black = "#414868", red = "#f7768e", green = "#9ece6a", yellow = "#e0af68", blue = "#7aa2f7", magenta = "#bb9af7", cyan = "#7dcfff", white = "#c0caf5" }
+"##;
+
+    #[test]
+    fn test_parse_alacritty_toml_scheme() {
+        let schemes = parse_scheme_file("tokyo.toml", ALACRITTY_TOML.as_bytes()).unwrap();
+        assert_eq!(schemes.len(), 1);
+        let (name, scheme) = &schemes[0];
+        assert_eq!(name, "tokyo");
+        assert_eq!(scheme.background, hex("#1a1b26"));
+        assert_eq!(scheme.foreground, hex("#c0caf5"));
+        assert_eq!(scheme.cursor, Some(hex("#ff9e64")));
+        assert_eq!(scheme.ansi[1], hex("#f7768e"));
+        assert_eq!(scheme.ansi[8], hex("#414868"));
+        assert_eq!(scheme.ansi[15], hex("#c0caf5"));
+    }
+
+    #[test]
+    fn test_parse_alacritty_yaml_scheme() {
+        let yaml = "colors:\n  primary:\n    background: '0x282828'\n    foreground: '0xebdbb2'\n  cursor:\n    text: CellBackground\n    cursor: CellForeground\n  normal:\n    black: '0x282828'\n    red: '0xcc241d'\n    green: '0x98971a'\n    yellow: '0xd79921'\n    blue: '0x458588'\n    magenta: '0xb16286'\n    cyan: '0x689d6a'\n    white: '0xa89984'\n  bright:\n    black: '0x928374'\n    red: '0xfb4934'\n    green: '0xb8bb26'\n    yellow: '0xfabd2f'\n    blue: '0x83a598'\n    magenta: '0xd3869b'\n    cyan: '0x8ec07c'\n    white: '0xebdbb2'\n";
+        let schemes = parse_scheme_file("gruvbox.yml", yaml.as_bytes()).unwrap();
+        let (name, scheme) = &schemes[0];
+        assert_eq!(name, "gruvbox");
+        assert_eq!(scheme.background, hex("#282828"));
+        assert_eq!(scheme.cursor, None);
+        assert_eq!(scheme.ansi[4], hex("#458588"));
+        assert_eq!(scheme.ansi[13], hex("#d3869b"));
+    }
+
+    #[test]
+    fn test_parse_base16_scheme() {
+        let bases = [
+            "181818", "282828", "383838", "585858", "b8b8b8", "d8d8d8", "e8e8e8", "f8f8f8",
+            "ab4642", "dc9656", "f7ca88", "a1b56c", "86c1b9", "7cafc2", "ba8baf", "a16946",
+        ];
+        let mut yaml = String::from("scheme: \"Default Dark\"\nauthor: \"Chris Kempson\"\n");
+        for (i, base) in bases.iter().enumerate() {
+            yaml.push_str(&format!("base0{:X}: \"{}\"\n", i, base));
+        }
+        let schemes = parse_scheme_file("default-dark.yaml", yaml.as_bytes()).unwrap();
+        let (name, scheme) = &schemes[0];
+        assert_eq!(name, "Default Dark");
+        assert_eq!(scheme.background, hex("181818"));
+        assert_eq!(scheme.foreground, hex("d8d8d8"));
+        assert_eq!(scheme.ansi[1], hex("ab4642"));
+        assert_eq!(scheme.ansi[8], hex("585858"));
+        assert_eq!(scheme.ansi[15], hex("f8f8f8"));
+    }
+
+    #[test]
+    fn test_parse_windows_terminal_schemes() {
+        let entry = |name: &str, bg: &str| {
+            format!(
+                r##"{{"name": "{}", "background": "{}", "foreground": "#CCCCCC", "cursorColor": "#FFFFFF",
+                "black": "#0C0C0C", "red": "#C50F1F", "green": "#13A10E", "yellow": "#C19C00",
+                "blue": "#0037DA", "purple": "#881798", "cyan": "#3A96DD", "white": "#CCCCCC",
+                "brightBlack": "#767676", "brightRed": "#E74856", "brightGreen": "#16C60C", "brightYellow": "#F9F1A5",
+                "brightBlue": "#3B78FF", "brightPurple": "#B4009E", "brightCyan": "#61D6D6", "brightWhite": "#F2F2F2"}}"##,
+                name, bg
+            )
+        };
+        let settings = format!(r#"{{"profiles": {{}}, "schemes": [{}, {}]}}"#, entry("Campbell", "#0C0C0C"), entry("Night", "#000000"));
+        let schemes = parse_scheme_file("settings.json", settings.as_bytes()).unwrap();
+        let names: Vec<_> = schemes.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["Campbell", "Night"]);
+        assert_eq!(schemes[0].1.ansi[5], hex("#881798"));
+        assert_eq!(schemes[1].1.background, hex("#000000"));
+
+        // A bare scheme object without a name takes the file stem
+        let single = entry("", "#0C0C0C").replacen(r#""name": "", "#, "", 1);
+        let schemes = parse_scheme_file("campbell.json", single.as_bytes()).unwrap();
+        assert_eq!(schemes[0].0, "campbell");
+    }
+
+    #[test]
+    fn test_parse_itermcolors_scheme() {
+        let component = |r: f32, g: f32, b: f32| {
+            format!(
+                "<dict><key>Blue Component</key><real>{}</real><key>Green Component</key><real>{}</real><key>Red Component</key><real>{}</real></dict>",
+                b, g, r
+            )
+        };
+        let mut body = String::new();
+        for i in 0..16 {
+            body.push_str(&format!("<key>Ansi {} Color</key>{}", i, component(i as f32 / 15.0, 0.0, 0.0)));
+        }
+        body.push_str(&format!("<key>Background Color</key>{}", component(0.0, 0.0, 0.0)));
+        body.push_str(&format!("<key>Foreground Color</key>{}", component(1.0, 1.0, 1.0)));
+        body.push_str(&format!("<key>Cursor Color</key>{}", component(0.0, 1.0, 0.0)));
+        let plist = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><plist version="1.0"><dict>{}</dict></plist>"#,
+            body
+        );
+        let schemes = parse_scheme_file("Solarized.itermcolors", plist.as_bytes()).unwrap();
+        let (name, scheme) = &schemes[0];
+        assert_eq!(name, "Solarized");
+        assert_eq!(scheme.foreground, Color::rgb(1.0, 1.0, 1.0));
+        assert_eq!(scheme.cursor, Some(Color::rgb(0.0, 1.0, 0.0)));
+        assert_eq!(scheme.ansi[15], Color::rgb(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_parse_scheme_file_errors() {
+        assert!(parse_scheme_file("theme.conf", b"").is_err());
+        // Missing colors are reported rather than defaulted
+        let err = parse_scheme_file("broken.json", br##"{"foreground": "#ffffff"}"##).unwrap_err();
+        assert!(err.to_string().contains("missing color"), "{}", err);
+    }
+
+    const WAIT: std::time::Duration = std::time::Duration::from_secs(10);
+
+    #[test]
+    fn test_headless_terminal_echoes_input() {
+        let script = r#"printf 'name? '; read name; printf '\033[31mhi %s\033[0m\n' "$name""#;
+        let mut term = HeadlessTerminal::spawn("/bin/sh", &["-c", script]).unwrap();
+        term.wait_for_text(r"name\?", WAIT).unwrap();
+        term.send_keys("tide\r");
+        assert_eq!(term.wait_for_text(r"hi \w+", WAIT).unwrap(), "hi tide");
+        assert!(term.wait_for_exit(WAIT));
+
+        let snapshot = term.snapshot();
+        assert_eq!(snapshot.text(), "name? tide\nhi tide");
+        // Attributed: the greeting is red, the typed echo is not
+        let red = Terminal::named_color_to_rgb(true, NamedColor::Red);
+        assert_eq!(snapshot.grid.cells[1][0].style.foreground, red);
+        assert_ne!(snapshot.grid.cells[0][6].style.foreground, red);
+    }
+
+    #[test]
+    fn test_headless_terminal_sends_encoded_keys() {
+        // `cat -v` shows control bytes as ^X
+        let mut term = HeadlessTerminal::spawn("/bin/sh", &["-c", "stty raw -echo; echo ready; cat -v"]).unwrap();
+        term.wait_for_text("ready", WAIT).unwrap();
+        term.send_key(Key::Up, Modifiers::default());
+        term.send_key(Key::Char('x'), Modifiers::default());
+        term.wait_for_text(r"\^\[\[Ax", WAIT).unwrap();
+        term.send_key(Key::Char('c'), Modifiers { ctrl: true, ..Default::default() });
+        term.wait_for_text(r"\^C", WAIT).unwrap();
+    }
+
+    #[test]
+    fn test_headless_wait_times_out_with_screen() {
+        let mut term = HeadlessTerminal::spawn("/bin/sh", &["-c", "echo ready; sleep 5"]).unwrap();
+        term.wait_for_text("ready", WAIT).unwrap();
+        let err = term.wait_for_text("never", std::time::Duration::from_millis(50)).unwrap_err();
+        assert!(err.to_string().contains("screen:\nready"), "{}", err);
+        assert!(term.wait_for_text("(", WAIT).is_err(), "bad regex");
+    }
+
+    #[test]
+    fn test_output_written_before_exit_is_kept() {
+        // Every terminal drains its PTY when the child exits, so a burst right
+        // before exiting is all on screen once `wait_for_exit` returns
+        for _ in 0..5 {
+            let mut term = HeadlessTerminal::spawn("/bin/sh", &["-c", "seq 1 2000; printf done"]).unwrap();
+            assert!(term.wait_for_exit(WAIT));
+            assert!(term.snapshot().text().ends_with("1999\n2000\ndone"), "{}", term.snapshot().text());
+        }
+    }
+
+    #[test]
+    fn test_recent_text_spans_scrollback_and_joins_wraps() {
+        let mut term = HeadlessTerminal::with_command(
+            10,
+            3,
+            None,
+            &ShellCommand {
+                program: Some("/bin/sh".to_string()),
+                args: vec!["-c".to_string(), "printf 'a\\nb\\nc\\n0123456789wrapped\\nend'; sleep 5".to_string()],
+                env: Vec::new(),
+            },
+        )
+        .unwrap();
+        term.wait_for_text("end", WAIT).unwrap();
+        let terminal = term.terminal();
+        assert_eq!(terminal.recent_text(100), "a\nb\nc\n0123456789wrapped\nend");
+        // Rows, not lines: the last three rows hold the wrapped line and "end"
+        assert_eq!(terminal.recent_text(3), "0123456789wrapped\nend");
+        assert_eq!(terminal.recent_text(2), "wrapped\nend");
+    }
+
+    #[test]
+    fn test_shell_sees_its_terminal_id() {
+        let mut term = HeadlessTerminal::with_command(
+            40,
+            3,
+            None,
+            &ShellCommand {
+                program: Some("/bin/sh".to_string()),
+                args: vec!["-c".to_string(), "echo id=$TIDE_TERMINAL_ID.; sleep 5".to_string()],
+                env: Vec::new(),
+            },
+        )
+        .unwrap();
+        let id = term.terminal().terminal_id().expect("spawned shells have an id");
+        term.wait_for_text(&format!("id={}.", id), WAIT).unwrap();
+        let other = Terminal::with_cwd(10, 3, None, true).unwrap();
+        assert_ne!(other.terminal_id(), Some(id));
+    }
+
+    fn trigger(pattern: &str, action: TriggerAction) -> Trigger {
+        Trigger { regex: regex::Regex::new(pattern).unwrap(), action }
+    }
+
+    #[test]
+    fn test_triggers_fire_once_per_match() {
+        let mut syncer = test_syncer(20, 4);
+        syncer.triggers.lock().unwrap().set_rules(
+            vec![
+                trigger(r"ERROR (\w+)", TriggerAction::Notify("failed: $1".to_string())),
+                trigger(r"https://\S+", TriggerAction::Open(String::new())),
+                trigger(r"Password:", TriggerAction::Reply("hunter2\r".to_string())),
+            ],
+            0,
+        );
+        // A prompt on the cursor's line matches before the line is finished
+        feed(&syncer, b"ok\r\nERROR disk full\r\nPassword:");
+        syncer.sync();
+        let events = syncer.triggers.lock().unwrap().take_events();
+        assert_eq!(
+            events,
+            [
+                TriggerEvent { action: TriggerAction::Notify("failed: disk".to_string()), line: "ERROR disk full".to_string() },
+                TriggerEvent { action: TriggerAction::Reply("hunter2\r".to_string()), line: "Password:".to_string() },
+            ]
+        );
+
+        // Finishing the line, or scrolling matched lines past later syncs,
+        // doesn't fire them again
+        syncer.sync();
+        feed(&syncer, b" ****\r\nsee https://example.com/x\r\n\r\n\r\n");
+        syncer.sync();
+        syncer.sync();
+        let actions: Vec<_> = syncer.triggers.lock().unwrap().take_events().into_iter().map(|e| e.action).collect();
+        assert_eq!(actions, [TriggerAction::Open("https://example.com/x".to_string())]);
+    }
+
+    #[test]
+    fn test_trigger_matches_soft_wrapped_line() {
+        let mut syncer = test_syncer(10, 4);
+        syncer.triggers.lock().unwrap().set_rules(vec![trigger("BUILD FAILED", TriggerAction::Badge)], 0);
+        // Wraps after "abcdefBUIL"
+        feed(&syncer, b"abcdefBUILD FAILED\r\n");
+        syncer.sync();
+        let events = syncer.triggers.lock().unwrap().take_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].line, "abcdefBUILD FAILED");
+    }
+
+    #[test]
+    fn test_trigger_highlight_paints_and_follows_scroll() {
+        let mut syncer = test_syncer(20, 3);
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        syncer.triggers.lock().unwrap().set_rules(vec![trigger("warn", TriggerAction::Highlight(red))], 0);
+        feed(&syncer, b"a warn b\r\n");
+        syncer.sync();
+        let bg = |syncer: &GridSyncer, row: usize, col: usize| syncer.grid.cells[row][col].style.background;
+        assert_eq!(bg(&syncer, 0, 1), None);
+        assert_eq!(bg(&syncer, 0, 2), Some(red));
+        assert_eq!(bg(&syncer, 0, 5), Some(red));
+        assert_eq!(bg(&syncer, 0, 6), None);
+        // Highlights are not reported as events
+        assert!(syncer.triggers.lock().unwrap().take_events().is_empty());
+
+        // Repainting the same cells is not a new frame
+        let generation = syncer.grid_generation;
+        syncer.sync();
+        assert_eq!(syncer.grid_generation, generation);
+        assert_eq!(bg(&syncer, 0, 2), Some(red));
+
+        // Two more lines scroll it up by one row
+        feed(&syncer, b"x\r\ny\r\n");
+        syncer.sync();
+        assert_eq!(bg(&syncer, 0, 2), None);
+        assert_eq!(syncer.grid.cells[0][0].character, 'x');
+    }
+
+    #[test]
+    fn test_replaced_triggers_skip_earlier_output() {
+        let mut syncer = test_syncer(20, 4);
+        *syncer.triggers.lock().unwrap() = TriggerSet::starting_at(1);
+        feed(&syncer, b"restored\r\nERROR early\r\n");
+        syncer.sync();
+        // The first rules see the output since the program started
+        syncer.triggers.lock().unwrap().set_rules(vec![trigger("ERROR", TriggerAction::Badge)], 2);
+        syncer.sync();
+        let lines = |syncer: &GridSyncer| {
+            syncer.triggers.lock().unwrap().take_events().into_iter().map(|e| e.line).collect::<Vec<_>>()
+        };
+        assert_eq!(lines(&syncer), ["ERROR early"]);
+
+        // Replacing them starts at the cursor's line
+        feed(&syncer, b"ERROR old\r\n");
+        syncer.triggers.lock().unwrap().set_rules(vec![trigger("ERROR", TriggerAction::Badge)], 3);
+        feed(&syncer, b"ERROR new\r\n");
+        syncer.sync();
+        assert_eq!(lines(&syncer), ["ERROR new"]);
+    }
+
+    fn regions(marks: &Arc<Mutex<ShellMarks>>) -> Vec<CommandRegion> {
+        marks.lock().unwrap().regions().iter().copied().collect()
+    }
+
+    #[test]
+    fn test_shell_scripts_dir_is_private_and_repaired() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("tide-shell-scripts-test-{}", std::process::id()));
+        let mode = |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        crate::shell_integration::write_scripts(&dir).unwrap();
+        assert_eq!(mode(&dir), 0o700);
+        assert!(dir.join("zsh/.zshenv").is_file());
+
+        // Loosened permissions are tightened, removed or edited scripts restored
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::remove_file(dir.join("zsh/.zshenv")).unwrap();
+        std::fs::write(dir.join("tide.bash"), "echo changed").unwrap();
+        crate::shell_integration::write_scripts(&dir).unwrap();
+        assert_eq!(mode(&dir), 0o700);
+        assert!(dir.join("zsh/.zshenv").is_file());
+        assert_ne!(std::fs::read_to_string(dir.join("tide.bash")).unwrap(), "echo changed");
+
+        // A file (or a link) where the directory should be is refused
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::write(&dir, "").unwrap();
+        assert!(crate::shell_integration::write_scripts(&dir).is_err());
+        let _ = std::fs::remove_file(&dir);
+    }
+
+    #[test]
+    fn test_osc133_marks_record_command_region() {
+        let (syncer, marks) = test_syncer_with_marks(20, 5);
+        feed(&syncer, b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07a.txt\r\nb.txt\r\n\x1b]133;D;0\x07");
+        feed(&syncer, b"\x1b]133;A\x1b\\$ \x1b]133;B\x1b\\");
+
+        let regions = regions(&marks);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].prompt_line, 0);
+        assert_eq!(regions[0].command_start, Some((0, 2)));
+        assert_eq!(regions[0].output_line, Some(1));
+        assert_eq!(regions[0].end, Some((3, 0)));
+        assert!(!regions[0].is_running());
+        assert_eq!(regions[1].prompt_line, 3);
+        assert_eq!(regions[1].command_start, Some((3, 2)));
+    }
+
+    #[test]
+    fn test_osc133_lines_stay_stable_when_scrolling() {
+        let (syncer, marks) = test_syncer_with_marks(20, 3);
+        feed(&syncer, b"\x1b]133;A\x07$ \x1b]133;B\x07seq 5\r\n\x1b]133;C\x071\r\n2\r\n3\r\n4\r\n5\r\n\x1b]133;D;0\x07");
+        feed(&syncer, b"\x1b]133;A\x07$ ");
+
+        let regions = regions(&marks);
+        // Six lines scrolled past a 3-line screen: the second prompt is at
+        // stable line 6 even though it sits on screen line 2.
+        assert_eq!(regions[0].end, Some((6, 0)));
+        assert_eq!(regions[1].prompt_line, 6);
+        let term = syncer.term.lock();
+        assert_eq!(term.grid().scrolled_lines(), 4);
+        assert_eq!(term.grid().cursor.point.line.0, 2);
+    }
+
+    #[test]
+    fn test_osc133_empty_command_and_redrawn_prompt() {
+        let (syncer, marks) = test_syncer_with_marks(20, 5);
+        // Empty command line: D without C is ignored
+        feed(&syncer, b"\x1b]133;A\x07$ \x1b]133;B\x07\r\n\x1b]133;D;0\x07");
+        // Prompt redrawn in place replaces the previous mark
+        feed(&syncer, b"\x1b]133;A\x07$ \r\x1b]133;A\x07$ ");
+        let regions = regions(&marks);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].end, None);
+        assert_eq!(regions[1].prompt_line, 1);
+    }
+
+    #[test]
+    fn test_osc133_statuses_outlive_pruned_regions() {
+        let (syncer, marks) = test_syncer_with_marks(20, 4);
+        feed(&syncer, b"\x1b]133;A\x07$ \x1b]133;B\x07false\r\n\x1b]133;C\x07\x1b]133;D;1\x07");
+        feed(&syncer, b"\x1b]133;A\x07$ \x1b]133;B\x07true\r\n\x1b]133;C\x07\x1b]133;D;0\x07");
+        // Both prompts leave scrollback before anyone reads them
+        marks.lock().unwrap().prune(u64::MAX);
+        assert!(regions(&marks).is_empty());
+
+        let guard = marks.lock().unwrap();
+        let codes = |since| guard.finished_since(since).iter().map(|s| s.exit_code).collect::<Vec<_>>();
+        assert_eq!(guard.finished(), 2);
+        assert_eq!(codes(0), [Some(1), Some(0)]);
+        assert_eq!(codes(1), [Some(0)]);
+        assert!(codes(2).is_empty());
+    }
+
+    #[test]
+    fn test_osc_passthrough_ignores_handled_and_split_sequences() {
+        let (syncer, marks) = test_syncer_with_marks(20, 5);
+        // OSC 0 (title) is vte's, not forwarded
+        feed(&syncer, b"\x1b]0;title\x07");
+        assert!(regions(&marks).is_empty());
+
+        // A sequence split across reads is still recognized
+        use alacritty_terminal::event_loop::OscPassthrough;
+        use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+        let mut processor: Processor<StdSyncHandler> = Processor::new();
+        let mut passthrough = OscPassthrough::default();
+        passthrough.advance(&mut processor, &mut *syncer.term.lock(), b"ab\x1b]13");
+        passthrough.advance(&mut processor, &mut *syncer.term.lock(), b"3;A\x1b");
+        assert!(regions(&marks).is_empty());
+        passthrough.advance(&mut processor, &mut *syncer.term.lock(), b"\\cd");
+        let regions = regions(&marks);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].prompt_line, 0);
+        assert_eq!(syncer.term.lock().grid().cursor.point.column.0, 4);
+    }
+
+    #[test]
+    fn test_osc133_exit_status_and_prompt_marks() {
+        let (mut syncer, marks) = test_syncer_with_marks(20, 4);
+        feed(&syncer, b"\x1b]133;A\x07$ \x1b]133;B\x07false\r\n\x1b]133;C\x07\x1b]133;D;1\x07");
+        feed(&syncer, b"\x1b]133;A\x07$ \x1b]133;B\x07true\r\n\x1b]133;C\x07\x1b]133;D;0\x07");
+        feed(&syncer, b"\x1b]133;A\x07$ ");
+        syncer.sync();
+
+        let guard = marks.lock().unwrap();
+        let regions = guard.regions();
+        assert_eq!(regions[0].exit_code, Some(1));
+        assert_eq!(regions[1].exit_code, Some(0));
+        assert!(regions[0].started_at.is_some() && regions[0].duration.is_some());
+        assert_eq!(guard.finished(), 2);
+        drop(guard);
+        // The still-open third prompt has no marker
+        assert_eq!(
+            syncer.prompt_marks,
+            vec![PromptMark { row: 0, exit_code: 1 }, PromptMark { row: 1, exit_code: 0 }]
+        );
+    }
+
+    fn search(syncer: &GridSyncer, query: &str, options: SearchOptions) -> Vec<(usize, usize, usize)> {
+        use alacritty_terminal::term::search::RegexSearch;
+        let mut regex = RegexSearch::new(&options.pattern(query)).unwrap();
+        crate::search::search_term(&syncer.term.lock(), &mut regex)
+    }
+
+    #[test]
+    fn test_search_case_and_whole_word() {
+        let syncer = test_syncer(40, 3);
+        feed(&syncer, b"Foo foo food\r\nfoo.bar");
+        let plain = SearchOptions::default();
+        assert_eq!(search(&syncer, "foo", plain), vec![(0, 0, 3), (0, 4, 3), (0, 8, 3), (1, 0, 3)]);
+
+        let case = SearchOptions { case_sensitive: true, ..plain };
+        assert_eq!(search(&syncer, "Foo", case), vec![(0, 0, 3)]);
+
+        let word = SearchOptions { whole_word: true, ..plain };
+        assert_eq!(search(&syncer, "foo", word), vec![(0, 0, 3), (0, 4, 3), (1, 0, 3)]);
+
+        // Plain mode escapes regex metacharacters
+        assert_eq!(search(&syncer, "o.b", plain), vec![(1, 2, 3)]);
+    }
+
+    #[test]
+    fn test_search_regex_and_invalid_pattern() {
+        let syncer = test_syncer(40, 3);
+        feed(&syncer, b"error: E0433 and E0599");
+        let re = SearchOptions { regex: true, ..SearchOptions::default() };
+        assert_eq!(search(&syncer, r"E\d{4}", re), vec![(0, 7, 5), (0, 17, 5)]);
+        assert!(alacritty_terminal::term::search::RegexSearch::new(&re.pattern("(")).is_err());
+    }
+
+    #[test]
+    fn test_search_match_across_soft_wrap() {
+        let syncer = test_syncer(10, 3);
+        feed(&syncer, b"12345678hello world");
+        // "hello" starts at col 8 of row 0 and wraps onto row 1
+        assert_eq!(search(&syncer, "hello", SearchOptions::default()), vec![(0, 8, 5)]);
+        let re = SearchOptions { regex: true, ..SearchOptions::default() };
+        assert_eq!(search(&syncer, "h.*d", re), vec![(0, 8, 11)]);
+    }
+
+    #[test]
+    fn test_search_wide_chars() {
+        let syncer = test_syncer(20, 3);
+        feed(&syncer, "a 日本 b".as_bytes());
+        // 日本 occupies four cells
+        assert_eq!(search(&syncer, "日本", SearchOptions::default()), vec![(0, 2, 4)]);
+        assert_eq!(search(&syncer, "b", SearchOptions::default()), vec![(0, 7, 1)]);
+    }
+
+    fn copy_motion(syncer: &GridSyncer, motion: CopyMotion) {
+        crate::copy_mode::apply_motion(&mut syncer.term.lock(), motion);
+    }
+
+    fn copy_select(syncer: &GridSyncer, kind: CopySelection) {
+        crate::copy_mode::toggle_selection(&mut syncer.term.lock(), kind);
+    }
+
+    fn copy_view(syncer: &mut GridSyncer) -> CopyModeView {
+        syncer.sync();
+        syncer.copy_mode.clone().expect("in copy mode")
+    }
+
+    #[test]
+    fn test_copy_mode_motions_and_char_selection() {
+        let mut syncer = test_syncer(20, 3);
+        feed(&syncer, b"one two\r\nthree four\r\nfive");
+        crate::copy_mode::enter(&mut syncer.term.lock());
+        // Starts on the terminal cursor
+        assert_eq!(copy_view(&mut syncer).cursor, (2, 4));
+
+        copy_motion(&syncer, CopyMotion::LineStart);
+        copy_motion(&syncer, CopyMotion::Up);
+        copy_select(&syncer, CopySelection::Chars);
+        copy_motion(&syncer, CopyMotion::WordRight);
+        let view = copy_view(&mut syncer);
+        assert_eq!(view.cursor, (1, 6));
+        assert_eq!(view.selection, Some(CopySelection::Chars));
+        assert_eq!(syncer.selection_spans, vec![(1, 0, 7)]);
+        assert_eq!(crate::copy_mode::yank(&syncer.term.lock()).as_deref(), Some("three f"));
+
+        // Selection continues across lines
+        copy_motion(&syncer, CopyMotion::Up);
+        copy_view(&mut syncer);
+        assert_eq!(syncer.selection_spans, vec![(0, 6, 20), (1, 0, 1)]);
+
+        crate::copy_mode::exit(&mut syncer.term.lock());
+        syncer.sync();
+        assert_eq!(syncer.copy_mode, None);
+    }
+
+    #[test]
+    fn test_copy_mode_line_and_block_selection() {
+        let mut syncer = test_syncer(20, 3);
+        feed(&syncer, b"abcdef\r\nghijkl\r\nmnopqr");
+        crate::copy_mode::enter(&mut syncer.term.lock());
+        copy_motion(&syncer, CopyMotion::Top);
+        copy_motion(&syncer, CopyMotion::Right);
+        copy_select(&syncer, CopySelection::Block);
+        copy_motion(&syncer, CopyMotion::Down);
+        copy_motion(&syncer, CopyMotion::Right);
+        copy_motion(&syncer, CopyMotion::Right);
+        let view = copy_view(&mut syncer);
+        assert_eq!(view.selection, Some(CopySelection::Block));
+        assert_eq!(syncer.selection_spans, vec![(0, 1, 4), (1, 1, 4)]);
+        assert_eq!(crate::copy_mode::yank(&syncer.term.lock()).as_deref(), Some("bcd\nhij"));
+
+        // Switching kinds keeps the anchor; the same kind again clears it
+        copy_select(&syncer, CopySelection::Lines);
+        copy_view(&mut syncer);
+        assert_eq!(syncer.selection_spans, vec![(0, 0, 20), (1, 0, 20)]);
+        assert_eq!(crate::copy_mode::yank(&syncer.term.lock()).as_deref(), Some("abcdef\nghijkl\n"));
+        copy_select(&syncer, CopySelection::Lines);
+        let view = copy_view(&mut syncer);
+        assert_eq!(view.selection, None);
+        assert!(syncer.selection_spans.is_empty());
+    }
+
+    #[test]
+    fn test_copy_mode_search_scrolls_into_history() {
+        use alacritty_terminal::term::search::RegexSearch;
+        let mut syncer = test_syncer(10, 3);
+        feed_lines(&syncer, 10);
+        crate::copy_mode::enter(&mut syncer.term.lock());
+
+        let mut regex = RegexSearch::new(&SearchOptions::default().pattern("line 2")).unwrap();
+        assert!(crate::copy_mode::search(&mut syncer.term.lock(), &mut regex, false));
+        // "line 2" is above the screen, so the view scrolls up to it
+        assert!(syncer.term.lock().grid().display_offset() > 0);
+        let view = copy_view(&mut syncer);
+        assert_eq!(syncer.grid.cells[view.cursor.0][0].character, 'l');
+        assert_eq!(syncer.grid.cells[view.cursor.0][5].character, '2');
+
+        let mut missing = RegexSearch::new(&SearchOptions::default().pattern("nope")).unwrap();
+        assert!(!crate::copy_mode::search(&mut syncer.term.lock(), &mut missing, true));
+
+        copy_motion(&syncer, CopyMotion::Bottom);
+        assert_eq!(syncer.term.lock().grid().display_offset(), 0);
+    }
+
+    fn select(syncer: &mut GridSyncer, kind: SelectionKind, from: (usize, usize), to: (usize, usize)) -> Option<String> {
+        use alacritty_terminal::index::Side;
+        use alacritty_terminal::selection::Selection;
+        let selection = {
+            let mut term = syncer.term.lock();
+            let offset = term.grid().display_offset() as i32;
+            let point = |(row, col): (usize, usize)| Point::new(Line(row as i32 - offset), Column(col));
+            let mut selection = Selection::new(kind.selection_type(), point(from), Side::Left);
+            selection.update(point(to), Side::Right);
+            term.selection = Some(selection);
+            term.selection_to_string()
+        };
+        syncer.sync();
+        selection
+    }
+
+    #[test]
+    fn test_block_word_and_line_selection() {
+        let mut syncer = test_syncer(10, 4);
+        // The second line wraps onto the third
+        feed(&syncer, b"ab cd-ef\r\n0123456789XYZ\r\nlast");
+
+        assert_eq!(select(&mut syncer, SelectionKind::Block, (0, 1), (1, 3)).as_deref(), Some("b c\n123"));
+        assert_eq!(syncer.selection_spans, vec![(0, 1, 4), (1, 1, 4)]);
+
+        // Words end at the separators
+        assert_eq!(select(&mut syncer, SelectionKind::Word, (0, 4), (0, 4)).as_deref(), Some("cd-ef"));
+        syncer.term.lock().set_semantic_escape_chars(" -");
+        assert_eq!(select(&mut syncer, SelectionKind::Word, (0, 4), (0, 4)).as_deref(), Some("cd"));
+
+        // A line selection takes the whole soft-wrapped line
+        assert_eq!(select(&mut syncer, SelectionKind::Lines, (2, 1), (2, 1)).as_deref(), Some("0123456789XYZ\n"));
+        assert_eq!(syncer.selection_spans, vec![(1, 0, 10), (2, 0, 10)]);
+    }
+
+    fn smart(syncer: &GridSyncer, cell: (usize, usize)) -> Option<String> {
+        let term = syncer.term.lock();
+        let point = Point::new(Line(cell.0 as i32), Column(cell.1));
+        let (start, end) = crate::selection::smart_bounds(&term, point)?;
+        Some(term.bounds_to_string(start, end))
+    }
+
+    #[test]
+    fn test_smart_selection() {
+        let syncer = test_syncer(40, 6);
+        feed(&syncer, b"see https://example.com/a?b=1). ok\r\n");
+        feed(&syncer, b"at src/main.rs:12:5 from 10.0.0.1:80\r\n");
+        feed(&syncer, b"say \"hello world\" and don't\r\n");
+        feed(&syncer, b"addr fe80::1 in std::fmt\r\n");
+        feed(&syncer, b"                              /usr/local/bin/tool");
+
+        assert_eq!(smart(&syncer, (0, 10)).as_deref(), Some("https://example.com/a?b=1"));
+        assert_eq!(smart(&syncer, (1, 5)).as_deref(), Some("src/main.rs:12:5"));
+        assert_eq!(smart(&syncer, (1, 30)).as_deref(), Some("10.0.0.1:80"));
+        assert_eq!(smart(&syncer, (2, 10)).as_deref(), Some("hello world"));
+        assert_eq!(smart(&syncer, (2, 27)), None);
+        assert_eq!(smart(&syncer, (3, 7)).as_deref(), Some("fe80::1"));
+        assert_eq!(smart(&syncer, (3, 19)), None);
+        // The path wraps onto the last row
+        assert_eq!(smart(&syncer, (5, 2)).as_deref(), Some("/usr/local/bin/tool"));
+    }
+
+    fn history(syncer: &GridSyncer) -> usize {
+        syncer.term.lock().grid().history_size()
+    }
+
+    fn feed_lines(syncer: &GridSyncer, n: usize) {
+        for i in 0..n {
+            feed(syncer, format!("line {i}\r\n").as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_trim_history_keeps_limit() {
+        let syncer = test_syncer(10, 3);
+        feed_lines(&syncer, 50);
+        assert_eq!(history(&syncer), 48);
+
+        syncer.term.lock().trim_history(10);
+        assert_eq!(history(&syncer), 10);
+        // The oldest lines went first
+        let first = syncer.term.lock().grid()[Line(-10)][Column(5)].c;
+        assert_eq!(first, '3');
+
+        // History grows back because the limit didn't change
+        feed_lines(&syncer, 5);
+        assert_eq!(history(&syncer), 15);
+    }
+
+    #[test]
+    fn test_set_scrolling_history_caps_growth() {
+        let syncer = test_syncer(10, 3);
+        feed_lines(&syncer, 30);
+        syncer.term.lock().set_scrolling_history(12);
+        assert_eq!(history(&syncer), 12);
+        feed_lines(&syncer, 30);
+        assert_eq!(history(&syncer), 12);
+    }
+
+    #[test]
+    fn test_trim_history_in_alt_screen_trims_primary() {
+        let syncer = test_syncer(10, 3);
+        feed_lines(&syncer, 30);
+        feed(&syncer, b"\x1b[?1049h");
+        syncer.term.lock().trim_history(5);
+        feed(&syncer, b"\x1b[?1049l");
+        assert_eq!(history(&syncer), 5);
+    }
+
+    #[test]
+    fn test_dump_ansi_round_trips_text_and_sgr() {
+        use alacritty_terminal::vte::ansi::{Color as AnsiColor, NamedColor};
+        let source = test_syncer(20, 4);
+        feed(&source, b"plain\r\n\x1b[1;31mbold red\x1b[0m \x1b[38;5;42mi\x1b[48;2;1;2;3mrgb\x1b[0m\r\n\x1b[4munder\x1b[0m");
+        let dump = crate::restore::dump_term(&source.term.lock(), usize::MAX);
+
+        let replayed = test_syncer(20, 4);
+        feed(&replayed, &dump);
+        let term = replayed.term.lock();
+        let grid = term.grid();
+        let text = |line: i32| -> String {
+            (0..20).map(|c| grid[Line(line)][Column(c)].c).collect::<String>().trim_end().to_string()
+        };
+        assert_eq!(text(0), "plain");
+        assert_eq!(text(1), "bold red irgb");
+        assert_eq!(text(2), "under");
+
+        let bold = &grid[Line(1)][Column(0)];
+        assert!(bold.flags.contains(CellFlags::BOLD));
+        assert_eq!(bold.fg, AnsiColor::Named(NamedColor::Red));
+        assert_eq!(grid[Line(1)][Column(8)].fg, AnsiColor::Named(NamedColor::Foreground));
+        assert_eq!(grid[Line(1)][Column(9)].fg, AnsiColor::Indexed(42));
+        assert_eq!(grid[Line(1)][Column(10)].bg, AnsiColor::Spec(alacritty_terminal::vte::ansi::Rgb { r: 1, g: 2, b: 3 }));
+        assert!(grid[Line(2)][Column(0)].flags.contains(CellFlags::UNDERLINE));
+    }
+
+    #[test]
+    fn test_dump_ansi_rewraps_and_respects_cap() {
+        let source = test_syncer(10, 3);
+        feed(&source, b"first\r\n0123456789abcde\r\nlast");
+        let dump = crate::restore::dump_term(&source.term.lock(), usize::MAX);
+        // The soft-wrapped row is one logical line
+        assert_eq!(String::from_utf8(dump).unwrap(), "first\r\n0123456789abcde\r\nlast\r\n");
+
+        // The cap keeps whole lines from the end
+        let capped = crate::restore::dump_term(&source.term.lock(), 24);
+        assert_eq!(String::from_utf8(capped).unwrap(), "0123456789abcde\r\nlast\r\n");
+    }
+
+    #[test]
+    fn test_cast_recorder_writes_parseable_v2() {
+        let path = std::env::temp_dir().join(format!("tide-cast-test-{}.cast", std::process::id()));
+        let mut recorder = crate::cast::CastRecorder::create(&path, 80, 24).unwrap();
+        let now = std::time::Instant::now();
+        // "é" split across two PTY reads
+        recorder.output(now, b"caf\xc3").unwrap();
+        recorder.output(now, b"\xa9\r\n").unwrap();
+        recorder.resize(now, 100, 30).unwrap();
+        recorder.finish().unwrap();
+
+        let cast = Cast::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!((cast.width, cast.height), (80, 24));
+        let outputs: Vec<&str> = cast.events.iter().filter_map(|e| match e {
+            CastEvent::Output { data, .. } => Some(data.as_str()),
+            _ => None,
+        }).collect();
+        assert_eq!(outputs.concat(), "caf\u{e9}\r\n");
+        assert!(matches!(cast.events.last(), Some(CastEvent::Resize { cols: 100, rows: 30, .. })));
+    }
+
+    #[test]
+    fn test_cast_parse_limits_idle_time_and_rejects_v1() {
+        let text = concat!(
+            "{\"version\": 2, \"width\": 10, \"height\": 2, \"idle_time_limit\": 1.0}\n",
+            "[0.5, \"o\", \"a\"]\n",
+            "[10.5, \"o\", \"b\"]\n",
+            "[11.0, \"i\", \"x\"]\n",
+            "[11.5, \"r\", \"20x5\"]\n",
+        );
+        let cast = Cast::parse(text).unwrap();
+        let times: Vec<f64> = cast.events.iter().map(CastEvent::time).collect();
+        assert_eq!(times, vec![0.5, 1.5, 2.5]);
+        assert_eq!(cast.duration(), 2.5);
+
+        assert!(Cast::parse("{\"version\": 1, \"width\": 10, \"height\": 2}").is_err());
+        assert!(Cast::parse("{\"version\": 2, \"width\": 10, \"height\": 2}\n[1, \"r\", \"wide\"]").is_err());
+    }
+
+    #[test]
+    fn test_cast_player_seeks_both_ways() {
+        let cast = Cast::parse(concat!(
+            "{\"version\": 2, \"width\": 10, \"height\": 3}\n",
+            "[1.0, \"o\", \"one\\r\\n\"]\n",
+            "[2.0, \"o\", \"two\"]\n",
+        )).unwrap();
+        let mut player = CastPlayer::new(cast, true);
+        let row = |player: &CastPlayer, r: usize| -> String {
+            player.grid().cells[r].iter().map(|c| c.character).collect::<String>().trim_end().to_string()
+        };
+
+        player.seek(2.0);
+        player.process();
+        assert_eq!((row(&player, 0).as_str(), row(&player, 1).as_str()), ("one", "two"));
+        assert!(player.is_finished());
+
+        // Seeking back rebuilds the grid from the start
+        player.seek(1.5);
+        player.process();
+        assert_eq!((row(&player, 0).as_str(), row(&player, 1).as_str()), ("one", ""));
+        assert!(player.is_paused());
+    }
+
+    fn kitty(key: Key, mods: Modifiers, kind: KeyEventKind, text: Option<&str>, flags: KeyboardFlags) -> String {
+        String::from_utf8(Terminal::encode_key(&key, &mods, kind, text, flags)).unwrap()
+    }
+
+    #[test]
+    fn test_kitty_disambiguate_encodes_ambiguous_keys() {
+        let flags = KeyboardFlags { disambiguate: true, ..Default::default() };
+        let none = Modifiers::default();
+        let ctrl = Modifiers { ctrl: true, ..Default::default() };
+        let shift = Modifiers { shift: true, ..Default::default() };
+        let alt = Modifiers { alt: true, ..Default::default() };
+        let press = KeyEventKind::Press;
+        assert_eq!(kitty(Key::Char('i'), ctrl, press, None, flags), "\x1b[105;5u");
+        assert_eq!(kitty(Key::Tab, none, press, None, flags), "\t");
+        assert_eq!(kitty(Key::Escape, none, press, None, flags), "\x1b[27u");
+        assert_eq!(kitty(Key::Enter, none, press, None, flags), "\r");
+        assert_eq!(kitty(Key::Enter, shift, press, None, flags), "\x1b[13;2u");
+        assert_eq!(kitty(Key::Char('A'), shift, press, Some("A"), flags), "A");
+        assert_eq!(kitty(Key::Char('a'), alt, press, None, flags), "\x1b[97;3u");
+        assert_eq!(kitty(Key::Up, none, press, None, flags), "\x1b[A");
+        assert_eq!(kitty(Key::Up, ctrl, press, None, flags), "\x1b[1;5A");
+        assert_eq!(kitty(Key::Delete, shift, press, None, flags), "\x1b[3;2~");
+        // Releases need the report-events flag
+        assert_eq!(kitty(Key::Char('a'), none, KeyEventKind::Release, None, flags), "");
+    }
+
+    #[test]
+    fn test_kitty_release_events_and_all_keys() {
+        let none = Modifiers::default();
+        let shift = Modifiers { shift: true, ..Default::default() };
+        let (press, release) = (KeyEventKind::Press, KeyEventKind::Release);
+
+        let events = KeyboardFlags { disambiguate: true, report_events: true, ..Default::default() };
+        assert_eq!(kitty(Key::Char('a'), none, press, Some("a"), events), "a");
+        assert_eq!(kitty(Key::Char('a'), none, release, None, events), "\x1b[97;1:3u");
+        assert_eq!(kitty(Key::Enter, none, release, None, events), "");
+        assert_eq!(kitty(Key::Up, none, release, None, events), "\x1b[1;1:3A");
+        assert_eq!(kitty(Key::F(3), none, release, None, events), "\x1b[13;1:3~");
+
+        let all = KeyboardFlags {
+            report_events: true,
+            report_alternates: true,
+            report_all_keys: true,
+            report_text: true,
+            ..Default::default()
+        };
+        assert_eq!(kitty(Key::Char('a'), none, press, Some("a"), all), "\x1b[97;;97u");
+        assert_eq!(kitty(Key::Char('A'), shift, press, Some("A"), all), "\x1b[97:65;2;65u");
+        assert_eq!(kitty(Key::Enter, none, press, Some("\r"), all), "\x1b[13u");
+        assert_eq!(kitty(Key::Enter, none, release, None, all), "\x1b[13;1:3u");
+
+        // Legacy mode never reports releases
+        assert_eq!(kitty(Key::Char('a'), none, release, None, KeyboardFlags::default()), "");
+    }
+
+    #[test]
+    fn test_kitty_flag_stack_per_screen() {
+        let listener = test_listener();
+        let config = TermConfig { kitty_keyboard: true, ..TermConfig::default() };
+        let mut term = Term::new(config, &TermDimensions::new(10, 3), listener);
+        let mut processor: alacritty_terminal::vte::ansi::Processor = Default::default();
+        let mut feed = |term: &mut Term<TermEventListener>, bytes: &[u8]| processor.advance(term, bytes);
+        let kitty_mode = |term: &Term<TermEventListener>| *term.mode() & TermMode::KITTY_KEYBOARD_PROTOCOL;
+
+        feed(&mut term, b"\x1b[>1u");
+        assert_eq!(kitty_mode(&term), TermMode::DISAMBIGUATE_ESC_CODES);
+        // CSI = 2 ; 2 u: add report-events to the current flags
+        feed(&mut term, b"\x1b[=2;2u");
+        let both = TermMode::DISAMBIGUATE_ESC_CODES | TermMode::REPORT_EVENT_TYPES;
+        assert_eq!(kitty_mode(&term), both);
+        feed(&mut term, b"\x1b[>8u");
+        assert_eq!(kitty_mode(&term), TermMode::REPORT_ALL_KEYS_AS_ESC);
+        feed(&mut term, b"\x1b[<u");
+        assert_eq!(kitty_mode(&term), both);
+
+        // The alternate screen has its own stack
+        feed(&mut term, b"\x1b[?1049h");
+        assert_eq!(kitty_mode(&term), TermMode::empty());
+        feed(&mut term, b"\x1b[?1049l");
+        assert_eq!(kitty_mode(&term), both);
+    }
+
+    #[test]
+    fn test_program_titles_reach_listener() {
+        use alacritty_terminal::event_loop::OscPassthrough;
+        let titles = Arc::new(Mutex::new(TermTitles::default()));
+        let shell_marks = Arc::new(Mutex::new(ShellMarks::default()));
+        let listener = TermEventListener {
+            shell_marks: shell_marks.clone(),
+            titles: titles.clone(),
+            ..test_listener()
+        };
+        let mut term = Term::new(TermConfig::default(), &TermDimensions::new(20, 3), listener);
+        let mut processor: alacritty_terminal::vte::ansi::Processor = Default::default();
+        let mut feed = |bytes: &[u8]| OscPassthrough::default().advance(&mut processor, &mut term, bytes);
+        let title = || titles.lock().unwrap().title.clone();
+
+        feed(b"\x1b]2;vim main.rs\x07");
+        assert_eq!(title().as_deref(), Some("vim main.rs"));
+        feed(b"\x1b]1;vim\x1b\\");
+        assert_eq!(titles.lock().unwrap().icon_name.as_deref(), Some("vim"));
+
+        // Title stack: push, retitle, pop
+        feed(b"\x1b[22t\x1b]0;less\x07");
+        assert_eq!(title().as_deref(), Some("less"));
+        feed(b"\x1b[23t");
+        assert_eq!(title().as_deref(), Some("vim main.rs"));
+
+        // Control characters are dropped; a blank title resets
+        feed(b"\x1b]2;a\x7fb\x07");
+        assert_eq!(title().as_deref(), Some("ab"));
+        feed(b"\x1b]2; \x07");
+        assert_eq!(title(), None);
+
+        // Other unhandled OSCs still reach shell integration
+        feed(b"\x1b]133;A\x07");
+        assert!(!shell_marks.lock().unwrap().regions().is_empty());
+    }
+
+    #[test]
+    fn test_bell_and_notifications_are_counted() {
+        use alacritty_terminal::event_loop::OscPassthrough;
+        use std::sync::atomic::Ordering;
+        let attention = Arc::new(Attention::default());
+        let listener = TermEventListener { attention: attention.clone(), ..test_listener() };
+        let dirty = listener.dirty.clone();
+        let mut term = Term::new(TermConfig::default(), &TermDimensions::new(20, 3), listener);
+        let mut processor: alacritty_terminal::vte::ansi::Processor = Default::default();
+        let mut feed = |bytes: &[u8]| OscPassthrough::default().advance(&mut processor, &mut term, bytes);
+
+        feed(b"done\x07");
+        assert_eq!(attention.bells.load(Ordering::Relaxed), 1);
+        assert!(dirty.load(Ordering::Relaxed));
+
+        feed(b"\x1b]9;Build finished\x07");
+        feed(b"\x1b]777;notify;make;Build finished\x1b\\");
+        // ConEmu progress and an empty message are not notifications
+        feed(b"\x1b]9;4;1;50\x07\x1b]9;\x07");
+        assert_eq!(attention.notifications.load(Ordering::Relaxed), 2);
+        assert_eq!(attention.bells.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_osc52_requests_are_queued() {
+        use crate::clipboard::ClipboardQueue;
+        let clipboard = Arc::new(Mutex::new(ClipboardQueue::default()));
+        let listener = TermEventListener { clipboard: clipboard.clone(), ..test_listener() };
+        let config = TermConfig { osc52: alacritty_terminal::term::Osc52::CopyPaste, ..TermConfig::default() };
+        let mut term = Term::new(config, &TermDimensions::new(20, 3), listener);
+        let mut processor: alacritty_terminal::vte::ansi::Processor = Default::default();
+
+        // "hello" to the clipboard, then a read request
+        processor.advance(&mut term, b"\x1b]52;c;aGVsbG8=\x07\x1b]52;c;?\x07");
+        let requests = clipboard.lock().unwrap().take();
+        assert_eq!(requests.len(), 2);
+        assert!(matches!(&requests[0], ClipboardRequest::Store(text) if text == "hello"));
+        match &requests[1] {
+            ClipboardRequest::Load(reply) => assert_eq!((reply.0)("hi"), "\x1b]52;c;aGk=\x07"),
+            other => panic!("expected a load request, got {other:?}"),
+        }
+        assert!(clipboard.lock().unwrap().take().is_empty());
+    }
+
+    fn kitty_rgb(keys: &str, width: usize, height: usize, rgb: [u8; 3]) -> Vec<u8> {
+        use base64::Engine;
+        let pixels: Vec<u8> = rgb.iter().copied().cycle().take(width * height * 3).collect();
+        let payload = base64::engine::general_purpose::STANDARD.encode(pixels);
+        format!("\x1b_Gf=24,s={width},v={height},{keys};{payload}\x1b\\").into_bytes()
+    }
+
+    #[test]
+    fn test_kitty_image_anchored_to_cursor_and_scrolls() {
+        let mut syncer = test_syncer(20, 4);
+        syncer.images.lock().unwrap().set_cell_size(10.0, 20.0);
+        feed(&syncer, b"ab");
+        // 30x40 pixels = 3x2 cells
+        feed(&syncer, &kitty_rgb("a=T", 30, 40, [255, 0, 0]));
+        {
+            let term = syncer.term.lock();
+            // Right of the image, on its last row
+            assert_eq!(term.grid().cursor.point.line.0, 1);
+            assert_eq!(term.grid().cursor.point.column.0, 5);
+        }
+        syncer.sync();
+        assert_eq!(syncer.visible_images.len(), 1);
+        let image = &syncer.visible_images[0];
+        assert_eq!((image.row, image.col, image.cols, image.rows), (0, 2, 3.0, 2.0));
+        assert_eq!(&image.image.rgba[..4], &[255, 0, 0, 255]);
+
+        // Output scrolls the image up with the text
+        let generation = syncer.grid_generation;
+        feed(&syncer, b"\r\n\r\n\r\n");
+        syncer.sync();
+        assert_eq!(syncer.visible_images[0].row, -1);
+        assert!(syncer.grid_generation > generation);
+        feed(&syncer, b"\r\n");
+        syncer.sync();
+        assert!(syncer.visible_images.is_empty());
+
+        // Clearing scrollback drops it for good
+        feed(&syncer, b"\x1b[3J");
+        let mut visible = Vec::new();
+        syncer.images.lock().unwrap().visible(0, 100, false, &mut visible);
+        assert!(visible.is_empty());
+    }
+
+    #[test]
+    fn test_kitty_chunked_transfer_placement_and_delete() {
+        use alacritty_terminal::event::{Graphics, GraphicsCursor};
+        let mut store = ImageStore::default();
+        let at = GraphicsCursor { line: 0, column: 0, alt_screen: false };
+        let mut apply = |data: &[u8]| store.apply(Graphics::Kitty(data.to_vec()), at, None);
+
+        // RGBA 1x2 (two pixels) split across chunks, transmitted only
+        let (advance, reply) = apply(b"Ga=t,i=7,f=32,s=1,v=2,m=1;/wAA/w");
+        assert_eq!((advance, reply), (None, None));
+        let (advance, reply) = apply(b"Gm=0;D/AP8=");
+        assert_eq!(advance, None);
+        assert_eq!(reply.as_deref(), Some("\x1b_Gi=7;OK\x1b\\"));
+
+        // Put it twice; same placement id replaces, C=1 keeps the cursor
+        let (advance, _) = apply(b"Ga=p,i=7,p=1,c=4,r=2");
+        assert_eq!(advance, Some(CursorAdvance { lines: 1, columns: 4 }));
+        let (advance, reply) = apply(b"Ga=p,i=7,p=1,c=4,C=1,q=1");
+        assert_eq!((advance, reply), (None, None));
+
+        let (_, reply) = apply(b"Ga=p,i=8");
+        assert_eq!(reply.as_deref(), Some("\x1b_Gi=8;ENOENT:image not found\x1b\\"));
+
+        let mut visible = Vec::new();
+        store.visible(0, 5, false, &mut visible);
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].image.rgba, vec![255, 0, 0, 255, 0, 255, 0, 255]);
+        // Only c given: r keeps the 1x2 pixel aspect ratio (cells are 8x16)
+        assert_eq!((visible[0].cols, visible[0].rows), (4.0, 4.0));
+
+        let mut apply = |data: &[u8]| store.apply(Graphics::Kitty(data.to_vec()), at, None);
+        apply(b"Ga=d,d=I,i=7");
+        let (_, reply) = apply(b"Ga=p,i=7");
+        assert_eq!(reply.as_deref(), Some("\x1b_Gi=7;ENOENT:image not found\x1b\\"));
+    }
+
+    #[test]
+    fn test_kitty_file_medium_is_read_ahead_and_checked() {
+        use alacritty_terminal::event::{Graphics, GraphicsCursor};
+        use base64::Engine;
+        let at = GraphicsCursor { line: 0, column: 0, alt_screen: false };
+        let kitty = |medium: &str, path: &std::path::Path| {
+            let path = base64::engine::general_purpose::STANDARD.encode(path.to_str().unwrap());
+            Graphics::Kitty(format!("Ga=t,i=1,f=24,s=1,v=1,t={medium};{path}").into_bytes())
+        };
+        let temp = std::env::temp_dir();
+        let dir = temp.join(format!("tide-kitty-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // A temporary file is decoded (and removed) ahead of the parser; the
+        // store takes the result when it reaches the same sequence
+        let file = temp.join(format!("tty-graphics-protocol-{}.rgb", std::process::id()));
+        std::fs::write(&file, [255, 0, 0]).unwrap();
+        let mut decoder = ImageDecoder::default();
+        let mut store = ImageStore::default();
+        let graphics = kitty("t", &file);
+        decoder.prepare(&graphics);
+        assert!(!file.exists());
+        let decoded = decoder.take(&graphics);
+        assert!(decoded.is_some());
+        let (_, reply) = store.apply(graphics, at, decoded);
+        assert_eq!(reply.as_deref(), Some("\x1b_Gi=1;OK\x1b\\"));
+
+        // `..` can't pass off a file elsewhere as one in the temp directory
+        let nested = dir.join("tty-graphics-protocol.rgb");
+        std::fs::write(&nested, [0, 255, 0]).unwrap();
+        let dotted = temp.join("..").join(temp.file_name().unwrap()).join(dir.file_name().unwrap());
+        let (_, reply) = store.apply(kitty("t", &dotted.join("tty-graphics-protocol.rgb")), at, None);
+        assert_eq!(reply.as_deref(), Some("\x1b_Gi=1;OK\x1b\\"));
+        assert!(nested.exists());
+
+        // FIFOs and devices are refused without blocking
+        let fifo = dir.join("tty-graphics-protocol.fifo");
+        let c_path = std::ffi::CString::new(fifo.to_str().unwrap()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+        let (_, reply) = store.apply(kitty("f", &fifo), at, None);
+        assert_eq!(reply.as_deref(), Some("\x1b_Gi=1;EPERM:not a regular file\x1b\\"));
+        let (_, reply) = store.apply(kitty("f", std::path::Path::new("/dev/zero")), at, None);
+        assert_eq!(reply.as_deref(), Some("\x1b_Gi=1;EPERM:not a regular file\x1b\\"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sixel_decode() {
+        // Register 1 = pure red (RGB percent); two columns of all six pixels,
+        // then a band with only the top pixel in register 2 = HLS blue.
+        let image = crate::sixel::decode(b"0;1;0q\"1;1;3;12#1;2;100;0;0!2~-#2;1;0;50;100@").unwrap();
+        assert_eq!((image.width, image.height), (3, 12));
+        let pixel = |x: usize, y: usize| &image.rgba[(y * 3 + x) * 4..(y * 3 + x) * 4 + 4];
+        assert_eq!(pixel(0, 0), &[255, 0, 0, 255]);
+        assert_eq!(pixel(1, 5), &[255, 0, 0, 255]);
+        assert_eq!(pixel(0, 6), &[0, 0, 255, 255]);
+        // P2=1: untouched pixels are transparent
+        assert_eq!(pixel(2, 0), &[0, 0, 0, 0]);
+        assert_eq!(pixel(0, 7), &[0, 0, 0, 0]);
+        assert!(crate::sixel::decode(b"q").is_none());
+    }
+
+    #[test]
+    fn test_sixel_moves_cursor_below_image_and_alt_screen_clears() {
+        let mut syncer = test_syncer(20, 6);
+        syncer.images.lock().unwrap().set_cell_size(10.0, 20.0);
+        use alacritty_terminal::event_loop::OscPassthrough;
+        use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+        let mut processor: Processor<StdSyncHandler> = Processor::new();
+        let mut passthrough = OscPassthrough::default();
+        // 20x36 pixels = 2x1.8 cells, split across reads
+        passthrough.advance(&mut processor, &mut *syncer.term.lock(), b"x\x1bPq\"1;1;20;36#0;2;0;100;0!20~-!20~-");
+        passthrough.advance(&mut processor, &mut *syncer.term.lock(), b"!20~-!20~-!20~-!20~\x1b\\y");
+        {
+            let term = syncer.term.lock();
+            assert_eq!(term.grid().cursor.point.line.0, 2);
+            assert_eq!(term.grid().cursor.point.column.0, 2);
+        }
+        syncer.sync();
+        assert_eq!(syncer.visible_images.len(), 1);
+        assert_eq!((syncer.visible_images[0].cols, syncer.visible_images[0].rows), (2.0, 1.8));
+
+        // The alternate screen has its own images, gone when it's entered again
+        feed(&syncer, b"\x1b[?1049h\x1bPq#1~\x1b\\");
+        syncer.sync();
+        assert_eq!(syncer.visible_images.len(), 1);
+        assert_eq!(syncer.visible_images[0].image.width, 1);
+        feed(&syncer, b"\x1b[?1049l");
+        syncer.sync();
+        assert_eq!(syncer.visible_images[0].image.width, 20);
+        feed(&syncer, b"\x1b[?1049h");
+        syncer.sync();
+        assert!(syncer.visible_images.is_empty());
+    }
+
+    #[test]
+    fn test_tmux_layout_parse() {
+        assert_eq!(
+            TmuxLayout::parse("b25d,80x24,0,0,3"),
+            Some(TmuxLayout::Pane { id: 3, cols: 80, rows: 24 })
+        );
+        // A vertical stack inside a side-by-side split
+        let layout = TmuxLayout::parse("5d2c,80x24,0,0{40x24,0,0,1,39x24,41,0[39x12,41,0,2,39x11,41,13,4]}").unwrap();
+        assert_eq!(layout.size(), (80, 24));
+        assert_eq!(layout.panes(), vec![1, 2, 4]);
+        let TmuxLayout::Split { horizontal: true, children, .. } = &layout else { panic!("{:?}", layout) };
+        assert!(matches!(&children[1], TmuxLayout::Split { horizontal: false, rows: 24, .. }));
+        assert_eq!(TmuxLayout::parse("b25d,80x24,0,0{40x24,0,0,1"), None);
+        assert_eq!(TmuxLayout::parse("80x24"), None);
+    }
+
+    #[test]
+    fn test_tmux_unescape() {
+        assert_eq!(crate::tmux::unescape(r"hi\015\012\033[1m\\x\\"), b"hi\r\n\x1b[1m\\x\\");
+        assert_eq!(crate::tmux::unescape(r"\01"), b"\\01");
+    }
+
+    #[test]
+    fn test_tmux_control_protocol() {
+        // The intro means nothing unless tmux is in the foreground
+        let mut control = crate::tmux::TmuxControl::default();
+        control.feed(b"$ cat notes\r\n\x1bP1000p%begin 1 1 1\r\n@1 1 b25d,80x24,0,0,0 fake\r\n%end 1 1 1\r\n");
+        assert!(control.take_events().is_empty());
+        control.set_foreground_check(Box::new(|| false));
+        control.feed(b"\x1bP1000p%begin 1 1 1\r\n%end 1 1 1\r\n");
+        assert!(control.take_events().is_empty());
+
+        let mut control = crate::tmux::TmuxControl::default();
+        control.set_foreground_check(Box::new(|| true));
+        // Ordinary output is ignored; the intro may be split across reads
+        control.feed(b"$ tmux -CC attach\r\n\x1bP10");
+        assert!(control.take_events().is_empty());
+        control.feed(b"00p%begin 1 1 0\r\n%end 1 1 0\r\n%session-changed $0 work\r\n");
+        // The attach block isn't ours; the first reply answers our window list
+        control.feed(b"%begin 2 5 1\r\n@1 1 b25d,80x24,0,0,0 my shell\r\n%end 2 5 1\r\n");
+        let windows = vec![TmuxWindow {
+            id: 1,
+            name: "my shell".to_string(),
+            active: true,
+            layout: TmuxLayout::Pane { id: 0, cols: 80, rows: 24 },
+        }];
+        assert_eq!(control.take_events(), vec![TmuxEvent::Windows { session: "work".to_string(), windows }]);
+
+        // The session change asked for a second list, which fails
+        control.feed(b"%output %0 hi\\015\\012\r\n%begin 3 6 1\r\nno session\r\n%error 3 6 1\r\n");
+        assert_eq!(control.take_events(), vec![TmuxEvent::CommandFailed("no session".to_string())]);
+
+        // With nothing asked, a block claiming to be ours is ignored
+        control.feed(b"%begin 4 7 1\r\n@9 1 b25d,80x24,0,0,5 forged\r\n%end 4 7 1\r\n");
+        assert!(control.take_events().is_empty());
+
+        control.feed(b"%exit\r\n\x1b\\$ ");
+        assert_eq!(control.take_events(), vec![TmuxEvent::Exited]);
+    }
+
+    /// Wait for the first event that `accept` maps to a value.
+    fn wait_tmux<T>(gateway: &Terminal, mut accept: impl FnMut(TmuxEvent) -> Option<T>) -> T {
+        let deadline = std::time::Instant::now() + WAIT;
+        loop {
+            if let Some(found) = gateway.tmux_events().into_iter().find_map(&mut accept) {
+                return found;
+            }
+            assert!(std::time::Instant::now() < deadline, "timed out waiting for tmux");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_tmux_control_mode_against_server() {
+        if std::process::Command::new("tmux").arg("-V").output().is_err() {
+            eprintln!("tmux not installed, skipping");
+            return;
+        }
+        let socket = format!("tide-test-{}", std::process::id());
+        let command = ShellCommand {
+            program: Some("tmux".to_string()),
+            args: ["-L", &socket, "-f", "/dev/null", "-CC", "new-session", "-x", "80", "-y", "24", "/bin/sh"]
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+            env: Vec::new(),
+        };
+        let mut gateway = Terminal::with_command(80, 24, None, true, &command, None).unwrap();
+        let windows = |event| match event {
+            TmuxEvent::Windows { windows, .. } => Some(windows),
+            _ => None,
+        };
+        let first = wait_tmux(&gateway, windows);
+        let pane_id = first[0].layout.panes()[0];
+        let tmux = gateway.tmux_gateway();
+        assert!(tmux.is_active());
+        assert_eq!(tmux.windows(), first);
+
+        let mut pane = tmux.open_pane(pane_id, 80, 24, true).unwrap();
+        assert_eq!(pane.tmux_pane(), Some((tmux.clone(), pane_id)));
+        pane.write(b"echo tide-$((6*7))\r");
+        let deadline = std::time::Instant::now() + WAIT;
+        while !pane.recent_text(24).lines().any(|line| line == "tide-42") {
+            assert!(std::time::Instant::now() < deadline, "no output: {:?}", pane.recent_text(24));
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        tmux.split(pane_id, true);
+        let split = wait_tmux(&gateway, |event| windows(event).filter(|w| w[0].layout.panes().len() == 2));
+        assert!(matches!(split[0].layout, TmuxLayout::Split { horizontal: true, .. }));
+        let new_pane = split[0].layout.panes()[1];
+        tmux.kill_pane(new_pane);
+        wait_tmux(&gateway, |event| windows(event).filter(|w| w[0].layout.panes() == vec![pane_id]));
+        assert!(pane.is_child_alive());
+        tmux.resize_window(first[0].id, 100, 30);
+        wait_tmux(&gateway, |event| windows(event).filter(|w| w[0].layout.size() == (100, 30)));
+
+        // Typing into the gateway doesn't reach tmux's command line; Escape detaches
+        gateway.write(b"\x1b");
+        wait_tmux(&gateway, |event| (event == TmuxEvent::Exited).then_some(()));
+        assert!(!pane.is_child_alive());
+        assert!(!tmux.is_active());
+        let _ = std::process::Command::new("tmux").args(["-L", &socket, "kill-server"]).status();
+    }
+
+    #[test]
+    fn test_tmux_pane_leaves_query_replies_to_tmux() {
+        // The gateway's "tmux" is cat: the commands we send land in a file
+        let path = std::env::temp_dir().join(format!("tide-tmux-replies-{}", std::process::id()));
+        let command = ShellCommand {
+            program: Some("/bin/sh".to_string()),
+            args: vec!["-c".to_string(), format!("exec cat > {}", path.display())],
+            env: Vec::new(),
+        };
+        let gateway = Terminal::with_command(80, 24, None, true, &command, None).unwrap();
+        let tmux = gateway.tmux_gateway();
+        let feed = |bytes: &[u8]| tmux.0.lock().unwrap().feed(bytes);
+        tmux.0.lock().unwrap().set_foreground_check(Box::new(|| true));
+        feed(b"\x1bP1000p%begin 1 1 0\r\n%end 1 1 0\r\n");
+        feed(b"%begin 2 2 1\r\n@1 1 b25d,80x24,0,0,1 sh\r\n%end 2 2 1\r\n");
+        let mut pane = tmux.open_pane(1, 80, 24, true).unwrap();
+        // Empty screen capture, then the cursor position: the pane goes live
+        feed(b"%begin 3 3 1\r\n%end 3 3 1\r\n%begin 4 4 1\r\n0 0\r\n%end 4 4 1\r\n");
+
+        // A DA1 query, which tmux has answered already
+        feed(b"%output %1 \\033[cready\r\n");
+        let deadline = std::time::Instant::now() + WAIT;
+        while !pane.recent_text(24).contains("ready") {
+            assert!(std::time::Instant::now() < deadline, "no output: {:?}", pane.recent_text(24));
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        // Typed keys still go through, after any reply would have
+        pane.write(b"x");
+        let sent = || std::fs::read_to_string(&path).unwrap_or_default();
+        while !sent().contains("send-keys") {
+            assert!(std::time::Instant::now() < deadline, "no keys sent: {:?}", sent());
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let keys: Vec<String> = sent().lines().filter(|l| l.starts_with("send-keys")).map(String::from).collect();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(keys, vec!["send-keys -t %1 -H 78".to_string()]);
+    }
 }
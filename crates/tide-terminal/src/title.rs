@@ -0,0 +1,92 @@
+// Program-set titles
+//
+// OSC 0/2 (and the CSI 22/23 t title stack) reach the listener as
+// `Event::Title` / `Event::ResetTitle`; vte ignores OSC 1, so the icon name
+// arrives as an unhandled OSC. Both are kept in `TermTitles`, shared with the
+// `Terminal` on the main thread.
+
+use super::Terminal;
+
+/// Longest title kept, in characters. Longer titles are truncated.
+const MAX_TITLE_CHARS: usize = 256;
+
+/// Window title and icon name set by the program running in the terminal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct TermTitles {
+    pub(crate) title: Option<String>,
+    pub(crate) icon_name: Option<String>,
+}
+
+impl TermTitles {
+    pub(crate) fn set_title(&mut self, title: Option<&str>) {
+        self.title = title.and_then(sanitize);
+    }
+
+    /// Apply an OSC that vte doesn't handle. Returns false if it isn't OSC 1.
+    pub(crate) fn apply_osc(&mut self, payload: &str) -> bool {
+        match payload.strip_prefix("1;") {
+            Some(name) => {
+                self.icon_name = sanitize(name);
+                true
+            }
+            None => payload == "1",
+        }
+    }
+}
+
+/// Drop control characters and cap the length; blank titles count as unset.
+fn sanitize(title: &str) -> Option<String> {
+    let clean: String = title.chars().filter(|c| !c.is_control()).take(MAX_TITLE_CHARS).collect();
+    let clean = clean.trim();
+    (!clean.is_empty()).then(|| clean.to_string())
+}
+
+impl Terminal {
+    /// Title set by the running program (OSC 0/2), if any.
+    pub fn title(&self) -> Option<String> {
+        self.titles.lock().ok()?.title.clone()
+    }
+
+    /// Icon name set by the running program (OSC 1), if any.
+    pub fn icon_name(&self) -> Option<String> {
+        self.titles.lock().ok()?.icon_name.clone()
+    }
+
+    /// Name of the foreground process (the shell itself when idle).
+    pub fn foreground_process_name(&self) -> Option<String> {
+        foreground_process_name(self.child_pid?)
+    }
+}
+
+/// Name of the foreground process of the terminal whose child is `child_pid`.
+#[cfg(target_os = "macos")]
+pub(crate) fn foreground_process_name(child_pid: u32) -> Option<String> {
+    let shell = child_pid as i32;
+    let mut pids = [0i32; 16];
+    let count = unsafe {
+        libc::proc_listchildpids(
+            shell,
+            pids.as_mut_ptr() as *mut libc::c_void,
+            (pids.len() * std::mem::size_of::<i32>()) as i32,
+        )
+    };
+    let pid = if count > 0 { pids[0] } else { shell };
+    let mut buf = [0u8; 256];
+    let len = unsafe { libc::proc_name(pid, buf.as_mut_ptr() as *mut libc::c_void, buf.len() as u32) };
+    if len <= 0 {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&buf[..len as usize]).into_owned())
+}
+
+/// Name of the foreground process group leader of the terminal whose child
+/// is `child_pid`.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn foreground_process_name(child_pid: u32) -> Option<String> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", child_pid)).ok()?;
+    // The command name is parenthesized and may contain spaces
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    let tpgid = fields.get(5)?.parse::<i32>().ok().filter(|&pid| pid > 0)?;
+    let comm = std::fs::read_to_string(format!("/proc/{}/comm", tpgid)).ok()?;
+    Some(comm.trim_end().to_string())
+}
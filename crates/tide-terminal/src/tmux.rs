@@ -0,0 +1,704 @@
+// tmux control mode (`tmux -CC`)
+//
+// Every terminal watches its output for the DCS that tmux writes when it
+// starts in control mode (`ESC P 1000 p`); if tmux is the terminal's
+// foreground process at that point, the terminal becomes a gateway. Its
+// output is a line protocol: `%begin`/`%end` blocks answer the commands we
+// write to tmux's stdin (in order), and `%`-notifications report pane output
+// and layout changes. alacritty swallows the DCS, so the gateway itself shows
+// nothing new until tmux exits (`%exit`, then ST).
+//
+// Each tmux pane shown in Tide is an ordinary `Terminal` whose "PTY" is a
+// socket pair: `%output` is unescaped and queued for a writer thread that
+// feeds our end (never blocking the gateway's PTY thread), and keys written
+// to the terminal are read by a forwarder thread and sent as `send-keys -H`.
+// The emulator's own replies to queries are dropped: tmux answered them
+// already. A second socket pair stands in for SIGCHLD, so dropping a
+// pane's sink ends its event loop the way a child exit would. A new pane is
+// seeded with `capture-pane` and the cursor position before live output
+// starts. The window list is fetched again after every structural
+// notification and handed to the app as a `TmuxEvent`, which maps windows and
+// panes onto tab groups and splits.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+use alacritty_terminal::event::{OnResize, WindowSize};
+use alacritty_terminal::event_loop::{Msg, Notifier, OutputTap};
+use alacritty_terminal::polling::{Event, PollMode, Poller};
+use alacritty_terminal::tty::{self, ChildEvent, EventedPty, EventedReadWrite};
+
+use super::Terminal;
+
+/// What tmux writes when it enters control mode.
+const CONTROL_INTRO: &[u8] = b"\x1bP1000p";
+
+/// Key bytes per `send-keys` command.
+const SEND_KEYS_CHUNK: usize = 256;
+
+/// A change the app should apply, from a gateway terminal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TmuxEvent {
+    /// The session's windows, on attach and after every change.
+    Windows { session: String, windows: Vec<TmuxWindow> },
+    /// A command we sent failed; the text is tmux's message.
+    CommandFailed(String),
+    /// tmux left control mode (detached, or the session ended).
+    Exited,
+}
+
+/// One tmux window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TmuxWindow {
+    /// Window number (`@N`).
+    pub id: u32,
+    pub name: String,
+    pub active: bool,
+    pub layout: TmuxLayout,
+}
+
+/// A window's pane tree, from tmux's layout string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TmuxLayout {
+    /// A pane (`%N`) and its size in cells.
+    Pane { id: u32, cols: u16, rows: u16 },
+    /// Panes side by side (`horizontal`) or stacked, with the total size.
+    Split { horizontal: bool, cols: u16, rows: u16, children: Vec<TmuxLayout> },
+}
+
+impl TmuxLayout {
+    /// Parse a layout string such as `b25d,80x24,0,0{40x24,0,0,1,39x24,41,0,2}`.
+    pub fn parse(layout: &str) -> Option<Self> {
+        let (_checksum, tree) = layout.split_once(',')?;
+        let mut parser = LayoutParser { bytes: tree.as_bytes(), pos: 0 };
+        let node = parser.node()?;
+        (parser.pos == tree.len()).then_some(node)
+    }
+
+    /// Size in cells as `(cols, rows)`.
+    pub fn size(&self) -> (u16, u16) {
+        match *self {
+            Self::Pane { cols, rows, .. } | Self::Split { cols, rows, .. } => (cols, rows),
+        }
+    }
+
+    /// Pane ids, left to right and top to bottom.
+    pub fn panes(&self) -> Vec<u32> {
+        let mut ids = Vec::new();
+        self.collect_panes(&mut ids);
+        ids
+    }
+
+    fn collect_panes(&self, ids: &mut Vec<u32>) {
+        match self {
+            Self::Pane { id, .. } => ids.push(*id),
+            Self::Split { children, .. } => children.iter().for_each(|child| child.collect_panes(ids)),
+        }
+    }
+}
+
+struct LayoutParser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl LayoutParser<'_> {
+    /// `COLSxROWS,X,Y` then `,ID`, `{children}` or `[children]`.
+    fn node(&mut self) -> Option<TmuxLayout> {
+        let cols = self.number()?;
+        self.expect(b'x')?;
+        let rows = self.number()?;
+        self.expect(b',')?;
+        self.number()?;
+        self.expect(b',')?;
+        self.number()?;
+        let (cols, rows) = (u16::try_from(cols).ok()?, u16::try_from(rows).ok()?);
+        let close = match self.bytes.get(self.pos)? {
+            b',' => {
+                self.pos += 1;
+                return Some(TmuxLayout::Pane { id: self.number()?, cols, rows });
+            }
+            b'{' => b'}',
+            b'[' => b']',
+            _ => return None,
+        };
+        self.pos += 1;
+        let mut children = vec![self.node()?];
+        while self.bytes.get(self.pos) == Some(&b',') {
+            self.pos += 1;
+            children.push(self.node()?);
+        }
+        self.expect(close)?;
+        Some(TmuxLayout::Split { horizontal: close == b'}', cols, rows, children })
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let digits = self.bytes[self.pos..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let text = std::str::from_utf8(&self.bytes[self.pos..self.pos + digits]).ok()?;
+        self.pos += digits;
+        text.parse().ok()
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        (self.bytes.get(self.pos) == Some(&byte)).then(|| self.pos += 1)
+    }
+}
+
+/// Undo tmux's octal escaping (`\033`, `\\`) of `%output` and captures.
+pub(crate) fn unescape(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4).filter(|d| d.iter().all(|b| (b'0'..=b'7').contains(b)));
+        match (bytes[i], octal) {
+            (b'\\', Some(digits)) => {
+                out.push(digits.iter().fold(0u8, |acc, d| acc.wrapping_mul(8).wrapping_add(d - b'0')));
+                i += 4;
+            }
+            (b'\\', None) if bytes.get(i + 1) == Some(&b'\\') => {
+                out.push(b'\\');
+                i += 2;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Parse `list-windows` output in the format `list_windows` asks for.
+pub(crate) fn parse_windows(lines: &[String]) -> Vec<TmuxWindow> {
+    lines.iter().filter_map(|line| {
+        let mut fields = line.splitn(4, ' ');
+        let id = fields.next()?.strip_prefix('@')?.parse().ok()?;
+        let active = fields.next()? == "1";
+        let layout = TmuxLayout::parse(fields.next()?)?;
+        let name = fields.next().unwrap_or_default().to_string();
+        Some(TmuxWindow { id, name, active, layout })
+    }).collect()
+}
+
+/// What a command's reply block is for.
+#[derive(Debug)]
+enum Request {
+    Ignore,
+    ListWindows,
+    Capture(u32),
+    Cursor(u32),
+}
+
+/// An open `%begin` block.
+struct Reply {
+    /// Sent by this client (flag 1) while a request is pending, so it answers
+    /// the next one.
+    ours: bool,
+    lines: Vec<String>,
+}
+
+enum SinkState {
+    /// Waiting for the screen capture; output is dropped meanwhile.
+    Capturing,
+    /// Screen captured, waiting for the cursor position.
+    Captured(Vec<u8>),
+    Live,
+}
+
+/// Our end of a pane terminal's sockets.
+struct PaneSink {
+    // Dropped first, so the pane's event loop sees the "exit" before EOF
+    _hangup: UnixStream,
+    /// Output for the pane's writer thread.
+    output: mpsc::Sender<Vec<u8>>,
+    alive: Arc<AtomicBool>,
+    state: SinkState,
+}
+
+/// Control mode state of one terminal (shared with its PTY thread's tap).
+#[derive(Default)]
+pub(crate) struct TmuxControl {
+    active: bool,
+    /// Whether tmux is the terminal's foreground process. Without it (or
+    /// when it says no), the intro is just output.
+    foreground_is_tmux: Option<Box<dyn Fn() -> bool + Send>>,
+    /// Bytes after the last ESC of the previous chunk, while watching for
+    /// the intro.
+    tail: Vec<u8>,
+    line: Vec<u8>,
+    reply: Option<Reply>,
+    pending: VecDeque<Request>,
+    /// A window list is wanted once the pending one arrives.
+    relist: bool,
+    session: String,
+    windows: Vec<TmuxWindow>,
+    /// Window sizes last asked for with `refresh-client -C`.
+    sizes: HashMap<u32, (u16, u16)>,
+    panes: HashMap<u32, PaneSink>,
+    events: Vec<TmuxEvent>,
+    /// Writes to tmux's stdin.
+    writer: Option<Notifier>,
+}
+
+impl TmuxControl {
+    pub(crate) fn set_writer(&mut self, writer: Notifier) {
+        self.writer = Some(writer);
+    }
+
+    pub(crate) fn set_foreground_check(&mut self, check: Box<dyn Fn() -> bool + Send>) {
+        self.foreground_is_tmux = Some(check);
+    }
+
+    /// The gateway terminal is going away: its panes exit with it.
+    pub(crate) fn close(&mut self) {
+        if self.active {
+            self.finish();
+        }
+    }
+
+    pub(crate) fn take_events(&mut self) -> Vec<TmuxEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub(crate) fn feed(&mut self, bytes: &[u8]) {
+        let mut rest = bytes;
+        if !self.active {
+            let Some(start) = self.find_intro(bytes) else { return };
+            rest = &bytes[start..];
+            // Any program can print the intro; only tmux gets to drive the app
+            if !self.foreground_is_tmux.as_ref().is_some_and(|check| check()) {
+                self.feed(rest);
+                return;
+            }
+            self.active = true;
+            self.list_windows();
+        }
+        for (i, &byte) in rest.iter().enumerate() {
+            match byte {
+                // Only the closing ST is sent raw; everything else is escaped
+                0x1b => {
+                    self.finish();
+                    // A new session may start in the same chunk
+                    self.feed(&rest[i + 1..]);
+                    return;
+                }
+                b'\n' => {
+                    let mut line = std::mem::take(&mut self.line);
+                    if line.last() == Some(&b'\r') {
+                        line.pop();
+                    }
+                    self.handle_line(&String::from_utf8_lossy(&line));
+                    if !self.active {
+                        self.feed(&rest[i + 1..]);
+                        return;
+                    }
+                }
+                _ => self.line.push(byte),
+            }
+        }
+    }
+
+    /// Offset just past the intro in `bytes`, which may have begun in the
+    /// previous chunk.
+    fn find_intro(&mut self, bytes: &[u8]) -> Option<usize> {
+        if !self.tail.is_empty() {
+            let rest = &CONTROL_INTRO[self.tail.len()..];
+            if bytes.starts_with(rest) {
+                self.tail.clear();
+                return Some(rest.len());
+            }
+            if rest.starts_with(bytes) {
+                self.tail.extend_from_slice(bytes);
+                return None;
+            }
+            self.tail.clear();
+        }
+        if !bytes.contains(&0x1b) {
+            return None;
+        }
+        if let Some(at) = bytes.windows(CONTROL_INTRO.len()).position(|w| w == CONTROL_INTRO) {
+            return Some(at + CONTROL_INTRO.len());
+        }
+        // Carry a partial intro at the end over to the next chunk
+        let from = bytes.len().saturating_sub(CONTROL_INTRO.len() - 1);
+        if let Some(start) = (from..bytes.len()).find(|&i| CONTROL_INTRO.starts_with(&bytes[i..])) {
+            self.tail = bytes[start..].to_vec();
+        }
+        None
+    }
+
+    fn handle_line(&mut self, line: &str) {
+        if let Some(reply) = &mut self.reply {
+            let (ok, done) = match line.split(' ').next() {
+                Some("%end") => (true, true),
+                Some("%error") => (false, true),
+                _ => (false, false),
+            };
+            if !done {
+                reply.lines.push(line.to_string());
+                return;
+            }
+            let reply = self.reply.take().unwrap();
+            if reply.ours {
+                let request = self.pending.pop_front().unwrap_or(Request::Ignore);
+                self.handle_reply(request, ok, reply.lines);
+            }
+            return;
+        }
+
+        let (kind, args) = line.split_once(' ').unwrap_or((line, ""));
+        match kind {
+            "%begin" => {
+                let flags: u32 = args.split(' ').nth(2).and_then(|f| f.parse().ok()).unwrap_or(0);
+                // Blocks nobody asked for (a program printing them) are skipped
+                let ours = flags & 1 != 0 && !self.pending.is_empty();
+                self.reply = Some(Reply { ours, lines: Vec::new() });
+            }
+            "%output" => {
+                let Some((pane, data)) = args.split_once(' ') else { return };
+                let Some(pane) = pane.strip_prefix('%').and_then(|p| p.parse().ok()) else { return };
+                self.output(pane, &unescape(data));
+            }
+            "%session-changed" => {
+                self.session = args.split_once(' ').map(|(_, name)| name.to_string()).unwrap_or_default();
+                self.list_windows();
+            }
+            "%layout-change" | "%window-add" | "%window-close" | "%unlinked-window-close"
+            | "%window-renamed" | "%session-window-changed" | "%window-pane-changed" => self.list_windows(),
+            "%exit" => self.finish(),
+            _ => {}
+        }
+    }
+
+    fn handle_reply(&mut self, request: Request, ok: bool, lines: Vec<String>) {
+        if !ok {
+            if !matches!(request, Request::Ignore) {
+                log::warn!("tmux request {:?} failed: {}", request, lines.join(" "));
+            }
+            self.events.push(TmuxEvent::CommandFailed(lines.join("\n")));
+            return;
+        }
+        match request {
+            Request::Ignore => {}
+            Request::ListWindows => {
+                let windows = parse_windows(&lines);
+                // Panes closed in tmux end their terminals
+                let live: Vec<u32> = windows.iter().flat_map(|w| w.layout.panes()).collect();
+                self.panes.retain(|id, sink| {
+                    let keep = live.contains(id);
+                    if !keep {
+                        sink.alive.store(false, Ordering::Relaxed);
+                    }
+                    keep
+                });
+                self.windows = windows.clone();
+                self.events.push(TmuxEvent::Windows { session: self.session.clone(), windows });
+                if std::mem::take(&mut self.relist) {
+                    self.list_windows();
+                }
+            }
+            Request::Capture(pane) => {
+                if let Some(sink) = self.panes.get_mut(&pane) {
+                    sink.state = SinkState::Captured(unescape(&lines.join("\r\n")));
+                }
+            }
+            Request::Cursor(pane) => {
+                let mut coords = lines.first().map(String::as_str).unwrap_or_default().split(' ');
+                let mut next = || coords.next().and_then(|n| n.parse::<u32>().ok()).unwrap_or(0);
+                let (x, y) = (next(), next());
+                let Some(sink) = self.panes.get_mut(&pane) else { return };
+                let SinkState::Captured(screen) = std::mem::replace(&mut sink.state, SinkState::Live) else {
+                    return;
+                };
+                let mut seed = b"\x1b[0m\x1b[H\x1b[2J".to_vec();
+                seed.extend_from_slice(&screen);
+                seed.extend_from_slice(format!("\x1b[0m\x1b[{};{}H", y + 1, x + 1).as_bytes());
+                self.output(pane, &seed);
+            }
+        }
+    }
+
+    /// Queue pane output for its terminal, dropping the pane if it is gone.
+    fn output(&mut self, pane: u32, bytes: &[u8]) {
+        let Some(sink) = self.panes.get_mut(&pane) else { return };
+        if matches!(sink.state, SinkState::Live) && sink.output.send(bytes.to_vec()).is_err() {
+            sink.alive.store(false, Ordering::Relaxed);
+            self.panes.remove(&pane);
+        }
+    }
+
+    /// Control mode ended: every pane terminal exits.
+    fn finish(&mut self) {
+        for sink in self.panes.values() {
+            sink.alive.store(false, Ordering::Relaxed);
+        }
+        self.panes.clear();
+        self.windows.clear();
+        self.sizes.clear();
+        self.active = false;
+        self.line.clear();
+        self.reply = None;
+        self.pending.clear();
+        self.relist = false;
+        self.events.push(TmuxEvent::Exited);
+    }
+
+    /// Write one command line to tmux; its reply is handled as `request`.
+    fn send(&mut self, command: &str, request: Request) {
+        if !self.active {
+            return;
+        }
+        if let Some(writer) = &self.writer {
+            let line = format!("{}\n", command);
+            let _ = writer.0.send(Msg::Input(Cow::Owned(line.into_bytes())));
+        }
+        self.pending.push_back(request);
+    }
+
+    fn list_windows(&mut self) {
+        if self.pending.iter().any(|r| matches!(r, Request::ListWindows)) {
+            // That reply may predate the change
+            self.relist = true;
+            return;
+        }
+        self.send(
+            "list-windows -F '#{window_id} #{window_active} #{window_layout} #{window_name}'",
+            Request::ListWindows,
+        );
+    }
+}
+
+/// Output tap feeding a terminal's control mode state.
+pub(crate) fn control_tap(control: Arc<Mutex<TmuxControl>>) -> OutputTap {
+    Box::new(move |bytes| {
+        if let Ok(mut control) = control.lock() {
+            control.feed(bytes);
+        }
+    })
+}
+
+/// A gateway terminal's tmux connection.
+#[derive(Clone)]
+pub struct TmuxHandle(pub(crate) Arc<Mutex<TmuxControl>>);
+
+impl PartialEq for TmuxHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl std::fmt::Debug for TmuxHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TmuxHandle").field(&Arc::as_ptr(&self.0)).finish()
+    }
+}
+
+impl TmuxHandle {
+    fn with<R>(&self, f: impl FnOnce(&mut TmuxControl) -> R) -> Option<R> {
+        self.0.lock().ok().map(|mut control| f(&mut control))
+    }
+
+    /// Whether tmux is still in control mode.
+    pub fn is_active(&self) -> bool {
+        self.with(|c| c.active).unwrap_or(false)
+    }
+
+    /// Name of the attached session.
+    pub fn session(&self) -> String {
+        self.with(|c| c.session.clone()).unwrap_or_default()
+    }
+
+    /// The windows as last listed.
+    pub fn windows(&self) -> Vec<TmuxWindow> {
+        self.with(|c| c.windows.clone()).unwrap_or_default()
+    }
+
+    /// Split a pane, side by side when `horizontal`.
+    pub fn split(&self, pane: u32, horizontal: bool) {
+        let flag = if horizontal { "-h" } else { "-v" };
+        self.with(|c| c.send(&format!("split-window {} -t %{}", flag, pane), Request::Ignore));
+    }
+
+    pub fn kill_pane(&self, pane: u32) {
+        self.with(|c| c.send(&format!("kill-pane -t %{}", pane), Request::Ignore));
+    }
+
+    /// Size a window to the space Tide gives it. Repeats are not sent.
+    pub fn resize_window(&self, window: u32, cols: u16, rows: u16) {
+        self.with(|c| {
+            if c.active && c.sizes.insert(window, (cols, rows)) != Some((cols, rows)) {
+                c.send(&format!("refresh-client -C @{}:{}x{}", window, cols, rows), Request::Ignore);
+            }
+        });
+    }
+
+    /// Leave control mode; the session keeps running.
+    pub fn detach(&self) {
+        self.with(|c| c.send("detach-client", Request::Ignore));
+    }
+
+    /// A terminal showing tmux pane `%pane`.
+    pub fn open_pane(&self, pane: u32, cols: u16, rows: u16, dark_mode: bool) -> Result<Terminal, Box<dyn std::error::Error>> {
+        let (output, pty_side) = UnixStream::pair()?;
+        let (hangup, pty_hangup) = UnixStream::pair()?;
+        pty_side.set_nonblocking(true)?;
+        pty_hangup.set_nonblocking(true)?;
+        let alive = Arc::new(AtomicBool::new(true));
+
+        let input = output.try_clone()?;
+        let (output_tx, output_rx) = mpsc::channel();
+        {
+            let alive = alive.clone();
+            std::thread::Builder::new()
+                .name("tmux-output".to_string())
+                .spawn(move || write_output(output, output_rx, &alive))?;
+        }
+        self.with(|c| {
+            c.panes.insert(pane, PaneSink {
+                _hangup: hangup,
+                output: output_tx,
+                alive: alive.clone(),
+                state: SinkState::Capturing,
+            });
+            c.send(&format!("capture-pane -p -e -C -t %{}", pane), Request::Capture(pane));
+            c.send(&format!("display-message -p -t %{} '#{{cursor_x}} #{{cursor_y}}'", pane), Request::Cursor(pane));
+        });
+
+        let handle = self.clone();
+        std::thread::Builder::new()
+            .name("tmux-input".to_string())
+            .spawn(move || forward_input(input, handle, pane))?;
+
+        // tmux already answered the queries in `%output`; answering again
+        // would reach the program as typed input
+        let pty = TmuxPty { stream: pty_side, hangup: pty_hangup };
+        let mut terminal = Terminal::start(cols, rows, dark_mode, pty, None, None, true)?;
+        terminal.tmux_pane = Some(TmuxPaneLink { handle: self.clone(), pane, alive });
+        Ok(terminal)
+    }
+}
+
+/// Write a pane's output to its terminal until the pane is dropped.
+fn write_output(mut output: UnixStream, queue: mpsc::Receiver<Vec<u8>>, alive: &AtomicBool) {
+    for bytes in queue {
+        if output.write_all(&bytes).is_err() {
+            alive.store(false, Ordering::Relaxed);
+            return;
+        }
+    }
+}
+
+/// Send what a pane's emulator writes (typed keys, replies) as keys.
+fn forward_input(mut input: UnixStream, handle: TmuxHandle, pane: u32) {
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = match input.read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => return,
+        };
+        for chunk in buf[..n].chunks(SEND_KEYS_CHUNK) {
+            let keys: String = chunk.iter().map(|b| format!(" {:02x}", b)).collect();
+            handle.with(|c| c.send(&format!("send-keys -t %{} -H{}", pane, keys), Request::Ignore));
+        }
+    }
+}
+
+/// Which tmux pane a terminal shows.
+pub(crate) struct TmuxPaneLink {
+    pub(crate) handle: TmuxHandle,
+    pub(crate) pane: u32,
+    /// Cleared when the pane closes or tmux exits.
+    pub(crate) alive: Arc<AtomicBool>,
+}
+
+/// The "PTY" of a pane terminal.
+struct TmuxPty {
+    stream: UnixStream,
+    /// Reads EOF once the gateway drops the pane.
+    hangup: UnixStream,
+}
+
+impl EventedReadWrite for TmuxPty {
+    type Reader = UnixStream;
+    type Writer = UnixStream;
+
+    unsafe fn register(&mut self, poll: &Arc<Poller>, mut interest: Event, mode: PollMode) -> io::Result<()> {
+        interest.key = tty::PTY_READ_WRITE_TOKEN;
+        unsafe {
+            poll.add_with_mode(&self.stream, interest, mode)?;
+            poll.add_with_mode(&self.hangup, Event::readable(tty::PTY_CHILD_EVENT_TOKEN), PollMode::Level)
+        }
+    }
+
+    fn reregister(&mut self, poll: &Arc<Poller>, mut interest: Event, mode: PollMode) -> io::Result<()> {
+        interest.key = tty::PTY_READ_WRITE_TOKEN;
+        poll.modify_with_mode(&self.stream, interest, mode)?;
+        poll.modify_with_mode(&self.hangup, Event::readable(tty::PTY_CHILD_EVENT_TOKEN), PollMode::Level)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        poll.delete(&self.stream)?;
+        poll.delete(&self.hangup)
+    }
+
+    fn reader(&mut self) -> &mut UnixStream {
+        &mut self.stream
+    }
+
+    fn writer(&mut self) -> &mut UnixStream {
+        &mut self.stream
+    }
+}
+
+impl EventedPty for TmuxPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        let mut buf = [0u8; 1];
+        match self.hangup.read(&mut buf) {
+            Ok(0) => Some(ChildEvent::Exited(None)),
+            _ => None,
+        }
+    }
+}
+
+impl OnResize for TmuxPty {
+    // tmux sizes panes; the app resizes windows with `refresh-client -C`
+    fn on_resize(&mut self, _: WindowSize) {}
+}
+
+impl Terminal {
+    /// Changes reported by tmux running in control mode in this terminal.
+    pub fn tmux_events(&self) -> Vec<TmuxEvent> {
+        self.tmux.lock().map(|mut c| c.take_events()).unwrap_or_default()
+    }
+
+    /// Handle on tmux control mode in this terminal, which is active while
+    /// tmux runs in it with `-CC`.
+    pub fn tmux_gateway(&self) -> TmuxHandle {
+        TmuxHandle(self.tmux.clone())
+    }
+
+    /// Swallow input typed into a gateway, whose stdin is tmux's command
+    /// line; Escape detaches, as in iTerm2. Returns false for other terminals.
+    pub(crate) fn tmux_gateway_input(&self, data: &[u8]) -> bool {
+        let Ok(mut control) = self.tmux.lock() else { return false };
+        if !control.active {
+            return false;
+        }
+        if data == b"\x1b" {
+            control.send("detach-client", Request::Ignore);
+        }
+        true
+    }
+
+    /// The tmux connection and pane id, if this terminal shows a tmux pane.
+    pub fn tmux_pane(&self) -> Option<(TmuxHandle, u32)> {
+        self.tmux_pane.as_ref().map(|link| (link.handle.clone(), link.pane))
+    }
+}
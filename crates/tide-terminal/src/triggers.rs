@@ -0,0 +1,304 @@
+// Output triggers
+//
+// iTerm2-style rules: a regex matched against each new line of output, plus an
+// action. Matching runs on the sync thread: each sync copies the lines
+// written since the last one (soft-wrapped rows joined) while it holds the
+// term lock, and matches them after releasing it. Lines are tracked in stable
+// coordinates (see shell_integration.rs), so each is matched once however it
+// scrolls. The cursor's line is matched too, since a prompt waiting for input
+// never finishes its line; it is re-read every sync until finished, and a
+// rule fires at most once per match position on it. The alternate screen is
+// skipped, since full-screen programs redraw the same text over and over.
+//
+// Highlights stay on the sync thread, which paints them over the converted
+// cells next to the URL scan. Every other action is queued as a
+// `TriggerEvent` for the app to carry out on the main thread.
+
+use std::collections::VecDeque;
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line};
+use alacritty_terminal::term::cell::Flags as CellFlags;
+use alacritty_terminal::term::{Term, TermMode};
+use regex::Regex;
+
+use tide_core::{Color, TerminalCell};
+
+use super::Terminal;
+
+/// Lines matched per sync at most; a burst beyond this only has its last
+/// lines matched.
+const MAX_LINES_PER_SYNC: u64 = 2000;
+
+/// Highlights kept; the oldest are dropped first.
+const MAX_HIGHLIGHTS: usize = 10_000;
+
+/// Events kept until the app picks them up; older ones are dropped.
+const MAX_PENDING_EVENTS: usize = 64;
+
+/// A regex and what to do when a line of output matches it.
+#[derive(Debug, Clone)]
+pub struct Trigger {
+    pub regex: Regex,
+    pub action: TriggerAction,
+}
+
+/// What a trigger does. In the text of `Notify`, `Open` and `Reply`, `$1` and
+/// `${name}` expand to the match's capture groups (`$0` is the whole match).
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriggerAction {
+    /// Paint the match with this background color.
+    Highlight(Color),
+    /// Raise an attention badge on the terminal.
+    Badge,
+    /// Send a desktop notification. Empty = the matched line.
+    Notify(String),
+    /// Open a URL or file path. Empty = the match.
+    Open(String),
+    /// Write this text to the program, as if typed.
+    Reply(String),
+}
+
+/// A fired trigger, with its text expanded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerEvent {
+    pub action: TriggerAction,
+    /// The whole line that matched.
+    pub line: String,
+}
+
+/// Rules and fired events, shared between the sync thread and `Terminal`.
+#[derive(Debug, Default)]
+pub(crate) struct TriggerSet {
+    rules: Vec<Trigger>,
+    /// Stable line to start matching from, set with new rules.
+    restart: Option<u64>,
+    /// Whether rules were ever set.
+    started: bool,
+    events: VecDeque<TriggerEvent>,
+}
+
+impl TriggerSet {
+    /// No rules yet; the first ones are matched from stable line `start` on
+    /// (where the program's output begins).
+    pub(crate) fn starting_at(start: u64) -> Self {
+        Self { restart: Some(start), ..Self::default() }
+    }
+
+    /// New rules. The first ones also see the output written before them
+    /// (a program can print before the app sets its triggers); replacements
+    /// are matched from stable line `start` on.
+    pub(crate) fn set_rules(&mut self, rules: Vec<Trigger>, start: u64) {
+        if self.started {
+            self.restart = Some(start);
+        }
+        self.started = true;
+        self.rules = rules;
+        self.events.clear();
+    }
+
+    pub(crate) fn take_events(&mut self) -> Vec<TriggerEvent> {
+        self.events.drain(..).collect()
+    }
+}
+
+/// A logical line of output: its text and each character's cell.
+#[derive(Debug, Default)]
+struct OutputLine {
+    /// Stable line of its first row.
+    start: u64,
+    text: String,
+    cells: Vec<(u64, usize)>,
+}
+
+/// A highlighted span of one row, in stable coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Highlight {
+    line: u64,
+    start_col: usize,
+    end_col: usize,
+    color: Color,
+}
+
+/// Sync-thread state: how far output has been matched, and the highlights.
+#[derive(Debug, Default)]
+pub(crate) struct TriggerScanner {
+    /// First stable line not matched yet.
+    next_line: u64,
+    /// Lines copied under the term lock, matched after it's released.
+    lines: Vec<OutputLine>,
+    highlights: VecDeque<Highlight>,
+    /// (rule, byte offset) of the matches already fired on the line starting
+    /// at `fired_line`: the cursor's line, which is matched again each sync.
+    fired: Vec<(usize, usize)>,
+    fired_line: u64,
+    /// Cell indices (row * cols + col) painted by the last sync, sorted.
+    pub(crate) painted: Vec<usize>,
+    /// Cells painted by the sync before, to tell whether the highlights moved.
+    last_painted: Vec<usize>,
+}
+
+impl TriggerScanner {
+    /// Copy the lines finished since the last call, and the cursor's line.
+    /// Runs under the term lock.
+    pub(crate) fn collect<T>(&mut self, term: &Term<T>, set: &mut TriggerSet) {
+        self.lines.clear();
+        if let Some(line) = set.restart.take() {
+            self.next_line = line;
+            self.highlights.clear();
+            self.fired.clear();
+        }
+        if set.rules.is_empty() || term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+
+        let grid = term.grid();
+        let cols = grid.columns();
+        let scrolled = grid.scrolled_lines();
+        let first_line = scrolled.saturating_sub(grid.history_size() as u64);
+        let cursor_line = scrolled + grid.cursor.point.line.0 as u64;
+        let mut line = self.next_line.max(first_line).max(cursor_line.saturating_sub(MAX_LINES_PER_SYNC));
+        let mut current = OutputLine { start: line, ..Default::default() };
+        while line <= cursor_line {
+            let row = &grid[Line((line as i64 - scrolled as i64) as i32)];
+            for col in 0..cols {
+                let cell = &row[Column(col)];
+                if cell.flags.intersects(CellFlags::WIDE_CHAR_SPACER | CellFlags::LEADING_WIDE_CHAR_SPACER) {
+                    continue;
+                }
+                current.text.push(cell.c);
+                current.cells.push((line, col));
+            }
+            let wrapped = cols > 0 && row[Column(cols - 1)].flags.contains(CellFlags::WRAPLINE);
+            line += 1;
+            if !wrapped || line > cursor_line {
+                let trimmed = current.text.trim_end_matches(' ').len();
+                current.cells.truncate(current.text[..trimmed].chars().count());
+                current.text.truncate(trimmed);
+                self.lines.push(std::mem::replace(&mut current, OutputLine { start: line, ..Default::default() }));
+                // The cursor's line (with any rows wrapping onto it) is read
+                // again next time
+                if line <= cursor_line {
+                    self.next_line = line;
+                }
+            }
+        }
+    }
+
+    /// Match the collected lines. Highlights are kept; other actions are
+    /// queued on `set`.
+    pub(crate) fn match_lines(&mut self, set: &mut TriggerSet) {
+        for output in self.lines.drain(..) {
+            if output.start != self.fired_line {
+                self.fired.clear();
+                self.fired_line = output.start;
+            }
+            for (index, rule) in set.rules.iter().enumerate() {
+                for caps in rule.regex.captures_iter(&output.text) {
+                    let Some(m) = caps.get(0) else { continue };
+                    if m.is_empty() || self.fired.contains(&(index, m.start())) {
+                        continue;
+                    }
+                    self.fired.push((index, m.start()));
+                    let expand = |template: &str, fallback: &str| {
+                        if template.is_empty() {
+                            return fallback.to_string();
+                        }
+                        let mut out = String::new();
+                        caps.expand(template, &mut out);
+                        out
+                    };
+                    let action = match &rule.action {
+                        TriggerAction::Highlight(color) => {
+                            let start = output.text[..m.start()].chars().count();
+                            let len = m.as_str().chars().count();
+                            push_highlights(&mut self.highlights, &output.cells[start..start + len], *color);
+                            continue;
+                        }
+                        TriggerAction::Badge => TriggerAction::Badge,
+                        TriggerAction::Notify(text) => TriggerAction::Notify(expand(text, &output.text)),
+                        TriggerAction::Open(target) => TriggerAction::Open(expand(target, m.as_str())),
+                        TriggerAction::Reply(text) => TriggerAction::Reply(expand(text, "")),
+                    };
+                    if set.events.len() >= MAX_PENDING_EVENTS {
+                        set.events.pop_front();
+                    }
+                    set.events.push_back(TriggerEvent { action, line: output.text.clone() });
+                }
+            }
+        }
+        while self.highlights.len() > MAX_HIGHLIGHTS {
+            self.highlights.pop_front();
+        }
+    }
+
+    /// Drop highlights on lines that left scrollback.
+    pub(crate) fn prune(&mut self, first_line: u64) {
+        while self.highlights.front().is_some_and(|h| h.line < first_line) {
+            self.highlights.pop_front();
+        }
+    }
+
+    /// Paint highlights over the viewport's cells and remember which cells
+    /// were painted, so the next sync re-converts them. Returns whether
+    /// they differ from the cells painted last time.
+    pub(crate) fn paint(&mut self, cells: &mut [Vec<TerminalCell>], viewport_top: u64) -> bool {
+        std::mem::swap(&mut self.painted, &mut self.last_painted);
+        self.painted.clear();
+        let rows = cells.len() as u64;
+        for h in &self.highlights {
+            if h.line < viewport_top || h.line >= viewport_top + rows {
+                continue;
+            }
+            let row_index = (h.line - viewport_top) as usize;
+            let row = &mut cells[row_index];
+            let cols = row.len();
+            for (col, cell) in row.iter_mut().enumerate().take(h.end_col).skip(h.start_col) {
+                cell.style.background = Some(h.color);
+                self.painted.push(row_index * cols + col);
+            }
+        }
+        self.painted.sort_unstable();
+        self.painted.dedup();
+        self.painted != self.last_painted
+    }
+}
+
+/// Highlight the cells of a match, one span per row.
+fn push_highlights(highlights: &mut VecDeque<Highlight>, cells: &[(u64, usize)], color: Color) {
+    let mut i = 0;
+    while i < cells.len() {
+        let (line, start_col) = cells[i];
+        let mut end_col = start_col + 1;
+        i += 1;
+        while i < cells.len() && cells[i].0 == line {
+            end_col = cells[i].1 + 1;
+            i += 1;
+        }
+        highlights.push_back(Highlight { line, start_col, end_col, color });
+    }
+}
+
+impl Terminal {
+    /// Replace the output triggers. The first rules are matched against all
+    /// of the program's output (not a restored scrollback); later ones start
+    /// with the line the cursor is on.
+    pub fn set_triggers(&mut self, rules: Vec<Trigger>) {
+        let cursor_line = {
+            let term = self.term.lock();
+            let grid = term.grid();
+            grid.scrolled_lines() + grid.cursor.point.line.0 as u64
+        };
+        if let Ok(mut set) = self.triggers.lock() {
+            set.set_rules(rules, cursor_line);
+        }
+        self.dirty.store(true, std::sync::atomic::Ordering::Relaxed);
+        self.notify_sync_thread();
+    }
+
+    /// Take the triggers fired since the last call (highlights aren't
+    /// reported; they're already painted).
+    pub fn take_trigger_events(&self) -> Vec<TriggerEvent> {
+        self.triggers.lock().map(|mut set| set.take_events()).unwrap_or_default()
+    }
+}